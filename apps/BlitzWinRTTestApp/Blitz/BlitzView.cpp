@@ -59,6 +59,7 @@ namespace winrt::Blitz::implementation
             m_host = winrt::BlitzWinUI::Host(m_attacher, width, height, rasterScale, initialHtml);
             // Apply current overlay setting (default false unless changed before init)
             try { m_host.SetDebugOverlay(m_debugOverlayEnabled); } catch (...) {}
+            try { m_host.SetDebugOverlayOptions(m_overlayLines, m_overlayCorner, m_overlayOpacity); } catch (...) {}
             // Create and inject network fetcher so that resource loads (images/stylesheets) can occur.
             try
             {
@@ -137,6 +138,36 @@ namespace winrt::Blitz::implementation
         if (pt.Properties().IsXButton1Pressed()) buttons |= 8;
         if (pt.Properties().IsXButton2Pressed()) buttons |= 16;
         try { m_host.PointerMove((float)pt.Position().X, (float)pt.Position().Y, buttons, modifiers); } catch (...) {}
+        UpdateCursor((float)pt.Position().X, (float)pt.Position().Y);
+    }
+
+    // Show the page's CSS cursor for the element under the pointer (see IHost.GetCursor for the values)
+    void BlitzView::UpdateCursor(float x, float y)
+    {
+        int32_t kind = 0;
+        try { kind = m_host.GetCursor(x, y); } catch (...) { return; }
+        if (kind == m_cursorKind) return;
+        m_cursorKind = kind;
+        using winrt::Microsoft::UI::Input::InputSystemCursor;
+        using winrt::Microsoft::UI::Input::InputSystemCursorShape;
+        InputSystemCursorShape shape = InputSystemCursorShape::Arrow;
+        switch (kind)
+        {
+        case 1: shape = InputSystemCursorShape::Hand; break;
+        case 2: shape = InputSystemCursorShape::IBeam; break;
+        case 3: shape = InputSystemCursorShape::Cross; break;
+        case 4: shape = InputSystemCursorShape::SizeAll; break;
+        case 5: shape = InputSystemCursorShape::Help; break;
+        case 6: shape = InputSystemCursorShape::Wait; break;
+        case 7: shape = InputSystemCursorShape::AppStarting; break;
+        case 8: shape = InputSystemCursorShape::UniversalNo; break;
+        case 9: shape = InputSystemCursorShape::SizeWestEast; break;
+        case 10: shape = InputSystemCursorShape::SizeNorthSouth; break;
+        case 11: shape = InputSystemCursorShape::SizeNortheastSouthwest; break;
+        case 12: shape = InputSystemCursorShape::SizeNorthwestSoutheast; break;
+        default: break; // Default, and None (there is no hidden system cursor)
+        }
+        try { this->ProtectedCursor(InputSystemCursor::Create(shape)); } catch (...) {}
     }
 
     void BlitzView::PanelPointerPressed(winrt::Windows::Foundation::IInspectable const&, PointerRoutedEventArgs const& e)
@@ -154,6 +185,8 @@ namespace winrt::Blitz::implementation
         if (pt.Properties().IsXButton2Pressed()) buttons |= 16;
         uint32_t modifiers = (uint32_t)e.KeyModifiers();
         try { m_host.PointerDown((float)pt.Position().X, (float)pt.Position().Y, button, buttons, modifiers); } catch (...) {}
+        // Take keyboard focus so clipboard shortcuts reach OnKeyDown
+        this->Focus(FocusState::Pointer);
     }
 
     void BlitzView::PanelPointerReleased(winrt::Windows::Foundation::IInspectable const&, PointerRoutedEventArgs const& e)
@@ -174,11 +207,15 @@ namespace winrt::Blitz::implementation
         double pixelsPerLine = 48.0;
         double dy = raw / 120.0 * linesPerNotch * pixelsPerLine;
         double dx = 0.0;
-        if ((e.KeyModifiers() & Windows::System::VirtualKeyModifiers::Shift) == Windows::System::VirtualKeyModifiers::Shift)
+        auto keyModifiers = e.KeyModifiers();
+        // Ctrl+wheel zooms the page; the host expects keyboard-types modifier bits (CONTROL = 0x8)
+        bool ctrl = (keyModifiers & Windows::System::VirtualKeyModifiers::Control) == Windows::System::VirtualKeyModifiers::Control;
+        if (!ctrl && (keyModifiers & Windows::System::VirtualKeyModifiers::Shift) == Windows::System::VirtualKeyModifiers::Shift)
         {
             dx = dy; dy = 0.0;
         }
-        try { m_host.WheelScroll(dx, dy); } catch (...) {}
+        uint32_t modifiers = ctrl ? 0x8u : 0u;
+        try { m_host.WheelScrollWithModifiers(dx, dy, modifiers); } catch (...) {}
         e.Handled(true);
     }
 
@@ -235,6 +272,52 @@ namespace winrt::Blitz::implementation
         PanelPointerWheelChanged(nullptr, e);
     }
 
+    // Ctrl+C/X/V: the host owns the Windows clipboard, so these go through IHost.Copy/Cut/Paste
+    void BlitzView::OnKeyDown(KeyRoutedEventArgs const& e)
+    {
+        if (!m_host) return;
+        using winrt::Windows::System::VirtualKey;
+        using winrt::Windows::UI::Core::CoreVirtualKeyStates;
+        using namespace winrt::Windows::ApplicationModel::DataTransfer;
+        auto ctrlState = winrt::Microsoft::UI::Input::InputKeyboardSource::GetKeyStateForCurrentThread(VirtualKey::Control);
+        if ((ctrlState & CoreVirtualKeyStates::Down) != CoreVirtualKeyStates::Down) return;
+        auto key = e.Key();
+        if (key == VirtualKey::C || key == VirtualKey::X)
+        {
+            e.Handled(true);
+            hstring text;
+            try { text = key == VirtualKey::C ? m_host.Copy() : m_host.Cut(); } catch (...) { return; }
+            if (text.empty()) return;
+            DataPackage package;
+            package.SetText(text);
+            try { Clipboard::SetContent(package); } catch (...) {}
+        }
+        else if (key == VirtualKey::V)
+        {
+            e.Handled(true);
+            PasteFromClipboard();
+        }
+    }
+
+    winrt::fire_and_forget BlitzView::PasteFromClipboard()
+    {
+        using namespace winrt::Windows::ApplicationModel::DataTransfer;
+        auto strong = get_strong();
+        hstring text;
+        try
+        {
+            auto content = Clipboard::GetContent();
+            if (!content.Contains(StandardDataFormats::Text())) co_return;
+            // Resumes on the UI thread, where the host must be called
+            text = co_await content.GetTextAsync();
+        }
+        catch (...) { co_return; }
+        if (m_host)
+        {
+            try { m_host.Paste(text); } catch (...) {}
+        }
+    }
+
     void BlitzView::OnXamlRootChanged(winrt::Windows::Foundation::IInspectable const&, winrt::Microsoft::UI::Xaml::XamlRootChangedEventArgs const&)
     {
         // DPI (RasterizationScale) may have changed even if logical size did not; trigger logical resize with same size but new scale.
@@ -270,5 +353,16 @@ namespace winrt::Blitz::implementation
             try { m_host.SetDebugOverlay(value); } catch (...) {}
         }
     }
+
+    void BlitzView::SetDebugOverlayOptions(uint32_t lines, uint8_t corner, float opacity)
+    {
+        m_overlayLines = lines;
+        m_overlayCorner = corner;
+        m_overlayOpacity = opacity;
+        if (m_host)
+        {
+            try { m_host.SetDebugOverlayOptions(lines, corner, opacity); } catch (...) {}
+        }
+    }
 }
 
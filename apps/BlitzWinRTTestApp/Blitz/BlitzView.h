@@ -23,10 +23,12 @@ namespace winrt::Blitz::implementation
         void OnPointerPressed(winrt::Microsoft::UI::Xaml::Input::PointerRoutedEventArgs const&);
         void OnPointerReleased(winrt::Microsoft::UI::Xaml::Input::PointerRoutedEventArgs const&);
         void OnPointerWheelChanged(winrt::Microsoft::UI::Xaml::Input::PointerRoutedEventArgs const&);
+        void OnKeyDown(winrt::Microsoft::UI::Xaml::Input::KeyRoutedEventArgs const&);
         winrt::hstring HTML() const; // Property getter
         void HTML(winrt::hstring const& value); // Property setter
     bool DebugOverlayEnabled() const;
     void DebugOverlayEnabled(bool value);
+    void SetDebugOverlayOptions(uint32_t lines, uint8_t corner, float opacity);
 
     private:
         // Lifecycle
@@ -47,6 +49,8 @@ namespace winrt::Blitz::implementation
 
         // Helpers
         void ForwardResize();
+        void UpdateCursor(float x, float y);
+        winrt::fire_and_forget PasteFromClipboard();
 
         // State
         winrt::Microsoft::UI::Xaml::Controls::SwapChainPanel m_panel{ nullptr };
@@ -57,6 +61,11 @@ namespace winrt::Blitz::implementation
         bool m_renderLoopAttached{ false };
         winrt::hstring m_html; // backing for HTML property
     bool m_debugOverlayEnabled{ false }; // backing for DebugOverlayEnabled property
+    // Overlay options applied once the host exists (default: every line, top-left, opaque)
+    uint32_t m_overlayLines{ 0xF };
+    uint8_t m_overlayCorner{ 0 };
+    float m_overlayOpacity{ 1.0f };
+    int32_t m_cursorKind{ -1 }; // last IHost.GetCursor value applied to ProtectedCursor (-1 = none yet)
 
         // Event tokens for cleanup (not strictly necessary yet)
         winrt::event_token m_loadedToken{};
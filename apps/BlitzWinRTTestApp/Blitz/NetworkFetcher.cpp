@@ -20,6 +20,39 @@ namespace
         std::wstring line = L"[Fetch] URL '" + std::wstring(v) + L"' (len=" + std::to_wstring(v.size()) + L")\n";
         OutputDebugStringW(line.c_str());
     }
+
+    std::wstring_view Trim(std::wstring_view v)
+    {
+        while (!v.empty() && iswspace(v.front())) v.remove_prefix(1);
+        while (!v.empty() && iswspace(v.back())) v.remove_suffix(1);
+        return v;
+    }
+
+    // Apply CRLF separated "Name: value" lines; Content-* headers belong to the content when there is one
+    void ApplyHeaders(HttpRequestMessage const& request, winrt::hstring const& headers)
+    {
+        std::wstring_view rest = headers;
+        while (!rest.empty())
+        {
+            auto eol = rest.find(L'\n');
+            std::wstring_view line = rest.substr(0, eol);
+            rest = eol == std::wstring_view::npos ? std::wstring_view{} : rest.substr(eol + 1);
+            auto colon = line.find(L':');
+            if (colon == std::wstring_view::npos) continue;
+            winrt::hstring name{ Trim(line.substr(0, colon)) };
+            winrt::hstring value{ Trim(line.substr(colon + 1)) };
+            if (name.empty()) continue;
+            bool isContent = _wcsnicmp(name.c_str(), L"content-", 8) == 0;
+            if (isContent && request.Content())
+            {
+                request.Content().Headers().TryAppendWithoutValidation(name, value);
+            }
+            else if (!isContent)
+            {
+                request.Headers().TryAppendWithoutValidation(name, value);
+            }
+        }
+    }
 }
 
 namespace winrt::Blitz::implementation
@@ -30,20 +63,30 @@ namespace winrt::Blitz::implementation
         m_client = HttpClient();
     }
 
-    void NetworkFetcher::Fetch(uint32_t requestId, uint32_t docId, winrt::hstring const& url, winrt::hstring const& method)
+    void NetworkFetcher::Fetch(uint32_t requestId, uint32_t docId, winrt::hstring const& url, winrt::hstring const& method,
+                               winrt::hstring const& headers, winrt::array_view<uint8_t const> body)
     {
-        auto urlCopy = url; (void)method; // only GET for now
-        DoFetch(requestId, docId, std::move(urlCopy), L"GET");
+        // The body view only lives for this call; copy it for the coroutine
+        DoFetch(requestId, docId, url, method.empty() ? winrt::hstring(L"GET") : method, headers,
+                std::vector<uint8_t>(body.begin(), body.end()));
     }
 
-    winrt::fire_and_forget NetworkFetcher::DoFetch(uint32_t requestId, uint32_t docId, winrt::hstring url, winrt::hstring method)
+    winrt::fire_and_forget NetworkFetcher::DoFetch(uint32_t requestId, uint32_t docId, winrt::hstring url, winrt::hstring method,
+                                                   winrt::hstring headers, std::vector<uint8_t> body)
     {
-        auto lifetime = get_strong(); (void)method;
+        auto lifetime = get_strong();
         LogUrl(url);
         try
         {
-            Uri uri(url);
-            HttpResponseMessage response = co_await m_client.GetAsync(uri);
+            HttpRequestMessage request(HttpMethod(method), Uri(url));
+            if (!body.empty())
+            {
+                DataWriter writer;
+                writer.WriteBytes(body);
+                request.Content(HttpBufferContent(writer.DetachBuffer()));
+            }
+            ApplyHeaders(request, headers);
+            HttpResponseMessage response = co_await m_client.SendRequestAsync(request);
             response.EnsureSuccessStatusCode();
             IBuffer buffer = co_await response.Content().ReadAsBufferAsync();
             DataReader reader = DataReader::FromBuffer(buffer);
@@ -4,6 +4,7 @@
 #include <winrt/BlitzWinUI.h>
 // Use dot-delimited C++/WinRT projection headers (directory style path was invalid)
 #include <winrt/Windows.Web.Http.h>
+#include <winrt/Windows.Web.Http.Headers.h>
 #include <winrt/Windows.Storage.Streams.h>
 
 namespace winrt::Blitz::implementation
@@ -12,12 +13,14 @@ namespace winrt::Blitz::implementation
     {
         NetworkFetcher(winrt::BlitzWinUI::Host const& host);
 
-        void Fetch(uint32_t requestId, uint32_t docId, winrt::hstring const& url, winrt::hstring const& method);
+        void Fetch(uint32_t requestId, uint32_t docId, winrt::hstring const& url, winrt::hstring const& method,
+                   winrt::hstring const& headers, winrt::array_view<uint8_t const> body);
     private:
         winrt::BlitzWinUI::Host m_host{ nullptr };
         winrt::Windows::Web::Http::HttpClient m_client{ nullptr };
 
-        winrt::fire_and_forget DoFetch(uint32_t requestId, uint32_t docId, winrt::hstring url, winrt::hstring method);
+        winrt::fire_and_forget DoFetch(uint32_t requestId, uint32_t docId, winrt::hstring url, winrt::hstring method,
+                                       winrt::hstring headers, std::vector<uint8_t> body);
     };
 }
 
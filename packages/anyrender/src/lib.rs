@@ -27,8 +27,8 @@
 //!  - [anyrender_vello](https://docs.rs/anyrender_vello)
 //!  - [anyrender_vello_cpu](https://docs.rs/anyrender_vello_cpu)
 
-use kurbo::{Affine, Rect, Shape, Stroke};
-use peniko::{BlendMode, BrushRef, Color, Fill, Font, Image, StyleRef};
+use kurbo::{Affine, Cap, Line, Rect, RoundedRect, Shape, Stroke};
+use peniko::{BlendMode, Blob, BrushRef, Color, Fill, Font, Image, Mix, StyleRef};
 use std::sync::Arc;
 
 pub mod wasm_send_sync;
@@ -139,6 +139,96 @@ pub trait PaintScene {
 
     // --- Provided methods
 
+    /// Draw an inset box shadow: `rect` is the unshadowed hole and everything outside it is painted, its edge
+    /// blurred with a gaussian of standard deviation `std_dev`. Callers clip the result to the element's padding box.
+    ///
+    /// The default implementation ignores `std_dev`: it fills a frame reaching 16384 units past the hole with a
+    /// sharp edge, which the caller's clip bounds.
+    fn draw_inset_box_shadow(
+        &mut self,
+        transform: Affine,
+        rect: Rect,
+        brush: Color,
+        radius: f64,
+        std_dev: f64,
+    ) {
+        let _ = std_dev;
+        let mut frame = rect.inflate(16384.0, 16384.0).to_path(0.1);
+        frame.extend(RoundedRect::from_rect(rect, radius).path_elements(0.1));
+        self.fill(Fill::EvenOdd, transform, brush, None, &frame);
+    }
+
+    /// Draws the shadow of a glyph run: the glyph shapes filled with `color` and blurred with a gaussian of
+    /// standard deviation `std_dev`, as for CSS `text-shadow`. `transform` already includes the shadow offset.
+    /// The default implementation draws the glyphs unblurred.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_glyph_shadow<'a, 's: 'a>(
+        &'s mut self,
+        font: &'a Font,
+        font_family: &str,
+        font_size: f32,
+        font_weight: u16,
+        normalized_coords: &'a [NormalizedCoord],
+        color: Color,
+        std_dev: f64,
+        transform: Affine,
+        glyph_transform: Option<Affine>,
+        glyphs: impl Iterator<Item = Glyph>,
+    ) {
+        let _ = std_dev;
+        self.draw_glyphs(
+            font,
+            font_family,
+            font_size,
+            font_weight,
+            true,
+            normalized_coords,
+            Fill::NonZero,
+            color,
+            1.0,
+            transform,
+            glyph_transform,
+            glyphs,
+        );
+    }
+
+    /// Draws a text decoration (underline or line-through) `thickness` thick along `line`, which is horizontal
+    /// in the text's coordinate space. Backends may snap it to whole device pixels. The default implementation
+    /// strokes the line with flat caps so it ends flush with the run.
+    fn draw_text_decoration<'a>(
+        &mut self,
+        transform: Affine,
+        brush: impl Into<BrushRef<'a>>,
+        line: Line,
+        thickness: f64,
+    ) {
+        let stroke = Stroke::new(thickness).with_caps(Cap::Butt);
+        self.stroke(&stroke, transform, brush, None, &line);
+    }
+
+    /// Pushes a layer clipped to `clip` whose content is masked by `mask`, as for CSS `mask-image`: each pixel is
+    /// kept in proportion to the mask's alpha at that point, or to its luminance when `luminance` is set
+    /// (`mask-mode: luminance`). The mask is positioned by `transform * mask_transform`. Popped with
+    /// [`pop_layer`](Self::pop_layer). The default implementation ignores the mask and only clips.
+    fn push_mask_layer<'a>(
+        &mut self,
+        transform: Affine,
+        clip: &impl Shape,
+        mask: impl Into<BrushRef<'a>>,
+        mask_transform: Option<Affine>,
+        luminance: bool,
+    ) {
+        let _ = (mask.into(), mask_transform, luminance);
+        self.push_layer(Mix::Clip, 1.0, transform, clip);
+    }
+
+    /// Blurs what has already been drawn under `shape` with a gaussian of standard deviation `std_dev`, as for
+    /// CSS `backdrop-filter: blur()`. Called before the element's own background is drawn. The default
+    /// implementation leaves the backdrop unblurred.
+    fn draw_backdrop_blur(&mut self, transform: Affine, shape: &impl Shape, std_dev: f64) {
+        let _ = (transform, shape, std_dev);
+    }
+
     /// Utility method to draw an image at it's natural size. For more advanced image drawing use the `fill` method
     fn draw_image(&mut self, image: &Image, transform: Affine) {
         self.fill(
@@ -149,4 +239,44 @@ pub trait PaintScene {
             &Rect::new(0.0, 0.0, image.width as f64, image.height as f64),
         );
     }
+
+    /// Draw an image whose pixels have the given alpha type, e.g. already premultiplied pixels that would
+    /// darken at partially transparent edges if premultiplied again.
+    ///
+    /// The default implementation converts premultiplied pixels to straight alpha and draws them with
+    /// [`draw_image`](Self::draw_image).
+    fn draw_image_with_alpha_type(
+        &mut self,
+        image: &Image,
+        transform: Affine,
+        alpha_type: ImageAlphaType,
+    ) {
+        if alpha_type == ImageAlphaType::Alpha {
+            self.draw_image(image, transform);
+            return;
+        }
+        let straight: Vec<u8> = image
+            .data
+            .data()
+            .chunks_exact(4)
+            .flat_map(|px| {
+                let a = px[3] as u32;
+                let unpremultiply = |c: u8| match a {
+                    0 => 0,
+                    a => ((c as u32 * 255 + a / 2) / a).min(255) as u8,
+                };
+                [
+                    unpremultiply(px[0]),
+                    unpremultiply(px[1]),
+                    unpremultiply(px[2]),
+                    px[3],
+                ]
+            })
+            .collect();
+        let image = Image {
+            data: Blob::new(Arc::new(straight)),
+            ..image.clone()
+        };
+        self.draw_image(&image, transform);
+    }
 }
@@ -5,12 +5,46 @@ use std::{any::Any, sync::Arc};
 
 pub type NormalizedCoord = i16;
 
-/// A positioned glyph.
+/// A positioned glyph. Construct it with [`Glyph::new`] so that adding fields isn't a breaking change.
 #[derive(Copy, Clone, Debug)]
+#[non_exhaustive]
 pub struct Glyph {
     pub id: u32,
     pub x: f32,
     pub y: f32,
+    /// The layout's advance for this glyph (0.0 if unknown). Backends that draw runs from advances
+    /// rather than positions need it where no following glyph gives the width, e.g. a trailing tab.
+    pub advance: f32,
+}
+
+impl Glyph {
+    /// A glyph at a position, with an unknown advance
+    pub fn new(id: u32, x: f32, y: f32) -> Self {
+        Self {
+            id,
+            x,
+            y,
+            advance: 0.0,
+        }
+    }
+
+    /// Set the layout's advance for this glyph
+    pub fn with_advance(mut self, advance: f32) -> Self {
+        self.advance = advance;
+        self
+    }
+}
+
+/// How the color channels of an image relate to its alpha channel. peniko 0.4 images don't carry this, so
+/// [`draw_image`](crate::PaintScene::draw_image) treats them as straight alpha; sources that are already
+/// premultiplied are drawn with [`draw_image_with_alpha_type`](crate::PaintScene::draw_image_with_alpha_type).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ImageAlphaType {
+    /// Straight (unassociated) alpha
+    #[default]
+    Alpha,
+    /// Color channels are already multiplied by alpha
+    AlphaPremultiplied,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -10,7 +10,7 @@
 //! 1. Acquire backbuffer (DXGI) as DXGI surface / D3D11 texture
 //! 2. Create (or reuse) a Direct2D Bitmap wrapping that surface
 //! 3. BeginDraw -> replay scene commands -> EndDraw
-//! 4. Present swapchain (done by host after render_once)
+//! 4. Present swapchain (done by the host when `take_frame_result` reports a drawn frame)
 //!
 //! We map the anyrender PaintScene commands onto Direct2D primitives.
 //! (Initial version implements a subset: fill rects, strokes, images, text placeholder.)
@@ -18,19 +18,21 @@
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 
+pub use anyrender::ImageAlphaType;
 use anyrender::{Glyph, NormalizedCoord, Paint, PaintScene, WindowHandle, WindowRenderer};
 use blitz_metrics::{
-    FrameTimings, begin_init_window, end_init_window, freeze, is_frozen,
+    FrameTimings, JsonNumber, begin_init_window, end_init_window, freeze, is_frozen,
     snapshot as metrics_snapshot, unfreeze_and_reset,
 };
 use kurbo::{Affine, PathEl, Rect, Shape, Stroke};
 use peniko::color; // for color space conversions
-use peniko::{BlendMode, BrushRef, Color, Fill, Font, StyleRef};
+use peniko::{BlendMode, BrushRef, Color, Fill, Font, Mix, StyleRef};
 use rustc_hash::FxHashMap;
 use std::hash::{Hash, Hasher};
 use std::time::Instant;
 use windows::Win32::Graphics::Direct2D::Common::*;
 use windows::Win32::Graphics::Direct2D::*;
+use windows::Win32::Graphics::Direct3D::D3D_DRIVER_TYPE_WARP;
 use windows::Win32::Graphics::Direct3D11::*;
 use windows::Win32::Graphics::DirectWrite::*;
 use windows::Win32::Graphics::Dxgi::Common::*;
@@ -98,16 +100,32 @@ struct D2DScene {
 enum Command {
     PushLayer {
         rect: Rect,
+        // Below 1.0 the layer is composited offscreen and blended once at this opacity.
+        alpha: f32,
+        // CSS mask-image: the layer's content is kept in proportion to this brush's alpha (luminance masks are
+        // converted to alpha when recorded).
+        mask: Option<Box<RecordedBrush>>,
     },
     PopLayer,
     FillPath {
         path: Vec<PathEl>,
         brush: RecordedBrush,
+        fill: Fill,
     },
     StrokePath {
         path: Vec<PathEl>,
         brush: RecordedBrush,
         width: f64,
+        style: StrokeStyleKey,
+    },
+    // Text underline/line-through: a horizontal line from x0 to x1 centred on y, `width` thick. Filled as a
+    // rect snapped to device pixels at playback.
+    DecorationLine {
+        x0: f64,
+        x1: f64,
+        y: f64,
+        width: f64,
+        brush: RecordedBrush,
     },
     BoxShadow {
         rect: Rect,
@@ -124,10 +142,87 @@ enum Command {
         style: GlyphRenderStyle,
         font: FontKey,
         var_coords: Vec<NormalizedCoord>,
+        // Non-solid fill (gradient); when set, glyphs are drawn as outline geometry filled with it.
+        brush: Option<RecordedBrush>,
+        // Vertical (vertical-rl) text: the run advances down from `origin` and is drawn turned a quarter
+        // clockwise with DWRITE_GLYPH_RUN.isSideways, so glyphs stand upright on vertical metrics.
+        vertical: bool,
+        // DWRITE_GLYPH_RUN.bidiLevel. Odd levels are right-to-left: glyphs are in logical order and
+        // `origin` is the run's right edge, with each advance moving the pen left.
+        bidi_level: u8,
+    },
+    // CSS text-shadow: the run's outline filled with `color` and gaussian-blurred, origin already offset.
+    GlyphShadow {
+        glyph_indices: Vec<u16>,
+        advances: Vec<f32>,
+        origin: (f32, f32),
+        size: f32,
+        font: FontKey,
+        color: Color,
+        std_dev: f64,
+        bidi_level: u8,
+    },
+    // CSS backdrop-filter: blur(): the target's pixels under `path` are blurred in place.
+    BackdropBlur {
+        path: Vec<PathEl>,
+        std_dev: f64,
     },
 }
 
-#[derive(Clone)]
+// Live PushLayer depth beyond which playback stops pushing clips/layers. Deeply nested DOMs can
+// otherwise exceed what Direct2D handles and fail EndDraw.
+const MAX_CLIP_DEPTH: usize = 256;
+
+// Blurred shadow bitmaps kept by default (see set_shadow_cache_capacity)
+const DEFAULT_SHADOW_CACHE_CAPACITY: usize = 64;
+
+// Uploaded image bitmaps kept; the oldest are evicted first, so a long animated image doesn't pin
+// a bitmap for every one of its frames
+const IMAGE_CACHE_CAPACITY: usize = 256;
+
+// What a PushLayer command pushed during playback
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum LayerKind {
+    // Axis-aligned clip
+    Clip,
+    // ID2D1Layer carrying group opacity
+    Layer,
+    // Nothing (past MAX_CLIP_DEPTH)
+    Skipped,
+}
+
+// Caps/join/miter of a stroke; doubles as the ID2D1StrokeStyle cache key (miter limit quantized).
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+struct StrokeStyleKey {
+    start_cap: i32, // D2D1_CAP_STYLE
+    end_cap: i32,
+    join: i32,          // D2D1_LINE_JOIN
+    miter_limit_q: u32, // miter limit * 100
+}
+
+impl StrokeStyleKey {
+    fn from_stroke(stroke: &Stroke) -> Self {
+        let cap = |c: kurbo::Cap| match c {
+            kurbo::Cap::Butt => D2D1_CAP_STYLE_FLAT.0,
+            kurbo::Cap::Square => D2D1_CAP_STYLE_SQUARE.0,
+            kurbo::Cap::Round => D2D1_CAP_STYLE_ROUND.0,
+        };
+        let join = match stroke.join {
+            kurbo::Join::Bevel => D2D1_LINE_JOIN_BEVEL.0,
+            // CSS/SVG miters fall back to bevel past the miter limit
+            kurbo::Join::Miter => D2D1_LINE_JOIN_MITER_OR_BEVEL.0,
+            kurbo::Join::Round => D2D1_LINE_JOIN_ROUND.0,
+        };
+        Self {
+            start_cap: cap(stroke.start_cap),
+            end_cap: cap(stroke.end_cap),
+            join,
+            miter_limit_q: (stroke.miter_limit.max(1.0) * 100.0).round() as u32,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
 enum GlyphRenderStyle {
     Fill { color: Color },
     Stroke { color: Color, width: f32 },
@@ -140,10 +235,12 @@ enum RecordedBrush {
     Image(RecordedImage),
 }
 
-// Key identifying a font face request (initially only default Segoe UI is used until full plumbing).
+// Key identifying a font face request. `families` is the ordered candidate list from CSS font-family,
+// generic keywords included; FontConfig::candidates maps those to concrete fonts and appends the
+// default family at lookup time. The first one present wins.
 #[derive(Clone, Hash, PartialEq, Eq)]
 struct FontKey {
-    family: String,
+    families: Vec<String>,
     weight: u16, // 100-900 CSS weights
     stretch: u8, // map to DWRITE_FONT_STRETCH_* (1..=9)
     italic: bool,
@@ -152,27 +249,58 @@ struct FontKey {
 impl FontKey {
     fn default() -> Self {
         Self {
-            family: "Segoe UI".to_string(),
+            families: Vec::new(), // only the configured default family
             weight: 400,
             stretch: 5,
             italic: false,
         }
     } // stretch=5 -> normal
+
+    // Key for a CSS font-family list and numeric weight (out-of-range weights fall back to normal)
+    fn for_css(font_family: &str, font_weight: u16) -> Self {
+        let mut fk = Self::default();
+        fk.families = family_candidates(font_family);
+        fk.weight = if (100..=900).contains(&font_weight) {
+            font_weight
+        } else {
+            400
+        };
+        fk
+    }
 }
 
 #[derive(Clone)]
 struct RecordedGradient {
     kind: peniko::GradientKind,
+    extend: peniko::Extend,
     stops: Vec<(f32, Color)>,
+    // Color space the stops interpolate in (CSS `in <colorspace>`); see gradient_ramp
+    interpolation_cs: color::ColorSpaceTag,
+    hue_direction: color::HueDirection,
+    // Gradient space -> device space (shape transform * brush transform)
+    transform: Affine,
 }
 
 #[derive(Clone)]
 struct RecordedImage {
     width: u32,
     height: u32,
+    // peniko blob id of the source pixels. blitz-paint keeps it stable for each frame of a decoded
+    // image across paints, so it identifies the (image, frame) pair for the bitmap cache.
+    source: u64,
     data: Vec<u8>,
     format: peniko::ImageFormat,
+    // Straight unless drawn with draw_image_with_alpha_type (peniko images don't carry it)
+    alpha_type: ImageAlphaType,
     alpha: f32,
+    // Image-space sub-rect to draw (see image_fill_rects); None draws the whole image.
+    source_rect: Option<Rect>,
+    // Image space -> device space (shape transform * brush transform); places the tiles of repeated fills
+    transform: Affine,
+    x_extend: peniko::Extend,
+    y_extend: peniko::Extend,
+    // Scaling filter from CSS image-rendering (Low = pixelated/crisp-edges); see d2d_interpolation_mode
+    quality: peniko::ImageQuality,
 }
 
 impl D2DScene {
@@ -207,6 +335,35 @@ fn verbose_log_d2d(msg: &str) {
         debug_log_d2d(msg);
     }
 }
+// BLITZ_* environment switches, read once per process instead of on every frame/command.
+struct EnvFlags {
+    verbose: bool,
+    disable_shadows: bool,
+    disable_overlay: bool,
+    gdi_small_text: bool,
+    // integer | half | auto
+    baseline_snap: String,
+}
+
+fn env_flags() -> &'static EnvFlags {
+    static FLAGS: std::sync::OnceLock<EnvFlags> = std::sync::OnceLock::new();
+    FLAGS.get_or_init(|| {
+        let on = |name: &str| {
+            std::env::var(name)
+                .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false)
+        };
+        EnvFlags {
+            verbose: on("BLITZ_VERBOSE"),
+            disable_shadows: on("BLITZ_DISABLE_SHADOWS"),
+            disable_overlay: on("BLITZ_DISABLE_OVERLAY"),
+            gdi_small_text: on("BLITZ_EXPERIMENT_GDI_SMALL_TEXT"),
+            baseline_snap: std::env::var("BLITZ_TEXT_BASELINE_SNAP")
+                .unwrap_or_else(|_| "auto".to_string()),
+        }
+    })
+}
+
 // Lightweight macro to avoid repeating VERBOSE_LOG.load boilerplate while preserving
 // ability to skip formatting cost when verbose logging is off.
 macro_rules! vlog { ($($t:tt)*) => { if VERBOSE_LOG.load(Ordering::Relaxed) { debug_log_d2d(&format!($($t)*)); } } }
@@ -218,7 +375,7 @@ impl<'a> PaintScene for D2DScenePainter<'a> {
     fn push_layer(
         &mut self,
         _blend: impl Into<BlendMode>,
-        _alpha: f32,
+        alpha: f32,
         transform: Affine,
         clip: &impl Shape,
     ) {
@@ -229,7 +386,30 @@ impl<'a> PaintScene for D2DScenePainter<'a> {
             if t[0] == 1.0 && t[1] == 0.0 && t[2] == 0.0 && t[3] == 1.0 {
                 rect = rect + kurbo::Vec2::new(t[4], t[5]);
             }
-            self.scene.commands.push(Command::PushLayer { rect });
+            self.scene.commands.push(Command::PushLayer {
+                rect,
+                alpha: alpha.clamp(0.0, 1.0),
+                mask: None,
+            });
+        }
+    }
+    fn push_mask_layer<'b>(
+        &mut self,
+        transform: Affine,
+        clip: &impl Shape,
+        mask: impl Into<BrushRef<'b>>,
+        mask_transform: Option<Affine>,
+        luminance: bool,
+    ) {
+        let mut mask = record_brush(mask.into());
+        set_brush_transform(&mut mask, transform, mask_transform);
+        if luminance {
+            luminance_to_alpha(&mut mask);
+        }
+        let len = self.scene.commands.len();
+        self.push_layer(Mix::Clip, 1.0, transform, clip);
+        if let Some(Command::PushLayer { mask: slot, .. }) = self.scene.commands.get_mut(len) {
+            *slot = Some(Box::new(mask));
         }
     }
     fn pop_layer(&mut self) {
@@ -240,10 +420,11 @@ impl<'a> PaintScene for D2DScenePainter<'a> {
         style: &Stroke,
         transform: Affine,
         brush: impl Into<BrushRef<'b>>,
-        _brush_transform: Option<Affine>,
+        brush_transform: Option<Affine>,
         shape: &impl Shape,
     ) {
-        let brush_rec = record_brush(brush.into());
+        let mut brush_rec = record_brush(brush.into());
+        set_brush_transform(&mut brush_rec, transform, brush_transform);
         // Removed rect fast path so rounded rectangles (and other shapes) retain corner geometry.
         // Fallback: record full path with translation baked in (ignore non-translation components for now).
         let mut v = Vec::new();
@@ -274,24 +455,39 @@ impl<'a> PaintScene for D2DScenePainter<'a> {
                 }
             }
         }
+        // Scale the width by the transform's uniform scale factor so strokes grow with their geometry.
+        let scale = (a * d - b * c).abs().sqrt();
         self.scene.commands.push(Command::StrokePath {
             path: v,
             brush: brush_rec,
-            width: style.width,
+            width: style.width * scale,
+            style: StrokeStyleKey::from_stroke(style),
         });
     }
     fn fill<'b>(
         &mut self,
-        _style: Fill,
+        style: Fill,
         transform: Affine,
         brush: impl Into<anyrender::Paint<'b>>,
-        _brush_transform: Option<Affine>,
+        brush_transform: Option<Affine>,
         shape: &impl Shape,
     ) {
-        let brush_rec = record_paint(brush.into());
+        let mut brush_rec = record_paint(brush.into());
+        set_brush_transform(&mut brush_rec, transform, brush_transform);
         // Removed rect fast path to allow rounded rect path elements to be recorded.
         let mut v = Vec::new();
-        shape_to_path_elements(shape, &mut v);
+        match (&mut brush_rec, brush_transform, shape.as_rect()) {
+            // Positioned image (e.g. object-fit) in a rect: draw only the part of the image under it.
+            // Other shapes keep their path and are filled with a bitmap brush placed by the transform.
+            (RecordedBrush::Image(img), Some(bt), Some(rect)) => {
+                let Some((source, dest)) = image_fill_rects(bt, rect, img.width, img.height) else {
+                    return;
+                };
+                img.source_rect = Some(source);
+                shape_to_path_elements(&dest, &mut v);
+            }
+            _ => shape_to_path_elements(shape, &mut v),
+        }
         let t = transform.as_coeffs();
         let (a, b, c, d, e, f_) = (t[0], t[1], t[2], t[3], t[4], t[5]);
         if !(a == 1.0 && b == 0.0 && c == 0.0 && d == 1.0 && e == 0.0 && f_ == 0.0) {
@@ -320,6 +516,7 @@ impl<'a> PaintScene for D2DScenePainter<'a> {
         self.scene.commands.push(Command::FillPath {
             path: v,
             brush: brush_rec,
+            fill: style,
         });
         if self.scene.commands.len() == 1 {
             vlog!("first command recorded (FillPath)");
@@ -341,9 +538,20 @@ impl<'a> PaintScene for D2DScenePainter<'a> {
         glyphs: impl Iterator<Item = Glyph>,
     ) {
         let style_ref: StyleRef<'b> = style.into();
-        let brush_color = match brush.into() {
-            BrushRef::Solid(c) => c.with_alpha(c.components[3] * brush_alpha),
-            _ => Color::BLACK,
+        let (brush_color, glyph_brush) = match brush.into() {
+            BrushRef::Solid(c) => (c.with_alpha(c.components[3] * brush_alpha), None),
+            gradient @ BrushRef::Gradient(_) => {
+                let mut rec = record_brush(gradient);
+                set_brush_transform(&mut rec, transform, None);
+                if let RecordedBrush::Gradient(g) = &mut rec {
+                    for (_, c) in &mut g.stops {
+                        *c = c.multiply_alpha(brush_alpha);
+                    }
+                }
+                (Color::BLACK, Some(rec))
+            }
+            // Image brushes are not supported as D2D brushes here; fall back to solid black.
+            BrushRef::Image(_) => (Color::BLACK, None),
         };
         let glyph_style = match style_ref {
             StyleRef::Fill(_) => GlyphRenderStyle::Fill { color: brush_color },
@@ -357,60 +565,100 @@ impl<'a> PaintScene for D2DScenePainter<'a> {
         if collected.is_empty() {
             return;
         }
-        // Single run: upstream stroke_text already iterates lines; we no longer split heuristically here.
-        let origin_x = collected.first().unwrap().x as f32 + transform.as_coeffs()[4] as f32; // e (translation x)
-        let origin_y = collected.first().unwrap().y as f32 + transform.as_coeffs()[5] as f32; // f (translation y)
-        let mut glyph_indices: Vec<u16> = Vec::with_capacity(collected.len());
-        let mut advances: Vec<f32> = Vec::with_capacity(collected.len());
-        for (i, g) in collected.iter().enumerate() {
-            glyph_indices.push(g.id as u16);
-            if i + 1 < collected.len() {
-                let mut adv = (collected[i + 1].x - g.x) as f32;
-                if adv < 0.0 {
-                    adv = 0.0;
-                }
-                let max_reasonable = font_size * 2.0;
-                if adv > max_reasonable {
-                    adv = font_size * 0.6;
-                }
-                advances.push(adv);
-            }
+        let fk = FontKey::for_css(font_family, font_weight);
+        let vertical = is_quarter_turn(transform);
+        // A batched run may span several lines; give each baseline its own run and origin.
+        for line in split_glyph_lines(&collected, font_size) {
+            let advances = glyph_advances(line, font_size);
+            let bidi_level = u8::from(!vertical && is_rtl_line(line));
+            let (origin_x, origin_y) = if vertical {
+                // Vertical glyphs hang from their vertical origin (top centre) on the line's central
+                // axis rather than sitting on the alphabetic baseline; shift by half of typical CJK
+                // ascent minus descent (0.88em / 0.12em) toward the line's top.
+                let origin = transform
+                    * kurbo::Point::new(line[0].x as f64, (line[0].y - font_size * 0.38) as f64);
+                (origin.x as f32, origin.y as f32)
+            } else {
+                (
+                    run_origin_x(line, &advances) + transform.as_coeffs()[4] as f32, // e (translation x)
+                    line[0].y as f32 + transform.as_coeffs()[5] as f32, // f (translation y)
+                )
+            };
+            let glyph_indices: Vec<u16> = line.iter().map(|g| g.id as u16).collect();
+            self.scene.commands.push(Command::GlyphRun {
+                glyph_indices,
+                advances,
+                origin: (origin_x, origin_y),
+                size: font_size,
+                style: glyph_style.clone(),
+                font: fk.clone(),
+                var_coords: Vec::new(),
+                brush: glyph_brush.clone(),
+                vertical,
+                bidi_level,
+            });
         }
-        let last_adv = if advances.is_empty() {
-            font_size * 0.6
-        } else {
-            (advances.iter().copied().sum::<f32>() / advances.len() as f32).max(1.0)
-        };
-        advances.push(last_adv);
-        let mut fk = FontKey::default();
-        // Map CSS generic families to concrete Windows fonts.
-        let lower = font_family.to_ascii_lowercase();
-        let resolved_family = match lower.as_str() {
-            "monospace" => "Consolas", // or Cascadia Mono if desired
-            "serif" => "Times New Roman",
-            "sans-serif" => "Segoe UI",
-            "system-ui" => "Segoe UI",
-            "cursive" => "Comic Sans MS",
-            "fantasy" => "Segoe UI", // placeholder
-            fam if fam.is_empty() => "Segoe UI",
-            other => other,
-        };
-        fk.family = resolved_family.to_string();
-        fk.weight = if (100..=900).contains(&font_weight) {
-            font_weight
-        } else {
-            400
-        } as u16;
-        self.scene.commands.push(Command::GlyphRun {
-            glyph_indices,
-            advances,
-            origin: (origin_x, origin_y),
-            size: font_size,
-            style: glyph_style,
-            font: fk,
-            var_coords: Vec::new(),
+    }
+    fn draw_text_decoration<'b>(
+        &mut self,
+        transform: Affine,
+        brush: impl Into<BrushRef<'b>>,
+        line: kurbo::Line,
+        thickness: f64,
+    ) {
+        let [_, b, c, d, _, _] = transform.as_coeffs();
+        // Rotated or skewed text can't be snapped to the pixel grid; stroke it like any other line.
+        if b != 0.0 || c != 0.0 || line.p0.y != line.p1.y {
+            let stroke = Stroke::new(thickness).with_caps(kurbo::Cap::Butt);
+            self.stroke(&stroke, transform, brush, None, &line);
+            return;
+        }
+        let (p0, p1) = (transform * line.p0, transform * line.p1);
+        let mut brush_rec = record_brush(brush.into());
+        set_brush_transform(&mut brush_rec, transform, None);
+        self.scene.commands.push(Command::DecorationLine {
+            x0: p0.x.min(p1.x),
+            x1: p0.x.max(p1.x),
+            y: p0.y,
+            width: thickness * d.abs(),
+            brush: brush_rec,
         });
     }
+    fn draw_glyph_shadow<'b, 's: 'b>(
+        &'s mut self,
+        _font: &'b Font,
+        font_family: &str,
+        font_size: f32,
+        font_weight: u16,
+        _norm: &'b [NormalizedCoord],
+        color: Color,
+        std_dev: f64,
+        transform: Affine,
+        _glyph_transform: Option<Affine>,
+        glyphs: impl Iterator<Item = Glyph>,
+    ) {
+        let collected: Vec<Glyph> = glyphs.collect();
+        // Shadows of vertical runs aren't supported yet; drawing them horizontally would misplace them
+        if collected.is_empty() || color.components[3] <= 0.0 || is_quarter_turn(transform) {
+            return;
+        }
+        let fk = FontKey::for_css(font_family, font_weight);
+        for line in split_glyph_lines(&collected, font_size) {
+            let advances = glyph_advances(line, font_size);
+            let origin_x = run_origin_x(line, &advances) + transform.as_coeffs()[4] as f32;
+            let origin_y = line[0].y + transform.as_coeffs()[5] as f32;
+            self.scene.commands.push(Command::GlyphShadow {
+                glyph_indices: line.iter().map(|g| g.id as u16).collect(),
+                advances,
+                origin: (origin_x, origin_y),
+                size: font_size,
+                font: fk.clone(),
+                color,
+                std_dev: std_dev.max(0.0),
+                bidi_level: u8::from(is_rtl_line(line)),
+            });
+        }
+    }
     fn draw_box_shadow(
         &mut self,
         transform: Affine,
@@ -424,16 +672,62 @@ impl<'a> PaintScene for D2DScenePainter<'a> {
         let tx = coeffs[4];
         let ty = coeffs[5];
         let translated = rect + kurbo::Vec2::new(tx, ty);
-        let inset = std_dev < 0.0;
-        let std_dev = std_dev.abs();
         self.scene.commands.push(Command::BoxShadow {
             rect: translated,
             color: brush,
             radius,
             std_dev,
-            inset,
+            inset: false,
+        });
+    }
+    fn draw_inset_box_shadow(
+        &mut self,
+        transform: Affine,
+        rect: Rect,
+        brush: Color,
+        radius: f64,
+        std_dev: f64,
+    ) {
+        // Translation only, as for draw_box_shadow
+        let coeffs = transform.as_coeffs();
+        let translated = rect + kurbo::Vec2::new(coeffs[4], coeffs[5]);
+        self.scene.commands.push(Command::BoxShadow {
+            rect: translated,
+            color: brush,
+            radius,
+            std_dev,
+            inset: true,
         });
     }
+    // Straight alpha sources are premultiplied on upload, premultiplied ones uploaded as-is
+    fn draw_image_with_alpha_type(
+        &mut self,
+        image: &peniko::Image,
+        transform: Affine,
+        alpha_type: ImageAlphaType,
+    ) {
+        self.draw_image(image, transform);
+        if let Some(Command::FillPath {
+            brush: RecordedBrush::Image(img),
+            ..
+        }) = self.scene.commands.last_mut()
+        {
+            img.alpha_type = alpha_type;
+        }
+    }
+    fn draw_backdrop_blur(&mut self, transform: Affine, shape: &impl Shape, std_dev: f64) {
+        if std_dev <= 0.0 {
+            return;
+        }
+        let mut path = Vec::new();
+        shape_to_path_elements(shape, &mut path);
+        for el in &mut path {
+            *el = transform * *el;
+        }
+        self.scene
+            .commands
+            .push(Command::BackdropBlur { path, std_dev });
+    }
 }
 
 fn shape_as_rect(shape: &impl Shape) -> Option<Rect> {
@@ -448,167 +742,1889 @@ fn shape_to_path_elements(shape: &impl Shape, out: &mut Vec<PathEl>) {
     }
 }
 
-fn record_brush(b: BrushRef<'_>) -> RecordedBrush {
-    match b {
-        BrushRef::Solid(c) => RecordedBrush::Solid(c),
-        BrushRef::Gradient(g) => RecordedBrush::Gradient(RecordedGradient {
-            kind: g.kind,
-            stops: g
-                .stops
-                .iter()
-                .map(|s| (s.offset, s.color.to_alpha_color::<color::Srgb>()))
-                .collect(),
-        }),
-        BrushRef::Image(img) => RecordedBrush::Image(RecordedImage {
-            width: img.width,
-            height: img.height,
-            data: img.data.as_ref().to_vec(),
-            format: img.format,
-            alpha: img.alpha,
-        }),
+// Split out subpaths whose points all coincide (zero-length). Returns the remaining path and the
+// location of each degenerate subpath that had at least one segment.
+fn split_degenerate_subpaths(path: &[PathEl]) -> (Vec<PathEl>, Vec<kurbo::Point>) {
+    let mut kept = Vec::with_capacity(path.len());
+    let mut dots = Vec::new();
+    let mut i = 0;
+    while i < path.len() {
+        let mut end = i + 1;
+        while end < path.len() && !matches!(path[end], PathEl::MoveTo(_)) {
+            end += 1;
+        }
+        let sub = &path[i..end];
+        let degenerate = match sub[0] {
+            PathEl::MoveTo(start) if sub.len() > 1 => sub[1..].iter().all(|el| match el {
+                PathEl::LineTo(p) => *p == start,
+                PathEl::QuadTo(p1, p2) => *p1 == start && *p2 == start,
+                PathEl::CurveTo(p1, p2, p3) => *p1 == start && *p2 == start && *p3 == start,
+                PathEl::ClosePath => true,
+                PathEl::MoveTo(_) => false,
+            }),
+            _ => false,
+        };
+        if degenerate {
+            if let PathEl::MoveTo(start) = sub[0] {
+                dots.push(start);
+            }
+        } else {
+            kept.extend_from_slice(sub);
+        }
+        i = end;
     }
+    (kept, dots)
 }
-fn record_paint(p: Paint<'_>) -> RecordedBrush {
-    match p {
-        Paint::Solid(c) => RecordedBrush::Solid(c),
-        Paint::Gradient(g) => RecordedBrush::Gradient(RecordedGradient {
-            kind: g.kind,
-            stops: g
-                .stops
-                .iter()
-                .map(|s| (s.offset, s.color.to_alpha_color::<color::Srgb>()))
-                .collect(),
-        }),
-        Paint::Image(img) => RecordedBrush::Image(RecordedImage {
-            width: img.width,
-            height: img.height,
-            data: img.data.as_ref().to_vec(),
-            format: img.format,
-            alpha: img.alpha,
-        }),
-        Paint::Custom(_) => RecordedBrush::Solid(Color::BLACK),
+
+// Gradient coordinates are in the shape's local space while recorded paths are already in device
+// space, so keep the combined transform to position the brush at playback.
+fn set_brush_transform(
+    rec: &mut RecordedBrush,
+    transform: Affine,
+    brush_transform: Option<Affine>,
+) {
+    match rec {
+        RecordedBrush::Gradient(g) => {
+            g.transform = transform * brush_transform.unwrap_or(Affine::IDENTITY)
+        }
+        RecordedBrush::Image(img) => {
+            img.transform = transform * brush_transform.unwrap_or(Affine::IDENTITY)
+        }
+        RecordedBrush::Solid(_) => {}
     }
 }
 
-/// Direct2D renderer bound to an existing DXGI swapchain (composition target).
-pub struct D2DWindowRenderer {
-    swapchain: Option<IDXGISwapChain1>,
-    d3d_device: Option<ID3D11Device>,
-    d2d_factory: Option<ID2D1Factory1>,
-    d2d_device: Option<ID2D1Device>,
-    d2d_ctx: Option<ID2D1DeviceContext>,
-    dwrite_factory: Option<IDWriteFactory>,
-    dwrite_font_face: Option<IDWriteFontFace>,
-    dwrite_text_format: Option<IDWriteTextFormat>,
-    font_face_cache: FxHashMap<FontKey, IDWriteFontFace>,
-    // caches
-    gradient_cache: FxHashMap<u64, ID2D1Brush>,
-    image_cache: FxHashMap<u64, ID2D1Bitmap>,
-    // shadow blur cache (bitmap of blurred rounded rect); separate from image_cache to control eviction separately
-    shadow_cache: FxHashMap<ShadowKey, ID2D1Bitmap1>,
-    shadow_cache_order: std::collections::VecDeque<ShadowKey>,
-    gaussian_blur_effect: Option<ID2D1Effect>,
-    scene: D2DScene,
+// For an image fill with a brush transform (image space -> shape space): the image-space rect visible
+// through `shape_bounds`, and where it lands in shape space. None when the shape misses the image.
+fn image_fill_rects(
+    brush_transform: Affine,
+    shape_bounds: Rect,
     width: u32,
     height: u32,
-    active: bool,
-    debug_shadow_logs: u32,
-    last_command_count: u32,
-    backbuffer_bitmap: Option<ID2D1Bitmap1>,
-    // --- instrumentation ---
-    init_start: Instant,
-    first_frame_done: bool,
-    first_frame_ms: f32,
-    device_init_ms: f32,
-    backbuffer_create_ms: f32,
-    playback_ms: f32,
-    host_init_ms: f32, // D3D device + swapchain creation (host side)
-    // host init sub-phases
-    host_dxgi_d3d_ms: f32,
-    host_swapchain_ms: f32,
-    host_panel_attach_queue_ms: f32,
-    host_panel_attach_exec_ms: f32,
-    host_panel_attach_sub_ui_add_ms: f32,
-    host_panel_attach_sub_set_swapchain_ms: f32,
-    host_first_text_init_ms: f32,
-    frame_start: Instant,
-    fps_accum_time: f32,
-    fps_frame_count: u32,
-    fps: f32,
-    last_frame_metrics: FrameTimings,
-    // Diagnostic: draw colored quadrants when true and no scene commands (placeholder visibility test)
-    test_pattern: bool,
-    show_debug_overlay: bool,
+) -> Option<(Rect, Rect)> {
+    if brush_transform.determinant().abs() < 1e-12 {
+        return None;
+    }
+    let source = brush_transform
+        .inverse()
+        .transform_rect_bbox(shape_bounds)
+        .intersect(Rect::new(0.0, 0.0, width as f64, height as f64));
+    if source.width() <= 0.0 || source.height() <= 0.0 {
+        return None;
+    }
+    Some((source, brush_transform.transform_rect_bbox(source)))
 }
 
-impl D2DWindowRenderer {
-    pub fn new() -> Self {
-        let init_start = Instant::now();
-        begin_init_window(init_start);
-        Self {
-            swapchain: None,
-            d3d_device: None,
-            d2d_factory: None,
-            d2d_device: None,
-            d2d_ctx: None,
-            dwrite_factory: None,
-            dwrite_font_face: None,
-            dwrite_text_format: None,
-            font_face_cache: FxHashMap::default(),
-            gradient_cache: FxHashMap::default(),
-            image_cache: FxHashMap::default(),
-            shadow_cache: FxHashMap::default(),
-            shadow_cache_order: std::collections::VecDeque::new(),
-            gaussian_blur_effect: None,
-            scene: D2DScene::default(),
-            width: 1,
-            height: 1,
-            active: false,
-            debug_shadow_logs: 0,
-            last_command_count: 0,
-            backbuffer_bitmap: None,
-            init_start,
-            first_frame_done: false,
-            first_frame_ms: 0.0,
-            device_init_ms: 0.0,
-            backbuffer_create_ms: 0.0,
-            playback_ms: 0.0,
-            host_init_ms: 0.0,
-            host_dxgi_d3d_ms: 0.0,
-            host_swapchain_ms: 0.0,
-            host_panel_attach_queue_ms: 0.0,
-            host_panel_attach_exec_ms: 0.0,
-            host_panel_attach_sub_ui_add_ms: 0.0,
-            host_panel_attach_sub_set_swapchain_ms: 0.0,
-            host_first_text_init_ms: 0.0,
-            frame_start: Instant::now(),
-            fps_accum_time: 0.0,
-            fps_frame_count: 0,
+// Luminance mask -> alpha mask: alpha becomes luminance * alpha (Rec. 709 weights on the sRGB components, as in
+// CSS Masking). Image masks keep their alpha.
+fn luminance_to_alpha(rec: &mut RecordedBrush) {
+    let to_alpha = |c: Color| {
+        let [r, g, b, a] = c.components;
+        Color::new([0.0, 0.0, 0.0, (0.2126 * r + 0.7152 * g + 0.0722 * b) * a])
+    };
+    match rec {
+        RecordedBrush::Solid(c) => *c = to_alpha(*c),
+        RecordedBrush::Gradient(g) => g.stops.iter_mut().for_each(|(_, c)| *c = to_alpha(*c)),
+        RecordedBrush::Image(_) => {}
+    }
+}
+
+// Whether an image fill covers exactly one copy of the image, so it can be drawn with DrawBitmap into
+// the path bounds instead of a tiling bitmap brush (e.g. repeated backgrounds cover many copies).
+fn fills_single_image(path: &[PathEl], img: &RecordedImage) -> bool {
+    if img.source_rect.is_some() {
+        return true;
+    }
+    let Some(bounds) = path_bounds(path).filter(|_| is_axis_aligned_rect(path)) else {
+        return false;
+    };
+    let c = img.transform.as_coeffs();
+    if c[1] != 0.0 || c[2] != 0.0 {
+        return false;
+    }
+    let footprint =
+        img.transform
+            .transform_rect_bbox(Rect::new(0.0, 0.0, img.width as f64, img.height as f64));
+    let near = |a: f64, b: f64| (a - b).abs() < 0.5;
+    near(bounds.x0, footprint.x0)
+        && near(bounds.y0, footprint.y0)
+        && near(bounds.x1, footprint.x1)
+        && near(bounds.y1, footprint.y1)
+}
+
+// Colors handed to D2D (brushes, gradient stops, Clear) are straight alpha even though every target is
+// D2D1_ALPHA_MODE_PREMULTIPLIED: D2D premultiplies them itself, so premultiplying here would darken
+// translucent fills twice. Only raw pixel data (bitmap uploads, CPU-rasterized gradients) is premultiplied.
+fn d2d_color(color: Color) -> D2D1_COLOR_F {
+    let [r, g, b, a] = color.components;
+    D2D1_COLOR_F { r, g, b, a }
+}
+
+fn factory_type(options: RendererOptions) -> D2D1_FACTORY_TYPE {
+    if options.multi_threaded {
+        D2D1_FACTORY_TYPE_MULTI_THREADED
+    } else {
+        D2D1_FACTORY_TYPE_SINGLE_THREADED
+    }
+}
+
+fn d2d_extend_mode(extend: peniko::Extend) -> D2D1_EXTEND_MODE {
+    match extend {
+        peniko::Extend::Pad => D2D1_EXTEND_MODE_CLAMP,
+        peniko::Extend::Repeat => D2D1_EXTEND_MODE_WRAP,
+        peniko::Extend::Reflect => D2D1_EXTEND_MODE_MIRROR,
+    }
+}
+
+// Image scaling filter: nearest-neighbor for image-rendering: pixelated/crisp-edges (peniko's Low
+// quality) so upscaled pixel art stays sharp, bilinear otherwise
+fn d2d_interpolation_mode(quality: peniko::ImageQuality) -> D2D1_INTERPOLATION_MODE {
+    match quality {
+        peniko::ImageQuality::Low => D2D1_INTERPOLATION_MODE_NEAREST_NEIGHBOR,
+        peniko::ImageQuality::Medium | peniko::ImageQuality::High => D2D1_INTERPOLATION_MODE_LINEAR,
+    }
+}
+
+// Bitmap brushes only take the legacy two-value interpolation enum
+fn d2d_bitmap_interpolation_mode(quality: peniko::ImageQuality) -> D2D1_BITMAP_INTERPOLATION_MODE {
+    match quality {
+        peniko::ImageQuality::Low => D2D1_BITMAP_INTERPOLATION_MODE_NEAREST_NEIGHBOR,
+        peniko::ImageQuality::Medium | peniko::ImageQuality::High => {
+            D2D1_BITMAP_INTERPOLATION_MODE_LINEAR
+        }
+    }
+}
+
+// Conservative device-space bounds of a path (includes curve control points).
+fn path_bounds(path: &[PathEl]) -> Option<Rect> {
+    let mut bounds: Option<Rect> = None;
+    let mut add = |p: &kurbo::Point| {
+        bounds = Some(match bounds {
+            Some(b) => b.union_pt(*p),
+            None => Rect::from_points(*p, *p),
+        });
+    };
+    for el in path {
+        match el {
+            PathEl::MoveTo(p) | PathEl::LineTo(p) => add(p),
+            PathEl::QuadTo(p1, p2) => {
+                add(p1);
+                add(p2);
+            }
+            PathEl::CurveTo(p1, p2, p3) => {
+                add(p1);
+                add(p2);
+                add(p3);
+            }
+            PathEl::ClosePath => {}
+        }
+    }
+    bounds
+}
+
+// Conservative scene-space extent of what a command can paint, for viewport culling. None for layer
+// commands and anything whose reach isn't bounded by its geometry (inset shadows fill their clip).
+fn command_bounds(cmd: &Command) -> Option<Rect> {
+    // Glyph ink can overhang the advance box (italics, accents); pad by the em size.
+    let run_bounds = |advances: &[f32], origin: (f32, f32), size: f32, bidi_level: u8| {
+        let (x0, x1) = run_x_extent(advances, origin.0, bidi_level);
+        let (y, size) = (origin.1 as f64, size as f64);
+        Rect::new(x0 as f64 - size, y - size * 1.5, x1 as f64 + size, y + size)
+    };
+    match cmd {
+        Command::PushLayer { .. } | Command::PopLayer => None,
+        Command::FillPath { path, .. } => path_bounds(path),
+        Command::StrokePath {
+            path, width, style, ..
+        } => {
+            // Miter joins reach up to miter_limit * width / 2; square caps sqrt(2) * width / 2
+            let miter = (style.miter_limit_q as f64 / 100.0).max(std::f64::consts::SQRT_2);
+            let reach = width * 0.5 * miter;
+            path_bounds(path).map(|b| b.inflate(reach, reach))
+        }
+        // Snapping moves the line by at most a pixel
+        Command::DecorationLine {
+            x0, x1, y, width, ..
+        } => Some(Rect::new(*x0, y - width / 2.0 - 1.0, *x1, y + width / 2.0 + 1.0)),
+        Command::BoxShadow { inset: true, .. } => None,
+        Command::BoxShadow { rect, std_dev, .. } => {
+            Some(rect.inflate(std_dev * 3.0 + 1.0, std_dev * 3.0 + 1.0))
+        }
+        Command::GlyphRun {
+            advances,
+            origin,
+            size,
+            vertical: true,
+            ..
+        } => {
+            let run_h: f32 = advances.iter().sum();
+            let (x, y, size) = (origin.0 as f64, origin.1 as f64, *size as f64);
+            Some(Rect::new(
+                x - size,
+                y - size,
+                x + size,
+                y + run_h as f64 + size,
+            ))
+        }
+        Command::GlyphRun {
+            advances,
+            origin,
+            size,
+            bidi_level,
+            ..
+        } => Some(run_bounds(advances, *origin, *size, *bidi_level)),
+        Command::GlyphShadow {
+            advances,
+            origin,
+            size,
+            std_dev,
+            bidi_level,
+            ..
+        } => Some(
+            run_bounds(advances, *origin, *size, *bidi_level)
+                .inflate(std_dev * 3.0 + 1.0, std_dev * 3.0 + 1.0),
+        ),
+        Command::BackdropBlur { path, .. } => path_bounds(path),
+    }
+}
+
+// Whether `cmd` paints nothing inside `visible` (the viewport narrowed by the repaint and layer clips)
+fn is_culled(cmd: &Command, visible: Rect) -> bool {
+    command_bounds(cmd).is_some_and(|b| {
+        b.x1 <= visible.x0 || b.x0 >= visible.x1 || b.y1 <= visible.y0 || b.y0 >= visible.y1
+    })
+}
+
+// Insert into an insertion-ordered cache of at most `capacity` entries, evicting the oldest to make room.
+// Existing keys are left as they are.
+fn insert_bounded<K: Hash + Eq + Copy, V>(
+    map: &mut FxHashMap<K, V>,
+    order: &mut std::collections::VecDeque<K>,
+    capacity: usize,
+    key: K,
+    value: V,
+) {
+    if capacity == 0 || map.contains_key(&key) {
+        return;
+    }
+    evict_to_capacity(map, order, capacity - 1);
+    order.push_back(key);
+    map.insert(key, value);
+}
+
+// Drop the oldest entries of an insertion-ordered cache until it holds at most `capacity`
+fn evict_to_capacity<K: Hash + Eq, V>(
+    map: &mut FxHashMap<K, V>,
+    order: &mut std::collections::VecDeque<K>,
+    capacity: usize,
+) {
+    while order.len() > capacity {
+        if let Some(old) = order.pop_front() {
+            map.remove(&old);
+        }
+    }
+}
+
+fn affine_to_matrix(t: Affine) -> windows::Foundation::Numerics::Matrix3x2 {
+    let c = t.as_coeffs();
+    windows::Foundation::Numerics::Matrix3x2 {
+        M11: c[0] as f32,
+        M12: c[1] as f32,
+        M21: c[2] as f32,
+        M22: c[3] as f32,
+        M31: c[4] as f32,
+        M32: c[5] as f32,
+    }
+}
+
+// Sample a gradient ramp at `t` (straight alpha, linear interpolation between stops).
+fn sample_stops(stops: &[(f32, Color)], t: f32) -> [f32; 4] {
+    let Some(first) = stops.first() else {
+        return [0.0; 4];
+    };
+    if t <= first.0 {
+        return first.1.components;
+    }
+    for pair in stops.windows(2) {
+        let (o0, c0) = pair[0];
+        let (o1, c1) = pair[1];
+        if t <= o1 {
+            let span = o1 - o0;
+            let f = if span > 0.0 { (t - o0) / span } else { 1.0 };
+            let mut out = [0.0; 4];
+            for i in 0..4 {
+                out[i] = c0.components[i] + (c1.components[i] - c0.components[i]) * f;
+            }
+            return out;
+        }
+    }
+    stops.last().unwrap().1.components
+}
+
+// Stops per segment when a gradient interpolating in a space Direct2D can't express is flattened to sRGB stops.
+const GRADIENT_RAMP_STEPS: usize = 8;
+
+/// How a recorded gradient's stops are handed to Direct2D, which only interpolates in sRGB or linear
+/// (scRGB) light.
+#[derive(Debug)]
+enum GradientRamp {
+    /// Stops as recorded, interpolated in sRGB.
+    Srgb(Vec<(f32, Color)>),
+    /// Stops converted to linear sRGB, for a collection whose pre-interpolation space is scRGB.
+    Linear(Vec<(f32, [f32; 4])>),
+}
+
+fn gradient_ramp(
+    stops: &[(f32, Color)],
+    cs: color::ColorSpaceTag,
+    hue: color::HueDirection,
+) -> GradientRamp {
+    match cs {
+        color::ColorSpaceTag::LinearSrgb => GradientRamp::Linear(
+            stops
+                .iter()
+                .map(|(o, c)| (*o, c.convert::<color::LinearSrgb>().components))
+                .collect(),
+        ),
+        _ => GradientRamp::Srgb(flatten_stops(stops, cs, hue)),
+    }
+}
+
+// sRGB stops approximating interpolation in `cs`: every segment is subdivided and the intermediate stops are
+// evaluated in that space, so plain sRGB interpolation only bridges short, near-linear steps.
+fn flatten_stops(
+    stops: &[(f32, Color)],
+    cs: color::ColorSpaceTag,
+    hue: color::HueDirection,
+) -> Vec<(f32, Color)> {
+    match cs {
+        color::ColorSpaceTag::Srgb => stops.to_vec(),
+        _ => {
+            let mut out = Vec::with_capacity(stops.len() * GRADIENT_RAMP_STEPS);
+            for pair in stops.windows(2) {
+                let (o0, c0) = pair[0];
+                let (o1, c1) = pair[1];
+                let interp = color::DynamicColor::from_alpha_color(c0).interpolate(
+                    color::DynamicColor::from_alpha_color(c1),
+                    cs,
+                    hue,
+                );
+                for i in 0..GRADIENT_RAMP_STEPS {
+                    let f = i as f32 / GRADIENT_RAMP_STEPS as f32;
+                    out.push((
+                        o0 + (o1 - o0) * f,
+                        interp.eval(f).to_alpha_color::<color::Srgb>(),
+                    ));
+                }
+            }
+            if let Some(last) = stops.last() {
+                out.push(*last);
+            }
+            out
+        }
+    }
+}
+
+// Alpha convention of a source image buffer. peniko 0.4's ImageFormat only has Rgba8, so there is no
+// BGRA (or other) layout to convert from; formats a later peniko adds map to None and are skipped
+// rather than uploaded as RGBA.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum SourcePixels {
+    Rgba8,
+    PremulRgba8,
+}
+
+// Bitmap cache key: the source blob id and how its pixels are converted on upload
+type ImageKey = (u64, SourcePixels);
+
+impl SourcePixels {
+    fn from_format(format: peniko::ImageFormat, alpha_type: ImageAlphaType) -> Option<Self> {
+        match (format, alpha_type) {
+            (peniko::ImageFormat::Rgba8, ImageAlphaType::Alpha) => Some(Self::Rgba8),
+            (peniko::ImageFormat::Rgba8, ImageAlphaType::AlphaPremultiplied) => {
+                Some(Self::PremulRgba8)
+            }
+            _ => None,
+        }
+    }
+}
+
+// Copy a tightly packed RGBA8 buffer as premultiplied RGBA8. None when the buffer is empty or
+// shorter than width * height * 4.
+fn to_premultiplied_rgba(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    layout: SourcePixels,
+) -> Option<Vec<u8>> {
+    let len = width as usize * height as usize * 4;
+    if len == 0 || data.len() < len {
+        return None;
+    }
+    let premultiplied = layout == SourcePixels::PremulRgba8;
+    let mut out = data[..len].to_vec();
+    for px in out.chunks_exact_mut(4) {
+        let a = px[3] as u16; // 0..255
+        if !premultiplied && a < 255 {
+            px[0] = ((px[0] as u16 * a + 127) / 255) as u8;
+            px[1] = ((px[1] as u16 * a + 127) / 255) as u8;
+            px[2] = ((px[2] as u16 * a + 127) / 255) as u8;
+        }
+    }
+    Some(out)
+}
+
+// DirectWrite advances for one line of positioned glyphs. The layout already folds kerning, letter-/
+// word-spacing, justification and tab stops into the positions, so each advance is the exact distance
+// to the next glyph (no clamping: large tracking and tabs in preformatted text are intentional).
+// The last glyph has nothing to measure against and keeps the layout's advance, falling back to the
+// line's mean advance when the glyph doesn't carry one.
+// Right-to-left lines arrive in logical order with x decreasing. There the pen starts at the first
+// glyph's right edge, so that glyph keeps its own advance and each later one is the gap back from
+// its predecessor.
+fn glyph_advances(line: &[Glyph], font_size: f32) -> Vec<f32> {
+    let rtl = is_rtl_line(line);
+    let mut advances: Vec<f32> = line
+        .windows(2)
+        .map(|pair| {
+            if rtl {
+                pair[0].x - pair[1].x
+            } else {
+                pair[1].x - pair[0].x
+            }
+        })
+        .collect();
+    let own = if rtl { line.first() } else { line.last() };
+    let own = match own {
+        Some(glyph) if glyph.advance > 0.0 => glyph.advance,
+        _ if advances.is_empty() => font_size * 0.6,
+        _ => (advances.iter().sum::<f32>() / advances.len() as f32).max(1.0),
+    };
+    if rtl {
+        advances.insert(0, own);
+    } else {
+        advances.push(own);
+    }
+    advances
+}
+
+// A line whose glyphs step leftwards: a right-to-left run handed over in logical order
+fn is_rtl_line(line: &[Glyph]) -> bool {
+    line.len() > 1 && line[line.len() - 1].x < line[0].x
+}
+
+// Untransformed x of a line's DirectWrite origin: the left edge, or the right edge for RTL lines
+fn run_origin_x(line: &[Glyph], advances: &[f32]) -> f32 {
+    if is_rtl_line(line) {
+        line[0].x + advances[0]
+    } else {
+        line[0].x
+    }
+}
+
+// Horizontal span (left, right) covered by a run's advances from `origin_x`
+fn run_x_extent(advances: &[f32], origin_x: f32, bidi_level: u8) -> (f32, f32) {
+    let run_w: f32 = advances.iter().sum();
+    if bidi_level % 2 == 1 {
+        (origin_x - run_w, origin_x)
+    } else {
+        (origin_x, origin_x + run_w)
+    }
+}
+
+// Merge consecutive solid-color glyph runs that share font, size, render style and baseline, so a
+// line laid out as several runs costs one DrawGlyphRun. Only adjacent commands merge, which keeps
+// paint order; the earlier run's guessed last advance becomes the exact gap to the next origin.
+// Vertical and right-to-left runs are left alone.
+fn coalesce_glyph_runs(commands: Vec<Command>) -> Vec<Command> {
+    let mut out: Vec<Command> = Vec::with_capacity(commands.len());
+    for cmd in commands {
+        if let (
+            Some(Command::GlyphRun {
+                glyph_indices,
+                advances,
+                origin,
+                size,
+                style,
+                font,
+                var_coords,
+                brush: None,
+                vertical: false,
+                bidi_level: 0,
+            }),
+            Command::GlyphRun {
+                glyph_indices: next_indices,
+                advances: next_advances,
+                origin: next_origin,
+                size: next_size,
+                style: next_style,
+                font: next_font,
+                var_coords: next_coords,
+                brush: None,
+                vertical: false,
+                bidi_level: 0,
+            },
+        ) = (out.last_mut(), &cmd)
+        {
+            let last_x = origin.0
+                + advances[..advances.len().saturating_sub(1)]
+                    .iter()
+                    .sum::<f32>();
+            let same_run_style = *size == *next_size
+                && *style == *next_style
+                && *font == *next_font
+                && *var_coords == *next_coords;
+            if same_run_style
+                && !advances.is_empty()
+                && (origin.1 - next_origin.1).abs() < 0.01
+                && next_origin.0 >= last_x
+            {
+                *advances.last_mut().unwrap() = next_origin.0 - last_x;
+                glyph_indices.extend_from_slice(next_indices);
+                advances.extend_from_slice(next_advances);
+                continue;
+            }
+        }
+        out.push(cmd);
+    }
+    out
+}
+
+// The face in `collection` best matching the key's weight/stretch/style for one family name.
+fn find_font_face(
+    collection: &IDWriteFontCollection,
+    family_name: &str,
+    key: &FontKey,
+) -> Option<IDWriteFontFace> {
+    unsafe {
+        let mut idx = 0u32;
+        let mut exists = false.into();
+        collection
+            .FindFamilyName(
+                &windows::core::HSTRING::from(family_name),
+                &mut idx,
+                &mut exists,
+            )
+            .ok()?;
+        if !exists.as_bool() {
+            return None;
+        }
+        let family = collection.GetFontFamily(idx).ok()?;
+        let weight = DWRITE_FONT_WEIGHT(key.weight as i32);
+        // Map stretch (1..=9) directly; default normal (5)
+        let stretch = DWRITE_FONT_STRETCH(key.stretch as i32);
+        let style = if key.italic {
+            DWRITE_FONT_STYLE_ITALIC
+        } else {
+            DWRITE_FONT_STYLE_NORMAL
+        };
+        family
+            .GetFirstMatchingFont(weight, stretch, style)
+            .ok()?
+            .CreateFontFace()
+            .ok()
+    }
+}
+
+// Rect covering a horizontal line of `width` thickness centred on y, with its top edge and thickness
+// rounded to whole device pixels (`scale` device px per unit), never thinner than one pixel.
+fn snap_decoration_rect((x0, x1, y): (f64, f64, f64), width: f64, scale: f64) -> Rect {
+    let scale = if scale > 0.0 { scale } else { 1.0 };
+    let thickness = (width * scale).round().max(1.0);
+    let top = ((y - width / 2.0) * scale).round();
+    Rect::new(x0, top / scale, x1, (top + thickness) / scale)
+}
+
+fn describe_brush(brush: &RecordedBrush) -> String {
+    match brush {
+        RecordedBrush::Solid(c) => format!("solid{}", describe_color(*c)),
+        RecordedBrush::Gradient(g) => {
+            let kind = match g.kind {
+                peniko::GradientKind::Linear { .. } => "linear",
+                peniko::GradientKind::Radial { .. } => "radial",
+                peniko::GradientKind::Sweep { .. } => "sweep",
+            };
+            format!("{kind}-gradient stops={}", g.stops.len())
+        }
+        RecordedBrush::Image(img) => {
+            format!("image {}x{} alpha={:.2}", img.width, img.height, img.alpha)
+        }
+    }
+}
+
+fn describe_color(c: Color) -> String {
+    let [r, g, b, a] = c.components;
+    format!("({r:.3},{g:.3},{b:.3},{a:.3})")
+}
+
+fn describe_rect(r: Rect) -> String {
+    format!(
+        "({:.1},{:.1} {:.1}x{:.1})",
+        r.x0,
+        r.y0,
+        r.width(),
+        r.height()
+    )
+}
+
+// One line per command, indented by clip depth; backs dump_scene/last_scene_dump.
+fn dump_commands(commands: &[Command]) -> String {
+    let mut out = String::new();
+    let mut depth = 0usize;
+    for (i, cmd) in commands.iter().enumerate() {
+        if matches!(cmd, Command::PopLayer) {
+            depth = depth.saturating_sub(1);
+        }
+        let line = match cmd {
+            Command::PushLayer { rect, alpha, mask } => format!(
+                "PushLayer clip={} alpha={alpha:.2}{}",
+                describe_rect(*rect),
+                mask.as_ref()
+                    .map_or(String::new(), |m| format!(" mask={}", describe_brush(m)))
+            ),
+            Command::PopLayer => "PopLayer".to_string(),
+            Command::FillPath { path, brush, fill } => format!(
+                "FillPath els={} fill={fill:?} bounds={} brush={}",
+                path.len(),
+                path_bounds(path).map_or("none".to_string(), describe_rect),
+                describe_brush(brush)
+            ),
+            Command::StrokePath {
+                path, brush, width, ..
+            } => format!(
+                "StrokePath els={} width={width:.2} bounds={} brush={}",
+                path.len(),
+                path_bounds(path).map_or("none".to_string(), describe_rect),
+                describe_brush(brush)
+            ),
+            Command::DecorationLine {
+                x0,
+                x1,
+                y,
+                width,
+                brush,
+            } => format!(
+                "DecorationLine x={x0:.1}..{x1:.1} y={y:.1} width={width:.2} brush={}",
+                describe_brush(brush)
+            ),
+            Command::BoxShadow {
+                rect,
+                color,
+                radius,
+                std_dev,
+                inset,
+            } => format!(
+                "BoxShadow{} rect={} radius={radius:.1} std_dev={std_dev:.1} color={}",
+                if *inset { "Inset" } else { "" },
+                describe_rect(*rect),
+                describe_color(*color)
+            ),
+            Command::GlyphShadow {
+                glyph_indices,
+                origin,
+                size,
+                font,
+                color,
+                std_dev,
+                ..
+            } => format!(
+                "GlyphShadow glyphs={} size={size:.1} origin=({:.1},{:.1}) font={:?} std_dev={std_dev:.1} color={}",
+                glyph_indices.len(),
+                origin.0,
+                origin.1,
+                font.families.first().map_or("", |f| f.as_str()),
+                describe_color(*color)
+            ),
+            Command::BackdropBlur { path, std_dev } => format!(
+                "BackdropBlur els={} bounds={} std_dev={std_dev:.1}",
+                path.len(),
+                path_bounds(path).map_or("none".to_string(), describe_rect)
+            ),
+            Command::GlyphRun {
+                glyph_indices,
+                origin,
+                size,
+                style,
+                font,
+                brush,
+                vertical,
+                bidi_level,
+                ..
+            } => format!(
+                "GlyphRun glyphs={}{}{} size={size:.1} origin=({:.1},{:.1}) font={:?} weight={} {} brush={}",
+                glyph_indices.len(),
+                if *vertical { " vertical" } else { "" },
+                if bidi_level % 2 == 1 { " rtl" } else { "" },
+                origin.0,
+                origin.1,
+                font.families.first().map_or("", |f| f.as_str()),
+                font.weight,
+                match style {
+                    GlyphRenderStyle::Fill { .. } => "fill".to_string(),
+                    GlyphRenderStyle::Stroke { width, .. } => format!("stroke={width:.2}"),
+                },
+                match (brush, style) {
+                    (Some(b), _) => describe_brush(b),
+                    (
+                        None,
+                        GlyphRenderStyle::Fill { color } | GlyphRenderStyle::Stroke { color, .. },
+                    ) => {
+                        format!("solid{}", describe_color(*color))
+                    }
+                }
+            ),
+        };
+        out.push_str(&format!("{i:>4} {}{line}\n", "  ".repeat(depth)));
+        if matches!(cmd, Command::PushLayer { .. }) {
+            depth += 1;
+        }
+    }
+    out
+}
+
+// Round a logical rect's edges to device pixels (logical * scale), keeping a non-empty side at
+// least one device pixel wide. Returned in logical px.
+fn snap_rect_to_device(rect: Rect, scale: f64) -> Rect {
+    let scale = if scale > 0.0 { scale } else { 1.0 };
+    let snap = |a: f64, b: f64| {
+        let (da, db) = ((a * scale).round(), (b * scale).round());
+        let db = if db <= da && b > a { da + 1.0 } else { db };
+        (da / scale, db / scale)
+    };
+    let (x0, x1) = snap(rect.x0, rect.x1);
+    let (y0, y1) = snap(rect.y0, rect.y1);
+    Rect::new(x0, y0, x1, y1)
+}
+
+// Snap a rectangle stroke: the width becomes a whole number of device pixels (at least one) and
+// the centre line sits on a pixel centre (odd widths) or boundary (even widths), so both stroke
+// edges land on pixel boundaries. Rect and width are returned in logical px.
+fn snap_stroke_rect(rect: Rect, width: f64, scale: f64) -> (Rect, f64) {
+    let scale = if scale > 0.0 { scale } else { 1.0 };
+    let w = (width * scale).round().max(1.0);
+    let offset = if w as i64 % 2 == 1 { 0.5 } else { 0.0 };
+    let snap = |v: f64| ((v * scale - offset).round() + offset) / scale;
+    (
+        Rect::new(snap(rect.x0), snap(rect.y0), snap(rect.x1), snap(rect.y1)),
+        w / scale,
+    )
+}
+
+// A frame repaints `current ∪ previous` damage, or everything (None) on a full repaint, when the
+// previous frame was full, or when nothing was marked.
+fn repaint_clip(full: bool, current: Option<Rect>, previous: Option<Rect>) -> Option<Rect> {
+    if full {
+        return None;
+    }
+    Some(current?.union(previous?))
+}
+
+// Split a CSS font-family list ("Foo", Bar, sans-serif) into family names in order, without
+// duplicates. Generic keywords are kept (lowercased) for FontConfig::candidates to map.
+fn family_candidates(list: &str) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for raw in list.split(',') {
+        let name = raw.trim().trim_matches(|c| c == '"' || c == '\'').trim();
+        let name = match name.to_ascii_lowercase().as_str() {
+            "" => continue,
+            generic @ ("serif" | "sans-serif" | "monospace" | "cursive" | "fantasy"
+            | "system-ui" | "emoji") => generic.to_string(),
+            _ => name.to_string(),
+        };
+        if !out.iter().any(|f| f.eq_ignore_ascii_case(&name)) {
+            out.push(name);
+        }
+    }
+    out
+}
+
+/// Fonts used where CSS doesn't name an installed family: the default family (unstyled text, and the
+/// last resort after every listed family) and the concrete family for each generic keyword.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FontConfig {
+    pub default_family: String,
+    /// Weight of the fallback face used when no listed family resolves (100-900)
+    pub default_weight: u16,
+    pub serif: String,
+    pub sans_serif: String,
+    pub monospace: String,
+    pub cursive: String,
+    pub fantasy: String,
+    pub system_ui: String,
+    pub emoji: String,
+}
+
+impl Default for FontConfig {
+    fn default() -> Self {
+        Self {
+            default_family: "Segoe UI".to_string(),
+            default_weight: 400,
+            serif: "Times New Roman".to_string(),
+            sans_serif: "Segoe UI".to_string(),
+            monospace: "Consolas".to_string(),
+            cursive: "Comic Sans MS".to_string(),
+            fantasy: "Segoe UI".to_string(), // placeholder
+            emoji: "Segoe UI Emoji".to_string(),
+            system_ui: "Segoe UI".to_string(),
+        }
+    }
+}
+
+impl FontConfig {
+    /// Concrete family names to try for a key's families, in order: generic keywords mapped, then the
+    /// default family.
+    fn candidates(&self, families: &[String]) -> Vec<String> {
+        let mut out: Vec<String> = Vec::new();
+        for name in families
+            .iter()
+            .map(String::as_str)
+            .chain([self.default_family.as_str()])
+        {
+            let resolved = match name {
+                "serif" => &self.serif,
+                "sans-serif" => &self.sans_serif,
+                "monospace" => &self.monospace,
+                "cursive" => &self.cursive,
+                "fantasy" => &self.fantasy,
+                "system-ui" => &self.system_ui,
+                "emoji" => &self.emoji,
+                _ => name,
+            };
+            if !out.iter().any(|f| f.eq_ignore_ascii_case(resolved)) {
+                out.push(resolved.to_string());
+            }
+        }
+        out
+    }
+}
+
+// Closed, axis-aligned rectangle (MoveTo + 3-4 LineTo, optional ClosePath) whose fill equals its bounds.
+fn is_axis_aligned_rect(path: &[PathEl]) -> bool {
+    let mut pts = Vec::with_capacity(5);
+    for (i, el) in path.iter().enumerate() {
+        match el {
+            PathEl::MoveTo(p) if i == 0 => pts.push(*p),
+            PathEl::LineTo(p) if i > 0 => pts.push(*p),
+            PathEl::ClosePath if i == path.len() - 1 => {}
+            _ => return false,
+        }
+    }
+    if pts.len() == 5 && pts[4] == pts[0] {
+        pts.pop();
+    }
+    if pts.len() != 4 {
+        return false;
+    }
+    (0..4).all(|i| {
+        let (a, b) = (pts[i], pts[(i + 1) % 4]);
+        (a.x == b.x) != (a.y == b.y)
+    })
+}
+
+// Split glyphs into consecutive runs that share a baseline. A y jump of more than a quarter of the
+// font size (or half a pixel for tiny text) starts a new line.
+fn split_glyph_lines(glyphs: &[Glyph], font_size: f32) -> Vec<&[Glyph]> {
+    let threshold = (font_size * 0.25).max(0.5);
+    let mut lines = Vec::new();
+    let mut start = 0;
+    for i in 1..glyphs.len() {
+        if (glyphs[i].y - glyphs[start].y).abs() > threshold {
+            lines.push(&glyphs[start..i]);
+            start = i;
+        }
+    }
+    if start < glyphs.len() {
+        lines.push(&glyphs[start..]);
+    }
+    lines
+}
+
+// A transform that turns content a quarter clockwise (x axis pointing down), as blitz-paint uses for
+// vertical-rl text
+fn is_quarter_turn(transform: Affine) -> bool {
+    let [a, b, c, d, _, _] = transform.as_coeffs();
+    a.abs() < 1e-6 && d.abs() < 1e-6 && b > 0.0 && c < 0.0 && (b + c).abs() < 1e-6
+}
+
+// Box-filter an RGBA8 image down so neither side exceeds `max_dim`, keeping the aspect ratio.
+fn downscale_rgba(data: &[u8], width: u32, height: u32, max_dim: u32) -> (Vec<u8>, u32, u32) {
+    let factor = (width.max(height) as f64 / max_dim as f64).max(1.0);
+    let dw = ((width as f64 / factor).floor() as u32).clamp(1, max_dim);
+    let dh = ((height as f64 / factor).floor() as u32).clamp(1, max_dim);
+    let mut out = vec![0u8; dw as usize * dh as usize * 4];
+    for y in 0..dh {
+        let sy0 = (y as u64 * height as u64 / dh as u64) as u32;
+        let sy1 = (((y + 1) as u64 * height as u64 / dh as u64) as u32).max(sy0 + 1);
+        for x in 0..dw {
+            let sx0 = (x as u64 * width as u64 / dw as u64) as u32;
+            let sx1 = (((x + 1) as u64 * width as u64 / dw as u64) as u32).max(sx0 + 1);
+            let mut acc = [0u64; 4];
+            for sy in sy0..sy1 {
+                let row = sy as usize * width as usize * 4;
+                for sx in sx0..sx1 {
+                    let i = row + sx as usize * 4;
+                    for c in 0..4 {
+                        acc[c] += data[i + c] as u64;
+                    }
+                }
+            }
+            let n = ((sy1 - sy0) * (sx1 - sx0)) as u64;
+            let o = (y as usize * dw as usize + x as usize) * 4;
+            for c in 0..4 {
+                out[o + c] = ((acc[c] + n / 2) / n) as u8;
+            }
+        }
+    }
+    (out, dw, dh)
+}
+
+// Approximate a Gaussian blur of `std_dev` with three separable box passes (W3C filter-effects
+// box sizes). Pixels outside the buffer count as transparent, like D2D1_BORDER_MODE_SOFT.
+fn box_blur_rgba(data: &mut [u8], width: usize, height: usize, std_dev: f64) {
+    if width == 0 || height == 0 || std_dev <= 0.0 {
+        return;
+    }
+    let d = ((std_dev * 3.0 * (2.0 * std::f64::consts::PI).sqrt() / 4.0) + 0.5).floor() as usize;
+    if d == 0 {
+        return;
+    }
+    // Pass layout per spec: odd d -> three centred boxes; even d -> two off-centre plus one of d + 1.
+    let passes: [(usize, usize); 3] = if d % 2 == 1 {
+        [(d / 2, d / 2); 3]
+    } else {
+        [(d / 2, d / 2 - 1), (d / 2 - 1, d / 2), (d / 2, d / 2)]
+    };
+    let mut line = Vec::new();
+    for (left, right) in passes {
+        for y in 0..height {
+            box_blur_line(data, y * width * 4, 4, width, left, right, &mut line);
+        }
+    }
+    for (left, right) in passes {
+        for x in 0..width {
+            box_blur_line(data, x * 4, width * 4, height, left, right, &mut line);
+        }
+    }
+}
+
+// One box pass over `len` pixels starting at `start`, `stride` bytes apart; the window covers
+// `left` pixels before and `right` pixels after each output pixel.
+fn box_blur_line(
+    data: &mut [u8],
+    start: usize,
+    stride: usize,
+    len: usize,
+    left: usize,
+    right: usize,
+    line: &mut Vec<[u32; 4]>,
+) {
+    line.clear();
+    line.extend((0..len).map(|i| {
+        let o = start + i * stride;
+        [
+            data[o] as u32,
+            data[o + 1] as u32,
+            data[o + 2] as u32,
+            data[o + 3] as u32,
+        ]
+    }));
+    let window = (left + right + 1) as u32;
+    let mut sum = [0u32; 4];
+    for px in line.iter().take(right.min(len)) {
+        for c in 0..4 {
+            sum[c] += px[c];
+        }
+    }
+    for i in 0..len {
+        if i + right < len {
+            for c in 0..4 {
+                sum[c] += line[i + right][c];
+            }
+        }
+        let o = start + i * stride;
+        for c in 0..4 {
+            data[o + c] = ((sum[c] + window / 2) / window) as u8;
+        }
+        if i >= left {
+            for c in 0..4 {
+                sum[c] -= line[i - left][c];
+            }
+        }
+    }
+}
+
+fn record_brush(b: BrushRef<'_>) -> RecordedBrush {
+    match b {
+        BrushRef::Solid(c) => RecordedBrush::Solid(c),
+        BrushRef::Gradient(g) => RecordedBrush::Gradient(RecordedGradient {
+            kind: g.kind,
+            extend: g.extend,
+            interpolation_cs: g.interpolation_cs,
+            hue_direction: g.hue_direction,
+            transform: Affine::IDENTITY,
+            stops: g
+                .stops
+                .iter()
+                .map(|s| (s.offset, s.color.to_alpha_color::<color::Srgb>()))
+                .collect(),
+        }),
+        BrushRef::Image(img) => RecordedBrush::Image(RecordedImage {
+            width: img.width,
+            height: img.height,
+            source: img.data.id(),
+            data: img.data.as_ref().to_vec(),
+            format: img.format,
+            alpha_type: ImageAlphaType::Alpha,
+            alpha: img.alpha,
+            source_rect: None,
+            transform: Affine::IDENTITY,
+            x_extend: img.x_extend,
+            y_extend: img.y_extend,
+            quality: img.quality,
+        }),
+    }
+}
+fn record_paint(p: Paint<'_>) -> RecordedBrush {
+    match p {
+        Paint::Solid(c) => RecordedBrush::Solid(c),
+        Paint::Gradient(g) => RecordedBrush::Gradient(RecordedGradient {
+            kind: g.kind,
+            extend: g.extend,
+            interpolation_cs: g.interpolation_cs,
+            hue_direction: g.hue_direction,
+            transform: Affine::IDENTITY,
+            stops: g
+                .stops
+                .iter()
+                .map(|s| (s.offset, s.color.to_alpha_color::<color::Srgb>()))
+                .collect(),
+        }),
+        Paint::Image(img) => RecordedBrush::Image(RecordedImage {
+            width: img.width,
+            height: img.height,
+            source: img.data.id(),
+            data: img.data.as_ref().to_vec(),
+            format: img.format,
+            alpha_type: ImageAlphaType::Alpha,
+            alpha: img.alpha,
+            source_rect: None,
+            transform: Affine::IDENTITY,
+            x_extend: img.x_extend,
+            y_extend: img.y_extend,
+            quality: img.quality,
+        }),
+        Paint::Custom(_) => RecordedBrush::Solid(Color::BLACK),
+    }
+}
+
+/// Antialiasing mode used for glyph runs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TextAntialiasMode {
+    /// Grayscale antialiasing. Safe over transparent/composited surfaces (SwapChainPanel).
+    #[default]
+    Grayscale,
+    /// ClearType subpixel antialiasing. Only looks right on opaque targets.
+    ClearType,
+    /// No antialiasing.
+    Aliased,
+}
+
+impl TextAntialiasMode {
+    fn to_d2d(self) -> D2D1_TEXT_ANTIALIAS_MODE {
+        match self {
+            TextAntialiasMode::Grayscale => D2D1_TEXT_ANTIALIAS_MODE_GRAYSCALE,
+            TextAntialiasMode::ClearType => D2D1_TEXT_ANTIALIAS_MODE_CLEARTYPE,
+            TextAntialiasMode::Aliased => D2D1_TEXT_ANTIALIAS_MODE_ALIASED,
+        }
+    }
+}
+
+/// Antialiasing mode used for filled and stroked geometry (and the clips pushed by layers).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GeometryAntialiasMode {
+    /// Direct2D's per-primitive antialiasing.
+    #[default]
+    PerPrimitive,
+    /// Hard pixel edges, e.g. for pixel art or deterministic reference-image comparisons.
+    Aliased,
+}
+
+impl GeometryAntialiasMode {
+    fn to_d2d(self) -> D2D1_ANTIALIAS_MODE {
+        match self {
+            GeometryAntialiasMode::PerPrimitive => D2D1_ANTIALIAS_MODE_PER_PRIMITIVE,
+            GeometryAntialiasMode::Aliased => D2D1_ANTIALIAS_MODE_ALIASED,
+        }
+    }
+}
+
+/// What `render` left in the backbuffer for the host to present (see `take_frame_result`).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FrameResult {
+    /// Device-pixel region that changed; None means the whole target (pass no dirty rects to Present1).
+    pub presented_region: Option<Rect>,
+    /// A frame was drawn and completed since the last take; false means Present would show nothing new.
+    pub needs_present: bool,
+}
+
+impl FrameResult {
+    // Fold in a later frame drawn before the host presented: the regions of both must go out.
+    fn merge(self, later: FrameResult) -> FrameResult {
+        if !self.needs_present {
+            return later;
+        }
+        if !later.needs_present {
+            return self;
+        }
+        FrameResult {
+            presented_region: self
+                .presented_region
+                .zip(later.presented_region)
+                .map(|(a, b)| a.union(b)),
+            needs_present: true,
+        }
+    }
+}
+
+/// Per-kind breakdown of the commands the last frame recorded, for diagnosing slow frames.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CommandStats {
+    pub fills: u32,
+    pub strokes: u32,
+    /// Glyph runs before coalescing, and the glyphs they carry
+    pub glyph_runs: u32,
+    pub glyphs: u32,
+    /// Box shadows and text shadows
+    pub shadows: u32,
+    pub text_shadows: u32,
+    pub layers: u32,
+    /// Deepest nesting of clips/layers reached during playback
+    pub max_clip_depth: u32,
+    /// Commands skipped during playback because they were outside the visible area
+    pub culled: u32,
+}
+
+impl CommandStats {
+    fn from_commands(commands: &[Command]) -> Self {
+        let mut stats = Self::default();
+        for cmd in commands {
+            match cmd {
+                Command::FillPath { .. } => stats.fills += 1,
+                Command::StrokePath { .. } | Command::DecorationLine { .. } => stats.strokes += 1,
+                Command::GlyphRun { glyph_indices, .. } => {
+                    stats.glyph_runs += 1;
+                    stats.glyphs += glyph_indices.len() as u32;
+                }
+                Command::BoxShadow { .. } => stats.shadows += 1,
+                Command::GlyphShadow { .. } => stats.text_shadows += 1,
+                Command::PushLayer { .. } => stats.layers += 1,
+                Command::PopLayer | Command::BackdropBlur { .. } => {}
+            }
+        }
+        stats
+    }
+
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"fills\":{},\"strokes\":{},\"glyph_runs\":{},\"glyphs\":{},\"shadows\":{},\"text_shadows\":{},\"layers\":{},\"max_clip_depth\":{},\"culled\":{}}}",
+            self.fills,
+            self.strokes,
+            self.glyph_runs,
+            self.glyphs,
+            self.shadows,
+            self.text_shadows,
+            self.layers,
+            self.max_clip_depth,
+            self.culled,
+        )
+    }
+}
+
+impl std::fmt::Display for CommandStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "fills: {}, strokes: {}, glyphs: {}, shadows: {}, layers: {} (depth {})",
+            self.fills,
+            self.strokes,
+            self.glyphs,
+            self.shadows + self.text_shadows,
+            self.layers,
+            self.max_clip_depth
+        )
+    }
+}
+
+/// Box-model rects (logical px, outermost first) of the element highlighted by the inspect overlay.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct InspectBox {
+    pub margin: Rect,
+    pub border: Rect,
+    pub padding: Rect,
+    pub content: Rect,
+}
+
+impl InspectBox {
+    // Translucent fills in devtools colors: the content box plus the padding, border and margin
+    // bands between consecutive rects.
+    fn fills(&self) -> Vec<(Rect, Color)> {
+        let mut out = vec![(self.content, Color::from_rgba8(66, 144, 245, 128))];
+        for (outer, inner, color) in [
+            (
+                self.padding,
+                self.content,
+                Color::from_rgba8(81, 144, 66, 128),
+            ),
+            (
+                self.border,
+                self.padding,
+                Color::from_rgba8(245, 66, 66, 128),
+            ),
+            (
+                self.margin,
+                self.border,
+                Color::from_rgba8(249, 204, 157, 128),
+            ),
+        ] {
+            out.extend(ring_rects(outer, inner).into_iter().map(|r| (r, color)));
+        }
+        out.retain(|(r, _)| r.width() > 0.0 && r.height() > 0.0);
+        out
+    }
+}
+
+// The parts of `outer` not covered by `inner` as (top, bottom, left, right) bands; `inner` is
+// clamped into `outer`.
+fn ring_rects(outer: Rect, inner: Rect) -> [Rect; 4] {
+    let inner = Rect::new(
+        inner.x0.clamp(outer.x0, outer.x1),
+        inner.y0.clamp(outer.y0, outer.y1),
+        inner.x1.clamp(outer.x0, outer.x1),
+        inner.y1.clamp(outer.y0, outer.y1),
+    );
+    [
+        Rect::new(outer.x0, outer.y0, outer.x1, inner.y0),
+        Rect::new(outer.x0, inner.y1, outer.x1, outer.y1),
+        Rect::new(outer.x0, inner.y0, inner.x0, inner.y1),
+        Rect::new(inner.x1, inner.y0, outer.x1, inner.y1),
+    ]
+}
+
+/// Target corner the debug overlay is anchored to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverlayCorner {
+    #[default]
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// How much [`D2DWindowRenderer::trim_memory`] sheds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrimLevel {
+    /// Gradient brushes and decoded image/sweep bitmaps, the cheapest to rebuild
+    Mild,
+    /// Everything but the devices, swapchain and backbuffer: also shadow bitmaps, font faces, stroke styles and
+    /// Direct2D's own internal resource cache
+    Aggressive,
+}
+
+/// What the debug overlay shows and how it is placed (see [`D2DWindowRenderer::set_overlay_options`]).
+/// The default shows every line in the top-left corner.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OverlayOptions {
+    /// First-frame phase timings (parse/style/layout/shape/scene, host and device setup) and their shares
+    pub phases: bool,
+    /// Frames per second
+    pub fps: bool,
+    /// Commands recorded for the last frame, by kind
+    pub commands: bool,
+    /// Buffer size, scale and text antialiasing mode
+    pub diagnostics: bool,
+    pub corner: OverlayCorner,
+    /// Opacity of the whole overlay, 0..1
+    pub opacity: f32,
+    /// Width (logical px) at which the text wraps
+    pub max_width: f32,
+}
+
+impl Default for OverlayOptions {
+    fn default() -> Self {
+        Self {
+            phases: true,
+            fps: true,
+            commands: true,
+            diagnostics: true,
+            corner: OverlayCorner::default(),
+            opacity: 1.0,
+            max_width: 980.0,
+        }
+    }
+}
+
+// Overlay text from the enabled line groups; empty lines (e.g. shares before the first frame) are left out
+fn overlay_text(
+    options: &OverlayOptions,
+    phases: &[String],
+    fps: String,
+    commands: String,
+    diagnostics: String,
+) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    if options.phases {
+        lines.extend(phases.iter().map(String::as_str));
+    }
+    if options.fps {
+        lines.push(&fps);
+    }
+    if options.commands {
+        lines.push(&commands);
+    }
+    if options.diagnostics {
+        lines.push(&diagnostics);
+    }
+    lines.retain(|line| !line.is_empty());
+    lines.join("\n")
+}
+
+// D2D target format for a swapchain surface format, when Direct2D can render to it directly.
+fn target_pixel_format(surface: DXGI_FORMAT) -> Option<DXGI_FORMAT> {
+    [
+        DXGI_FORMAT_B8G8R8A8_UNORM,
+        DXGI_FORMAT_B8G8R8A8_UNORM_SRGB,
+        DXGI_FORMAT_R8G8B8A8_UNORM,
+        DXGI_FORMAT_R8G8B8A8_UNORM_SRGB,
+        DXGI_FORMAT_R10G10B10A2_UNORM,
+        DXGI_FORMAT_R16G16B16A16_FLOAT,
+    ]
+    .into_iter()
+    .find(|&f| f == surface)
+}
+
+// Color a frame is cleared to. Empty scenes (nothing recorded yet) use the host's empty background;
+// otherwise the document canvas color, or opaque white so something is always visible. A transparent
+// clear on a target whose alpha is ignored would show up black, so such targets get white instead.
+fn frame_clear_color(
+    empty_scene: bool,
+    clear_color: Option<Color>,
+    empty_background: Color,
+    opaque_target: bool,
+) -> Color {
+    let color = if empty_scene {
+        Some(empty_background)
+    } else {
+        clear_color
+    };
+    match color {
+        Some(c) if c.components[3] > 0.0 || !opaque_target => c,
+        _ => Color::WHITE,
+    }
+}
+
+// Background rect of the debug overlay: the measured text plus `pad` on each side, `margin` in from
+// the chosen corner of a `target`-sized surface (all in device px).
+fn overlay_rect(
+    corner: OverlayCorner,
+    text: (f32, f32),
+    target: (f32, f32),
+    margin: f32,
+    pad: f32,
+) -> Rect {
+    let (w, h) = ((text.0 + 2.0 * pad) as f64, (text.1 + 2.0 * pad) as f64);
+    let (margin, target) = (margin as f64, (target.0 as f64, target.1 as f64));
+    let x = match corner {
+        OverlayCorner::TopLeft | OverlayCorner::BottomLeft => margin,
+        OverlayCorner::TopRight | OverlayCorner::BottomRight => (target.0 - margin - w).max(0.0),
+    };
+    let y = match corner {
+        OverlayCorner::TopLeft | OverlayCorner::TopRight => margin,
+        OverlayCorner::BottomLeft | OverlayCorner::BottomRight => (target.1 - margin - h).max(0.0),
+    };
+    Rect::new(x, y, x + w, y + h)
+}
+
+/// Time source for the renderer's frame timing, FPS and first-frame metrics. The default is
+/// [`SystemClock`]; tests can install a clock they advance by hand (see
+/// [`D2DWindowRenderer::set_clock`]).
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+/// The real monotonic clock.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Options fixed when creating a [`D2DWindowRenderer`] (see [`D2DWindowRenderer::new_with_options`]).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RendererOptions {
+    /// Create the D2D factory multi-threaded, so its devices and resources may be used from more than
+    /// one thread at the cost of a lock around every D2D call. Off by default: with a single-threaded
+    /// factory every call on the renderer (and on resources it hands out) must come from one thread,
+    /// as in the composited WinUI path where everything runs on the UI thread.
+    pub multi_threaded: bool,
+}
+
+/// Direct2D renderer bound to an existing DXGI swapchain (composition target).
+pub struct D2DWindowRenderer {
+    options: RendererOptions,
+    swapchain: Option<IDXGISwapChain1>,
+    d3d_device: Option<ID3D11Device>,
+    d2d_factory: Option<ID2D1Factory1>,
+    d2d_device: Option<ID2D1Device>,
+    d2d_ctx: Option<ID2D1DeviceContext>,
+    dwrite_factory: Option<IDWriteFactory>,
+    // Font data registered with add_font_data, and the in-memory collection built from it
+    custom_fonts: Vec<Vec<u8>>,
+    custom_collection: Option<IDWriteFontCollection>,
+    font_loader: Option<IDWriteInMemoryFontFileLoader>,
+    dwrite_font_face: Option<IDWriteFontFace>,
+    dwrite_text_format: Option<IDWriteTextFormat>,
+    // Font size the overlay text format was created at (device px)
+    dwrite_text_format_size: f32,
+    font_face_cache: FxHashMap<FontKey, IDWriteFontFace>,
+    // Default family and generic-family mapping (set_default_font / set_font_config)
+    font_config: FontConfig,
+    // caches
+    gradient_cache: FxHashMap<u64, ID2D1Brush>,
+    stroke_style_cache: FxHashMap<StrokeStyleKey, ID2D1StrokeStyle>,
+    image_cache: FxHashMap<ImageKey, ID2D1Bitmap>,
+    image_cache_order: std::collections::VecDeque<ImageKey>,
+    // rasterized sweep (conic) gradient bitmaps
+    sweep_cache: FxHashMap<u64, ID2D1Bitmap>,
+    // shadow blur cache (bitmap of blurred rounded rect); separate from image_cache to control eviction separately
+    shadow_cache: FxHashMap<ShadowKey, ID2D1Bitmap1>,
+    shadow_cache_order: std::collections::VecDeque<ShadowKey>,
+    shadow_cache_capacity: usize,
+    gaussian_blur_effect: Option<ID2D1Effect>,
+    scene: D2DScene,
+    width: u32,
+    height: u32,
+    active: bool,
+    debug_shadow_logs: u32,
+    last_command_count: u32,
+    last_command_stats: CommandStats,
+    backbuffer_bitmap: Option<ID2D1Bitmap1>,
+    // Times EndDraw reported D2DERR_RECREATE_TARGET and the device objects were rebuilt
+    device_lost_count: u32,
+    // A device loss not yet reported through take_device_lost
+    device_lost: bool,
+    // Test hook: treat the next EndDraw as D2DERR_RECREATE_TARGET
+    simulate_device_loss: bool,
+    // Set by set_size when the size changes; forces backbuffer bitmap recreation on the next frame
+    backbuffer_dirty: bool,
+    // Damage tracking (logical px). A frame with no marked damage, or with mark_full, repaints everything.
+    dirty_rect: Option<Rect>,
+    full_repaint: bool,
+    // Damage of the previous frame (None = it was a full repaint). With a two-buffer flip swapchain
+    // the buffer being drawn is one frame behind, so a partial repaint must cover both.
+    last_damage: Option<Rect>,
+    // Frames drawn since the host last took the result, waiting to be presented
+    frame_result: FrameResult,
+    // --- instrumentation ---
+    clock: Box<dyn Clock>,
+    init_start: Instant,
+    first_frame_done: bool,
+    // Fired once when the first frame completes (see on_first_frame)
+    first_frame_callback: Option<Box<dyn FnOnce(FrameTimings) + Send>>,
+    first_frame_ms: f32,
+    device_init_ms: f32,
+    backbuffer_create_ms: f32,
+    playback_ms: f32,
+    host_init_ms: f32, // D3D device + swapchain creation (host side)
+    // host init sub-phases
+    host_dxgi_d3d_ms: f32,
+    host_swapchain_ms: f32,
+    host_panel_attach_queue_ms: f32,
+    host_panel_attach_exec_ms: f32,
+    host_panel_attach_sub_ui_add_ms: f32,
+    host_panel_attach_sub_set_swapchain_ms: f32,
+    host_first_text_init_ms: f32,
+    frame_start: Instant,
+    fps_accum_time: f32,
+    fps_frame_count: u32,
+    fps: f32,
+    last_frame_metrics: FrameTimings,
+    // Diagnostic: draw colored quadrants when true and no scene commands (placeholder visibility test)
+    test_pattern: bool,
+    show_debug_overlay: bool,
+    overlay_options: OverlayOptions,
+    // Color the target is cleared to before playback (document canvas background); None = white
+    clear_color: Option<Color>,
+    // Color for frames with no scene commands (loading / between documents); transparent by default
+    empty_background: Color,
+    text_antialias_mode: TextAntialiasMode,
+    geometry_antialias_mode: GeometryAntialiasMode,
+    // Device scale applied as a playback transform (logical px -> backbuffer px)
+    scale: f32,
+    // Overlay scrollbar thumb in logical px, drawn on top of the scene
+    scrollbar_thumb: Option<Rect>,
+    // Devtools box-model highlight (see set_inspect_overlay)
+    inspect_overlay: bool,
+    inspect_box: Option<InspectBox>,
+    // Snap axis-aligned rect fills/strokes to device pixels (see set_pixel_snap)
+    pixel_snap: bool,
+    // When set, playback keeps a text dump of the commands it replayed (see last_scene_dump)
+    capture_scene: bool,
+    last_scene_dump: String,
+}
+
+impl D2DWindowRenderer {
+    /// Renderer with the default options (single-threaded D2D factory).
+    pub fn new() -> Self {
+        Self::new_with_options(RendererOptions::default())
+    }
+
+    pub fn new_with_options(options: RendererOptions) -> Self {
+        // BLITZ_VERBOSE seeds the verbose flag; set_verbose_logging can still override it later.
+        if env_flags().verbose {
+            set_verbose_logging(true);
+        }
+        let clock: Box<dyn Clock> = Box::new(SystemClock);
+        let init_start = clock.now();
+        begin_init_window(init_start);
+        Self {
+            options,
+            swapchain: None,
+            d3d_device: None,
+            d2d_factory: None,
+            d2d_device: None,
+            d2d_ctx: None,
+            dwrite_factory: None,
+            custom_fonts: Vec::new(),
+            custom_collection: None,
+            font_loader: None,
+            dwrite_font_face: None,
+            dwrite_text_format: None,
+            dwrite_text_format_size: 0.0,
+            font_face_cache: FxHashMap::default(),
+            font_config: FontConfig::default(),
+            gradient_cache: FxHashMap::default(),
+            stroke_style_cache: FxHashMap::default(),
+            image_cache: FxHashMap::default(),
+            image_cache_order: std::collections::VecDeque::new(),
+            sweep_cache: FxHashMap::default(),
+            shadow_cache: FxHashMap::default(),
+            shadow_cache_order: std::collections::VecDeque::new(),
+            shadow_cache_capacity: DEFAULT_SHADOW_CACHE_CAPACITY,
+            gaussian_blur_effect: None,
+            scene: D2DScene::default(),
+            width: 1,
+            height: 1,
+            active: false,
+            debug_shadow_logs: 0,
+            last_command_count: 0,
+            last_command_stats: CommandStats::default(),
+            backbuffer_bitmap: None,
+            device_lost_count: 0,
+            device_lost: false,
+            simulate_device_loss: false,
+            backbuffer_dirty: false,
+            dirty_rect: None,
+            full_repaint: true,
+            last_damage: None,
+            frame_result: FrameResult::default(),
+            clock,
+            init_start,
+            first_frame_done: false,
+            first_frame_callback: None,
+            first_frame_ms: 0.0,
+            device_init_ms: 0.0,
+            backbuffer_create_ms: 0.0,
+            playback_ms: 0.0,
+            host_init_ms: 0.0,
+            host_dxgi_d3d_ms: 0.0,
+            host_swapchain_ms: 0.0,
+            host_panel_attach_queue_ms: 0.0,
+            host_panel_attach_exec_ms: 0.0,
+            host_panel_attach_sub_ui_add_ms: 0.0,
+            host_panel_attach_sub_set_swapchain_ms: 0.0,
+            host_first_text_init_ms: 0.0,
+            frame_start: init_start,
+            fps_accum_time: 0.0,
+            fps_frame_count: 0,
             fps: 0.0,
             last_frame_metrics: FrameTimings::default(),
             test_pattern: false,
             show_debug_overlay: false,
+            overlay_options: OverlayOptions::default(),
+            clear_color: None,
+            empty_background: Color::TRANSPARENT,
+            text_antialias_mode: TextAntialiasMode::default(),
+            geometry_antialias_mode: GeometryAntialiasMode::default(),
+            scale: 1.0,
+            scrollbar_thumb: None,
+            inspect_overlay: false,
+            inspect_box: None,
+            pixel_snap: false,
+            capture_scene: false,
+            last_scene_dump: String::new(),
         }
     }
 
     pub fn restart_initial_measurement(&mut self) {
         // Reset metrics and restart init window for real content load
         unfreeze_and_reset();
-        self.init_start = Instant::now();
+        self.init_start = self.clock.now();
         self.first_frame_done = false;
         self.first_frame_ms = 0.0;
         begin_init_window(self.init_start);
     }
 
-    pub fn last_command_count(&self) -> u32 {
-        self.last_command_count
+    /// Register a callback invoked once, right after the first frame completes and the init-window
+    /// metrics freeze, with that frame's phase timings (`frame_total_ms` is `first_frame_ms`).
+    /// The callback is consumed when it fires; register again after `restart_initial_measurement`
+    /// or `reset_for_new_document` to hear about the next document's first frame. Registering
+    /// replaces a callback that has not fired yet.
+    pub fn on_first_frame(&mut self, callback: Box<dyn FnOnce(FrameTimings) + Send>) {
+        self.first_frame_callback = Some(callback);
+    }
+
+    /// Replace the time source used for frame timing, FPS and the first-frame metrics. The
+    /// init window restarts at the new clock's `now`, so install the clock before rendering.
+    pub fn set_clock(&mut self, clock: Box<dyn Clock>) {
+        self.clock = clock;
+        self.frame_start = self.clock.now();
+        self.restart_initial_measurement();
+    }
+
+    // Milliseconds on the renderer clock since `start`.
+    fn elapsed_ms(&self, start: Instant) -> f32 {
+        self.clock
+            .now()
+            .saturating_duration_since(start)
+            .as_secs_f32()
+            * 1000.0
+    }
+
+    /// Drop all per-document state before a new document is loaded: resource caches (brushes,
+    /// images, font faces, shadows), the recorded scene, damage tracking and frame counters. The
+    /// first-frame metrics and init window are re-armed as in `restart_initial_measurement`. The
+    /// D3D/D2D devices, swapchain and backbuffer are kept.
+    pub fn reset_for_new_document(&mut self) {
+        self.font_face_cache.clear();
+        self.gradient_cache.clear();
+        self.stroke_style_cache.clear();
+        self.clear_image_cache();
+        self.sweep_cache.clear();
+        self.clear_shadow_cache();
+        self.scene.reset();
+        self.scrollbar_thumb = None;
+        self.dirty_rect = None;
+        self.last_damage = None;
+        self.frame_result = FrameResult::default();
+        self.full_repaint = true;
+        self.debug_shadow_logs = 0;
+        self.last_command_count = 0;
+        self.last_command_stats = CommandStats::default();
+        self.playback_ms = 0.0;
+        self.frame_start = self.clock.now();
+        self.fps_accum_time = 0.0;
+        self.fps_frame_count = 0;
+        self.fps = 0.0;
+        self.last_frame_metrics = FrameTimings::default();
+        self.restart_initial_measurement();
+    }
+
+    /// Latest pipeline phase timings plus the D2D/host-side instrumentation as a JSON object
+    /// (the same numbers the debug overlay shows).
+    pub fn frame_timings_json(&self) -> String {
+        format!(
+            "{{{},\"first_frame_ms\":{},\"d2d_device_init_ms\":{},\"backbuffer_create_ms\":{},\"d2d_playback_ms\":{},\"host_init_ms\":{},\"host_dxgi_d3d_ms\":{},\"host_swapchain_ms\":{},\"host_panel_attach_queue_ms\":{},\"host_panel_attach_exec_ms\":{},\"host_first_text_init_ms\":{},\"fps\":{},\"command_count\":{},\"device_lost_count\":{}}}",
+            self.last_frame_metrics.json_fields(),
+            JsonNumber(self.first_frame_ms),
+            JsonNumber(self.device_init_ms),
+            JsonNumber(self.backbuffer_create_ms),
+            JsonNumber(self.playback_ms),
+            JsonNumber(self.host_init_ms),
+            JsonNumber(self.host_dxgi_d3d_ms),
+            JsonNumber(self.host_swapchain_ms),
+            JsonNumber(self.host_panel_attach_queue_ms),
+            JsonNumber(self.host_panel_attach_exec_ms),
+            JsonNumber(self.host_first_text_init_ms),
+            JsonNumber(self.fps),
+            self.last_command_count,
+            self.device_lost_count,
+        )
+    }
+
+    pub fn last_command_count(&self) -> u32 {
+        self.last_command_count
+    }
+
+    /// Per-kind command counts of the last frame, plus the clip depth and culling seen during its playback.
+    pub fn last_command_stats(&self) -> CommandStats {
+        self.last_command_stats
+    }
+
+    /// Whether `render` drew a frame since the last call, and which region of the backbuffer changed.
+    /// Hosts call this after `render` and only Present when `needs_present` is set; skipped frames
+    /// (inactive renderer, missing device or backbuffer, failed EndDraw) leave nothing to present.
+    pub fn take_frame_result(&mut self) -> FrameResult {
+        std::mem::take(&mut self.frame_result)
+    }
+
+    /// Maximum number of blurred box-shadow bitmaps kept for reuse (default 64); the oldest are evicted first.
+    /// Shrinking below the current size evicts immediately; 0 disables caching.
+    pub fn set_shadow_cache_capacity(&mut self, capacity: usize) {
+        self.shadow_cache_capacity = capacity;
+        evict_to_capacity(
+            &mut self.shadow_cache,
+            &mut self.shadow_cache_order,
+            capacity,
+        );
+    }
+
+    /// Release cached device resources under memory pressure. The recorded scene is kept and the next frame
+    /// recreates whatever it needs, so nothing has to be repainted.
+    pub fn trim_memory(&mut self, level: TrimLevel) {
+        self.gradient_cache.clear();
+        self.clear_image_cache();
+        self.sweep_cache.clear();
+        if level == TrimLevel::Aggressive {
+            self.clear_shadow_cache();
+            self.font_face_cache.clear();
+            self.stroke_style_cache.clear();
+            if let Some(device) = &self.d2d_device {
+                unsafe { device.ClearResources(0) };
+            }
+        }
+        vlog!("trim_memory: level={:?}", level);
+    }
+
+    /// Drop all cached shadow bitmaps, e.g. when navigating. Also done by `reset_for_new_document`.
+    pub fn clear_shadow_cache(&mut self) {
+        self.shadow_cache.clear();
+        self.shadow_cache_order.clear();
+    }
+
+    fn clear_image_cache(&mut self) {
+        self.image_cache.clear();
+        self.image_cache_order.clear();
+    }
+
+    pub fn set_test_pattern(&mut self, on: bool) {
+        self.test_pattern = on;
+    }
+    pub fn set_debug_overlay(&mut self, on: bool) {
+        self.show_debug_overlay = on;
+    }
+    /// Choose what the debug overlay shows, where and how opaque. BLITZ_DISABLE_OVERLAY=1 still hides it.
+    pub fn set_overlay_options(&mut self, options: OverlayOptions) {
+        self.overlay_options = OverlayOptions {
+            opacity: options.opacity.clamp(0.0, 1.0),
+            max_width: options.max_width.max(1.0),
+            ..options
+        };
+    }
+    pub fn overlay_options(&self) -> &OverlayOptions {
+        &self.overlay_options
+    }
+    /// Anchor the debug overlay to a corner of the target (top-left by default).
+    pub fn set_overlay_corner(&mut self, corner: OverlayCorner) {
+        self.overlay_options.corner = corner;
+    }
+    /// Width (logical px) at which the debug overlay text wraps; it also never exceeds the target.
+    pub fn set_overlay_max_width(&mut self, width: f32) {
+        self.overlay_options.max_width = width.max(1.0);
+    }
+    /// Set the color the target is cleared to at the start of each frame, typically the
+    /// document's root/body background. `None` restores the opaque white fallback.
+    pub fn set_clear_color(&mut self, color: Option<Color>) {
+        self.clear_color = color;
+    }
+    /// Set the color of frames that have no scene commands, e.g. while a document loads. Defaults
+    /// to transparent so the composited panel shows the host's own background instead of a flash
+    /// of white.
+    pub fn set_empty_background(&mut self, color: Color) {
+        self.empty_background = color;
+    }
+    /// Select how glyph runs are antialiased. Defaults to grayscale since the composited
+    /// swapchain surface has alpha and ClearType fringes over it.
+    pub fn set_text_antialias_mode(&mut self, mode: TextAntialiasMode) {
+        self.text_antialias_mode = mode;
+    }
+    /// Select how geometry (fills, strokes, layer clips) is antialiased. Independent of the text mode.
+    pub fn set_geometry_antialias(&mut self, mode: GeometryAntialiasMode) {
+        self.geometry_antialias_mode = mode;
+    }
+    /// Set the device scale (backbuffer px per scene px). Scenes are recorded in logical px and
+    /// magnified by this factor at playback.
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = if scale > 0.0 { scale } else { 1.0 };
+    }
+
+    /// Snap axis-aligned rectangle fills and rectangle strokes (edges and stroke width) to whole
+    /// device pixels so 1px borders and rules stay crisp at fractional scales. Curved or rotated
+    /// geometry is drawn unchanged. Off by default.
+    pub fn set_pixel_snap(&mut self, enabled: bool) {
+        self.pixel_snap = enabled;
+    }
+
+    /// Human-readable listing of the commands recorded so far for the next frame (one per line:
+    /// path element counts, brush kinds, glyph runs, shadows, clips). Empty after playback.
+    pub fn dump_scene(&self) -> String {
+        dump_commands(&self.scene.commands)
+    }
+
+    /// Keep a `dump_scene` listing of each frame's commands as playback consumes them.
+    pub fn set_scene_capture(&mut self, enabled: bool) {
+        self.capture_scene = enabled;
+        if !enabled {
+            self.last_scene_dump.clear();
+        }
+    }
+
+    /// Listing of the last replayed frame; empty unless scene capture was on for that frame.
+    pub fn last_scene_dump(&self) -> &str {
+        &self.last_scene_dump
+    }
+
+    /// Mark a region (logical px) as changed. If only marked regions changed since the last frame,
+    /// the next frame clips clearing and replay to them so Direct2D skips untouched pixels.
+    pub fn mark_dirty(&mut self, rect: Rect) {
+        self.dirty_rect = Some(self.dirty_rect.map_or(rect, |d| d.union(rect)));
+    }
+
+    /// Force the next frame to repaint the whole target.
+    pub fn mark_full(&mut self) {
+        self.full_repaint = true;
+    }
+
+    /// Whether anything has been marked since the last frame.
+    pub fn is_dirty(&self) -> bool {
+        self.full_repaint || self.dirty_rect.is_some()
+    }
+
+    // Region the coming frame must repaint (None = everything); consumes this frame's damage.
+    fn take_repaint_clip(&mut self) -> Option<Rect> {
+        let full = self.full_repaint || self.backbuffer_dirty;
+        let clip = repaint_clip(full, self.dirty_rect, self.last_damage);
+        self.last_damage = if full { None } else { self.dirty_rect };
+        self.dirty_rect = None;
+        self.full_repaint = false;
+        clip
     }
 
-    pub fn set_test_pattern(&mut self, on: bool) {
-        self.test_pattern = on;
+    /// Overlay scrollbar thumb (logical px) drawn above the scene; None hides it.
+    pub fn set_scrollbar_thumb(&mut self, thumb: Option<Rect>) {
+        self.scrollbar_thumb = thumb;
     }
-    pub fn set_debug_overlay(&mut self, on: bool) {
-        self.show_debug_overlay = on;
+
+    /// Highlight the box model set with `set_inspect_box` over the scene, like browser devtools.
+    pub fn set_inspect_overlay(&mut self, enabled: bool) {
+        self.inspect_overlay = enabled;
+    }
+
+    /// Box model of the element under inspection (typically the hovered node); None clears it.
+    pub fn set_inspect_box(&mut self, inspect_box: Option<InspectBox>) {
+        self.inspect_box = inspect_box;
     }
 
     pub fn set_swapchain(&mut self, sc: IDXGISwapChain1, width: u32, height: u32) {
@@ -621,6 +2637,17 @@ impl D2DWindowRenderer {
         self.active = true;
     }
 
+    /// Whether the Direct2D device context came up on the swapchain's device. False means the swapchain's
+    /// D3D device couldn't back Direct2D and frames won't render.
+    pub fn is_device_ready(&self) -> bool {
+        self.d2d_ctx.is_some()
+    }
+
+    /// Whether the DirectWrite factory was created; without it no text renders.
+    pub fn is_text_ready(&self) -> bool {
+        self.dwrite_factory.is_some()
+    }
+
     pub fn accumulate_host_init_ms(&mut self, ms: f32) {
         // Only accumulate while first frame still measuring
         if !self.first_frame_done {
@@ -666,83 +2693,211 @@ impl D2DWindowRenderer {
     }
 
     fn init_devices_from_swapchain(&mut self) {
-        let t0 = Instant::now();
+        let t0 = self.clock.now();
         if let Some(sc) = &self.swapchain {
             unsafe {
                 // Get D3D11 device from swapchain
                 if let Ok(tex) = sc.GetBuffer::<ID3D11Texture2D>(0) {
                     let res: &ID3D11Resource = (&tex).into();
                     if let Ok(dev) = res.GetDevice() {
-                        self.d3d_device = Some(dev.clone());
-                        // Create D2D device via DXGI device
-                        if let Ok(dxgi_dev) = dev.cast::<IDXGIDevice>() {
-                            // Create D2D factory
-                            if let Ok(factory) = D2D1CreateFactory::<ID2D1Factory1>(
-                                D2D1_FACTORY_TYPE_MULTI_THREADED,
-                                None,
-                            ) {
-                                self.d2d_factory = Some(factory.clone());
-                                if let Ok(d2d_dev) = factory.CreateDevice(&dxgi_dev) {
-                                    if let Ok(ctx) = d2d_dev
-                                        .CreateDeviceContext(D2D1_DEVICE_CONTEXT_OPTIONS_NONE)
-                                    {
-                                        self.d2d_device = Some(d2d_dev);
-                                        self.d2d_ctx = Some(ctx);
-                                        // DirectWrite factory
-                                        if let Ok(dwf) = DWriteCreateFactory::<IDWriteFactory>(
-                                            DWRITE_FACTORY_TYPE_SHARED,
-                                        ) {
-                                            self.dwrite_factory = Some(dwf.clone());
-                                            // Create a default font face (Segoe UI) for glyph runs.
-                                            let mut collection: Option<IDWriteFontCollection> =
-                                                None;
-                                            if dwf
-                                                .GetSystemFontCollection(&mut collection, false)
-                                                .is_ok()
-                                            {
-                                                if let Some(collection) = collection {
-                                                    let mut idx = 0u32;
-                                                    let mut exists = false.into();
-                                                    if collection
-                                                        .FindFamilyName(
-                                                            windows::core::w!("Segoe UI"),
-                                                            &mut idx,
-                                                            &mut exists,
-                                                        )
-                                                        .is_ok()
-                                                        && exists.as_bool()
-                                                    {
-                                                        if let Ok(family) =
-                                                            collection.GetFontFamily(idx)
-                                                        {
-                                                            if let Ok(font) = family
-                                                                .GetFirstMatchingFont(
-                                                                    DWRITE_FONT_WEIGHT_NORMAL,
-                                                                    DWRITE_FONT_STRETCH_NORMAL,
-                                                                    DWRITE_FONT_STYLE_NORMAL,
-                                                                )
-                                                            {
-                                                                if let Ok(face) =
-                                                                    font.CreateFontFace()
-                                                                {
-                                                                    self.dwrite_font_face =
-                                                                        Some(face);
-                                                                }
-                                                            }
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
+                        self.init_devices_from_d3d(dev);
+                    }
+                }
+            }
+        }
+        self.device_init_ms = self.elapsed_ms(t0);
+    }
+
+    // Create the D2D factory/device/context and DirectWrite objects on top of a D3D11 device.
+    fn init_devices_from_d3d(&mut self, dev: ID3D11Device) {
+        self.d3d_device = Some(dev.clone());
+        unsafe {
+            // Create D2D device via DXGI device
+            if let Ok(dxgi_dev) = dev.cast::<IDXGIDevice>() {
+                // Create D2D factory
+                if let Ok(factory) =
+                    D2D1CreateFactory::<ID2D1Factory1>(factory_type(self.options), None)
+                {
+                    self.d2d_factory = Some(factory.clone());
+                    if let Ok(d2d_dev) = factory.CreateDevice(&dxgi_dev) {
+                        if let Ok(ctx) =
+                            d2d_dev.CreateDeviceContext(D2D1_DEVICE_CONTEXT_OPTIONS_NONE)
+                        {
+                            self.d2d_device = Some(d2d_dev);
+                            self.d2d_ctx = Some(ctx);
+                            // DirectWrite factory
+                            if let Ok(dwf) =
+                                DWriteCreateFactory::<IDWriteFactory>(DWRITE_FACTORY_TYPE_SHARED)
+                            {
+                                self.dwrite_factory = Some(dwf);
+                                // Default font face for glyph runs whose font can't be resolved
+                                self.load_default_font_face();
                             }
                         }
                     }
                 }
             }
         }
-        self.device_init_ms = t0.elapsed().as_secs_f32() * 1000.0;
+    }
+
+    // Headless path: create a WARP (software) D3D device when no swapchain provided one.
+    fn ensure_headless_devices(&mut self) -> bool {
+        if self.d2d_ctx.is_some() {
+            return true;
+        }
+        let t0 = self.clock.now();
+        let mut device: Option<ID3D11Device> = None;
+        let hr = unsafe {
+            D3D11CreateDevice(
+                None,
+                D3D_DRIVER_TYPE_WARP,
+                None,
+                D3D11_CREATE_DEVICE_BGRA_SUPPORT,
+                None,
+                D3D11_SDK_VERSION,
+                Some(&mut device),
+                None,
+                None,
+            )
+        };
+        match (hr, device) {
+            (Ok(()), Some(dev)) => self.init_devices_from_d3d(dev),
+            (res, _) => {
+                debug_log_d2d(&format!(
+                    "ensure_headless_devices: WARP device creation failed {:?}",
+                    res
+                ));
+                return false;
+            }
+        }
+        self.device_init_ms = self.elapsed_ms(t0);
+        self.d2d_ctx.is_some()
+    }
+
+    /// Render a scene offscreen and read it back as tightly packed premultiplied RGBA8 rows.
+    ///
+    /// Works without a swapchain (a WARP device is created if none is set), which makes it usable
+    /// for golden-image tests and screenshots. Returns an empty buffer if the device or target
+    /// bitmap cannot be created.
+    pub fn render_to_rgba<F: FnOnce(&mut D2DScenePainter<'_>)>(
+        &mut self,
+        width: u32,
+        height: u32,
+        draw_fn: F,
+    ) -> Vec<u8> {
+        let width = width.max(1);
+        let height = height.max(1);
+        if !self.ensure_headless_devices() {
+            return Vec::new();
+        }
+        {
+            let mut painter = D2DScenePainter {
+                scene: &mut self.scene,
+            };
+            painter.reset();
+            draw_fn(&mut painter);
+        }
+        let ctx = self.d2d_ctx.clone().unwrap();
+        let size = D2D_SIZE_U { width, height };
+        let pixel_format = D2D1_PIXEL_FORMAT {
+            format: DXGI_FORMAT_B8G8R8A8_UNORM,
+            alphaMode: D2D1_ALPHA_MODE_PREMULTIPLIED,
+        };
+        let target_props = D2D1_BITMAP_PROPERTIES1 {
+            pixelFormat: pixel_format,
+            dpiX: 96.0,
+            dpiY: 96.0,
+            bitmapOptions: D2D1_BITMAP_OPTIONS_TARGET,
+            colorContext: std::mem::ManuallyDrop::new(None::<ID2D1ColorContext>),
+        };
+        let readback_props = D2D1_BITMAP_PROPERTIES1 {
+            pixelFormat: pixel_format,
+            dpiX: 96.0,
+            dpiY: 96.0,
+            bitmapOptions: D2D1_BITMAP_OPTIONS_CPU_READ | D2D1_BITMAP_OPTIONS_CANNOT_DRAW,
+            colorContext: std::mem::ManuallyDrop::new(None::<ID2D1ColorContext>),
+        };
+        unsafe {
+            let target = match ctx.CreateBitmap(size, None, 0, &target_props) {
+                Ok(b) => b,
+                Err(e) => {
+                    debug_log_d2d(&format!(
+                        "render_to_rgba: target bitmap creation failed {:?}",
+                        e
+                    ));
+                    return Vec::new();
+                }
+            };
+            self.mark_full();
+            // Offscreen frames never reach the swapchain, so they leave the pending present alone
+            let pending = self.frame_result;
+            self.playback(&target);
+            self.frame_result = pending;
+            let _ = ctx.SetTarget(None::<&ID2D1Image>);
+            let readback = match ctx.CreateBitmap(size, None, 0, &readback_props) {
+                Ok(b) => b,
+                Err(e) => {
+                    debug_log_d2d(&format!(
+                        "render_to_rgba: readback bitmap creation failed {:?}",
+                        e
+                    ));
+                    return Vec::new();
+                }
+            };
+            if readback.CopyFromBitmap(None, &target, None).is_err() {
+                return Vec::new();
+            }
+            let mapped = match readback.Map(D2D1_MAP_OPTIONS_READ) {
+                Ok(m) => m,
+                Err(_) => return Vec::new(),
+            };
+            let row_bytes = width as usize * 4;
+            let mut out = Vec::with_capacity(row_bytes * height as usize);
+            for y in 0..height as usize {
+                let row = std::slice::from_raw_parts(
+                    mapped.bits.add(y * mapped.pitch as usize),
+                    row_bytes,
+                );
+                // BGRA -> RGBA
+                for px in row.chunks_exact(4) {
+                    out.extend_from_slice(&[px[2], px[1], px[0], px[3]]);
+                }
+            }
+            let _ = readback.Unmap();
+            out
+        }
+    }
+
+    /// Screenshot variant of [`render_to_rgba`](Self::render_to_rgba): the scene is magnified by
+    /// `scale` instead of the device scale, and the debug overlay, scrollbar thumb and inspect
+    /// highlight are left out. Renderer settings are restored afterwards, so this can run between
+    /// swapchain frames.
+    pub fn capture_to_rgba<F: FnOnce(&mut D2DScenePainter<'_>)>(
+        &mut self,
+        width: u32,
+        height: u32,
+        scale: f32,
+        draw_fn: F,
+    ) -> Vec<u8> {
+        let saved = (
+            self.scale,
+            self.show_debug_overlay,
+            self.scrollbar_thumb.take(),
+            self.inspect_overlay,
+        );
+        self.scale = scale;
+        self.show_debug_overlay = false;
+        self.inspect_overlay = false;
+        let pixels = self.render_to_rgba(width, height, draw_fn);
+        (
+            self.scale,
+            self.show_debug_overlay,
+            self.scrollbar_thumb,
+            self.inspect_overlay,
+        ) = saved;
+        // The next swapchain frame must repaint fully over the shared scene state
+        self.mark_full();
+        pixels
     }
 
     /// Release any bound D2D target (backbuffer bitmap) so the swapchain can ResizeBuffers.
@@ -754,6 +2909,49 @@ impl D2DWindowRenderer {
         }
     }
 
+    // The D2D device is gone (GPU reset, driver update): drop every device-bound object. Headless renderers
+    // re-run device init on a new WARP device next frame and repaint in full. The swapchain was created on the
+    // removed device, so it is dropped too and frames aren't drawn until the host hands over a new one.
+    fn handle_device_lost(&mut self) {
+        self.device_lost_count += 1;
+        debug_log_d2d(&format!(
+            "EndDraw: D2DERR_RECREATE_TARGET; recreating devices (loss #{})",
+            self.device_lost_count
+        ));
+        self.d2d_ctx = None;
+        self.d2d_device = None;
+        self.d2d_factory = None;
+        self.d3d_device = None;
+        self.backbuffer_bitmap = None;
+        self.gaussian_blur_effect = None;
+        self.gradient_cache.clear();
+        self.stroke_style_cache.clear();
+        self.clear_image_cache();
+        self.sweep_cache.clear();
+        self.clear_shadow_cache();
+        self.swapchain = None;
+        self.device_lost = true;
+        self.backbuffer_dirty = true;
+        self.full_repaint = true;
+    }
+
+    /// Whether the device was lost since the last call. A windowed host must then create a new D3D device and
+    /// swapchain and pass the swapchain to [`set_swapchain`](Self::set_swapchain).
+    pub fn take_device_lost(&mut self) -> bool {
+        std::mem::take(&mut self.device_lost)
+    }
+
+    /// Number of times the D2D device was lost (EndDraw returned D2DERR_RECREATE_TARGET) and rebuilt.
+    pub fn device_lost_count(&self) -> u32 {
+        self.device_lost_count
+    }
+
+    /// Test hook: report the next frame's EndDraw as D2DERR_RECREATE_TARGET to exercise device recovery.
+    #[doc(hidden)]
+    pub fn simulate_device_loss(&mut self) {
+        self.simulate_device_loss = true;
+    }
+
     /// Release target and cached backbuffer bitmap so the swapchain buffers can be resized.
     pub fn release_backbuffer_resources(&mut self) {
         if let Some(ctx) = &self.d2d_ctx {
@@ -768,7 +2966,9 @@ impl D2DWindowRenderer {
     }
 
     fn recreate_backbuffer_bitmap(&mut self, surface: &IDXGISurface) -> bool {
-        let t0 = Instant::now();
+        let t0 = self.clock.now();
+        // New buffer contents are undefined
+        self.full_repaint = true;
         self.backbuffer_bitmap = None;
         let ctx = match &self.d2d_ctx {
             Some(c) => c,
@@ -781,25 +2981,37 @@ impl D2DWindowRenderer {
             let mut dpi_x = 0.0f32;
             let mut dpi_y = 0.0f32;
             ctx.GetDpi(&mut dpi_x, &mut dpi_y);
-            if let Ok(desc) = surface.GetDesc() {
-                verbose_log_d2d(&format!(
-                    "recreate_backbuffer_bitmap: surface desc fmt={:?} w={} h={}",
-                    desc.Format, desc.Width, desc.Height
-                ));
-            }
-        // Preferred properties using current context DPI. Use ALPHA_MODE_IGNORE (opaque) to allow ClearType.
-            let props_ctx = D2D1_BITMAP_PROPERTIES1 {
-                pixelFormat: D2D1_PIXEL_FORMAT {
-                    format: DXGI_FORMAT_B8G8R8A8_UNORM,
-            alphaMode: D2D1_ALPHA_MODE_IGNORE,
-                },
-                dpiX: dpi_x,
-                dpiY: dpi_y,
-                bitmapOptions: D2D1_BITMAP_OPTIONS_TARGET,
-                colorContext: std::mem::ManuallyDrop::new(None::<ID2D1ColorContext>),
+            // Match the target format to the swapchain's (8-bit BGRA, sRGB, 10-bit, FP16) so the
+            // explicit properties are accepted first time; unknown formats go to the fallbacks.
+            let surface_format = match surface.GetDesc() {
+                Ok(desc) => {
+                    verbose_log_d2d(&format!(
+                        "recreate_backbuffer_bitmap: surface desc fmt={:?} w={} h={}",
+                        desc.Format, desc.Width, desc.Height
+                    ));
+                    target_pixel_format(desc.Format)
+                }
+                Err(_) => None,
             };
-            if let Ok(bmp) = ctx.CreateBitmapFromDxgiSurface(surface, Some(&props_ctx)) {
-                self.backbuffer_bitmap = Some(bmp);
+            // Preferred properties using current context DPI. Use ALPHA_MODE_IGNORE (opaque) to allow ClearType.
+            if let Some(format) = surface_format {
+                let props_ctx = D2D1_BITMAP_PROPERTIES1 {
+                    pixelFormat: D2D1_PIXEL_FORMAT {
+                        format,
+                        alphaMode: D2D1_ALPHA_MODE_IGNORE,
+                    },
+                    dpiX: dpi_x,
+                    dpiY: dpi_y,
+                    bitmapOptions: D2D1_BITMAP_OPTIONS_TARGET,
+                    colorContext: std::mem::ManuallyDrop::new(None::<ID2D1ColorContext>),
+                };
+                match ctx.CreateBitmapFromDxgiSurface(surface, Some(&props_ctx)) {
+                    Ok(bmp) => self.backbuffer_bitmap = Some(bmp),
+                    Err(e) => debug_log_d2d(&format!(
+                        "recreate_backbuffer_bitmap: {:?} target rejected {:?}",
+                        format, e
+                    )),
+                }
             }
             // Fallback: inherit surface props (Some drivers reject explicit props)
             if self.backbuffer_bitmap.is_none() {
@@ -807,7 +3019,7 @@ impl D2DWindowRenderer {
                     self.backbuffer_bitmap = Some(bmp_inherit);
                 }
             }
-            // Final fallback: force 96 DPI props
+            // Final fallback: force 96 DPI BGRA props
             if self.backbuffer_bitmap.is_none() {
                 let props_96 = D2D1_BITMAP_PROPERTIES1 {
                     pixelFormat: D2D1_PIXEL_FORMAT {
@@ -826,7 +3038,7 @@ impl D2DWindowRenderer {
         }
         let ok = self.backbuffer_bitmap.is_some();
         if ok {
-            self.backbuffer_create_ms = t0.elapsed().as_secs_f32() * 1000.0;
+            self.backbuffer_create_ms = self.elapsed_ms(t0);
         } else {
             debug_log_d2d("recreate_backbuffer_bitmap: all creation attempts failed");
         }
@@ -834,32 +3046,31 @@ impl D2DWindowRenderer {
     }
 
     fn playback(&mut self, target: &ID2D1Bitmap1) {
-        let t0 = Instant::now();
+        let t0 = self.clock.now();
         let ctx = match &self.d2d_ctx {
             Some(ctx) => ctx.clone(),
             None => return,
         };
-        // Allow enabling verbose logging dynamically via environment.
-        if std::env::var("BLITZ_VERBOSE")
-            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
-            .unwrap_or(false)
-        {
-            set_verbose_logging(true);
-        }
-
         unsafe {
             ctx.BeginDraw();
             // SetTarget exists on ID2D1DeviceContext
             let _ = ctx.SetTarget(target);
-            // Configure antialiasing + ClearType after binding target (Step C)
-            let _ = ctx.SetAntialiasMode(D2D1_ANTIALIAS_MODE_PER_PRIMITIVE);
-            let _ = ctx.SetTextAntialiasMode(D2D1_TEXT_ANTIALIAS_MODE_CLEARTYPE);
+            // Scene coordinates are logical (CSS) px; magnify uniformly to the device-scaled target.
+            ctx.SetTransform(&affine_to_matrix(Affine::scale(self.scale as f64)));
+            // Configure antialiasing + text antialias mode after binding target (Step C)
+            let geometry_aa = self.geometry_antialias_mode.to_d2d();
+            let _ = ctx.SetAntialiasMode(geometry_aa);
+            let text_aa = self.text_antialias_mode.to_d2d();
+            let _ = ctx.SetTextAntialiasMode(text_aa);
             let actual_mode = ctx.GetTextAntialiasMode();
-            if actual_mode != D2D1_TEXT_ANTIALIAS_MODE_CLEARTYPE {
-                debug_log_d2d(&format!("playback: requested CLEARTYPE but got {:?}", actual_mode));
+            if actual_mode != text_aa {
+                debug_log_d2d(&format!(
+                    "playback: requested text AA {:?} but got {:?}",
+                    text_aa, actual_mode
+                ));
             }
-            // Clear: previously we filled with transparent which caused full window transparency when scene content lacked opaque background.
-            // Use an opaque fallback (white) so something is always visible; later we can sample actual page background color.
+            // Clear to the document canvas background supplied by the host (see set_clear_color), or
+            // to the empty background when there is nothing to draw (see frame_clear_color).
             let size = target.GetSize();
             let full = D2D_RECT_F {
                 left: 0.0,
@@ -867,17 +3078,61 @@ impl D2DWindowRenderer {
                 right: size.width,
                 bottom: size.height,
             };
-            let fallback_bg_brush = self.create_solid_brush(Color::WHITE); // TODO: replace with document root background
-            let _ = ctx.FillRectangle(&full, &fallback_bg_brush);
-            vlog!("fallback bg {}x{}", size.width as u32, size.height as u32);
+            let opaque_target = target.GetPixelFormat().alphaMode == D2D1_ALPHA_MODE_IGNORE;
+            // Partial repaint: restrict Clear and replay to the damaged region (popped before the overlay).
+            let repaint_clip = self.take_repaint_clip();
+            if let Some(r) = repaint_clip {
+                vlog!(
+                    "playback: partial repaint ({}, {}, {}, {})",
+                    r.x0,
+                    r.y0,
+                    r.x1,
+                    r.y1
+                );
+                let r = r.expand();
+                ctx.PushAxisAlignedClip(
+                    &D2D_RECT_F {
+                        left: r.x0 as f32,
+                        top: r.y0 as f32,
+                        right: r.x1 as f32,
+                        bottom: r.y1 as f32,
+                    },
+                    D2D1_ANTIALIAS_MODE_ALIASED,
+                );
+            }
+            let clear = frame_clear_color(
+                self.scene.commands.is_empty() && !self.test_pattern,
+                self.clear_color,
+                self.empty_background,
+                opaque_target,
+            );
+            let _ = ctx.Clear(Some(&d2d_color(clear)));
+            vlog!(
+                "clear bg {}x{} rgba={:?}",
+                size.width as u32,
+                size.height as u32,
+                clear.components
+            );
             // (Removed always-on debug rect; keep codebase clean. Use VERBOSE logs for diagnostics.)
             // Reset per-frame debug counters
             self.debug_shadow_logs = 0;
 
             // Collect commands to avoid borrow checker issues
             let commands = std::mem::take(&mut self.scene.commands);
+            if self.capture_scene {
+                self.last_scene_dump = dump_commands(&commands);
+            }
             let command_count = commands.len();
             self.last_command_count = command_count as u32;
+            let mut command_stats = CommandStats::from_commands(&commands);
+            let commands = coalesce_glyph_runs(commands);
+            if commands.len() < command_count {
+                vlog!(
+                    "playback: coalesced glyph runs {} -> {} cmds",
+                    command_count,
+                    commands.len()
+                );
+            }
             if command_count == 0 {
                 vlog!("playback: 0 cmds");
             } else {
@@ -888,12 +3143,8 @@ impl D2DWindowRenderer {
                 let dbg = self.create_solid_brush(Color::new([0.92, 0.92, 0.95, 1.0]));
                 let _ = ctx.FillRectangle(&full, &dbg);
             }
-            let shadow_count = commands
-                .iter()
-                .filter(|c| matches!(c, Command::BoxShadow { .. }))
-                .count();
-            if shadow_count > 0 {
-                vlog!("shadows: {}", shadow_count);
+            if command_stats.shadows > 0 {
+                vlog!("shadows: {}", command_stats.shadows);
             }
 
             // Diagnostic test pattern if no commands (placeholder frame visibility)
@@ -949,7 +3200,20 @@ impl D2DWindowRenderer {
             let mut fill_path_count = 0u32;
             let mut stroke_path_count = 0u32;
             let mut clip_depth: i32 = 0;
+            // What each open PushLayer pushed, so pops unwind the matching D2D call
+            let mut layer_kinds: Vec<LayerKind> = Vec::new();
             let mut max_clip_depth: i32 = 0;
+            let mut dropped_clips = 0u32;
+            // Visible scene-space rect per open layer (top = current), for culling off-screen commands
+            let viewport = Rect::new(
+                0.0,
+                0.0,
+                (size.width / self.scale) as f64,
+                (size.height / self.scale) as f64,
+            );
+            let mut visible_stack: Vec<Rect> =
+                vec![repaint_clip.map_or(viewport, |r| r.intersect(viewport))];
+            let mut culled_count = 0u32;
             // Isolation flags
             // Pruned experimental env toggles; retain only minimal isolation switches.
             let disable_clips = false; // clip stack stable
@@ -957,10 +3221,10 @@ impl D2DWindowRenderer {
             let recreate_effect_per_shadow = false; // effect reused
             let disable_inset_shadows = false; // inset stable
             // Experimental flag: use GDI Classic measuring for small font sizes for potentially crisper small text.
-            let use_gdi_for_small = std::env::var("BLITZ_EXPERIMENT_GDI_SMALL_TEXT").map(|v| v=="1" || v.eq_ignore_ascii_case("true")).unwrap_or(false);
+            let use_gdi_for_small = env_flags().gdi_small_text;
             // Baseline snapping strategy: integer (default historic), half, or auto (choose .5 if frac in [0.25,0.75])
             // Env: BLITZ_TEXT_BASELINE_SNAP = integer | half | auto
-            let baseline_mode = std::env::var("BLITZ_TEXT_BASELINE_SNAP").unwrap_or_else(|_| "auto".to_string());
+            let baseline_mode = &env_flags().baseline_snap;
             let snap_baseline = |y: f32| -> f32 {
                 match baseline_mode.as_str() {
                     // Always round to nearest whole device pixel
@@ -978,12 +3242,31 @@ impl D2DWindowRenderer {
             };
             for (cmd_index, cmd) in commands.into_iter().enumerate() {
                 // max command limit feature removed (kept simpler playback path)
+                let visible = *visible_stack.last().unwrap();
+                // Layers past MAX_CLIP_DEPTH are skipped in playback and don't clip, so they mustn't
+                // narrow the cull rect either
+                let layer_clips = !disable_clips && visible_stack.len() <= MAX_CLIP_DEPTH;
+                match &cmd {
+                    Command::PushLayer { rect, .. } if layer_clips => {
+                        visible_stack.push(visible.intersect(*rect))
+                    }
+                    Command::PushLayer { .. } => visible_stack.push(visible),
+                    Command::PopLayer if visible_stack.len() > 1 => {
+                        visible_stack.pop();
+                    }
+                    _ if is_culled(&cmd, visible) => {
+                        culled_count += 1;
+                        continue;
+                    }
+                    _ => {}
+                }
                 vlog!(
                     "cmd {} {}",
                     cmd_index,
                     match &cmd {
                         Command::FillPath { .. } => "FillPath",
                         Command::StrokePath { .. } => "StrokePath",
+                        Command::DecorationLine { .. } => "DecorationLine",
                         Command::PushLayer { .. } => "PushLayer",
                         Command::PopLayer => "PopLayer",
                         Command::BoxShadow { inset, .. } =>
@@ -993,28 +3276,56 @@ impl D2DWindowRenderer {
                                 "BoxShadow"
                             },
                         Command::GlyphRun { .. } => "GlyphRun",
+                        Command::GlyphShadow { .. } => "GlyphShadow",
+                        Command::BackdropBlur { .. } => "BackdropBlur",
                     }
                 );
                 match cmd {
-                    Command::FillPath { path, brush } => {
+                    Command::FillPath { path, brush, fill } => {
                         fill_path_count += 1;
                         if let RecordedBrush::Image(img) = &brush {
                             // Build geometry to honor any complex shape / potential future rounded corners.
-                            if let Some(geom) = self.build_path_geometry(&path) {
+                            if let Some(geom) = self.build_path_geometry(&path, Some(fill)) {
                                 // Bounds give target box (CSS layout size already applied in path coordinates).
                                 let bounds = geom.GetBounds(None).unwrap_or(D2D_RECT_F{ left:0.0, top:0.0, right:0.0, bottom:0.0 });
                                 let w = bounds.right - bounds.left;
                                 let h = bounds.bottom - bounds.top;
-                                if w > 0.5 && h > 0.5 {
-                                    let bitmap = self.get_or_create_image_bitmap(img);
-                                    // Optional clip to geometry (handles non-rect paths); keep simple axis clip when rectangular.
-                                    // Detect rectangular by comparing path bbox to layout; if not exact we can push clip.
-                                    let dest = bounds; // scale bitmap to fit dest
-                                    ctx.DrawBitmap(&bitmap, Some(&dest), img.alpha, D2D1_INTERPOLATION_MODE_LINEAR, None, None);
+                                if w > 0.5 && h > 0.5 && !fills_single_image(&path, img) {
+                                    // Tiled (or transformed) image: fill the shape with a bitmap brush placed by
+                                    // the image transform, rescaled if the bitmap was downscaled on upload
+                                    if let Some(bitmap) = self.get_or_create_image_bitmap(img) {
+                                        let bitmap_size = bitmap.GetSize();
+                                        let to_bitmap = Affine::scale_non_uniform(
+                                            img.width as f64 / bitmap_size.width.max(1.0) as f64,
+                                            img.height as f64 / bitmap_size.height.max(1.0) as f64,
+                                        );
+                                        let props = D2D1_BITMAP_BRUSH_PROPERTIES {
+                                            extendModeX: d2d_extend_mode(img.x_extend),
+                                            extendModeY: d2d_extend_mode(img.y_extend),
+                                            interpolationMode: d2d_bitmap_interpolation_mode(img.quality),
+                                        };
+                                        let brush_props = D2D1_BRUSH_PROPERTIES { opacity: img.alpha, transform: affine_to_matrix(img.transform * to_bitmap) };
+                                        if let Ok(bitmap_brush) = ctx.CreateBitmapBrush(&bitmap, Some(&props), Some(&brush_props)) {
+                                            ctx.FillGeometry(&geom, &bitmap_brush, None);
+                                        }
+                                    }
+                                } else if w > 0.5 && h > 0.5 {
+                                    if let Some(bitmap) = self.get_or_create_image_bitmap(img) {
+                                        // Optional clip to geometry (handles non-rect paths); keep simple axis clip when rectangular.
+                                        // Detect rectangular by comparing path bbox to layout; if not exact we can push clip.
+                                        let dest = bounds; // scale bitmap to fit dest
+                                        // Source rects are in image pixels; the bitmap may have been downscaled
+                                        let bitmap_size = bitmap.GetSize();
+                                        let source = img.source_rect.map(|r| {
+                                            let (sx, sy) = (bitmap_size.width as f64 / img.width as f64, bitmap_size.height as f64 / img.height as f64);
+                                            D2D_RECT_F { left: (r.x0 * sx) as f32, top: (r.y0 * sy) as f32, right: (r.x1 * sx) as f32, bottom: (r.y1 * sy) as f32 }
+                                        });
+                                        ctx.DrawBitmap(&bitmap, Some(&dest), img.alpha, d2d_interpolation_mode(img.quality), source.as_ref().map(|r| r as *const _), None);
+                                    }
                                 }
                             }
-                        } else if let Some(geom) = self.build_path_geometry(&path) {
-                            let brush_obj = self.get_or_create_brush(&brush);
+                        } else if let Some(geom) = self.build_path_geometry(&path, Some(fill)) {
+                            let brush_obj = self.get_or_create_brush(&brush, path_bounds(&path));
                             if fill_path_count <= 8 {
                                 if let Ok(sol) = brush_obj.cast::<ID2D1SolidColorBrush>() {
                                     let col = sol.GetColor();
@@ -1023,6 +3334,16 @@ impl D2DWindowRenderer {
                                     vlog!("FillPath idx={} cmd={} (non-solid)", fill_path_count, cmd_index);
                                 }
                             }
+                            if self.pixel_snap && is_axis_aligned_rect(&path) {
+                                if let Some(b) = path_bounds(&path) {
+                                    let r = snap_rect_to_device(b, self.scale as f64);
+                                    ctx.FillRectangle(
+                                        &D2D_RECT_F { left: r.x0 as f32, top: r.y0 as f32, right: r.x1 as f32, bottom: r.y1 as f32 },
+                                        &brush_obj,
+                                    );
+                                    continue;
+                                }
+                            }
                             // Attempt rectangle snapping: if geometry bounds form an axis-aligned rect very close to integer edges, snap to avoid half-pixel fill blur.
                             let bounds = geom.GetBounds(None).unwrap_or(D2D_RECT_F{ left:0.0, top:0.0, right:0.0, bottom:0.0 });
                             let mut snapped = false;
@@ -1031,7 +3352,7 @@ impl D2DWindowRenderer {
                             let r_round = bounds.right.round();
                             let b_round = bounds.bottom.round();
                             let eps = 0.01; // tolerance in px
-                            if (bounds.left - l_round).abs() < eps && (bounds.top - t_round).abs() < eps && (bounds.right - r_round).abs() < eps && (bounds.bottom - b_round).abs() < eps {
+                            if is_axis_aligned_rect(&path) && (bounds.left - l_round).abs() < eps && (bounds.top - t_round).abs() < eps && (bounds.right - r_round).abs() < eps && (bounds.bottom - b_round).abs() < eps {
                                 // Only snap if width/height are >= 1 to avoid collapsing hairlines unexpectedly
                                 if (r_round - l_round) >= 1.0 && (b_round - t_round) >= 1.0 {
                                     let rect = D2D_RECT_F { left: l_round, top: t_round, right: r_round, bottom: b_round };
@@ -1042,10 +3363,58 @@ impl D2DWindowRenderer {
                             if !snapped { let _ = ctx.FillGeometry(&geom, &brush_obj, None); }
                         }
                     }
-                    Command::StrokePath { path, brush, width } => {
+                    Command::DecorationLine { x0, x1, y, width, brush } => {
+                        stroke_path_count += 1;
+                        // Filled as a device-pixel-snapped rect at least one pixel thick so underlines stay crisp
+                        // and don't fade out at small font sizes.
+                        let rect = snap_decoration_rect((x0, x1, y), width, self.scale as f64);
+                        let brush = self.get_or_create_brush(&brush, Some(rect));
+                        ctx.FillRectangle(
+                            &D2D_RECT_F { left: rect.x0 as f32, top: rect.y0 as f32, right: rect.x1 as f32, bottom: rect.y1 as f32 },
+                            &brush,
+                        );
+                    }
+                    Command::StrokePath { path, brush, width, style } => {
                         stroke_path_count += 1;
-                        if let Some(geom) = self.build_path_geometry(&path) {
-                            let brush = self.get_or_create_brush(&brush);
+                        let stroke_style = self.get_or_create_stroke_style(style);
+                        // Zero-length subpaths don't stroke in D2D; draw their caps (dot/square) explicitly per SVG.
+                        let (path, dots) = split_degenerate_subpaths(&path);
+                        if !dots.is_empty() && style.start_cap != D2D1_CAP_STYLE_FLAT.0 {
+                            let dot_bounds = dots
+                                .iter()
+                                .fold(None::<Rect>, |acc, p| Some(acc.map_or(Rect::from_points(*p, *p), |b| b.union_pt(*p))))
+                                .map(|b| b.inflate(width / 2.0, width / 2.0));
+                            let dot_brush = self.get_or_create_brush(&brush, dot_bounds);
+                            let r = (width / 2.0) as f32;
+                            for p in &dots {
+                                let (x, y) = (p.x as f32, p.y as f32);
+                                if style.start_cap == D2D1_CAP_STYLE_ROUND.0 {
+                                    let e = D2D1_ELLIPSE { point: D2D_POINT_2F { x, y }, radiusX: r, radiusY: r };
+                                    let _ = ctx.FillEllipse(&e, &dot_brush);
+                                } else {
+                                    let sq = D2D_RECT_F { left: x - r, top: y - r, right: x + r, bottom: y + r };
+                                    let _ = ctx.FillRectangle(&sq, &dot_brush);
+                                }
+                            }
+                        }
+                        if path.is_empty() {
+                            continue;
+                        }
+                        if self.pixel_snap && is_axis_aligned_rect(&path) {
+                            if let Some(b) = path_bounds(&path) {
+                                let (r, w) = snap_stroke_rect(b, width, self.scale as f64);
+                                let brush = self.get_or_create_brush(&brush, Some(r.inflate(w / 2.0, w / 2.0)));
+                                ctx.DrawRectangle(
+                                    &D2D_RECT_F { left: r.x0 as f32, top: r.y0 as f32, right: r.x1 as f32, bottom: r.y1 as f32 },
+                                    &brush,
+                                    w as f32,
+                                    stroke_style.as_ref(),
+                                );
+                                continue;
+                            }
+                        }
+                        if let Some(geom) = self.build_path_geometry(&path, None) {
+                            let brush = self.get_or_create_brush(&brush, path_bounds(&path).map(|b| b.inflate(width / 2.0, width / 2.0)));
                             // Stroke rectangle snapping heuristic: shift geometry by +/-0.5 when beneficial for crisp pixel alignment.
                             let mut xs: Vec<f64> = Vec::new();
                             let mut ys: Vec<f64> = Vec::new();
@@ -1091,33 +3460,59 @@ impl D2DWindowRenderer {
                                         PathEl::ClosePath => shifted.push(PathEl::ClosePath),
                                     }
                                 }
-                                if let Some(shifted_geom) = self.build_path_geometry(&shifted) {
+                                if let Some(shifted_geom) = self.build_path_geometry(&shifted, None) {
                                     vlog!("StrokePath snap dx={:.2} dy={:.2} w={:.2}", dx_shift, dy_shift, width);
-                                    let _ = ctx.DrawGeometry(&shifted_geom, &brush, width as f32, None);
+                                    let _ = ctx.DrawGeometry(&shifted_geom, &brush, width as f32, stroke_style.as_ref());
                                     continue;
                                 }
                             }
-                            let _ = ctx.DrawGeometry(&geom, &brush, width as f32, None);
+                            let _ = ctx.DrawGeometry(&geom, &brush, width as f32, stroke_style.as_ref());
                         }
                     }
-                    Command::PushLayer { rect } => {
+                    Command::PushLayer { rect, alpha, mask } => {
                         if disable_clips {
                             continue;
                         }
+                        if layer_kinds.len() >= MAX_CLIP_DEPTH {
+                            // Past the cap the content is only clipped by the outer layers; the
+                            // placeholder keeps the matching PopLayer balanced.
+                            layer_kinds.push(LayerKind::Skipped);
+                            dropped_clips += 1;
+                            continue;
+                        }
                         let r = D2D_RECT_F {
                             left: rect.x0 as f32,
                             top: rect.y0 as f32,
                             right: rect.x1 as f32,
                             bottom: rect.y1 as f32,
                         };
-                        let _ = ctx.PushAxisAlignedClip(&r, D2D1_ANTIALIAS_MODE_PER_PRIMITIVE);
+                        // The mask brush's alpha scales the layer's content as it is composited
+                        let opacity_brush = mask.map(|m| self.get_or_create_brush(&m, Some(rect)));
+                        let use_layer = alpha < 1.0 || opacity_brush.is_some();
+                        if use_layer {
+                            // Group opacity: children composite together, then blend once.
+                            let params = D2D1_LAYER_PARAMETERS1 {
+                                contentBounds: r,
+                                geometricMask: std::mem::ManuallyDrop::new(None),
+                                maskAntialiasMode: geometry_aa,
+                                maskTransform: affine_to_matrix(Affine::IDENTITY),
+                                opacity: alpha,
+                                opacityBrush: std::mem::ManuallyDrop::new(opacity_brush),
+                                layerOptions: D2D1_LAYER_OPTIONS1_NONE,
+                            };
+                            ctx.PushLayer(&params, None);
+                        } else {
+                            ctx.PushAxisAlignedClip(&r, geometry_aa);
+                        }
+                        layer_kinds.push(if use_layer { LayerKind::Layer } else { LayerKind::Clip });
                         clip_depth += 1;
                         if clip_depth > max_clip_depth {
                             max_clip_depth = clip_depth;
                         }
                         vlog!(
-                            "PushLayer depth={} rect=({}, {}, {}, {})",
+                            "PushLayer depth={} alpha={:.2} rect=({}, {}, {}, {})",
                             clip_depth,
+                            alpha,
                             rect.x0,
                             rect.y0,
                             rect.x1,
@@ -1128,12 +3523,16 @@ impl D2DWindowRenderer {
                         if disable_clips {
                             continue;
                         }
-                        if clip_depth <= 0 {
-                            vlog!("PopLayer underflow");
-                        } else {
-                            clip_depth -= 1;
+                        match layer_kinds.pop() {
+                            Some(LayerKind::Layer) => ctx.PopLayer(),
+                            Some(LayerKind::Clip) => ctx.PopAxisAlignedClip(),
+                            Some(LayerKind::Skipped) => continue,
+                            None => {
+                                vlog!("PopLayer underflow");
+                                continue;
+                            }
                         }
-                        ctx.PopAxisAlignedClip();
+                        clip_depth -= 1;
                         vlog!("PopLayer depth={}", clip_depth);
                     }
                     Command::BoxShadow {
@@ -1144,10 +3543,7 @@ impl D2DWindowRenderer {
                         inset,
                     } => {
                         // Allow disabling shadows for isolation (BLITZ_DISABLE_SHADOWS=1)
-                        if std::env::var("BLITZ_DISABLE_SHADOWS")
-                            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
-                            .unwrap_or(false)
-                        {
+                        if env_flags().disable_shadows {
                             continue;
                         } // keep one isolation flag
                         if inset && disable_inset_shadows {
@@ -1176,6 +3572,31 @@ impl D2DWindowRenderer {
                             self.draw_gaussian_box_shadow(&ctx, rect, color, radius, std_dev);
                         }
                     }
+                    Command::GlyphShadow {
+                        glyph_indices,
+                        advances,
+                        origin,
+                        size,
+                        font,
+                        color,
+                        std_dev,
+                        bidi_level,
+                    } => {
+                        if disable_text || env_flags().disable_shadows {
+                            continue;
+                        }
+                        let face_opt = self
+                            .get_or_create_font_face(&font)
+                            .or_else(|| self.dwrite_font_face.clone());
+                        if let Some(face) = face_opt {
+                            if !glyph_indices.is_empty() && advances.len() == glyph_indices.len() {
+                                self.draw_blurred_glyph_run(&ctx, &face, size, &glyph_indices, &advances, origin, bidi_level, color, std_dev);
+                            }
+                        }
+                    }
+                    Command::BackdropBlur { path, std_dev } => {
+                        self.draw_backdrop_blur(&ctx, target, &path, std_dev);
+                    }
                     Command::GlyphRun {
                         glyph_indices,
                         advances,
@@ -1184,6 +3605,9 @@ impl D2DWindowRenderer {
                         style,
                         font,
                         var_coords: _,
+                        brush: glyph_brush,
+                        vertical,
+                        bidi_level,
                     } => {
                         if disable_text {
                             continue;
@@ -1200,13 +3624,54 @@ impl D2DWindowRenderer {
                                         (color, Some(width))
                                     }
                                 };
-                                let brush = self.create_solid_brush(color);
+                                let brush: ID2D1Brush = match &glyph_brush {
+                                    Some(recorded) => {
+                                        // Approximate run bounds: advance extent x (ascent + descent) around the baseline.
+                                        let (x0, x1) = run_x_extent(&advances, origin.0, bidi_level);
+                                        let run_bounds = Rect::new(
+                                            x0 as f64,
+                                            (origin.1 - size) as f64,
+                                            x1 as f64,
+                                            (origin.1 + size * 0.3) as f64,
+                                        );
+                                        self.get_or_create_brush(recorded, Some(run_bounds))
+                                    }
+                                    None => self.create_solid_brush(color).cast().unwrap(),
+                                };
+                                if vertical {
+                                    // Turn the run a quarter clockwise about its origin; sideways glyphs then
+                                    // stand upright and advance down the page
+                                    let run = DWRITE_GLYPH_RUN {
+                                        fontFace: std::mem::ManuallyDrop::new(Some(face.clone())),
+                                        fontEmSize: size,
+                                        glyphCount: glyph_indices.len() as u32,
+                                        glyphIndices: glyph_indices.as_ptr(),
+                                        glyphAdvances: advances.as_ptr(),
+                                        glyphOffsets: std::ptr::null(),
+                                        isSideways: true.into(),
+                                        bidiLevel: 0,
+                                    };
+                                    let base = Affine::scale(self.scale as f64);
+                                    let turn = Affine::rotate_about(std::f64::consts::FRAC_PI_2, (origin.0 as f64, origin.1 as f64).into());
+                                    ctx.SetTransform(&affine_to_matrix(base * turn));
+                                    let origin_pt = D2D_POINT_2F { x: origin.0, y: origin.1 };
+                                    let _ = ctx.DrawGlyphRun(origin_pt, &run, None, &brush, DWRITE_MEASURING_MODE_NATURAL);
+                                    ctx.SetTransform(&affine_to_matrix(base));
+                                    continue;
+                                }
                                 if let Some(stroke_width) = stroke_width_opt {
-                                    if let Some(geom) = self.build_glyph_outline_geometry(&face, size, &glyph_indices, &advances) {
+                                    if let Some(geom) = self.build_glyph_outline_geometry_at(&face, size, &glyph_indices, &advances, origin, bidi_level) {
                                         let _ = ctx.DrawGeometry(&geom, &brush, stroke_width, None);
                                         continue; // stroke done
                                     }
                                     // fall through: outline failed, use glyph run fill
+                                } else if glyph_brush.is_some() {
+                                    // Gradient fill: fill the outline geometry so the brush spans the glyphs in the
+                                    // same coordinate space as FillPath; solid fills keep the DrawGlyphRun fast path.
+                                    if let Some(geom) = self.build_glyph_outline_geometry_at(&face, size, &glyph_indices, &advances, origin, bidi_level) {
+                                        let _ = ctx.FillGeometry(&geom, &brush, None);
+                                        continue;
+                                    }
                                 }
                                 let run = DWRITE_GLYPH_RUN {
                                     fontFace: std::mem::ManuallyDrop::new(Some(face.clone())),
@@ -1216,7 +3681,7 @@ impl D2DWindowRenderer {
                                     glyphAdvances: advances.as_ptr(),
                                     glyphOffsets: std::ptr::null(),
                                     isSideways: false.into(),
-                                    bidiLevel: 0,
+                                    bidiLevel: bidi_level as u32,
                                 };
                                 let snapped_y = snap_baseline(origin.1);
                                 let origin_pt = D2D_POINT_2F { x: origin.0.round(), y: snapped_y };
@@ -1229,40 +3694,125 @@ impl D2DWindowRenderer {
                     }
                 }
             }
-            if clip_depth != 0 {
-                while clip_depth > 0 {
-                    ctx.PopAxisAlignedClip();
-                    clip_depth -= 1;
+            while let Some(kind) = layer_kinds.pop() {
+                match kind {
+                    LayerKind::Layer => ctx.PopLayer(),
+                    LayerKind::Clip => ctx.PopAxisAlignedClip(),
+                    LayerKind::Skipped => {}
                 }
             }
+            if dropped_clips > 0 {
+                vlog!(
+                    "clip depth cap {} reached: {} clips dropped (max depth {})",
+                    MAX_CLIP_DEPTH,
+                    dropped_clips,
+                    max_clip_depth
+                );
+            }
             vlog!(
-                "counts fp={} sp={} cmds={} shadows={}",
+                "counts fp={} sp={} cmds={} shadows={} culled={}",
                 fill_path_count,
                 stroke_path_count,
                 command_count,
-                shadow_count
+                command_stats.shadows,
+                culled_count
             );
+            command_stats.max_clip_depth = max_clip_depth as u32;
+            command_stats.culled = culled_count;
+            self.last_command_stats = command_stats;
+            if repaint_clip.is_some() {
+                ctx.PopAxisAlignedClip();
+            }
+            let presented_region =
+                self.presented_region(repaint_clip, size.width as f64, size.height as f64);
             // If no commands, fallback bg already drawn earlier.
-            // Transform: currently implicit identity (no cumulative transform stack applied here).
+            if let Some(inspect_box) = self.inspect_box.filter(|_| self.inspect_overlay) {
+                for (rect, color) in inspect_box.fills() {
+                    let brush = self.create_solid_brush(color);
+                    ctx.FillRectangle(
+                        &D2D_RECT_F {
+                            left: rect.x0 as f32,
+                            top: rect.y0 as f32,
+                            right: rect.x1 as f32,
+                            bottom: rect.y1 as f32,
+                        },
+                        &brush,
+                    );
+                }
+            }
+            if let Some(thumb) = self.scrollbar_thumb {
+                let rr = D2D1_ROUNDED_RECT {
+                    rect: D2D_RECT_F {
+                        left: thumb.x0 as f32,
+                        top: thumb.y0 as f32,
+                        right: thumb.x1 as f32,
+                        bottom: thumb.y1 as f32,
+                    },
+                    radiusX: (thumb.width() / 2.0) as f32,
+                    radiusY: (thumb.width() / 2.0) as f32,
+                };
+                let brush = self.create_solid_brush(Color::new([0.0, 0.0, 0.0, 0.45]));
+                ctx.FillRoundedRectangle(&rr, &brush);
+            }
+            // Overlay is drawn in device pixels.
+            ctx.SetTransform(&affine_to_matrix(Affine::IDENTITY));
             // Draw overlay before EndDraw so it is visible
             if self.show_debug_overlay {
                 self.draw_debug_overlay(&ctx);
             }
-            let end_res = ctx.EndDraw(None, None);
-            if let Err(e) = end_res {
-                debug_log_d2d(&format!("EndDraw error {:?}", e));
-            } else {
-                vlog!("EndDraw ok");
+            let mut end_res = ctx.EndDraw(None, None);
+            if std::mem::take(&mut self.simulate_device_loss) {
+                end_res = Err(windows::Win32::Foundation::D2DERR_RECREATE_TARGET.into());
+            }
+            match end_res {
+                Err(e) if e.code() == windows::Win32::Foundation::D2DERR_RECREATE_TARGET => {
+                    self.handle_device_lost()
+                }
+                Err(e) => debug_log_d2d(&format!("EndDraw error {:?}", e)),
+                Ok(()) => {
+                    vlog!("EndDraw ok");
+                    let frame = FrameResult {
+                        presented_region,
+                        needs_present: true,
+                    };
+                    self.frame_result = self.frame_result.merge(frame);
+                }
+            }
+        }
+        self.playback_ms = self.elapsed_ms(t0);
+    }
+
+    // Device-pixel region a frame changed: the repaint clip plus the overlays drawn over it unclipped.
+    // None (everything) for full repaints and when the debug overlay is shown.
+    fn presented_region(
+        &self,
+        repaint_clip: Option<Rect>,
+        width: f64,
+        height: f64,
+    ) -> Option<Rect> {
+        if self.show_debug_overlay && !env_flags().disable_overlay {
+            return None;
+        }
+        let mut region = repaint_clip?;
+        if let Some(thumb) = self.scrollbar_thumb {
+            region = region.union(thumb);
+        }
+        if let Some(inspect_box) = self.inspect_box.filter(|_| self.inspect_overlay) {
+            for (rect, _) in inspect_box.fills() {
+                region = region.union(rect);
             }
         }
-        self.playback_ms = t0.elapsed().as_secs_f32() * 1000.0;
+        let region = region.scale_from_origin(self.scale as f64).expand();
+        Some(region.intersect(Rect::new(0.0, 0.0, width, height)))
     }
 
+    // Overlay text format at 12 DIPs scaled to the device; recreated when the scale changes.
     fn ensure_text_format(&mut self) {
-        if self.dwrite_text_format.is_some() {
+        let size = 12.0 * self.scale;
+        if self.dwrite_text_format.is_some() && self.dwrite_text_format_size == size {
             return;
         }
-        let t0 = Instant::now();
+        let t0 = self.clock.now();
         let factory = match &self.dwrite_factory {
             Some(f) => f.clone(),
             None => return,
@@ -1270,26 +3820,25 @@ impl D2DWindowRenderer {
         unsafe {
             use windows::core::w;
             if let Ok(tf) = factory.CreateTextFormat(
-                w!("Consolas"),
+                &windows::core::HSTRING::from(self.font_config.monospace.as_str()),
                 None,
                 DWRITE_FONT_WEIGHT_NORMAL,
                 DWRITE_FONT_STYLE_NORMAL,
                 DWRITE_FONT_STRETCH_NORMAL,
-                12.0,
+                size,
                 w!("en-US"),
             ) {
+                let _ = tf.SetWordWrapping(DWRITE_WORD_WRAPPING_WRAP);
                 self.dwrite_text_format = Some(tf);
-                let ms = t0.elapsed().as_secs_f32() * 1000.0;
+                self.dwrite_text_format_size = size;
+                let ms = self.elapsed_ms(t0);
                 self.add_host_first_text_init_ms(ms);
             }
         }
     }
 
     fn draw_debug_overlay(&mut self, ctx: &ID2D1DeviceContext) {
-        if std::env::var("BLITZ_DISABLE_OVERLAY")
-            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
-            .unwrap_or(false)
-        {
+        if env_flags().disable_overlay {
             return;
         }
         self.ensure_text_format();
@@ -1355,7 +3904,7 @@ impl D2DWindowRenderer {
         let critical_path = content_path
             .max(self.host_init_ms + self.host_panel_attach_exec_ms + self.device_init_ms); // include device init on host side if present
         let stats_line2 = format!(
-            "dev {:.1} host {:.1} cp {:.1} d3d {:.1} sc {:.1} att {:.1} (w {:.1} x {:.1}) txt {:.1} back {:.1} play {:.1} unx {:.1} slow {} {:.2} (all:{} {:.2}) fps {:.1} cmds {}",
+            "dev {:.1} host {:.1} cp {:.1} d3d {:.1} sc {:.1} att {:.1} (w {:.1} x {:.1}) txt {:.1} back {:.1} play {:.1} unx {:.1} slow {} {:.2} (all:{} {:.2})",
             self.device_init_ms,
             self.host_init_ms,
             critical_path,
@@ -1372,8 +3921,6 @@ impl D2DWindowRenderer {
             slow_ms,
             slowest_overall_label,
             slowest_overall_ms,
-            self.fps,
-            self.last_command_count
         );
         let stats_line2b = format!(
             "att.sub ui_add {:.1} set_sw {:.1}",
@@ -1401,97 +3948,220 @@ impl D2DWindowRenderer {
         };
         let buffer_w = self.width;
         let buffer_h = self.height;
-    let text_aa_mode = unsafe { ctx.GetTextAntialiasMode() };
-    let diag_line = format!("buf={}x{} css={}x{} scale=1.0 textAA={:?}", buffer_w, buffer_h, self.width, self.height, text_aa_mode);
-        let stats = format!(
-            "{}\n{}\n{}\n{}\n{}",
-            stats_line1, stats_line2, stats_line2b, stats_line3, diag_line
+        let text_aa_mode = unsafe { ctx.GetTextAntialiasMode() };
+        let diag_line = format!(
+            "buf={}x{} css={}x{} scale={:.2} textAA={:?}",
+            buffer_w, buffer_h, self.width, self.height, self.scale, text_aa_mode
         );
-        // Background rect enlarged for extra fields
-    let bg = D2D_RECT_F { left: 6.0, top: 6.0, right: 6.0 + 980.0, bottom: 6.0 + 125.0 };
-        let bg_brush = self.create_solid_brush(Color::new([0.0, 0.0, 0.0, 0.55]));
-        unsafe {
-            ctx.FillRectangle(&bg, &bg_brush);
+        let options = self.overlay_options;
+        let stats = overlay_text(
+            &options,
+            &[stats_line1, stats_line2, stats_line2b, stats_line3],
+            format!("fps {:.1}", self.fps),
+            format!(
+                "cmds {} {}",
+                self.last_command_count, self.last_command_stats
+            ),
+            diag_line,
+        );
+        if stats.is_empty() || options.opacity <= 0.0 {
+            return;
         }
-        // Text brush
-        let txt_brush = self.create_solid_brush(Color::new([1.0, 1.0, 1.0, 0.95]));
-        // Convert text to wide
+        // Lay the text out wrapped to the max width (never wider than the target) and size the
+        // background to the measured text. Overlay coordinates are device pixels.
+        let Some(factory) = self.dwrite_factory.clone() else {
+            return;
+        };
+        let margin = 6.0 * self.scale;
+        let pad = 4.0 * self.scale;
+        let target = (self.width as f32, self.height as f32);
+        let max_width = (options.max_width * self.scale)
+            .min(target.0 - 2.0 * (margin + pad))
+            .max(1.0);
         let wide: Vec<u16> = stats.encode_utf16().collect();
+        let Ok(layout) =
+            (unsafe { factory.CreateTextLayout(&wide, &fmt, max_width, target.1.max(1.0)) })
+        else {
+            return;
+        };
+        let mut text_metrics = DWRITE_TEXT_METRICS::default();
+        if unsafe { layout.GetMetrics(&mut text_metrics) }.is_err() {
+            return;
+        }
+        let bg = overlay_rect(
+            options.corner,
+            (text_metrics.width, text_metrics.height),
+            target,
+            margin,
+            pad,
+        );
+        let bg_brush = self.create_solid_brush(Color::new([0.0, 0.0, 0.0, 0.55 * options.opacity]));
+        let txt_brush =
+            self.create_solid_brush(Color::new([1.0, 1.0, 1.0, 0.95 * options.opacity]));
         unsafe {
-            ctx.DrawText(
-                &wide,
-                &fmt,
+            ctx.FillRectangle(
                 &D2D_RECT_F {
-                    left: 10.0,
-                    top: 10.0,
-                    right: 970.0,
-                    bottom: 200.0,
+                    left: bg.x0 as f32,
+                    top: bg.y0 as f32,
+                    right: bg.x1 as f32,
+                    bottom: bg.y1 as f32,
                 },
-                &txt_brush,
-                D2D1_DRAW_TEXT_OPTIONS_NONE,
-                DWRITE_MEASURING_MODE_NATURAL,
+                &bg_brush,
             );
+            // The layout box is max_width wide; shift by the metrics' left edge so the text starts at the padding
+            let origin = D2D_POINT_2F {
+                x: bg.x0 as f32 + pad - text_metrics.left,
+                y: bg.y0 as f32 + pad - text_metrics.top,
+            };
+            ctx.DrawTextLayout(origin, &layout, &txt_brush, D2D1_DRAW_TEXT_OPTIONS_CLIP);
         }
     }
 
+    // Semi-transparent colors (overlay background, scrollbar thumb, inspect fills, shadows) go in straight:
+    // see d2d_color.
     fn create_solid_brush(&self, color: Color) -> ID2D1SolidColorBrush {
         let ctx = self.d2d_ctx.as_ref().unwrap();
-        unsafe {
-            let col = D2D1_COLOR_F {
-                r: color.components[0] as f32,
-                g: color.components[1] as f32,
-                b: color.components[2] as f32,
-                a: color.components[3] as f32,
-            };
-            ctx.CreateSolidColorBrush(&col, None).unwrap()
-        }
+        unsafe { ctx.CreateSolidColorBrush(&d2d_color(color), None).unwrap() }
     }
 
     // Resolve (and cache) a font face for the provided key using DirectWrite system collection.
+    // Fonts added with add_font_data are searched before the system collection, per family candidate.
     fn get_or_create_font_face(&mut self, key: &FontKey) -> Option<IDWriteFontFace> {
         if let Some(face) = self.font_face_cache.get(key) {
             return Some(face.clone());
         }
         let factory = self.dwrite_factory.clone()?;
+        let custom = self.custom_font_collection();
+        let mut system: Option<IDWriteFontCollection> = None;
         unsafe {
-            let mut collection_opt: Option<IDWriteFontCollection> = None;
-            if factory
-                .GetSystemFontCollection(&mut collection_opt, false)
-                .is_ok()
-            {
-                if let Some(collection) = collection_opt {
-                    let mut idx = 0u32;
-                    let mut exists = false.into();
-                    if collection
-                        .FindFamilyName(
-                            &windows::core::HSTRING::from(&key.family),
-                            &mut idx,
-                            &mut exists,
-                        )
-                        .is_ok()
-                        && exists.as_bool()
-                    {
-                        if let Ok(family) = collection.GetFontFamily(idx) {
-                            let weight = DWRITE_FONT_WEIGHT(key.weight as i32);
-                            // Map stretch (1..=9) directly; default normal (5)
-                            let stretch = DWRITE_FONT_STRETCH(key.stretch as i32);
-                            let style = if key.italic {
-                                DWRITE_FONT_STYLE_ITALIC
-                            } else {
-                                DWRITE_FONT_STYLE_NORMAL
-                            };
-                            if let Ok(font) = family.GetFirstMatchingFont(weight, stretch, style) {
-                                if let Ok(face) = font.CreateFontFace() {
-                                    self.font_face_cache.insert(key.clone(), face.clone());
-                                    return Some(face);
-                                }
-                            }
-                        }
-                    }
+            let _ = factory.GetSystemFontCollection(&mut system, false);
+        }
+        for name in &self.font_config.candidates(&key.families) {
+            for collection in custom.iter().chain(system.iter()) {
+                if let Some(face) = find_font_face(collection, name, key) {
+                    // Cached under the full candidate list so later runs skip the lookups
+                    self.font_face_cache.insert(key.clone(), face.clone());
+                    return Some(face);
+                }
+            }
+            vlog!(
+                "font family '{}' not installed; trying next candidate",
+                name
+            );
+        }
+        None
+    }
+
+    /// Register font data (TTF/OTF/TTC bytes, e.g. a bundled or `@font-face` font) for text
+    /// rendering. Its families are matched before installed fonts. The data is copied.
+    pub fn add_font_data(&mut self, data: &[u8]) {
+        if data.is_empty() {
+            return;
+        }
+        self.custom_fonts.push(data.to_vec());
+        // Rebuilt on next lookup; faces resolved before may now map to the new font
+        self.custom_collection = None;
+        self.font_face_cache.clear();
+        self.load_default_font_face();
+    }
+
+    /// Use `family` (installed, or registered with `add_font_data`) at `weight` for text whose CSS
+    /// families are all missing and for unstyled text, in place of Segoe UI.
+    pub fn set_default_font(&mut self, family: &str, weight: u16) {
+        self.set_font_config(FontConfig {
+            default_family: family.to_string(),
+            default_weight: if (100..=900).contains(&weight) {
+                weight
+            } else {
+                400
+            },
+            ..self.font_config.clone()
+        });
+    }
+
+    /// Replace the default family and the generic-family mapping; already recorded text picks it up on
+    /// the next frame.
+    pub fn set_font_config(&mut self, config: FontConfig) {
+        if config == self.font_config {
+            return;
+        }
+        self.font_config = config;
+        self.font_face_cache.clear();
+        // The overlay's monospace format
+        self.dwrite_text_format = None;
+        self.load_default_font_face();
+        self.mark_full();
+    }
+
+    pub fn font_config(&self) -> &FontConfig {
+        &self.font_config
+    }
+
+    // Face drawn when a run's font can't be resolved: the default family at the default weight
+    fn load_default_font_face(&mut self) {
+        if self.dwrite_factory.is_none() {
+            return;
+        }
+        let key = FontKey {
+            weight: self.font_config.default_weight,
+            ..FontKey::default()
+        };
+        if let Some(face) = self.get_or_create_font_face(&key) {
+            self.dwrite_font_face = Some(face);
+        }
+    }
+
+    /// Forget all fonts registered with `add_font_data`.
+    pub fn clear_font_data(&mut self) {
+        self.custom_fonts.clear();
+        self.custom_collection = None;
+        self.font_face_cache.clear();
+    }
+
+    // In-memory collection over the registered font data, built on demand.
+    fn custom_font_collection(&mut self) -> Option<IDWriteFontCollection> {
+        if self.custom_fonts.is_empty() {
+            return None;
+        }
+        if let Some(collection) = &self.custom_collection {
+            return Some(collection.clone());
+        }
+        let factory: IDWriteFactory5 = self.dwrite_factory.as_ref()?.cast().ok()?;
+        unsafe {
+            if self.font_loader.is_none() {
+                let loader = factory.CreateInMemoryFontFileLoader().ok()?;
+                factory.RegisterFontFileLoader(&loader).ok()?;
+                self.font_loader = Some(loader);
+            }
+            let loader = self.font_loader.as_ref()?;
+            let builder = factory.CreateFontSetBuilder2().ok()?;
+            for data in &self.custom_fonts {
+                // No owner object: DirectWrite keeps its own copy of the bytes
+                let added = loader
+                    .CreateInMemoryFontFileReference(
+                        &factory,
+                        data.as_ptr() as *const _,
+                        data.len() as u32,
+                        None::<&windows::core::IUnknown>,
+                    )
+                    .and_then(|file| builder.AddFontFile(&file));
+                if let Err(e) = added {
+                    debug_log_d2d(&format!(
+                        "add_font_data: font rejected ({} bytes) {:?}",
+                        data.len(),
+                        e
+                    ));
                 }
             }
+            let set = builder.CreateFontSet().ok()?;
+            let collection: IDWriteFontCollection = factory
+                .CreateFontCollectionFromFontSet(&set)
+                .ok()?
+                .cast()
+                .ok()?;
+            self.custom_collection = Some(collection.clone());
+            Some(collection)
         }
-        None
     }
 
     // Build outline geometry for glyph run; returns a path geometry or None on failure.
@@ -1501,6 +4171,7 @@ impl D2DWindowRenderer {
         em_size: f32,
         glyph_indices: &[u16],
         advances: &[f32],
+        bidi_level: u8,
     ) -> Option<ID2D1PathGeometry> {
         if glyph_indices.is_empty() {
             return None;
@@ -1518,7 +4189,7 @@ impl D2DWindowRenderer {
                 None,
                 glyph_indices.len() as u32,
                 false,
-                false,
+                bidi_level % 2 == 1,
                 &simple,
             );
             if hr.is_ok() {
@@ -1529,10 +4200,48 @@ impl D2DWindowRenderer {
         None
     }
 
-    fn get_or_create_brush(&mut self, recorded: &RecordedBrush) -> ID2D1Brush {
+    // Glyph run outline positioned at the run's baseline origin (outlines are built relative to (0,0)).
+    fn build_glyph_outline_geometry_at(
+        &self,
+        face: &IDWriteFontFace,
+        em_size: f32,
+        glyph_indices: &[u16],
+        advances: &[f32],
+        origin: (f32, f32),
+        bidi_level: u8,
+    ) -> Option<ID2D1Geometry> {
+        let geom =
+            self.build_glyph_outline_geometry(face, em_size, glyph_indices, advances, bidi_level)?;
+        let factory = self.d2d_factory.as_ref()?;
+        let translate = affine_to_matrix(Affine::translate((origin.0 as f64, origin.1 as f64)));
+        unsafe {
+            let transformed = factory.CreateTransformedGeometry(&geom, &translate).ok()?;
+            transformed.cast().ok()
+        }
+    }
+
+    // `bounds` is the device-space area the brush will cover; sweep gradients are rasterized over it.
+    fn get_or_create_brush(
+        &mut self,
+        recorded: &RecordedBrush,
+        bounds: Option<Rect>,
+    ) -> ID2D1Brush {
         match recorded {
             RecordedBrush::Solid(c) => self.create_solid_brush(*c).cast().unwrap(),
-            RecordedBrush::Gradient(g) => self.get_or_create_gradient_brush(g),
+            RecordedBrush::Gradient(g) if matches!(g.kind, peniko::GradientKind::Sweep { .. }) => {
+                match bounds.and_then(|b| self.get_or_create_sweep_brush(g, b)) {
+                    Some(brush) => brush,
+                    None => self
+                        .create_solid_brush(g.stops.first().map(|s| s.1).unwrap_or(Color::TRANSPARENT))
+                        .cast()
+                        .unwrap(),
+                }
+            }
+            RecordedBrush::Gradient(g) => {
+                let brush = self.get_or_create_gradient_brush(g);
+                unsafe { brush.SetTransform(&affine_to_matrix(g.transform)) };
+                brush
+            }
             // Images are not expressed as brushes in our fill path; we draw them via DrawBitmap.
             // Fallback: return a transparent solid brush (never actually used for image drawing).
             RecordedBrush::Image(_img) => {
@@ -1542,16 +4251,199 @@ impl D2DWindowRenderer {
         }
     }
 
+    // Direct2D has no sweep (conic) gradient, so rasterize the ramp over the covered device-space
+    // bounds into a bitmap and draw it through a bitmap brush. Bitmaps are cached by stops, angles,
+    // the linear part of the transform and the bounds relative to the gradient center, so scrolling
+    // an element reuses its bitmap.
+    fn get_or_create_sweep_brush(
+        &mut self,
+        g: &RecordedGradient,
+        bounds: Rect,
+    ) -> Option<ID2D1Brush> {
+        const MAX_SWEEP_DIM: f64 = 2048.0;
+        const MAX_SWEEP_CACHE: usize = 32;
+        let peniko::GradientKind::Sweep {
+            center,
+            start_angle,
+            end_angle,
+        } = g.kind
+        else {
+            return None;
+        };
+        let bounds = bounds.expand();
+        if bounds.width() < 1.0 || bounds.height() < 1.0 || g.stops.is_empty() {
+            return None;
+        }
+        let inverse = g.transform.inverse();
+        let device_center = g.transform * center;
+        let step = (bounds.width().max(bounds.height()) / MAX_SWEEP_DIM).max(1.0);
+        let w = (bounds.width() / step).ceil() as u32;
+        let h = (bounds.height() / step).ceil() as u32;
+
+        let mut hasher = rustc_hash::FxHasher::default();
+        let c = g.transform.as_coeffs();
+        (
+            c[0].to_bits(),
+            c[1].to_bits(),
+            c[2].to_bits(),
+            c[3].to_bits(),
+        )
+            .hash(&mut hasher);
+        (
+            start_angle.to_bits(),
+            end_angle.to_bits(),
+            g.extend as u8,
+            w,
+            h,
+        )
+            .hash(&mut hasher);
+        (g.interpolation_cs, g.hue_direction).hash(&mut hasher);
+        (
+            ((bounds.x0 - device_center.x) * 4.0).round() as i64,
+            ((bounds.y0 - device_center.y) * 4.0).round() as i64,
+        )
+            .hash(&mut hasher);
+        for (o, col) in &g.stops {
+            o.to_bits().hash(&mut hasher);
+            for comp in col.components {
+                comp.to_bits().hash(&mut hasher);
+            }
+        }
+        let key = hasher.finish();
+
+        let ctx = self.d2d_ctx.clone()?;
+        let bitmap = match self.sweep_cache.get(&key) {
+            Some(b) => b.clone(),
+            None => {
+                let stops = flatten_stops(&g.stops, g.interpolation_cs, g.hue_direction);
+                let span = end_angle - start_angle;
+                let mut data = vec![0u8; (w * h * 4) as usize];
+                for y in 0..h {
+                    for x in 0..w {
+                        let device = kurbo::Point::new(
+                            bounds.x0 + (x as f64 + 0.5) * step,
+                            bounds.y0 + (y as f64 + 0.5) * step,
+                        );
+                        let local = inverse * device - center;
+                        let mut angle = local.y.atan2(local.x) as f32;
+                        if angle < 0.0 {
+                            angle += std::f32::consts::TAU;
+                        }
+                        let mut t = if span.abs() > f32::EPSILON {
+                            (angle - start_angle) / span
+                        } else {
+                            0.0
+                        };
+                        t = match g.extend {
+                            peniko::Extend::Pad => t.clamp(0.0, 1.0),
+                            peniko::Extend::Repeat => t.rem_euclid(1.0),
+                            peniko::Extend::Reflect => {
+                                let r = t.rem_euclid(2.0);
+                                if r > 1.0 { 2.0 - r } else { r }
+                            }
+                        };
+                        let [r, gg, b, a] = sample_stops(&stops, t);
+                        // BGRA, premultiplied
+                        let i = ((y * w + x) * 4) as usize;
+                        data[i] = (b * a * 255.0).round() as u8;
+                        data[i + 1] = (gg * a * 255.0).round() as u8;
+                        data[i + 2] = (r * a * 255.0).round() as u8;
+                        data[i + 3] = (a * 255.0).round() as u8;
+                    }
+                }
+                let props = D2D1_BITMAP_PROPERTIES1 {
+                    pixelFormat: D2D1_PIXEL_FORMAT {
+                        format: DXGI_FORMAT_B8G8R8A8_UNORM,
+                        alphaMode: D2D1_ALPHA_MODE_PREMULTIPLIED,
+                    },
+                    dpiX: 96.0,
+                    dpiY: 96.0,
+                    bitmapOptions: D2D1_BITMAP_OPTIONS_NONE,
+                    colorContext: std::mem::ManuallyDrop::new(None),
+                };
+                let bmp: ID2D1Bitmap = unsafe {
+                    ctx.CreateBitmap(
+                        D2D_SIZE_U {
+                            width: w,
+                            height: h,
+                        },
+                        Some(data.as_ptr() as *const _),
+                        w * 4,
+                        &props,
+                    )
+                    .ok()?
+                    .into()
+                };
+                if self.sweep_cache.len() >= MAX_SWEEP_CACHE {
+                    self.sweep_cache.clear();
+                }
+                self.sweep_cache.insert(key, bmp.clone());
+                bmp
+            }
+        };
+        unsafe {
+            let brush_props = D2D1_BITMAP_BRUSH_PROPERTIES {
+                extendModeX: D2D1_EXTEND_MODE_CLAMP,
+                extendModeY: D2D1_EXTEND_MODE_CLAMP,
+                interpolationMode: D2D1_BITMAP_INTERPOLATION_MODE_LINEAR,
+            };
+            let brush = ctx
+                .CreateBitmapBrush(&bitmap, Some(&brush_props), None)
+                .ok()?;
+            let placement =
+                Affine::scale(step).then_translate(kurbo::Vec2::new(bounds.x0, bounds.y0));
+            brush.SetTransform(&affine_to_matrix(placement));
+            brush.cast().ok()
+        }
+    }
+
     fn get_or_create_gradient_brush(&mut self, g: &RecordedGradient) -> ID2D1Brush {
         use std::hash::{Hash, Hasher};
         let mut hasher = rustc_hash::FxHasher::default();
         // hash kind & stops
-        (match &g.kind {
-            peniko::GradientKind::Linear { .. } => 1u8,
-            peniko::GradientKind::Radial { .. } => 2u8,
-            peniko::GradientKind::Sweep { .. } => 3u8,
-        })
-        .hash(&mut hasher);
+        match &g.kind {
+            peniko::GradientKind::Linear { start, end } => (
+                1u8,
+                start.x.to_bits(),
+                start.y.to_bits(),
+                end.x.to_bits(),
+                end.y.to_bits(),
+            )
+                .hash(&mut hasher),
+            peniko::GradientKind::Radial {
+                start_center,
+                start_radius,
+                end_center,
+                end_radius,
+            } => (
+                2u8,
+                (
+                    start_center.x.to_bits(),
+                    start_center.y.to_bits(),
+                    start_radius.to_bits(),
+                ),
+                (
+                    end_center.x.to_bits(),
+                    end_center.y.to_bits(),
+                    end_radius.to_bits(),
+                ),
+            )
+                .hash(&mut hasher),
+            peniko::GradientKind::Sweep {
+                center,
+                start_angle,
+                end_angle,
+            } => (
+                3u8,
+                center.x.to_bits(),
+                center.y.to_bits(),
+                start_angle.to_bits(),
+                end_angle.to_bits(),
+            )
+                .hash(&mut hasher),
+        }
+        (g.extend as u8).hash(&mut hasher);
+        (g.interpolation_cs, g.hue_direction).hash(&mut hasher);
         for (o, c) in &g.stops {
             let comps = c.components;
             ((
@@ -1571,29 +4463,54 @@ impl D2DWindowRenderer {
         }
         let ctx = self.d2d_ctx.as_ref().unwrap();
         unsafe {
-            // Build gradient stops
-            let stops: Vec<D2D1_GRADIENT_STOP> = g
-                .stops
-                .iter()
-                .map(|(o, c)| {
-                    let comps = c.components;
-                    D2D1_GRADIENT_STOP {
-                        position: *o,
-                        color: D2D1_COLOR_F {
-                            r: comps[0],
-                            g: comps[1],
-                            b: comps[2],
-                            a: comps[3],
-                        },
-                    }
-                })
-                .collect();
+            // Build gradient stops. Linear-light stops go in as scRGB, which is also the space D2D interpolates
+            // in, and come out as sRGB; the float buffer keeps the dark end of the ramp from banding.
+            let (stops, pre_space, precision): (Vec<D2D1_GRADIENT_STOP>, _, _) =
+                match gradient_ramp(&g.stops, g.interpolation_cs, g.hue_direction) {
+                    GradientRamp::Srgb(stops) => (
+                        stops
+                            .iter()
+                            .map(|(o, c)| D2D1_GRADIENT_STOP {
+                                position: *o,
+                                color: d2d_color(*c),
+                            })
+                            .collect(),
+                        D2D1_COLOR_SPACE_SRGB,
+                        D2D1_BUFFER_PRECISION_8BPC_UNORM,
+                    ),
+                    GradientRamp::Linear(stops) => (
+                        stops
+                            .iter()
+                            .map(|(o, [r, g, b, a])| D2D1_GRADIENT_STOP {
+                                position: *o,
+                                color: D2D1_COLOR_F {
+                                    r: *r,
+                                    g: *g,
+                                    b: *b,
+                                    a: *a,
+                                },
+                            })
+                            .collect(),
+                        D2D1_COLOR_SPACE_SCRGB,
+                        D2D1_BUFFER_PRECISION_16BPC_FLOAT,
+                    ),
+                };
+            if !matches!(
+                g.interpolation_cs,
+                color::ColorSpaceTag::Srgb | color::ColorSpaceTag::LinearSrgb
+            ) {
+                vlog!(
+                    "gradient in {:?} approximated with {} sRGB stops",
+                    g.interpolation_cs,
+                    stops.len()
+                );
+            }
             let stop_collection = ctx
                 .CreateGradientStopCollection(
                     &stops,
+                    pre_space,
                     D2D1_COLOR_SPACE_SRGB,
-                    D2D1_COLOR_SPACE_SRGB,
-                    D2D1_BUFFER_PRECISION_8BPC_UNORM,
+                    precision,
                     D2D1_EXTEND_MODE_CLAMP,
                     D2D1_COLOR_INTERPOLATION_MODE_STRAIGHT,
                 )
@@ -1639,7 +4556,7 @@ impl D2DWindowRenderer {
                         .unwrap()
                 }
                 peniko::GradientKind::Sweep { .. } => {
-                    // No native sweep; approximate by linear
+                    // Sweeps are rasterized by get_or_create_sweep_brush; this is only a fallback
                     let props = D2D1_LINEAR_GRADIENT_BRUSH_PROPERTIES {
                         startPoint: D2D_POINT_2F { x: 0.0, y: 0.0 },
                         endPoint: D2D_POINT_2F { x: 100.0, y: 0.0 },
@@ -1655,51 +4572,118 @@ impl D2DWindowRenderer {
         }
     }
 
-    fn get_or_create_image_bitmap(&mut self, img: &RecordedImage) -> ID2D1Bitmap {
-        use std::hash::{Hash, Hasher};
-        let mut hasher = rustc_hash::FxHasher::default();
-        (img.width, img.height, img.alpha.to_bits()).hash(&mut hasher);
-        for b in img.data.iter().take(32) { b.hash(&mut hasher); }
-        let key = hasher.finish();
-        if let Some(existing) = self.image_cache.get(&key) { return existing.clone(); }
-        let ctx = self.d2d_ctx.as_ref().unwrap();
-        // Prepare premultiplied RGBA copy (Direct2D expects premultiplied alpha when using PREMULTIPLIED mode)
-        let mut premul: Vec<u8> = img.data.clone();
-        for px in premul.chunks_exact_mut(4) {
-            let a = px[3] as u16; // 0..255
-            if a < 255 { // only adjust when needed
-                px[0] = ((px[0] as u16 * a + 127) / 255) as u8;
-                px[1] = ((px[1] as u16 * a + 127) / 255) as u8;
-                px[2] = ((px[2] as u16 * a + 127) / 255) as u8;
-            }
+    // Returns None (and logs) instead of panicking when the image is malformed or the device refuses
+    // the bitmap; images larger than the device's max bitmap size are downscaled to fit.
+    fn get_or_create_image_bitmap(&mut self, img: &RecordedImage) -> Option<ID2D1Bitmap> {
+        // `alpha` is applied as DrawBitmap opacity, never baked into the pixels, so it isn't part of the key.
+        let Some(layout) = SourcePixels::from_format(img.format, img.alpha_type) else {
+            vlog!("image bitmap skipped: unsupported format {:?}", img.format);
+            return None;
+        };
+        let key = (img.source, layout);
+        if let Some(existing) = self.image_cache.get(&key) {
+            return Some(existing.clone());
         }
+        let ctx = self.d2d_ctx.as_ref()?;
+        // Convert to the premultiplied RGBA8 layout the bitmap is created with (already premultiplied
+        // sources are only repacked, never multiplied twice)
+        let Some(premul) = to_premultiplied_rgba(&img.data, img.width, img.height, layout) else {
+            vlog!(
+                "image bitmap skipped: {}x{} with {} bytes",
+                img.width,
+                img.height,
+                img.data.len()
+            );
+            return None;
+        };
+        let max_dim = unsafe { ctx.GetMaximumBitmapSize() }.max(1);
+        let (data, width, height) = if img.width > max_dim || img.height > max_dim {
+            vlog!(
+                "image bitmap {}x{} exceeds device max {}; downscaling",
+                img.width,
+                img.height,
+                max_dim
+            );
+            downscale_rgba(&premul, img.width, img.height, max_dim)
+        } else {
+            (premul, img.width, img.height)
+        };
         unsafe {
             let pf = D2D1_PIXEL_FORMAT { format: DXGI_FORMAT_R8G8B8A8_UNORM, alphaMode: D2D1_ALPHA_MODE_PREMULTIPLIED };
             let bp = D2D1_BITMAP_PROPERTIES1 { pixelFormat: pf, dpiX: 96.0, dpiY: 96.0, bitmapOptions: D2D1_BITMAP_OPTIONS_NONE, colorContext: std::mem::ManuallyDrop::new(None) };
-            let pitch = (img.width * 4) as u32;
-            let bitmap = ctx.CreateBitmap(
-                D2D_SIZE_U { width: img.width, height: img.height },
-                Some(premul.as_ptr() as *const _),
+            let pitch = width * 4;
+            let bitmap = match ctx.CreateBitmap(
+                D2D_SIZE_U { width, height },
+                Some(data.as_ptr() as *const _),
                 pitch,
                 &bp,
-            ).unwrap();
-            self.image_cache.insert(key, bitmap.clone().into());
-            bitmap.into()
+            ) {
+                Ok(b) => b,
+                Err(e) => {
+                    vlog!("image bitmap CreateBitmap {}x{} failed: {:?}", width, height, e);
+                    return None;
+                }
+            };
+            insert_bounded(
+                &mut self.image_cache,
+                &mut self.image_cache_order,
+                IMAGE_CACHE_CAPACITY,
+                key,
+                bitmap.clone().into(),
+            );
+            Some(bitmap.into())
         }
     }
 
     // Removed legacy text_format_cache based path; glyph runs now used directly.
-    fn build_path_geometry(&self, path: &[PathEl]) -> Option<ID2D1PathGeometry> {
+    fn get_or_create_stroke_style(&mut self, key: StrokeStyleKey) -> Option<ID2D1StrokeStyle> {
+        if let Some(s) = self.stroke_style_cache.get(&key) {
+            return Some(s.clone());
+        }
+        let factory = self.d2d_factory.as_ref()?;
+        let props = D2D1_STROKE_STYLE_PROPERTIES1 {
+            startCap: D2D1_CAP_STYLE(key.start_cap),
+            endCap: D2D1_CAP_STYLE(key.end_cap),
+            dashCap: D2D1_CAP_STYLE(key.end_cap),
+            lineJoin: D2D1_LINE_JOIN(key.join),
+            miterLimit: key.miter_limit_q as f32 / 100.0,
+            dashStyle: D2D1_DASH_STYLE_SOLID,
+            dashOffset: 0.0,
+            transformType: D2D1_STROKE_TRANSFORM_TYPE_NORMAL,
+        };
+        let style: ID2D1StrokeStyle =
+            unsafe { factory.CreateStrokeStyle(&props, None).ok()?.cast().ok()? };
+        self.stroke_style_cache.insert(key, style.clone());
+        Some(style)
+    }
+
+    // `fill` is the winding rule for fills (D2D's own default is alternate/even-odd) and None for
+    // strokes. Subpaths without a ClosePath are implicitly closed for fills but left open for strokes,
+    // so a stroke doesn't gain a closing segment.
+    fn build_path_geometry(
+        &self,
+        path: &[PathEl],
+        fill: Option<Fill>,
+    ) -> Option<ID2D1PathGeometry> {
         let factory = self.d2d_factory.as_ref()?;
         unsafe {
             let geom1 = factory.CreatePathGeometry().ok()?;
             let geom: ID2D1PathGeometry = geom1.cast().ok()?;
             let sink = geom.Open().ok()?;
+            sink.SetFillMode(match fill {
+                Some(Fill::NonZero) | None => D2D1_FILL_MODE_WINDING,
+                Some(Fill::EvenOdd) => D2D1_FILL_MODE_ALTERNATE,
+            });
+            let unclosed_end = if fill.is_some() {
+                D2D1_FIGURE_END_CLOSED
+            } else {
+                D2D1_FIGURE_END_OPEN
+            };
             let mut figure_open = false;
             for el in path {
                 match el {
                     PathEl::MoveTo(p) => {
-                        if figure_open { sink.EndFigure(D2D1_FIGURE_END_OPEN); }
+                        if figure_open { sink.EndFigure(unclosed_end); }
                         sink.BeginFigure(D2D_POINT_2F { x: p.x as f32, y: p.y as f32 }, D2D1_FIGURE_BEGIN_FILLED);
                         figure_open = true;
                     }
@@ -1728,7 +4712,9 @@ impl D2DWindowRenderer {
                     }
                 }
             }
-            if figure_open { sink.EndFigure(D2D1_FIGURE_END_OPEN); }
+            if figure_open {
+                sink.EndFigure(unclosed_end);
+            }
             let _ = sink.Close();
             Some(geom)
         }
@@ -1770,7 +4756,7 @@ impl D2DWindowRenderer {
             let _ = temp_ctx.SetTarget(&offscreen);
             temp_ctx.BeginDraw();
             temp_ctx.Clear(Some(&D2D1_COLOR_F { r:0.0,g:0.0,b:0.0,a:0.0 }));
-            let col = D2D1_COLOR_F { r: color.components[0] as f32, g: color.components[1] as f32, b: color.components[2] as f32, a: color.components[3] as f32 };
+            let col = d2d_color(color);
             let solid_brush = temp_ctx.CreateSolidColorBrush(&col, None).unwrap();
             let local_rect = D2D_RECT_F { left: pad as f32, top: pad as f32, right: pad as f32 + rect.width() as f32, bottom: pad as f32 + rect.height() as f32 };
             if corner_radius > 0.0 {
@@ -1795,8 +4781,18 @@ impl D2DWindowRenderer {
                     ctx.DrawBitmap(&offscreen, Some(&dest), 1.0, D2D1_INTERPOLATION_MODE_LINEAR, None, None);
                 }
             } else {
+                // No GPU blur effect on this device: blur on the CPU (falls back to the hard shape,
+                // which isn't cached under the blurred shadow's key so later frames retry the blur).
                 let dest = D2D_RECT_F { left:(rect.x0 - pad) as f32, top:(rect.y0 - pad) as f32, right:(rect.x0 - pad) as f32 + ow as f32, bottom:(rect.y0 - pad) as f32 + oh as f32 };
-                ctx.DrawBitmap(&offscreen, Some(&dest), 1.0, D2D1_INTERPOLATION_MODE_LINEAR, None, None);
+                match self.cpu_blur_bitmap(&offscreen, ow, oh, std_dev) {
+                    Some(blurred) => {
+                        ctx.DrawBitmap(&blurred, Some(&dest), 1.0, D2D1_INTERPOLATION_MODE_LINEAR, None, None);
+                        self.insert_shadow_cache(key, blurred);
+                    }
+                    None => ctx.DrawBitmap(&offscreen, Some(&dest), 1.0, D2D1_INTERPOLATION_MODE_LINEAR, None, None),
+                }
+                debug_log_d2d("draw_gaussian_box_shadow: end (cpu blur)");
+                return;
             }
             // Cache
             if let Some(effect) = &self.gaussian_blur_effect {
@@ -1818,8 +4814,6 @@ impl D2DWindowRenderer {
                 } else {
                     self.insert_shadow_cache(key, offscreen.clone());
                 }
-            } else {
-                self.insert_shadow_cache(key, offscreen.clone());
             }
         }
         debug_log_d2d("draw_gaussian_box_shadow: end");
@@ -1833,19 +4827,21 @@ impl D2DWindowRenderer {
         radius: f64,
         std_dev: f64,
     ) {
-        // Revised inset shadow: create a thin ring just inside the element rect and blur inward.
+        // Inset shadow: `rect` is the unshadowed "hole" (padding box shrunk by spread, shifted by the
+        // shadow offset). Everything outside it is shadow colour; only the band around the hole edge
+        // needs blurring. The caller's layer clips the result to the element's padding box.
         let std_dev = std_dev.clamp(0.5, 64.0);
-        if rect.width() <= 0.0 || rect.height() <= 0.0 {
+        if rect.width() < 0.0 || rect.height() < 0.0 {
             return;
         }
         debug_log_d2d(&format!(
             "draw_inset_gaussian_box_shadow: begin rect=({}, {}, {}, {}) radius={} sd={} a={:.3}",
             rect.x0, rect.y0, rect.x1, rect.y1, radius, std_dev, color.components[3]
         ));
-        let ring_thickness = 1.5_f64
-            .max(std_dev * 0.4)
-            .min(rect.width().min(rect.height()) * 0.5 - 0.5);
-        let pad = (std_dev * 1.5).ceil().max(1.0); // inward spread
+        // Blur reach; the offscreen keeps a second `reach` margin of solid colour so the blur's
+        // transparent edge padding never bleeds into the part we draw.
+        let reach = (std_dev * 3.0).ceil() + 1.0;
+        let pad = reach * 2.0;
         let off_w = (rect.width() + pad * 2.0).ceil() as u32;
         let off_h = (rect.height() + pad * 2.0).ceil() as u32;
         if off_w == 0 || off_h == 0 {
@@ -1863,8 +4859,9 @@ impl D2DWindowRenderer {
             Some(f) => f.clone(),
             None => return,
         };
+        let col = d2d_color(color);
         unsafe {
-            // Use a temporary device context to draw the inner ring to avoid SetTarget on primary context.
+            // Use a temporary device context to draw the frame to avoid SetTarget on primary context.
             let d2d_device = match &self.d2d_device {
                 Some(d) => d.clone(),
                 None => return,
@@ -1883,149 +4880,508 @@ impl D2DWindowRenderer {
                 bitmapOptions: D2D1_BITMAP_OPTIONS_TARGET,
                 colorContext: std::mem::ManuallyDrop::new(None::<ID2D1ColorContext>),
             };
-            let off_bmp = match temp_ctx.CreateBitmap(
+            let off_bmp = match temp_ctx.CreateBitmap(
+                D2D_SIZE_U {
+                    width: off_w,
+                    height: off_h,
+                },
+                None,
+                0,
+                &bmp_props,
+            ) {
+                Ok(b) => b,
+                Err(_) => return,
+            };
+            let _ = temp_ctx.SetTarget(&off_bmp);
+            temp_ctx.BeginDraw();
+            temp_ctx.Clear(Some(&col));
+            // Punch the hole out of the solid fill.
+            let hole_rr = D2D1_ROUNDED_RECT {
+                rect: D2D_RECT_F {
+                    left: pad as f32,
+                    top: pad as f32,
+                    right: (pad + rect.width()) as f32,
+                    bottom: (pad + rect.height()) as f32,
+                },
+                radiusX: radius as f32,
+                radiusY: radius as f32,
+            };
+            if let Ok(clear_brush) = temp_ctx.CreateSolidColorBrush(
+                &D2D1_COLOR_F {
+                    r: 0.0,
+                    g: 0.0,
+                    b: 0.0,
+                    a: 0.0,
+                },
+                None,
+            ) {
+                temp_ctx.SetPrimitiveBlend(D2D1_PRIMITIVE_BLEND_COPY);
+                temp_ctx.FillRoundedRectangle(&hole_rr, &clear_brush);
+                temp_ctx.SetPrimitiveBlend(D2D1_PRIMITIVE_BLEND_SOURCE_OVER);
+            }
+            let _ = temp_ctx.EndDraw(None, None);
+            // Draw only the inner part of the blurred frame (hole + reach), unaffected by edge padding.
+            let band = rect.inflate(reach, reach);
+            let src = D2D_RECT_F {
+                left: (pad - reach) as f32,
+                top: (pad - reach) as f32,
+                right: (pad + rect.width() + reach) as f32,
+                bottom: (pad + rect.height() + reach) as f32,
+            };
+            // Blur frame using main context effect
+            if self.gaussian_blur_effect.is_none() {
+                if let Ok(e) = ctx.CreateEffect(&CLSID_D2D1GaussianBlur) {
+                    self.gaussian_blur_effect = Some(e);
+                }
+            }
+            if let Some(effect) = self.gaussian_blur_effect.clone() {
+                let _ = effect.SetInput(0, &off_bmp, true);
+                let sigma = std_dev as f32;
+                let sigma_bytes = std::slice::from_raw_parts(
+                    (&sigma) as *const f32 as *const u8,
+                    std::mem::size_of::<f32>(),
+                );
+                let _ = effect.SetValue(
+                    D2D1_GAUSSIANBLUR_PROP_STANDARD_DEVIATION.0 as u32,
+                    D2D1_PROPERTY_TYPE_FLOAT,
+                    sigma_bytes,
+                );
+                let border_mode = D2D1_BORDER_MODE_SOFT;
+                let border_u32: u32 = border_mode.0 as u32;
+                let border_bytes = std::slice::from_raw_parts(
+                    (&border_u32) as *const u32 as *const u8,
+                    std::mem::size_of::<u32>(),
+                );
+                let _ = effect.SetValue(
+                    D2D1_GAUSSIANBLUR_PROP_BORDER_MODE.0 as u32,
+                    D2D1_PROPERTY_TYPE_UINT32,
+                    border_bytes,
+                );
+                if let Ok(effect_img) = effect.cast::<ID2D1Image>() {
+                    let offset = D2D_POINT_2F {
+                        x: band.x0 as f32,
+                        y: band.y0 as f32,
+                    };
+                    ctx.DrawImage(
+                        &effect_img,
+                        Some(&offset),
+                        Some(&src),
+                        D2D1_INTERPOLATION_MODE_LINEAR,
+                        D2D1_COMPOSITE_MODE_SOURCE_OVER,
+                    );
+                }
+            } else if let Some(blurred) = self.cpu_blur_bitmap(&off_bmp, off_w, off_h, std_dev) {
+                let dest = D2D_RECT_F {
+                    left: band.x0 as f32,
+                    top: band.y0 as f32,
+                    right: band.x1 as f32,
+                    bottom: band.y1 as f32,
+                };
+                ctx.DrawBitmap(
+                    &blurred,
+                    Some(&dest),
+                    1.0,
+                    D2D1_INTERPOLATION_MODE_LINEAR,
+                    Some(&src),
+                    None,
+                );
+            }
+            // Solid shadow beyond the blurred band covers large offsets / spreads.
+            let far = band.inflate(16384.0, 16384.0);
+            let to_d2d = |r: Rect| D2D_RECT_F {
+                left: r.x0 as f32,
+                top: r.y0 as f32,
+                right: r.x1 as f32,
+                bottom: r.y1 as f32,
+            };
+            if let (Ok(outer), Ok(inner)) = (
+                factory.CreateRectangleGeometry(&to_d2d(far)),
+                factory.CreateRectangleGeometry(&to_d2d(band)),
+            ) {
+                let geoms: [Option<ID2D1Geometry>; 2] =
+                    [Some(outer.cast().unwrap()), Some(inner.cast().unwrap())];
+                if let Ok(group) = factory.CreateGeometryGroup(D2D1_FILL_MODE_ALTERNATE, &geoms) {
+                    if let Ok(brush) = ctx.CreateSolidColorBrush(&col, None) {
+                        ctx.FillGeometry(&group, &brush, None);
+                    }
+                }
+            }
+            debug_log_d2d(&format!(
+                "draw_inset_gaussian_box_shadow: drew inset rect=({}, {}, {}, {}) radius={} sd={} reach={}",
+                rect.x0, rect.y0, rect.x1, rect.y1, radius, std_dev, reach
+            ));
+            debug_log_d2d("draw_inset_gaussian_box_shadow: end");
+        }
+    }
+
+    // Text shadow: fill the run's outline into an offscreen bitmap padded by the blur reach, then draw it
+    // through the shared gaussian blur effect (CPU blur without it). Unblurred shadows fill directly.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_blurred_glyph_run(
+        &mut self,
+        ctx: &ID2D1DeviceContext,
+        face: &IDWriteFontFace,
+        size: f32,
+        glyph_indices: &[u16],
+        advances: &[f32],
+        origin: (f32, f32),
+        bidi_level: u8,
+        color: Color,
+        std_dev: f64,
+    ) {
+        if std_dev < 0.05 {
+            if let Some(geom) = self.build_glyph_outline_geometry_at(
+                face,
+                size,
+                glyph_indices,
+                advances,
+                origin,
+                bidi_level,
+            ) {
+                let brush = self.create_solid_brush(color);
+                unsafe { ctx.FillGeometry(&geom, &brush, None) };
+            }
+            return;
+        }
+        let Some(geom) =
+            self.build_glyph_outline_geometry(face, size, glyph_indices, advances, bidi_level)
+        else {
+            return;
+        };
+        let std_dev = std_dev.min(200.0);
+        let pad = (std_dev * 3.0).ceil() as f32 + 1.0;
+        unsafe {
+            let Ok(bounds) = geom.GetBounds(None) else {
+                return;
+            };
+            if !(bounds.right > bounds.left && bounds.bottom > bounds.top) {
+                return;
+            }
+            let ow = (bounds.right - bounds.left + pad * 2.0).ceil() as u32;
+            let oh = (bounds.bottom - bounds.top + pad * 2.0).ceil() as u32;
+            if ow > 16384 || oh > 16384 {
+                return;
+            }
+            if self.gaussian_blur_effect.is_none() {
+                if let Ok(effect) = ctx.CreateEffect(&CLSID_D2D1GaussianBlur) {
+                    self.gaussian_blur_effect = Some(effect);
+                }
+            }
+            let d2d_device = match &self.d2d_device {
+                Some(d) => d.clone(),
+                None => return,
+            };
+            let temp_ctx = match d2d_device.CreateDeviceContext(D2D1_DEVICE_CONTEXT_OPTIONS_NONE) {
+                Ok(c) => c,
+                Err(_) => return,
+            };
+            let pf = D2D1_PIXEL_FORMAT {
+                format: DXGI_FORMAT_B8G8R8A8_UNORM,
+                alphaMode: D2D1_ALPHA_MODE_PREMULTIPLIED,
+            };
+            let bp = D2D1_BITMAP_PROPERTIES1 {
+                pixelFormat: pf,
+                dpiX: 96.0,
+                dpiY: 96.0,
+                bitmapOptions: D2D1_BITMAP_OPTIONS_TARGET,
+                colorContext: std::mem::ManuallyDrop::new(None),
+            };
+            let offscreen = match temp_ctx.CreateBitmap(
+                D2D_SIZE_U {
+                    width: ow,
+                    height: oh,
+                },
+                None,
+                0,
+                &bp,
+            ) {
+                Ok(b) => b,
+                Err(_) => return,
+            };
+            let _ = temp_ctx.SetTarget(&offscreen);
+            temp_ctx.BeginDraw();
+            temp_ctx.Clear(Some(&D2D1_COLOR_F {
+                r: 0.0,
+                g: 0.0,
+                b: 0.0,
+                a: 0.0,
+            }));
+            let local = Affine::translate(((pad - bounds.left) as f64, (pad - bounds.top) as f64));
+            temp_ctx.SetTransform(&affine_to_matrix(local));
+            let col = d2d_color(color);
+            if let Ok(brush) = temp_ctx.CreateSolidColorBrush(&col, None) {
+                temp_ctx.FillGeometry(&geom, &brush, None);
+            }
+            let _ = temp_ctx.EndDraw(None, None);
+            let left = origin.0 + bounds.left - pad;
+            let top = origin.1 + bounds.top - pad;
+            if let Some(effect) = &self.gaussian_blur_effect {
+                let _ = effect.SetInput(0, &offscreen, true);
+                let sigma = std_dev as f32;
+                let sigma_bytes: &[u8] = std::slice::from_raw_parts(
+                    (&sigma) as *const f32 as *const u8,
+                    std::mem::size_of::<f32>(),
+                );
+                let _ = effect.SetValue(
+                    D2D1_GAUSSIANBLUR_PROP_STANDARD_DEVIATION.0 as u32,
+                    D2D1_PROPERTY_TYPE_FLOAT,
+                    sigma_bytes,
+                );
+                let border_val: u32 = D2D1_BORDER_MODE_SOFT.0 as u32;
+                let border_bytes: &[u8] = std::slice::from_raw_parts(
+                    (&border_val) as *const u32 as *const u8,
+                    std::mem::size_of::<u32>(),
+                );
+                let _ = effect.SetValue(
+                    D2D1_GAUSSIANBLUR_PROP_BORDER_MODE.0 as u32,
+                    D2D1_PROPERTY_TYPE_UINT32,
+                    border_bytes,
+                );
+                if let Ok(effect_img) = effect.cast::<ID2D1Image>() {
+                    ctx.DrawImage(
+                        &effect_img,
+                        Some(&D2D_POINT_2F { x: left, y: top }),
+                        None,
+                        D2D1_INTERPOLATION_MODE_LINEAR,
+                        D2D1_COMPOSITE_MODE_SOURCE_OVER,
+                    );
+                    return;
+                }
+            }
+            let blurred = self
+                .cpu_blur_bitmap(&offscreen, ow, oh, std_dev)
+                .unwrap_or(offscreen);
+            let dest = D2D_RECT_F {
+                left,
+                top,
+                right: left + ow as f32,
+                bottom: top + oh as f32,
+            };
+            ctx.DrawBitmap(
+                &blurred,
+                Some(&dest),
+                1.0,
+                D2D1_INTERPOLATION_MODE_LINEAR,
+                None,
+                None,
+            );
+        }
+    }
+
+    // backdrop-filter: blur(): copy the target pixels under `path`, padded by the blur reach so the edges
+    // blur in what lies around the element, blur the copy through the shared gaussian blur effect (CPU
+    // blur without it) and draw it back clipped to `path`. Content of an open opacity layer hasn't
+    // reached the target yet, so it isn't part of the backdrop.
+    fn draw_backdrop_blur(
+        &mut self,
+        ctx: &ID2D1DeviceContext,
+        target: &ID2D1Bitmap1,
+        path: &[PathEl],
+        std_dev: f64,
+    ) {
+        let Some(bounds) = path_bounds(path) else {
+            return;
+        };
+        let scale = self.scale as f64;
+        // The copy is in device pixels
+        let sigma = std_dev.min(200.0) * scale;
+        let reach = (sigma * 3.0).ceil();
+        unsafe {
+            let target_size = target.GetPixelSize();
+            let device = bounds
+                .scale_from_origin(scale)
+                .inflate(reach, reach)
+                .expand()
+                .intersect(Rect::new(
+                    0.0,
+                    0.0,
+                    target_size.width as f64,
+                    target_size.height as f64,
+                ));
+            if device.width() < 1.0 || device.height() < 1.0 {
+                return;
+            }
+            let (w, h) = (device.width() as u32, device.height() as u32);
+            // Same pixel format as the target, as CopyFromBitmap requires
+            let bp = D2D1_BITMAP_PROPERTIES1 {
+                pixelFormat: target.GetPixelFormat(),
+                dpiX: 96.0,
+                dpiY: 96.0,
+                bitmapOptions: D2D1_BITMAP_OPTIONS_NONE,
+                colorContext: std::mem::ManuallyDrop::new(None),
+            };
+            let Ok(backdrop) = ctx.CreateBitmap(
                 D2D_SIZE_U {
-                    width: off_w,
-                    height: off_h,
+                    width: w,
+                    height: h,
                 },
                 None,
                 0,
-                &bmp_props,
-            ) {
-                Ok(b) => b,
-                Err(_) => return,
-            };
-            let _ = temp_ctx.SetTarget(&off_bmp);
-            temp_ctx.BeginDraw();
-            temp_ctx.Clear(Some(&D2D1_COLOR_F {
-                r: 0.0,
-                g: 0.0,
-                b: 0.0,
-                a: 0.0,
-            }));
-            // Build ring using geometry group of two rounded rects (outer + inner hole).
-            let rr = radius; // preserve original radius
-            let outer_rr = D2D1_ROUNDED_RECT {
-                rect: D2D_RECT_F {
-                    left: pad as f32,
-                    top: pad as f32,
-                    right: (pad + rect.width()) as f32,
-                    bottom: (pad + rect.height()) as f32,
-                },
-                radiusX: rr as f32,
-                radiusY: rr as f32,
+                &bp,
+            ) else {
+                return;
             };
-            let inner_rr = {
-                let shrink = ring_thickness as f32;
-                let mut r_inner = radius as f32 - shrink;
-                if r_inner < 0.0 {
-                    r_inner = 0.0;
-                }
-                D2D1_ROUNDED_RECT {
-                    rect: D2D_RECT_F {
-                        left: pad as f32 + shrink,
-                        top: pad as f32 + shrink,
-                        right: (pad + rect.width()) as f32 - shrink,
-                        bottom: (pad + rect.height()) as f32 - shrink,
-                    },
-                    radiusX: r_inner,
-                    radiusY: r_inner,
-                }
+            let src = D2D_RECT_U {
+                left: device.x0 as u32,
+                top: device.y0 as u32,
+                right: device.x1 as u32,
+                bottom: device.y1 as u32,
             };
-            let outer_geom1 = match factory.CreateRoundedRectangleGeometry(&outer_rr) {
-                Ok(g) => g,
-                Err(_) => {
-                    return;
-                }
+            // Flushes the pending batch, so everything drawn so far is in the copy
+            if let Err(e) = backdrop.CopyFromBitmap(None, target, Some(&src)) {
+                debug_log_d2d(&format!("draw_backdrop_blur: copy failed {:?}", e));
+                return;
+            }
+            let Some(geom) = self.build_path_geometry(path, Some(Fill::NonZero)) else {
+                return;
             };
-            let inner_geom1 = match factory.CreateRoundedRectangleGeometry(&inner_rr) {
-                Ok(g) => g,
-                Err(_) => {
-                    return;
-                }
+            let Ok(mask) = geom.cast::<ID2D1Geometry>() else {
+                return;
             };
-            let geoms_vec: [Option<ID2D1Geometry>; 2] = [
-                Some(outer_geom1.cast().unwrap()),
-                Some(inner_geom1.cast().unwrap()),
-            ];
-            if let Ok(group) = factory.CreateGeometryGroup(D2D1_FILL_MODE_ALTERNATE, &geoms_vec) {
-                let mut comps = color.components;
-                comps[3] *= 0.9;
-                let col = D2D1_COLOR_F {
-                    r: comps[0] as f32,
-                    g: comps[1] as f32,
-                    b: comps[2] as f32,
-                    a: comps[3] as f32,
-                };
-                if let Ok(ring_brush) = temp_ctx.CreateSolidColorBrush(&col, None) {
-                    temp_ctx.FillGeometry(&group, &ring_brush, None);
+            if self.gaussian_blur_effect.is_none() {
+                if let Ok(effect) = ctx.CreateEffect(&CLSID_D2D1GaussianBlur) {
+                    self.gaussian_blur_effect = Some(effect);
                 }
             }
-            let _ = temp_ctx.EndDraw(None, None);
-            // Blur ring using main context effect
-            let effect = if let Some(e) = &self.gaussian_blur_effect {
-                e.clone()
-            } else {
-                match ctx.CreateEffect(&CLSID_D2D1GaussianBlur) {
-                    Ok(e) => {
-                        self.gaussian_blur_effect = Some(e.clone());
-                        e
-                    }
-                    Err(_) => {
-                        return;
+            // The mask is fixed under the scene transform when pushed; the blurred copy is then drawn unscaled
+            let params = D2D1_LAYER_PARAMETERS1 {
+                contentBounds: D2D_RECT_F {
+                    left: bounds.x0 as f32,
+                    top: bounds.y0 as f32,
+                    right: bounds.x1 as f32,
+                    bottom: bounds.y1 as f32,
+                },
+                geometricMask: std::mem::ManuallyDrop::new(Some(mask)),
+                maskAntialiasMode: self.geometry_antialias_mode.to_d2d(),
+                maskTransform: affine_to_matrix(Affine::IDENTITY),
+                opacity: 1.0,
+                opacityBrush: std::mem::ManuallyDrop::new(None),
+                layerOptions: D2D1_LAYER_OPTIONS1_NONE,
+            };
+            ctx.PushLayer(&params, None);
+            ctx.SetTransform(&affine_to_matrix(Affine::IDENTITY));
+            let offset = D2D_POINT_2F {
+                x: device.x0 as f32,
+                y: device.y0 as f32,
+            };
+            let drawn = match &self.gaussian_blur_effect {
+                Some(effect) => {
+                    let _ = effect.SetInput(0, &backdrop, true);
+                    let sigma = sigma as f32;
+                    let sigma_bytes: &[u8] = std::slice::from_raw_parts(
+                        (&sigma) as *const f32 as *const u8,
+                        std::mem::size_of::<f32>(),
+                    );
+                    let _ = effect.SetValue(
+                        D2D1_GAUSSIANBLUR_PROP_STANDARD_DEVIATION.0 as u32,
+                        D2D1_PROPERTY_TYPE_FLOAT,
+                        sigma_bytes,
+                    );
+                    // Hard borders keep the blur inside the copied region
+                    let border_val: u32 = D2D1_BORDER_MODE_HARD.0 as u32;
+                    let border_bytes: &[u8] = std::slice::from_raw_parts(
+                        (&border_val) as *const u32 as *const u8,
+                        std::mem::size_of::<u32>(),
+                    );
+                    let _ = effect.SetValue(
+                        D2D1_GAUSSIANBLUR_PROP_BORDER_MODE.0 as u32,
+                        D2D1_PROPERTY_TYPE_UINT32,
+                        border_bytes,
+                    );
+                    match effect.cast::<ID2D1Image>() {
+                        Ok(effect_img) => {
+                            ctx.DrawImage(
+                                &effect_img,
+                                Some(&offset),
+                                None,
+                                D2D1_INTERPOLATION_MODE_LINEAR,
+                                D2D1_COMPOSITE_MODE_SOURCE_OVER,
+                            );
+                            true
+                        }
+                        Err(_) => false,
                     }
                 }
+                None => false,
             };
-            // Provide ring bitmap to blur effect
-            let _ = effect.SetInput(0, &off_bmp, true);
-            let sigma = std_dev as f32;
-            let sigma_bytes = std::slice::from_raw_parts(
-                (&sigma) as *const f32 as *const u8,
-                std::mem::size_of::<f32>(),
-            );
-            let _ = effect.SetValue(
-                D2D1_GAUSSIANBLUR_PROP_STANDARD_DEVIATION.0 as u32,
-                D2D1_PROPERTY_TYPE_FLOAT,
-                sigma_bytes,
-            );
-            let border_mode = D2D1_BORDER_MODE_SOFT;
-            let border_u32: u32 = border_mode.0 as u32;
-            let border_bytes = std::slice::from_raw_parts(
-                (&border_u32) as *const u32 as *const u8,
-                std::mem::size_of::<u32>(),
-            );
-            let _ = effect.SetValue(
-                D2D1_GAUSSIANBLUR_PROP_BORDER_MODE.0 as u32,
-                D2D1_PROPERTY_TYPE_UINT32,
-                border_bytes,
-            );
-            // Clip to element rect and draw
-            let clip = D2D_RECT_F {
-                left: rect.x0 as f32,
-                top: rect.y0 as f32,
-                right: rect.x1 as f32,
-                bottom: rect.y1 as f32,
-            };
-            ctx.PushAxisAlignedClip(&clip, D2D1_ANTIALIAS_MODE_PER_PRIMITIVE);
-            if let Ok(effect_img) = effect.cast::<ID2D1Image>() {
-                let offset = D2D_POINT_2F {
-                    x: (rect.x0 - pad) as f32,
-                    y: (rect.y0 - pad) as f32,
-                };
-                ctx.DrawImage(
-                    &effect_img,
-                    Some(&offset),
+            if !drawn {
+                if let Some(blurred) = self.cpu_blur_bitmap(&backdrop, w, h, sigma) {
+                    let dest = D2D_RECT_F {
+                        left: offset.x,
+                        top: offset.y,
+                        right: offset.x + w as f32,
+                        bottom: offset.y + h as f32,
+                    };
+                    ctx.DrawBitmap(
+                        &blurred,
+                        Some(&dest),
+                        1.0,
+                        D2D1_INTERPOLATION_MODE_LINEAR,
+                        None,
+                        None,
+                    );
+                }
+            }
+            ctx.SetTransform(&affine_to_matrix(Affine::scale(scale)));
+            ctx.PopLayer();
+        }
+    }
+
+    // CPU fallback for CLSID_D2D1GaussianBlur: read back a premultiplied BGRA bitmap, box-blur it and
+    // upload the result. Only used when the effect can't be created (some WARP/driver setups).
+    fn cpu_blur_bitmap(
+        &self,
+        src: &ID2D1Bitmap1,
+        width: u32,
+        height: u32,
+        std_dev: f64,
+    ) -> Option<ID2D1Bitmap1> {
+        let ctx = self.d2d_ctx.as_ref()?;
+        let size = D2D_SIZE_U { width, height };
+        let pixel_format = D2D1_PIXEL_FORMAT {
+            format: DXGI_FORMAT_B8G8R8A8_UNORM,
+            alphaMode: D2D1_ALPHA_MODE_PREMULTIPLIED,
+        };
+        let props = |options| D2D1_BITMAP_PROPERTIES1 {
+            pixelFormat: pixel_format,
+            dpiX: 96.0,
+            dpiY: 96.0,
+            bitmapOptions: options,
+            colorContext: std::mem::ManuallyDrop::new(None::<ID2D1ColorContext>),
+        };
+        let row_bytes = width as usize * 4;
+        unsafe {
+            let readback = ctx
+                .CreateBitmap(
+                    size,
                     None,
-                    D2D1_INTERPOLATION_MODE_LINEAR,
-                    D2D1_COMPOSITE_MODE_SOURCE_OVER,
-                );
+                    0,
+                    &props(D2D1_BITMAP_OPTIONS_CPU_READ | D2D1_BITMAP_OPTIONS_CANNOT_DRAW),
+                )
+                .ok()?;
+            readback.CopyFromBitmap(None, src, None).ok()?;
+            let mapped = readback.Map(D2D1_MAP_OPTIONS_READ).ok()?;
+            let mut pixels = Vec::with_capacity(row_bytes * height as usize);
+            for y in 0..height as usize {
+                pixels.extend_from_slice(std::slice::from_raw_parts(
+                    mapped.bits.add(y * mapped.pitch as usize),
+                    row_bytes,
+                ));
+            }
+            let _ = readback.Unmap();
+            box_blur_rgba(&mut pixels, width as usize, height as usize, std_dev);
+            match ctx.CreateBitmap(
+                size,
+                Some(pixels.as_ptr() as *const _),
+                row_bytes as u32,
+                &props(D2D1_BITMAP_OPTIONS_NONE),
+            ) {
+                Ok(b) => Some(b),
+                Err(e) => {
+                    debug_log_d2d(&format!("cpu_blur_bitmap: upload failed {:?}", e));
+                    None
+                }
             }
-            ctx.PopAxisAlignedClip();
-            debug_log_d2d(&format!(
-                "draw_inset_gaussian_box_shadow: drew inset ring rect=({}, {}, {}, {}) radius={} sd={} pad={} ring_thickness={}",
-                rect.x0, rect.y0, rect.x1, rect.y1, radius, std_dev, pad, ring_thickness
-            ));
-            debug_log_d2d("draw_inset_gaussian_box_shadow: end");
         }
     }
 
@@ -2056,17 +5412,13 @@ impl D2DWindowRenderer {
     }
 
     fn insert_shadow_cache(&mut self, key: ShadowKey, bmp: ID2D1Bitmap1) {
-        const MAX_SHADOWS: usize = 64;
-        if self.shadow_cache.contains_key(&key) {
-            return;
-        }
-        if self.shadow_cache_order.len() >= MAX_SHADOWS {
-            if let Some(old) = self.shadow_cache_order.pop_front() {
-                self.shadow_cache.remove(&old);
-            }
-        }
-        self.shadow_cache_order.push_back(key);
-        self.shadow_cache.insert(key, bmp);
+        insert_bounded(
+            &mut self.shadow_cache,
+            &mut self.shadow_cache_order,
+            self.shadow_cache_capacity,
+            key,
+            bmp,
+        );
     }
 }
 
@@ -2083,7 +5435,16 @@ impl WindowRenderer for D2DWindowRenderer {
     fn is_active(&self) -> bool {
         self.active
     }
+    /// Resize contract: call `set_size` first (on a real change it drops the D2D target and the
+    /// cached backbuffer bitmap, which releases every reference to the swapchain buffers), then
+    /// `IDXGISwapChain::ResizeBuffers` on the host, then `render`, which recreates the bitmap. A host
+    /// that may call `ResizeBuffers` without a size change must `release_backbuffer_resources` first.
     fn set_size(&mut self, width: u32, height: u32) {
+        let (width, height) = (width.max(1), height.max(1));
+        if (self.width, self.height) != (width, height) {
+            self.release_backbuffer_resources();
+            self.backbuffer_dirty = true;
+        }
         self.width = width;
         self.height = height;
     }
@@ -2092,7 +5453,7 @@ impl WindowRenderer for D2DWindowRenderer {
             return;
         }
         // Start frame timer for FPS
-        self.frame_start = Instant::now();
+        self.frame_start = self.clock.now();
         // Build scene
         {
             let mut painter = D2DScenePainter {
@@ -2133,7 +5494,7 @@ impl WindowRenderer for D2DWindowRenderer {
                         return;
                     }
                     // (Re)create backbuffer bitmap if absent or size changed
-                    let need_new = match &self.backbuffer_bitmap {
+                    let need_new = self.backbuffer_dirty || match &self.backbuffer_bitmap {
                         Some(bmp) => {
                             let sz = bmp.GetSize();
                             (sz.width as u32) != self.width || (sz.height as u32) != self.height
@@ -2147,16 +5508,25 @@ impl WindowRenderer for D2DWindowRenderer {
                             );
                             return;
                         }
+                        self.backbuffer_dirty = false;
                     }
                     if let Some(bmp) = self.backbuffer_bitmap.take() {
                         self.playback(&bmp);
-                        self.backbuffer_bitmap = Some(bmp);
+                        // Keep the target unless playback lost the device (it belongs to the dead context)
+                        if self.d2d_ctx.is_some() {
+                            self.backbuffer_bitmap = Some(bmp);
+                        }
                     }
                 }
             }
         }
-        // Frame end / FPS calc
-        let dt = self.frame_start.elapsed().as_secs_f32();
+        self.finish_frame();
+    }
+
+    // Frame end: fold the frame's duration into the FPS average (recomputed every half second)
+    // and complete the first frame.
+    fn finish_frame(&mut self) {
+        let dt = self.elapsed_ms(self.frame_start) / 1000.0;
         self.fps_accum_time += dt;
         self.fps_frame_count += 1;
         if self.fps_accum_time >= 0.5 {
@@ -2164,14 +5534,1505 @@ impl WindowRenderer for D2DWindowRenderer {
             self.fps_accum_time = 0.0;
             self.fps_frame_count = 0;
         }
-        if !self.first_frame_done {
-            // first_frame_ms is total wall-clock from renderer construction to end of first frame
-            self.first_frame_ms = self.init_start.elapsed().as_secs_f32() * 1000.0;
-            self.first_frame_done = true;
-            end_init_window();
-            if !is_frozen() {
-                freeze();
-            }
+        self.complete_first_frame();
+    }
+
+    // Freeze the init window at the end of the first completed frame and hand its timings to the
+    // on_first_frame callback. No-op once the first frame is done.
+    fn complete_first_frame(&mut self) {
+        if self.first_frame_done {
+            return;
+        }
+        // first_frame_ms is total wall-clock from renderer construction to end of first frame
+        self.first_frame_ms = self.elapsed_ms(self.init_start);
+        self.first_frame_done = true;
+        end_init_window();
+        if !is_frozen() {
+            freeze();
+        }
+        if let Some(callback) = self.first_frame_callback.take() {
+            callback(self.first_frame_timings());
+        }
+    }
+
+    // Pipeline phases of the first frame plus the renderer-side phases; frame_total_ms carries
+    // first_frame_ms.
+    fn first_frame_timings(&self) -> FrameTimings {
+        FrameTimings {
+            device_init_ms: self.device_init_ms,
+            backbuffer_ms: self.backbuffer_create_ms,
+            playback_ms: self.playback_ms,
+            frame_total_ms: self.first_frame_ms,
+            ..self.last_frame_metrics.clone()
+        }
+    }
+}
+
+#[test]
+fn render_to_rgba_fills_red_rect() {
+    let mut renderer = D2DWindowRenderer::new();
+    let pixels = renderer.render_to_rgba(16, 16, |scene| {
+        scene.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            Color::new([1.0, 0.0, 0.0, 1.0]),
+            None,
+            &Rect::new(4.0, 4.0, 12.0, 12.0),
+        );
+    });
+    assert_eq!(pixels.len(), 16 * 16 * 4);
+    let px = |x: usize, y: usize| &pixels[(y * 16 + x) * 4..(y * 16 + x) * 4 + 4];
+    assert_eq!(px(4, 4), &[255, 0, 0, 255]);
+    assert_eq!(px(8, 8), &[255, 0, 0, 255]);
+    assert_eq!(px(11, 11), &[255, 0, 0, 255]);
+    // Outside the rect the white fallback clear shows through (the scene has content but no canvas color)
+    assert_eq!(px(0, 0), &[255, 255, 255, 255]);
+    assert_eq!(px(13, 13), &[255, 255, 255, 255]);
+}
+
+#[test]
+fn unclosed_fill_paths_fill_as_closed_shapes() {
+    // Triangle without a ClosePath: filled as if its last point joined the first
+    let mut triangle = kurbo::BezPath::new();
+    triangle.move_to((2.0, 2.0));
+    triangle.line_to((14.0, 2.0));
+    triangle.line_to((2.0, 14.0));
+    let mut renderer = D2DWindowRenderer::new();
+    let pixels = renderer.render_to_rgba(16, 16, |scene| {
+        scene.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            Color::new([1.0, 0.0, 0.0, 1.0]),
+            None,
+            &triangle,
+        );
+    });
+    let px = |x: usize, y: usize| &pixels[(y * 16 + x) * 4..(y * 16 + x) * 4 + 4];
+    assert_eq!(px(4, 4), &[255, 0, 0, 255]);
+    assert_eq!(px(3, 10), &[255, 0, 0, 255]);
+    // Outside the triangle
+    assert_eq!(px(12, 12), &[255, 255, 255, 255]);
+    assert_eq!(px(0, 8), &[255, 255, 255, 255]);
+
+    // Stroked, the same path has no edge from (2, 14) back to (2, 2)
+    let pixels = renderer.render_to_rgba(16, 16, |scene| {
+        scene.stroke(
+            &kurbo::Stroke::new(1.0),
+            Affine::IDENTITY,
+            Color::new([1.0, 0.0, 0.0, 1.0]),
+            None,
+            &triangle,
+        );
+    });
+    let px = |x: usize, y: usize| &pixels[(y * 16 + x) * 4..(y * 16 + x) * 4 + 4];
+    assert_eq!(px(1, 8), &[255, 255, 255, 255]);
+    assert_eq!(px(2, 8), &[255, 255, 255, 255]);
+}
+
+#[test]
+fn downscale_rgba_fits_max_dim() {
+    let (w, h) = (10u32, 4u32);
+    let data: Vec<u8> = (0..w * h).flat_map(|_| [200u8, 100, 50, 255]).collect();
+    let (out, dw, dh) = downscale_rgba(&data, w, h, 5);
+    assert_eq!((dw, dh), (5, 2));
+    assert_eq!(out.len(), 5 * 2 * 4);
+    assert_eq!(&out[..4], &[200, 100, 50, 255]);
+}
+
+#[test]
+fn box_blur_spreads_and_preserves_mass() {
+    let (w, h) = (21usize, 21usize);
+    let mut data = vec![0u8; w * h * 4];
+    let centre = (10 * w + 10) * 4;
+    data[centre..centre + 4].copy_from_slice(&[255, 0, 0, 255]);
+    box_blur_rgba(&mut data, w, h, 1.0);
+    let alpha = |x: usize, y: usize| data[(y * w + x) * 4 + 3] as i32;
+    assert!(alpha(10, 10) > 0 && alpha(10, 10) < 255);
+    assert!(alpha(8, 10) > 0 && alpha(10, 12) > 0);
+    assert!((alpha(9, 10) - alpha(11, 10)).abs() <= 1);
+    let total: i32 = data.chunks_exact(4).map(|p| p[3] as i32).sum();
+    assert!((200..=300).contains(&total), "total alpha {total}");
+}
+
+#[test]
+fn straight_source_converts_to_premultiplied_rgba() {
+    let rgba = [0u8, 0, 255, 255, 255, 0, 0, 128];
+    let out = to_premultiplied_rgba(&rgba, 2, 1, SourcePixels::Rgba8).unwrap();
+    assert_eq!(&out[..4], &[0, 0, 255, 255]);
+    assert_eq!(&out[4..], &[128, 0, 0, 128]);
+    let premul = to_premultiplied_rgba(&[10, 20, 30, 40], 1, 1, SourcePixels::PremulRgba8).unwrap();
+    assert_eq!(premul, vec![10, 20, 30, 40]);
+    assert!(to_premultiplied_rgba(&rgba, 3, 1, SourcePixels::Rgba8).is_none());
+}
+
+#[test]
+fn render_to_rgba_keeps_image_channel_order() {
+    let data: Vec<u8> = [
+        [255u8, 0, 0, 255],
+        [0, 255, 0, 255],
+        [0, 0, 255, 255],
+        [255, 255, 0, 255],
+    ]
+    .concat();
+    let image = peniko::Image::new(
+        peniko::Blob::new(Arc::new(data)),
+        peniko::ImageFormat::Rgba8,
+        2,
+        2,
+    );
+    let mut renderer = D2DWindowRenderer::new();
+    let pixels = renderer.render_to_rgba(2, 2, |scene| scene.draw_image(&image, Affine::IDENTITY));
+    assert_eq!(&pixels[0..4], &[255, 0, 0, 255]);
+    assert_eq!(&pixels[4..8], &[0, 255, 0, 255]);
+    assert_eq!(&pixels[8..12], &[0, 0, 255, 255]);
+    assert_eq!(&pixels[12..16], &[255, 255, 0, 255]);
+}
+
+#[test]
+fn unusable_image_sources_are_skipped() {
+    let red = Color::new([1.0, 0.0, 0.0, 1.0]);
+    // 2x2 RGBA8 needs 16 bytes; a short buffer must not be read past or uploaded
+    let short = peniko::Image::new(
+        peniko::Blob::new(Arc::new(vec![0u8, 0, 255, 255])),
+        peniko::ImageFormat::Rgba8,
+        2,
+        2,
+    );
+    let mut renderer = D2DWindowRenderer::new();
+    let pixels = renderer.render_to_rgba(2, 2, |scene| {
+        scene.fill(Fill::NonZero, Affine::IDENTITY, red, None, &Rect::new(0.0, 0.0, 2.0, 2.0));
+        scene.draw_image(&short, Affine::IDENTITY);
+    });
+    assert!(pixels.chunks_exact(4).all(|px| px == [255, 0, 0, 255]));
+    assert!(renderer.image_cache.is_empty());
+    assert_eq!(
+        SourcePixels::from_format(peniko::ImageFormat::Rgba8, ImageAlphaType::Alpha),
+        Some(SourcePixels::Rgba8)
+    );
+}
+
+#[test]
+fn image_bitmaps_are_cached_per_blob_and_bounded() {
+    let frame = |id: u64, rgba: [u8; 4]| {
+        peniko::Image::new(
+            peniko::Blob::from_raw_parts(Arc::new(rgba.to_vec()), id),
+            peniko::ImageFormat::Rgba8,
+            1,
+            1,
+        )
+    };
+    let mut renderer = D2DWindowRenderer::new();
+    // Same blob id, new buffer: the cached upload is reused
+    renderer.render_to_rgba(1, 1, |scene| {
+        scene.draw_image(&frame(1, [255, 0, 0, 255]), Affine::IDENTITY)
+    });
+    renderer.render_to_rgba(1, 1, |scene| {
+        scene.draw_image(&frame(1, [255, 0, 0, 255]), Affine::IDENTITY)
+    });
+    assert_eq!(renderer.image_cache.len(), 1);
+    // A new frame is a new blob id, drawn with its own pixels
+    let pixels = renderer.render_to_rgba(1, 1, |scene| {
+        scene.draw_image(&frame(2, [0, 0, 255, 255]), Affine::IDENTITY)
+    });
+    assert_eq!(&pixels[0..4], &[0, 0, 255, 255]);
+    // Frames past the capacity evict the oldest instead of accumulating
+    for id in 3..IMAGE_CACHE_CAPACITY as u64 + 10 {
+        renderer.render_to_rgba(1, 1, |scene| {
+            scene.draw_image(&frame(id, [0, 255, 0, 255]), Affine::IDENTITY)
+        });
+    }
+    assert_eq!(renderer.image_cache.len(), IMAGE_CACHE_CAPACITY);
+    assert!(!renderer.image_cache.keys().any(|(id, _)| *id == 1));
+}
+
+#[test]
+fn set_size_marks_backbuffer_dirty_only_on_change() {
+    let mut renderer = D2DWindowRenderer::new();
+    renderer.set_size(1, 1);
+    assert!(!renderer.backbuffer_dirty);
+    renderer.set_size(0, 0); // clamped to 1x1: no change
+    assert!(!renderer.backbuffer_dirty);
+    renderer.set_size(640, 480);
+    assert!(renderer.backbuffer_dirty);
+    assert!(renderer.backbuffer_bitmap.is_none());
+}
+
+#[test]
+fn decoration_lines_snap_to_device_pixels() {
+    let seg = (2.0, 40.0, 10.3);
+    let r = snap_decoration_rect(seg, 0.6, 1.0);
+    assert_eq!((r.x0, r.x1), (2.0, 40.0));
+    assert_eq!((r.y0, r.y1), (10.0, 11.0));
+    let r2 = snap_decoration_rect(seg, 1.0, 2.0);
+    assert_eq!((r2.y0, r2.y1), (10.0, 11.0));
+}
+
+#[test]
+fn only_text_decorations_are_recorded_as_decoration_lines() {
+    let mut scene = D2DScene {
+        commands: Vec::new(),
+    };
+    let mut painter = D2DScenePainter { scene: &mut scene };
+    let line = kurbo::Line::new((2.0, 10.0), (40.0, 10.0));
+    painter.draw_text_decoration(Affine::scale(2.0), Color::BLACK, line, 1.0);
+    // A flat-capped horizontal stroke (an SVG <line>, a border) stays a stroke
+    let flat = Stroke::new(1.0).with_caps(kurbo::Cap::Butt);
+    painter.stroke(&flat, Affine::IDENTITY, Color::BLACK, None, &line);
+    let Command::DecorationLine {
+        x0, x1, y, width, ..
+    } = &scene.commands[0]
+    else {
+        panic!("expected a decoration line");
+    };
+    assert_eq!((*x0, *x1, *y, *width), (4.0, 80.0, 20.0, 2.0));
+    assert!(matches!(scene.commands[1], Command::StrokePath { .. }));
+}
+
+#[test]
+fn repaint_clip_covers_current_and_previous_damage() {
+    let a = Rect::new(0.0, 0.0, 10.0, 10.0);
+    let b = Rect::new(20.0, 20.0, 30.0, 30.0);
+    assert_eq!(repaint_clip(true, Some(a), Some(b)), None);
+    assert_eq!(repaint_clip(false, None, Some(b)), None);
+    assert_eq!(repaint_clip(false, Some(a), None), None);
+    assert_eq!(
+        repaint_clip(false, Some(a), Some(b)),
+        Some(Rect::new(0.0, 0.0, 30.0, 30.0))
+    );
+
+    let mut renderer = D2DWindowRenderer::new();
+    assert!(renderer.is_dirty());
+    assert_eq!(renderer.take_repaint_clip(), None); // first frame is always full
+    renderer.mark_dirty(a);
+    assert_eq!(renderer.take_repaint_clip(), None); // previous frame was full
+    renderer.mark_dirty(b);
+    assert_eq!(renderer.take_repaint_clip(), Some(a.union(b)));
+    assert!(!renderer.is_dirty());
+}
+
+#[test]
+fn frame_results_accumulate_until_taken() {
+    let a = Rect::new(0.0, 0.0, 10.0, 10.0);
+    let b = Rect::new(20.0, 20.0, 30.0, 30.0);
+    let frame = |region| FrameResult {
+        presented_region: region,
+        needs_present: true,
+    };
+    assert_eq!(FrameResult::default().merge(frame(Some(a))), frame(Some(a)));
+    assert_eq!(frame(Some(a)).merge(FrameResult::default()), frame(Some(a)));
+    assert_eq!(
+        frame(Some(a)).merge(frame(Some(b))),
+        frame(Some(a.union(b)))
+    );
+    assert_eq!(frame(Some(a)).merge(frame(None)), frame(None));
+
+    let mut renderer = D2DWindowRenderer::new();
+    renderer.set_scale(2.0);
+    // Partial repaints present the clip in device px, widened by the unclipped scrollbar thumb
+    renderer.set_scrollbar_thumb(Some(Rect::new(95.0, 0.0, 100.0, 20.0)));
+    assert_eq!(
+        renderer.presented_region(Some(a), 200.0, 200.0),
+        Some(Rect::new(0.0, 0.0, 200.0, 40.0))
+    );
+    assert_eq!(renderer.presented_region(None, 200.0, 200.0), None);
+    renderer.show_debug_overlay = true;
+    assert_eq!(renderer.presented_region(Some(a), 200.0, 200.0), None);
+
+    // Offscreen renders don't ask the host to present
+    renderer.render_to_rgba(4, 4, |_| {});
+    assert!(!renderer.take_frame_result().needs_present);
+}
+
+#[test]
+fn family_candidates_keep_css_order_and_map_generics() {
+    assert_eq!(
+        family_candidates("\"Foo Sans\", Bar, monospace, sans-serif"),
+        vec!["Foo Sans", "Bar", "monospace", "sans-serif"]
+    );
+    assert!(family_candidates("").is_empty());
+
+    let config = FontConfig::default();
+    assert_eq!(
+        config.candidates(&family_candidates("\"Foo Sans\", monospace")),
+        vec!["Foo Sans", "Consolas", "Segoe UI"]
+    );
+    assert_eq!(
+        config.candidates(&family_candidates("system-ui, Segoe UI")),
+        vec!["Segoe UI"]
+    );
+    assert_eq!(config.candidates(&[]), vec!["Segoe UI"]);
+
+    // A host default (e.g. a CJK font) replaces Segoe UI for unstyled and unmatched text
+    let cjk = FontConfig {
+        default_family: "Yu Gothic UI".to_string(),
+        serif: "Yu Mincho".to_string(),
+        ..FontConfig::default()
+    };
+    assert_eq!(cjk.candidates(&[]), vec!["Yu Gothic UI"]);
+    assert_eq!(
+        cjk.candidates(&family_candidates("Missing, serif")),
+        vec!["Missing", "Yu Mincho", "Yu Gothic UI"]
+    );
+}
+
+#[test]
+fn fill_rule_controls_pentagram_center() {
+    // Five-point star drawn as one self-intersecting figure: the centre pentagon has winding 2
+    let mut star = kurbo::BezPath::new();
+    for (i, k) in [0, 2, 4, 1, 3].into_iter().enumerate() {
+        let a = (-90.0 + k as f64 * 72.0_f64).to_radians();
+        let p = (20.0 + 18.0 * a.cos(), 20.0 + 18.0 * a.sin());
+        if i == 0 {
+            star.move_to(p);
+        } else {
+            star.line_to(p);
+        }
+    }
+    star.close_path();
+    let mut renderer = D2DWindowRenderer::new();
+    let mut center = |fill: Fill| {
+        let pixels = renderer.render_to_rgba(40, 40, |scene| {
+            scene.fill(
+                fill,
+                Affine::IDENTITY,
+                Color::new([1.0, 0.0, 0.0, 1.0]),
+                None,
+                &star,
+            );
+        });
+        pixels[(20 * 40 + 20) * 4..(20 * 40 + 20) * 4 + 4].to_vec()
+    };
+    assert_eq!(center(Fill::NonZero), vec![255, 0, 0, 255]);
+    assert_eq!(center(Fill::EvenOdd), vec![255, 255, 255, 255]);
+}
+
+#[test]
+fn axis_aligned_rect_detection() {
+    let rect: Vec<PathEl> = Rect::new(0.0, 0.0, 4.0, 4.0).path_elements(0.1).collect();
+    assert!(is_axis_aligned_rect(&rect));
+    let tri = [
+        PathEl::MoveTo((0.0, 0.0).into()),
+        PathEl::LineTo((4.0, 0.0).into()),
+        PathEl::LineTo((0.0, 4.0).into()),
+        PathEl::ClosePath,
+    ];
+    assert!(!is_axis_aligned_rect(&tri));
+}
+
+#[test]
+fn draw_glyphs_splits_runs_per_line() {
+    let mut scene = D2DScene {
+        commands: Vec::new(),
+    };
+    let mut painter = D2DScenePainter { scene: &mut scene };
+    let font = Font::new(peniko::Blob::new(Arc::new(Vec::<u8>::new())), 0);
+    let glyph = Glyph::new;
+    painter.draw_glyphs(
+        &font,
+        "Segoe UI",
+        16.0,
+        400,
+        false,
+        &[],
+        Fill::NonZero,
+        Color::BLACK,
+        1.0,
+        Affine::translate((10.0, 5.0)),
+        None,
+        [
+            glyph(1, 0.0, 20.0),
+            glyph(2, 8.0, 20.0),
+            glyph(3, 0.0, 40.0),
+            glyph(4, 9.0, 40.0),
+        ]
+        .into_iter(),
+    );
+    let runs: Vec<_> = scene
+        .commands
+        .iter()
+        .filter_map(|c| match c {
+            Command::GlyphRun {
+                glyph_indices,
+                advances,
+                origin,
+                ..
+            } => Some((glyph_indices.clone(), advances[0], *origin)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(runs.len(), 2);
+    assert_eq!(runs[0], (vec![1, 2], 8.0, (10.0, 25.0)));
+    assert_eq!(runs[1], (vec![3, 4], 9.0, (10.0, 45.0)));
+}
+
+#[test]
+fn quarter_turned_glyphs_record_vertical_runs() {
+    let mut scene = D2DScene {
+        commands: Vec::new(),
+    };
+    let mut painter = D2DScenePainter { scene: &mut scene };
+    let font = Font::new(peniko::Blob::new(Arc::new(Vec::<u8>::new())), 0);
+    let glyph = |id, x| Glyph::new(id, x, 16.0).with_advance(16.0);
+    // vertical-rl text as blitz-paint places it: turned clockwise about the box's top-right corner
+    let transform = Affine::translate((100.0, 0.0)) * Affine::rotate(std::f64::consts::FRAC_PI_2);
+    assert!(is_quarter_turn(transform) && !is_quarter_turn(Affine::translate((100.0, 0.0))));
+    painter.draw_glyphs(
+        &font,
+        "Yu Gothic",
+        16.0,
+        400,
+        false,
+        &[],
+        Fill::NonZero,
+        Color::BLACK,
+        1.0,
+        transform,
+        None,
+        [glyph(1, 0.0), glyph(2, 16.0)].into_iter(),
+    );
+    painter.draw_glyph_shadow(
+        &font,
+        "Yu Gothic",
+        16.0,
+        400,
+        &[],
+        Color::BLACK,
+        2.0,
+        transform,
+        None,
+        [glyph(1, 0.0)].into_iter(),
+    );
+    assert_eq!(scene.commands.len(), 1, "vertical shadows are skipped");
+    let Command::GlyphRun {
+        advances,
+        origin,
+        vertical,
+        ..
+    } = &scene.commands[0]
+    else {
+        panic!()
+    };
+    assert!(*vertical);
+    assert_eq!(advances, &[16.0, 16.0]);
+    // The baseline 16px into the line lands 16px left of the right edge, nudged to the central axis
+    assert!((origin.0 - (100.0 - 16.0 + 16.0 * 0.38)).abs() < 1e-3 && origin.1.abs() < 1e-3);
+    let bounds = command_bounds(&scene.commands[0]).unwrap();
+    assert!(bounds.y1 >= 32.0 && bounds.x0 < origin.0 as f64);
+}
+
+#[test]
+fn rtl_glyph_runs_start_at_the_right_edge() {
+    let mut scene = D2DScene {
+        commands: Vec::new(),
+    };
+    let mut painter = D2DScenePainter { scene: &mut scene };
+    let font = Font::new(peniko::Blob::new(Arc::new(Vec::<u8>::new())), 0);
+    let glyph = |id, x, advance| Glyph::new(id, x, 20.0).with_advance(advance);
+    // An Arabic run in logical order: the first glyph sits rightmost
+    let glyphs = [
+        glyph(1, 30.0, 10.0),
+        glyph(2, 22.0, 8.0),
+        glyph(3, 10.0, 12.0),
+    ];
+    let transform = Affine::translate((5.0, 0.0));
+    painter.draw_glyphs(
+        &font,
+        "Segoe UI",
+        16.0,
+        400,
+        false,
+        &[],
+        Fill::NonZero,
+        Color::BLACK,
+        1.0,
+        transform,
+        None,
+        glyphs.into_iter(),
+    );
+    painter.draw_glyph_shadow(
+        &font,
+        "Segoe UI",
+        16.0,
+        400,
+        &[],
+        Color::BLACK,
+        2.0,
+        transform,
+        None,
+        glyphs.into_iter(),
+    );
+    let Command::GlyphRun {
+        glyph_indices,
+        advances,
+        origin,
+        bidi_level,
+        ..
+    } = &scene.commands[0]
+    else {
+        panic!()
+    };
+    assert_eq!(glyph_indices, &[1, 2, 3]);
+    assert_eq!(*bidi_level, 1);
+    assert_eq!(advances, &[10.0, 8.0, 12.0]);
+    assert_eq!(*origin, (45.0, 20.0));
+    // The run covers the 30px to the left of its origin
+    let bounds = command_bounds(&scene.commands[0]).unwrap();
+    assert!(bounds.x0 <= 15.0 && bounds.x1 >= 45.0 && bounds.x1 < 45.0 + 30.0);
+    let Command::GlyphShadow {
+        origin: shadow_origin,
+        bidi_level: 1,
+        ..
+    } = &scene.commands[1]
+    else {
+        panic!()
+    };
+    assert_eq!(shadow_origin, origin);
+}
+
+#[test]
+fn opacity_layer_composites_group_once() {
+    let half_red = Color::new([1.0, 0.0, 0.0, 0.5]);
+    let mut renderer = D2DWindowRenderer::new();
+    let pixels = renderer.render_to_rgba(30, 10, |scene| {
+        let clip = Rect::new(0.0, 0.0, 30.0, 10.0);
+        scene.push_layer(BlendMode::default(), 0.5, Affine::IDENTITY, &clip);
+        scene.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            half_red,
+            None,
+            &Rect::new(0.0, 0.0, 20.0, 10.0),
+        );
+        scene.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            half_red,
+            None,
+            &Rect::new(10.0, 0.0, 30.0, 10.0),
+        );
+        scene.pop_layer();
+    });
+    // Reference: the group's own coverage (0.5 alone, 0.75 where the rects overlap) scaled by 0.5
+    // and composited over the white fallback clear (no canvas color is set).
+    let green = |coverage: f32| ((1.0 - coverage * 0.5) * 255.0).round() as i32;
+    let px_green = |x: usize| pixels[(5 * 30 + x) * 4 + 1] as i32;
+    assert!((px_green(5) - green(0.5)).abs() <= 1);
+    assert!((px_green(15) - green(0.75)).abs() <= 1);
+    assert!((px_green(25) - green(0.5)).abs() <= 1);
+}
+
+#[test]
+fn reset_for_new_document_rearms_first_frame() {
+    let mut renderer = D2DWindowRenderer::new();
+    renderer.first_frame_done = true;
+    renderer.first_frame_ms = 42.0;
+    renderer.last_command_count = 7;
+    renderer.last_command_stats.fills = 7;
+    renderer.full_repaint = false;
+    renderer.scrollbar_thumb = Some(Rect::new(0.0, 0.0, 10.0, 40.0));
+    renderer.reset_for_new_document();
+    assert!(!renderer.first_frame_done);
+    assert_eq!(renderer.first_frame_ms, 0.0);
+    assert_eq!(renderer.last_command_count, 0);
+    assert_eq!(renderer.last_command_stats, CommandStats::default());
+    assert!(renderer.full_repaint);
+    assert!(renderer.scrollbar_thumb.is_none());
+    assert!(renderer.image_cache.is_empty() && renderer.shadow_cache.is_empty());
+}
+
+#[test]
+fn trim_memory_sheds_caches_by_level() {
+    let gradient = peniko::Gradient::new_linear((0.0, 0.0), (16.0, 0.0))
+        .with_stops([Color::BLACK, Color::WHITE]);
+    let mut renderer = D2DWindowRenderer::new();
+    renderer.render_to_rgba(32, 32, |scene| {
+        scene.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            &gradient,
+            None,
+            &Rect::new(0.0, 0.0, 16.0, 16.0),
+        );
+        scene.draw_box_shadow(
+            Affine::IDENTITY,
+            Rect::new(8.0, 8.0, 24.0, 24.0),
+            Color::BLACK,
+            2.0,
+            3.0,
+        );
+    });
+    assert!(!renderer.gradient_cache.is_empty() && !renderer.shadow_cache.is_empty());
+
+    renderer.trim_memory(TrimLevel::Mild);
+    assert!(renderer.gradient_cache.is_empty());
+    assert!(!renderer.shadow_cache.is_empty());
+
+    renderer.trim_memory(TrimLevel::Aggressive);
+    assert!(renderer.shadow_cache.is_empty() && renderer.shadow_cache_order.is_empty());
+    // Devices survive, so the next frame just rebuilds what it needs
+    assert!(renderer.d2d_ctx.is_some());
+}
+
+#[test]
+fn pixel_snap_aligns_rects_in_device_space() {
+    // 1.5x scale: logical 10.2..20.1 is device 15.3..30.15 -> 15..30
+    let r = snap_rect_to_device(Rect::new(10.2, 0.0, 20.1, 0.2), 1.5);
+    assert_eq!((r.x0 * 1.5, r.x1 * 1.5), (15.0, 30.0));
+    // A sliver thinner than a device pixel keeps one pixel instead of vanishing
+    assert_eq!((r.y0 * 1.5, r.y1 * 1.5), (0.0, 1.0));
+    // 1 logical px stroke at 1.5x -> 2 device px (even) centred on pixel boundaries
+    let (s, w) = snap_stroke_rect(Rect::new(10.3, 10.3, 20.0, 20.0), 1.0, 1.5);
+    assert_eq!(w * 1.5, 2.0);
+    assert_eq!(s.x0 * 1.5, 15.0);
+    // 1 px stroke at 1x -> centre on .5 so the line covers exactly one pixel row
+    let (s, w) = snap_stroke_rect(Rect::new(4.0, 4.0, 9.0, 9.0), 1.0, 1.0);
+    assert_eq!((s.x0, s.y1, w), (4.5, 9.5, 1.0));
+}
+
+#[test]
+fn dump_scene_lists_recorded_commands() {
+    let mut renderer = D2DWindowRenderer::new();
+    {
+        let mut painter = D2DScenePainter {
+            scene: &mut renderer.scene,
+        };
+        let clip = Rect::new(0.0, 0.0, 50.0, 50.0);
+        painter.push_layer(BlendMode::default(), 1.0, Affine::IDENTITY, &clip);
+        let red = Color::new([1.0, 0.0, 0.0, 1.0]);
+        painter.fill(
+            Fill::EvenOdd,
+            Affine::IDENTITY,
+            red,
+            None,
+            &Rect::new(1.0, 2.0, 11.0, 22.0),
+        );
+        painter.pop_layer();
+    }
+    let dump = renderer.dump_scene();
+    let lines: Vec<&str> = dump.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].contains("PushLayer clip=(0.0,0.0 50.0x50.0)"));
+    assert!(lines[1].contains("  FillPath els=5 fill=EvenOdd bounds=(1.0,2.0 10.0x20.0) brush=solid(1.000,0.000,0.000,1.000)"));
+    assert!(lines[2].trim_start().starts_with("2 PopLayer"));
+}
+
+#[test]
+fn over_deep_clip_nesting_still_renders() {
+    let mut renderer = D2DWindowRenderer::new();
+    let depth = MAX_CLIP_DEPTH + 64;
+    let pixels = renderer.render_to_rgba(8, 8, |scene| {
+        let clip = Rect::new(0.0, 0.0, 8.0, 8.0);
+        for _ in 0..depth {
+            scene.push_layer(BlendMode::default(), 1.0, Affine::IDENTITY, &clip);
+        }
+        let red = Color::new([1.0, 0.0, 0.0, 1.0]);
+        scene.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            red,
+            None,
+            &Rect::new(0.0, 0.0, 8.0, 8.0),
+        );
+        for _ in 0..depth {
+            scene.pop_layer();
+        }
+    });
+    // An EndDraw failure would come back as an empty buffer
+    assert_eq!(pixels.len(), 8 * 8 * 4);
+    assert_eq!(&pixels[..4], &[255, 0, 0, 255]);
+}
+
+#[test]
+fn layers_past_the_clip_cap_do_not_cull() {
+    let mut renderer = D2DWindowRenderer::new();
+    let pixels = renderer.render_to_rgba(8, 8, |scene| {
+        let full = Rect::new(0.0, 0.0, 8.0, 8.0);
+        for _ in 0..MAX_CLIP_DEPTH {
+            scene.push_layer(BlendMode::default(), 1.0, Affine::IDENTITY, &full);
+        }
+        // Skipped in playback, so it neither clips nor culls the fill outside it
+        let corner = Rect::new(0.0, 0.0, 1.0, 1.0);
+        scene.push_layer(BlendMode::default(), 1.0, Affine::IDENTITY, &corner);
+        let red = Color::new([1.0, 0.0, 0.0, 1.0]);
+        scene.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            red,
+            None,
+            &Rect::new(4.0, 4.0, 8.0, 8.0),
+        );
+        for _ in 0..=MAX_CLIP_DEPTH {
+            scene.pop_layer();
+        }
+    });
+    let at = |x: usize, y: usize| &pixels[(y * 8 + x) * 4..][..4];
+    assert_eq!(at(5, 5), &[255, 0, 0, 255]);
+}
+
+#[test]
+fn inspect_box_fills_bands_between_edges() {
+    let bands = ring_rects(
+        Rect::new(0.0, 0.0, 10.0, 10.0),
+        Rect::new(2.0, 1.0, 7.0, 9.0),
+    );
+    assert_eq!(bands[0], Rect::new(0.0, 0.0, 10.0, 1.0));
+    assert_eq!(bands[1], Rect::new(0.0, 9.0, 10.0, 10.0));
+    assert_eq!(bands[2], Rect::new(0.0, 1.0, 2.0, 9.0));
+    assert_eq!(bands[3], Rect::new(7.0, 1.0, 10.0, 9.0));
+    // No padding/margin: only the content and border bands remain
+    let inner = Rect::new(2.0, 2.0, 8.0, 8.0);
+    let inspect_box = InspectBox {
+        margin: Rect::new(0.0, 0.0, 10.0, 10.0),
+        border: Rect::new(0.0, 0.0, 10.0, 10.0),
+        padding: inner,
+        content: inner,
+    };
+    let fills = inspect_box.fills();
+    assert_eq!(fills.len(), 5);
+    assert_eq!(fills[0].0, inner);
+    assert!(
+        fills[1..]
+            .iter()
+            .all(|(_, color)| color.to_rgba8() == Color::from_rgba8(245, 66, 66, 128).to_rgba8())
+    );
+
+    let mut renderer = D2DWindowRenderer::new();
+    renderer.set_inspect_box(Some(inspect_box));
+    renderer.set_inspect_overlay(true);
+    let pixels = renderer.render_to_rgba(10, 10, |_| {});
+    let px = |x: usize, y: usize| &pixels[(y * 10 + x) * 4..(y * 10 + x) * 4 + 4];
+    // Blue content and red border over the empty scene's transparent background
+    assert!(px(5, 5)[2] > px(5, 5)[0]);
+    assert!(px(0, 5)[0] > px(0, 5)[2]);
+}
+
+#[test]
+fn on_first_frame_fires_once_per_document() {
+    let fired = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let register = |renderer: &mut D2DWindowRenderer| {
+        let fired = fired.clone();
+        renderer.on_first_frame(Box::new(move |timings| {
+            fired.lock().unwrap().push(timings.frame_total_ms)
+        }));
+    };
+    let mut renderer = D2DWindowRenderer::new();
+    register(&mut renderer);
+    renderer.complete_first_frame();
+    renderer.complete_first_frame();
+    assert_eq!(fired.lock().unwrap().len(), 1);
+    assert_eq!(fired.lock().unwrap()[0], renderer.first_frame_ms);
+    // A new document re-arms the first frame for a newly registered callback
+    renderer.reset_for_new_document();
+    register(&mut renderer);
+    renderer.complete_first_frame();
+    assert_eq!(fired.lock().unwrap().len(), 2);
+}
+
+// Test clock advanced by hand; clones share the same time.
+#[cfg(test)]
+#[derive(Clone)]
+struct ManualClock(Arc<std::sync::Mutex<Instant>>);
+
+#[cfg(test)]
+impl ManualClock {
+    fn advance_ms(&self, ms: u64) {
+        *self.0.lock().unwrap() += std::time::Duration::from_millis(ms);
+    }
+}
+
+#[cfg(test)]
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        *self.0.lock().unwrap()
+    }
+}
+
+#[test]
+fn frame_timing_follows_the_injected_clock() {
+    let clock = ManualClock(Arc::new(std::sync::Mutex::new(Instant::now())));
+    let mut renderer = D2DWindowRenderer::new();
+    renderer.set_clock(Box::new(clock.clone()));
+    let fired = Arc::new(std::sync::atomic::AtomicU32::new(0));
+    let counter = fired.clone();
+    renderer.on_first_frame(Box::new(move |_| {
+        counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    }));
+
+    // 30ms of startup, then 100ms frames: the FPS average updates once 0.5s of frames accumulate
+    clock.advance_ms(30);
+    for frame in 1..=5 {
+        renderer.frame_start = clock.now();
+        clock.advance_ms(100);
+        renderer.finish_frame();
+        if frame == 1 {
+            assert!((renderer.first_frame_ms - 130.0).abs() < 1e-3);
         }
+        if frame < 5 {
+            assert_eq!(renderer.fps, 0.0);
+        }
+    }
+    assert!((renderer.fps - 10.0).abs() < 1e-3, "fps {}", renderer.fps);
+    assert_eq!(renderer.fps_frame_count, 0);
+    // The first frame froze once; later frames leave it alone
+    assert_eq!(fired.load(std::sync::atomic::Ordering::SeqCst), 1);
+    assert!((renderer.first_frame_ms - 130.0).abs() < 1e-3);
+}
+
+#[test]
+fn premultiplied_images_are_not_premultiplied_again() {
+    // 50% red: straight (255, 0, 0, 128) is premultiplied (128, 0, 0, 128)
+    let straight = peniko::Image::new(
+        peniko::Blob::new(Arc::new(vec![255u8, 0, 0, 128])),
+        peniko::ImageFormat::Rgba8,
+        1,
+        1,
+    );
+    let premul = peniko::Image::new(
+        peniko::Blob::new(Arc::new(vec![128u8, 0, 0, 128])),
+        peniko::ImageFormat::Rgba8,
+        1,
+        1,
+    );
+    let layout = SourcePixels::from_format(
+        peniko::ImageFormat::Rgba8,
+        ImageAlphaType::AlphaPremultiplied,
+    )
+    .unwrap();
+    assert_eq!(
+        to_premultiplied_rgba(premul.data.data(), 1, 1, layout).unwrap(),
+        vec![128, 0, 0, 128]
+    );
+
+    let mut renderer = D2DWindowRenderer::new();
+    let from_straight =
+        renderer.render_to_rgba(1, 1, |scene| scene.draw_image(&straight, Affine::IDENTITY));
+    let from_premul = renderer.render_to_rgba(1, 1, |scene| {
+        scene.draw_image_with_alpha_type(
+            &premul,
+            Affine::IDENTITY,
+            ImageAlphaType::AlphaPremultiplied,
+        )
+    });
+    // Both composite to the same pink over white; double premultiplication would darken the red
+    assert_eq!(from_straight.len(), 4);
+    for (a, b) in from_straight.iter().zip(&from_premul) {
+        assert!(
+            (*a as i32 - *b as i32).abs() <= 1,
+            "{from_straight:?} vs {from_premul:?}"
+        );
+    }
+    assert!(from_premul[1] >= 126, "{from_premul:?}");
+}
+
+#[test]
+fn overlay_text_shows_only_enabled_lines() {
+    let phases = ["init 1.0".to_string(), String::new()];
+    let text = |options: &OverlayOptions| {
+        overlay_text(
+            options,
+            &phases,
+            "fps 60.0".to_string(),
+            "cmds 3".to_string(),
+            "buf=1x1".to_string(),
+        )
+    };
+    assert_eq!(
+        text(&OverlayOptions::default()),
+        "init 1.0\nfps 60.0\ncmds 3\nbuf=1x1"
+    );
+    let fps_only = OverlayOptions {
+        phases: false,
+        commands: false,
+        diagnostics: false,
+        ..Default::default()
+    };
+    assert_eq!(text(&fps_only), "fps 60.0");
+
+    let mut renderer = D2DWindowRenderer::new();
+    renderer.set_overlay_options(OverlayOptions {
+        opacity: 1.7,
+        max_width: 0.0,
+        ..fps_only
+    });
+    assert_eq!(
+        (
+            renderer.overlay_options().opacity,
+            renderer.overlay_options().max_width
+        ),
+        (1.0, 1.0)
+    );
+    renderer.set_overlay_corner(OverlayCorner::TopRight);
+    assert_eq!(renderer.overlay_options().corner, OverlayCorner::TopRight);
+}
+
+#[test]
+fn overlay_rect_anchors_to_corner() {
+    let target = (400.0, 300.0);
+    assert_eq!(
+        overlay_rect(OverlayCorner::TopLeft, (100.0, 50.0), target, 6.0, 4.0),
+        Rect::new(6.0, 6.0, 114.0, 64.0)
+    );
+    assert_eq!(
+        overlay_rect(OverlayCorner::BottomRight, (100.0, 50.0), target, 6.0, 4.0),
+        Rect::new(286.0, 236.0, 394.0, 294.0)
+    );
+    assert_eq!(
+        overlay_rect(OverlayCorner::TopRight, (100.0, 50.0), target, 6.0, 4.0).x0,
+        286.0
+    );
+    // Text wider than the target pins to the left edge instead of going negative
+    assert_eq!(
+        overlay_rect(OverlayCorner::BottomRight, (500.0, 50.0), target, 6.0, 4.0).x0,
+        0.0
+    );
+}
+
+#[test]
+fn backbuffer_target_format_follows_swapchain() {
+    assert_eq!(
+        target_pixel_format(DXGI_FORMAT_R10G10B10A2_UNORM),
+        Some(DXGI_FORMAT_R10G10B10A2_UNORM)
+    );
+    assert_eq!(
+        target_pixel_format(DXGI_FORMAT_B8G8R8A8_UNORM_SRGB),
+        Some(DXGI_FORMAT_B8G8R8A8_UNORM_SRGB)
+    );
+    assert_eq!(
+        target_pixel_format(DXGI_FORMAT_R16G16B16A16_FLOAT),
+        Some(DXGI_FORMAT_R16G16B16A16_FLOAT)
+    );
+    // Not a D2D render target format: left to the inherit fallback
+    assert_eq!(target_pixel_format(DXGI_FORMAT_NV12), None);
+}
+
+#[test]
+fn empty_scenes_clear_to_the_empty_background() {
+    let dark = Color::from_rgba8(32, 32, 32, 255);
+    let doc = Color::from_rgba8(0, 0, 255, 255);
+    // Nothing painted yet: the host background shows through instead of white
+    assert_eq!(
+        frame_clear_color(true, None, Color::TRANSPARENT, false).to_rgba8(),
+        Color::TRANSPARENT.to_rgba8()
+    );
+    assert_eq!(
+        frame_clear_color(true, Some(doc), dark, false).to_rgba8(),
+        dark.to_rgba8()
+    );
+    // Content uses the document canvas, with the white fallback
+    assert_eq!(
+        frame_clear_color(false, Some(doc), dark, false).to_rgba8(),
+        doc.to_rgba8()
+    );
+    assert_eq!(
+        frame_clear_color(false, None, dark, false).to_rgba8(),
+        Color::WHITE.to_rgba8()
+    );
+    // Alpha-ignoring targets never clear to transparent
+    assert_eq!(
+        frame_clear_color(true, None, Color::TRANSPARENT, true).to_rgba8(),
+        Color::WHITE.to_rgba8()
+    );
+}
+
+#[test]
+fn adjacent_glyph_runs_on_a_baseline_coalesce() {
+    let run = |ids: &[u16], x: f32, y: f32, color: Color| Command::GlyphRun {
+        glyph_indices: ids.to_vec(),
+        advances: vec![8.0; ids.len()],
+        origin: (x, y),
+        size: 16.0,
+        style: GlyphRenderStyle::Fill { color },
+        font: FontKey::default(),
+        var_coords: Vec::new(),
+        brush: None,
+        vertical: false,
+        bidi_level: 0,
+    };
+    let black = Color::BLACK;
+    let commands = vec![
+        run(&[1, 2], 10.0, 20.0, black),
+        run(&[3], 30.0, 20.0, black),
+        run(&[4, 5], 38.0, 20.0, black),
+        // Different color, then a new line: both start new runs
+        run(&[6], 54.0, 20.0, Color::WHITE),
+        run(&[7], 10.0, 40.0, Color::WHITE),
+    ];
+    let merged = coalesce_glyph_runs(commands);
+    assert_eq!(merged.len(), 3);
+    let Command::GlyphRun {
+        glyph_indices,
+        advances,
+        origin,
+        ..
+    } = &merged[0]
+    else {
+        panic!()
+    };
+    assert_eq!(glyph_indices, &[1, 2, 3, 4, 5]);
+    // The gap before the second run (x 18 -> 30) is folded into the preceding advance
+    assert_eq!(advances, &[8.0, 12.0, 8.0, 8.0, 8.0]);
+    assert_eq!(*origin, (10.0, 20.0));
+}
+
+#[test]
+fn glyph_advances_keep_letter_spacing() {
+    // 16px glyphs tracked 40px apart (letter-spacing far beyond 2x the font size), then a word gap
+    let xs = [0.0, 40.0, 80.0, 200.0];
+    let line: Vec<Glyph> = xs
+        .iter()
+        .enumerate()
+        .map(|(i, &x)| Glyph::new(i as u32, x, 0.0))
+        .collect();
+    let advances = glyph_advances(&line, 16.0);
+    assert_eq!(&advances[..3], &[40.0, 40.0, 120.0]);
+    assert_eq!(advances.len(), 4);
+    assert_eq!(glyph_advances(&line[..1], 10.0), vec![6.0]);
+}
+
+#[test]
+fn glyph_advances_keep_tab_widths() {
+    // `<pre>` line "\tx\t": 8px glyphs with 64px tab stops; the trailing tab has no next glyph
+    let glyph = |id, x, advance| Glyph::new(id, x, 0.0).with_advance(advance);
+    let line = [
+        glyph(3, 0.0, 64.0),
+        glyph(91, 64.0, 8.0),
+        glyph(3, 72.0, 56.0),
+    ];
+    assert_eq!(glyph_advances(&line, 16.0), vec![64.0, 8.0, 56.0]);
+}
+
+#[test]
+fn registered_font_data_is_found_before_system_fonts() {
+    let mut renderer = D2DWindowRenderer::new();
+    assert!(renderer.ensure_headless_devices());
+    let key = FontKey {
+        families: vec!["Mozilla Bullet".to_string()],
+        ..FontKey::default()
+    };
+    assert!(renderer.get_or_create_font_face(&key).is_none());
+    renderer.add_font_data(include_bytes!("../../blitz-dom/assets/moz-bullet-font.otf"));
+    assert!(renderer.get_or_create_font_face(&key).is_some());
+    renderer.clear_font_data();
+    assert!(renderer.get_or_create_font_face(&key).is_none());
+}
+
+#[test]
+fn glyph_shadows_record_offset_runs_and_skip_transparent_colors() {
+    let mut scene = D2DScene {
+        commands: Vec::new(),
+    };
+    let mut painter = D2DScenePainter { scene: &mut scene };
+    let font = Font::new(peniko::Blob::new(Arc::new(Vec::<u8>::new())), 0);
+    let glyphs = || {
+        [
+            Glyph::new(1, 0.0, 20.0).with_advance(8.0),
+            Glyph::new(2, 8.0, 20.0).with_advance(8.0),
+        ]
+        .into_iter()
+    };
+    let shadow = Color::new([0.0, 0.0, 0.0, 0.5]);
+    painter.draw_glyph_shadow(
+        &font,
+        "Segoe UI",
+        16.0,
+        700,
+        &[],
+        shadow,
+        2.0,
+        Affine::translate((12.0, 7.0)),
+        None,
+        glyphs(),
+    );
+    painter.draw_glyph_shadow(
+        &font,
+        "Segoe UI",
+        16.0,
+        700,
+        &[],
+        Color::TRANSPARENT,
+        2.0,
+        Affine::IDENTITY,
+        None,
+        glyphs(),
+    );
+    assert_eq!(scene.commands.len(), 1);
+    let Command::GlyphShadow {
+        glyph_indices,
+        origin,
+        font,
+        std_dev,
+        ..
+    } = &scene.commands[0]
+    else {
+        panic!()
+    };
+    assert_eq!(
+        (glyph_indices.as_slice(), *origin, font.weight, *std_dev),
+        (&[1u16, 2][..], (12.0, 27.0), 700, 2.0)
+    );
+}
+
+#[test]
+fn commands_outside_the_visible_rect_are_culled() {
+    let visible = Rect::new(0.0, 0.0, 800.0, 600.0);
+    let fill = |y: f64| Command::FillPath {
+        path: Rect::new(10.0, y, 110.0, y + 50.0)
+            .path_elements(0.1)
+            .collect(),
+        brush: RecordedBrush::Solid(Color::BLACK),
+        fill: Fill::NonZero,
+    };
+    assert!(!is_culled(&fill(100.0), visible));
+    assert!(is_culled(&fill(5000.0), visible));
+    // A shadow whose rect is off-screen still blurs into view
+    let shadow = |std_dev: f64| Command::BoxShadow {
+        rect: Rect::new(10.0, 610.0, 110.0, 700.0),
+        color: Color::BLACK,
+        radius: 0.0,
+        std_dev,
+        inset: false,
+    };
+    assert!(!is_culled(&shadow(8.0), visible));
+    assert!(is_culled(&shadow(0.0), visible));
+    // Glyphs hanging above the baseline below the viewport are kept
+    let run = |y: f32| Command::GlyphRun {
+        glyph_indices: vec![1, 2],
+        advances: vec![8.0, 8.0],
+        origin: (10.0, y),
+        size: 16.0,
+        style: GlyphRenderStyle::Fill {
+            color: Color::BLACK,
+        },
+        font: FontKey::default(),
+        var_coords: Vec::new(),
+        brush: None,
+        vertical: false,
+        bidi_level: 0,
+    };
+    assert!(!is_culled(&run(610.0), visible));
+    assert!(is_culled(&run(-20.0), visible));
+    // Layer commands are never culled
+    assert!(!is_culled(
+        &Command::PushLayer {
+            rect: Rect::new(0.0, 5000.0, 1.0, 5001.0),
+            alpha: 1.0,
+            mask: None
+        },
+        visible
+    ));
+}
+
+#[test]
+fn positioned_image_fills_crop_the_source() {
+    // 200x100 image scaled 2x (cover) into a 200x200 box: only its middle 100x100 is visible
+    let bt = Affine::translate((-100.0, 0.0)) * Affine::scale(2.0);
+    let (source, dest) = image_fill_rects(bt, Rect::new(0.0, 0.0, 200.0, 200.0), 200, 100).unwrap();
+    assert_eq!(source, Rect::new(50.0, 0.0, 150.0, 100.0));
+    assert_eq!(dest, Rect::new(0.0, 0.0, 200.0, 200.0));
+    // Contain: the whole image, letterboxed inside the shape
+    let bt = Affine::translate((0.0, 50.0));
+    let (source, dest) = image_fill_rects(bt, Rect::new(0.0, 0.0, 200.0, 200.0), 200, 100).unwrap();
+    assert_eq!(source, Rect::new(0.0, 0.0, 200.0, 100.0));
+    assert_eq!(dest, Rect::new(0.0, 50.0, 200.0, 150.0));
+    // Shape beside the image
+    assert!(image_fill_rects(bt, Rect::new(300.0, 0.0, 400.0, 100.0), 200, 100).is_none());
+}
+
+#[test]
+fn positioned_image_fills_keep_non_rect_shapes() {
+    let red = peniko::Image::new(
+        peniko::Blob::new(Arc::new(vec![255u8, 0, 0, 255])),
+        peniko::ImageFormat::Rgba8,
+        1,
+        1,
+    );
+    let scaled = Some(Affine::scale(8.0));
+    let mut renderer = D2DWindowRenderer::new();
+    // Over the empty scene's transparent background
+    let pixels = renderer.render_to_rgba(8, 8, |scene| {
+        let shape = kurbo::RoundedRect::new(0.0, 0.0, 8.0, 8.0, 4.0);
+        scene.fill(Fill::NonZero, Affine::IDENTITY, &red, scaled, &shape);
+    });
+    let at = |x: usize, y: usize| &pixels[(y * 8 + x) * 4..][..4];
+    assert_eq!(at(4, 4), &[255, 0, 0, 255]);
+    assert_eq!(at(0, 0), &[0, 0, 0, 0]);
+}
+
+#[test]
+fn bounded_cache_evicts_oldest_first() {
+    let mut map: FxHashMap<u32, u32> = FxHashMap::default();
+    let mut order = std::collections::VecDeque::new();
+    for k in 0..5 {
+        insert_bounded(&mut map, &mut order, 3, k, k * 10);
+    }
+    assert_eq!(order, [2, 3, 4]);
+    assert_eq!(map.len(), 3);
+    assert!(!map.contains_key(&1) && map[&4] == 40);
+    // Re-inserting a cached key keeps its position
+    insert_bounded(&mut map, &mut order, 3, 2, 0);
+    assert_eq!((order.front(), map[&2]), (Some(&2), 20));
+    // Shrinking evicts immediately, oldest first
+    evict_to_capacity(&mut map, &mut order, 1);
+    assert_eq!(order, [4]);
+    assert_eq!(map.keys().copied().collect::<Vec<_>>(), [4]);
+    insert_bounded(&mut map, &mut order, 0, 9, 90);
+    assert!(!map.contains_key(&9));
+}
+
+#[test]
+fn repeated_image_fills_use_a_tiling_brush() {
+    let img = |transform: Affine| RecordedImage {
+        width: 20,
+        height: 10,
+        source: 0,
+        data: Vec::new(),
+        format: peniko::ImageFormat::Rgba8,
+        alpha_type: ImageAlphaType::Alpha,
+        alpha: 1.0,
+        source_rect: None,
+        transform,
+        x_extend: peniko::Extend::Repeat,
+        y_extend: peniko::Extend::Repeat,
+        quality: peniko::ImageQuality::Medium,
+    };
+    let rect = |r: Rect| -> Vec<PathEl> { r.path_elements(0.1).collect() };
+    // A no-repeat background scaled 2x and centered: one copy, drawn directly
+    let placed = img(Affine::translate((30.0, 40.0)) * Affine::scale(2.0));
+    assert!(fills_single_image(
+        &rect(Rect::new(30.0, 40.0, 70.0, 60.0)),
+        &placed
+    ));
+    // Repeating over a wider box needs the tiling brush
+    assert!(!fills_single_image(
+        &rect(Rect::new(0.0, 40.0, 200.0, 60.0)),
+        &placed
+    ));
+    // So do rotated placements
+    assert!(!fills_single_image(
+        &rect(Rect::new(0.0, 0.0, 20.0, 10.0)),
+        &img(Affine::rotate(0.5))
+    ));
+    assert_eq!(
+        d2d_extend_mode(peniko::Extend::Reflect),
+        D2D1_EXTEND_MODE_MIRROR
+    );
+}
+
+#[test]
+fn command_stats_count_each_kind() {
+    let rect = Rect::new(0.0, 0.0, 10.0, 10.0);
+    let fill = || Command::FillPath {
+        path: rect.path_elements(0.1).collect(),
+        brush: RecordedBrush::Solid(Color::BLACK),
+        fill: Fill::NonZero,
+    };
+    let shadow = Command::BoxShadow {
+        rect,
+        color: Color::BLACK,
+        radius: 0.0,
+        std_dev: 2.0,
+        inset: false,
+    };
+    let commands = [
+        Command::PushLayer {
+            rect,
+            alpha: 1.0,
+            mask: None,
+        },
+        fill(),
+        fill(),
+        shadow,
+        Command::PopLayer,
+    ];
+    let stats = CommandStats::from_commands(&commands);
+    assert_eq!(
+        (stats.fills, stats.strokes, stats.shadows, stats.layers),
+        (2, 0, 1, 1)
+    );
+    assert_eq!(
+        stats.to_string(),
+        "fills: 2, strokes: 0, glyphs: 0, shadows: 1, layers: 1 (depth 0)"
+    );
+    assert!(stats.to_json().starts_with("{\"fills\":2,\"strokes\":0,"));
+}
+
+#[test]
+fn pixelated_images_scale_with_nearest_neighbor() {
+    assert_eq!(
+        d2d_interpolation_mode(peniko::ImageQuality::Low),
+        D2D1_INTERPOLATION_MODE_NEAREST_NEIGHBOR
+    );
+    assert_eq!(
+        d2d_interpolation_mode(peniko::ImageQuality::Medium),
+        D2D1_INTERPOLATION_MODE_LINEAR
+    );
+    assert_eq!(
+        d2d_bitmap_interpolation_mode(peniko::ImageQuality::Low),
+        D2D1_BITMAP_INTERPOLATION_MODE_NEAREST_NEIGHBOR
+    );
+}
+
+#[test]
+fn factory_is_single_threaded_unless_requested() {
+    assert_eq!(
+        factory_type(RendererOptions::default()),
+        D2D1_FACTORY_TYPE_SINGLE_THREADED
+    );
+    assert_eq!(
+        factory_type(RendererOptions {
+            multi_threaded: true
+        }),
+        D2D1_FACTORY_TYPE_MULTI_THREADED
+    );
+}
+
+#[test]
+fn geometry_antialias_modes_map_to_d2d() {
+    assert_eq!(
+        GeometryAntialiasMode::default().to_d2d(),
+        D2D1_ANTIALIAS_MODE_PER_PRIMITIVE
+    );
+    assert_eq!(
+        GeometryAntialiasMode::Aliased.to_d2d(),
+        D2D1_ANTIALIAS_MODE_ALIASED
+    );
+}
+
+#[test]
+fn translucent_fills_composite_once_over_premultiplied_targets() {
+    assert_eq!(d2d_color(Color::new([0.0, 0.0, 0.0, 0.55])).a, 0.55);
+    // The debug overlay background: 55% black over the white fallback clear (no canvas color) leaves 45% white
+    let mut renderer = D2DWindowRenderer::new();
+    let pixels = renderer.render_to_rgba(4, 4, |scene| {
+        scene.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            Color::new([0.0, 0.0, 0.0, 0.55]),
+            None,
+            &Rect::new(0.0, 0.0, 4.0, 4.0),
+        );
+    });
+    assert_eq!(pixels.len(), 4 * 4 * 4);
+    for channel in &pixels[0..3] {
+        assert!((*channel as i32 - 115).abs() <= 1, "{:?}", &pixels[0..4]);
     }
+    assert_eq!(pixels[3], 255);
+}
+
+#[test]
+fn lost_device_is_rebuilt_on_the_next_frame() {
+    let red = |scene: &mut D2DScenePainter<'_>| {
+        scene.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            Color::new([1.0, 0.0, 0.0, 1.0]),
+            None,
+            &Rect::new(0.0, 0.0, 2.0, 2.0),
+        );
+    };
+    let mut renderer = D2DWindowRenderer::new();
+    renderer.render_to_rgba(2, 2, red);
+    renderer.simulate_device_loss();
+    renderer.render_to_rgba(2, 2, red);
+    assert_eq!(renderer.device_lost_count(), 1);
+    assert!(renderer.take_device_lost());
+    assert!(!renderer.take_device_lost());
+    assert!(renderer.d2d_ctx.is_none());
+    assert!(renderer.image_cache.is_empty());
+
+    let pixels = renderer.render_to_rgba(2, 2, red);
+    assert!(renderer.d2d_ctx.is_some());
+    assert_eq!(&pixels[0..4], &[255, 0, 0, 255]);
+    assert_eq!(renderer.device_lost_count(), 1);
+}
+
+#[test]
+fn linear_light_gradients_keep_a_bright_midtone() {
+    let mut gradient = peniko::Gradient::new_linear((0.0, 0.0), (64.0, 0.0))
+        .with_stops([Color::BLACK, Color::WHITE]);
+    let mut midtone = |cs| {
+        gradient.interpolation_cs = cs;
+        let mut renderer = D2DWindowRenderer::new();
+        let pixels = renderer.render_to_rgba(64, 1, |scene| {
+            scene.fill(
+                Fill::NonZero,
+                Affine::IDENTITY,
+                &gradient,
+                None,
+                &Rect::new(0.0, 0.0, 64.0, 1.0),
+            );
+        });
+        pixels[32 * 4]
+    };
+    // Halfway in sRGB is ~50% gray; halfway in linear light is ~74% once encoded back to sRGB
+    assert!((midtone(color::ColorSpaceTag::Srgb) as i32 - 130).abs() <= 6);
+    assert!((midtone(color::ColorSpaceTag::LinearSrgb) as i32 - 189).abs() <= 6);
+}
+
+#[test]
+fn backdrop_blur_records_the_transformed_shape() {
+    let mut scene = D2DScene::default();
+    let mut painter = D2DScenePainter { scene: &mut scene };
+    let rect = Rect::new(0.0, 0.0, 100.0, 50.0);
+    painter.draw_backdrop_blur(Affine::translate((10.0, 20.0)), &rect, 4.0);
+    // No blur, nothing to record
+    painter.draw_backdrop_blur(Affine::IDENTITY, &rect, 0.0);
+    assert_eq!(scene.commands.len(), 1);
+    let Command::BackdropBlur { path, std_dev } = &scene.commands[0] else {
+        panic!()
+    };
+    assert_eq!(path_bounds(path), Some(Rect::new(10.0, 20.0, 110.0, 70.0)));
+    assert_eq!(*std_dev, 4.0);
+    // Culled by its shape like a fill
+    assert!(is_culled(
+        &scene.commands[0],
+        Rect::new(0.0, 100.0, 800.0, 600.0)
+    ));
+}
+
+#[test]
+fn mask_layers_record_luminance_as_alpha() {
+    let mut scene = D2DScene::default();
+    let mut painter = D2DScenePainter { scene: &mut scene };
+    let rect = Rect::new(0.0, 0.0, 100.0, 50.0);
+    painter.push_mask_layer(
+        Affine::translate((10.0, 20.0)),
+        &rect,
+        Color::new([1.0, 1.0, 1.0, 0.5]),
+        None,
+        true,
+    );
+    painter.pop_layer();
+    painter.push_mask_layer(
+        Affine::IDENTITY,
+        &rect,
+        Color::new([0.0, 0.0, 0.0, 0.5]),
+        None,
+        false,
+    );
+    let Command::PushLayer {
+        rect: r,
+        alpha,
+        mask: Some(mask),
+    } = &scene.commands[0]
+    else {
+        panic!()
+    };
+    assert_eq!((*r, *alpha), (Rect::new(10.0, 20.0, 110.0, 70.0), 1.0));
+    // White at half alpha keeps half the content
+    let RecordedBrush::Solid(c) = **mask else {
+        panic!()
+    };
+    assert!((c.components[3] - 0.5).abs() < 1e-4);
+    // An alpha mask keeps the color's own alpha
+    let Command::PushLayer {
+        mask: Some(mask), ..
+    } = &scene.commands[2]
+    else {
+        panic!()
+    };
+    let RecordedBrush::Solid(c) = **mask else {
+        panic!()
+    };
+    assert_eq!(c.components[3], 0.5);
 }
@@ -4,6 +4,7 @@ use crate::layout::construct::collect_layout_children;
 use crate::mutator::ViewportMut;
 use crate::net::{Resource, StylesheetLoader};
 use crate::node::{ImageData, NodeFlags, RasterImageData, SpecialElementData, Status, TextBrush};
+use crate::selection::TextSelection;
 use crate::stylo_to_cursor_icon::stylo_to_cursor_icon;
 use crate::traversal::TreeTraverser;
 use crate::url::DocumentUrl;
@@ -77,6 +78,34 @@ pub trait Document: Deref<Target = BaseDocument> + DerefMut + 'static {
     }
 }
 
+/// The CSS box model of a laid-out node in window coordinates (layout units), from the outermost
+/// edge inwards. Used by devtools-style box highlighting.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoxModel {
+    pub margin: kurbo::Rect,
+    pub border: kurbo::Rect,
+    pub padding: kurbo::Rect,
+    pub content: kurbo::Rect,
+}
+
+/// System colors for forced-colors (Windows high contrast) rendering, named after the CSS
+/// system colors they stand in for. Hosts typically fill these from the platform's UI settings.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ForcedColorScheme {
+    /// Page and element backgrounds.
+    pub canvas: peniko::Color,
+    /// Text, borders and outlines.
+    pub canvas_text: peniko::Color,
+    /// Text inside links.
+    pub link_text: peniko::Color,
+    /// Button backgrounds.
+    pub button_face: peniko::Color,
+    /// Button text and borders.
+    pub button_text: peniko::Color,
+    /// Selected text background.
+    pub highlight: peniko::Color,
+}
+
 pub struct BaseDocument {
     /// ID of the document
     id: usize,
@@ -86,6 +115,8 @@ pub struct BaseDocument {
     pub(crate) url: DocumentUrl,
     // Devtool settings. Currently used to render debug overlays
     pub(crate) devtool_settings: DevtoolSettings,
+    // System colors that override document colors while painting, if forced colors are active
+    pub(crate) forced_colors: Option<ForcedColorScheme>,
     // Viewport details such as the dimensions, HiDPI scale, and zoom factor,
     pub(crate) viewport: Viewport,
     // Scroll within our viewport
@@ -119,8 +150,21 @@ pub struct BaseDocument {
     pub(crate) active_node_id: Option<usize>,
     /// The node which recieved a mousedown event (if any)
     pub(crate) mousedown_node_id: Option<usize>,
+    /// Whether the current press dispatched `contextmenu` (so its release doesn't click)
+    pub(crate) context_menu_press: bool,
+    /// The node which has captured the pointer (if any). Pointer moves/ups are routed to it
+    /// regardless of hit-testing until the next mouseup or an explicit release.
+    pub(crate) pointer_capture_node_id: Option<usize>,
+    /// The document text selection (outside text inputs), if any
+    pub(crate) text_selection: Option<TextSelection>,
+    /// Per-inline-root byte ranges covered by `text_selection`, in document order
+    pub(crate) text_selection_ranges: Vec<(usize, std::ops::Range<usize>)>,
     /// Whether there are active animations (so we should re-render every frame)
     pub(crate) is_animating: bool,
+    /// When the text caret's blink cycle last restarted (focus change, typing, caret movement)
+    pub(crate) caret_blink_start: std::time::Instant,
+    /// Whether the caret is in the shown half of its blink cycle
+    pub(crate) caret_visible: bool,
 
     /// Map of node ID's for fast lookups
     pub(crate) nodes_to_id: HashMap<String, usize>,
@@ -224,6 +268,7 @@ impl BaseDocument {
             nodes_to_id,
             viewport,
             devtool_settings: DevtoolSettings::default(),
+            forced_colors: None,
             viewport_scroll: kurbo::Point::ZERO,
             url: base_url,
             ua_stylesheets: HashMap::new(),
@@ -235,7 +280,13 @@ impl BaseDocument {
             focus_node_id: None,
             active_node_id: None,
             mousedown_node_id: None,
+            context_menu_press: false,
+            pointer_capture_node_id: None,
+            text_selection: None,
+            text_selection_ranges: Vec::new(),
             is_animating: false,
+            caret_blink_start: std::time::Instant::now(),
+            caret_visible: true,
             changed_nodes: HashSet::new(),
             controls_to_form: HashMap::new(),
             net_provider,
@@ -658,8 +709,49 @@ impl BaseDocument {
                                 ImageData::Raster(RasterImageData::new(width, height, image_data))
                         }
                     }
+                    ImageType::BorderImage => {
+                        if let Some(border_image) =
+                            node.element_data_mut().and_then(|el| el.border_image.as_mut())
+                        {
+                            border_image.status = Status::Ok;
+                            border_image.image =
+                                ImageData::Raster(RasterImageData::new(width, height, image_data))
+                        }
+                    }
                 }
             }
+            Resource::AnimatedImage(node_id, kind, width, height, frames) => {
+                let image = RasterImageData::animated(width, height, frames);
+                let node = self.get_node_mut(node_id).unwrap();
+
+                match kind {
+                    ImageType::Image => {
+                        node.element_data_mut().unwrap().special_data =
+                            SpecialElementData::Image(Box::new(ImageData::Raster(image)));
+
+                        // Clear layout cache
+                        node.cache.clear();
+                    }
+                    ImageType::Background(idx) => {
+                        if let Some(Some(bg_image)) = node
+                            .element_data_mut()
+                            .and_then(|el| el.background_images.get_mut(idx))
+                        {
+                            bg_image.status = Status::Ok;
+                            bg_image.image = ImageData::Raster(image)
+                        }
+                    }
+                    ImageType::BorderImage => {
+                        if let Some(border_image) =
+                            node.element_data_mut().and_then(|el| el.border_image.as_mut())
+                        {
+                            border_image.status = Status::Ok;
+                            border_image.image = ImageData::Raster(image)
+                        }
+                    }
+                }
+                self.is_animating = true;
+            }
             #[cfg(feature = "svg")]
             Resource::Svg(node_id, kind, tree) => {
                 let node = self.get_node_mut(node_id).unwrap();
@@ -681,6 +773,14 @@ impl BaseDocument {
                             bg_image.image = ImageData::Svg(tree);
                         }
                     }
+                    ImageType::BorderImage => {
+                        if let Some(border_image) =
+                            node.element_data_mut().and_then(|el| el.border_image.as_mut())
+                        {
+                            border_image.status = Status::Ok;
+                            border_image.image = ImageData::Svg(tree);
+                        }
+                    }
                 }
             }
             Resource::Font(bytes) => {
@@ -809,6 +909,8 @@ impl BaseDocument {
 
         // Fix up tree for layout (insert anonymous blocks as necessary, etc)
         self.resolve_layout_children();
+        // Inline layouts (and anonymous blocks) were just rebuilt
+        self.revalidate_text_selection();
 
         timer.record_time("construct");
 
@@ -855,6 +957,24 @@ impl BaseDocument {
     pub fn set_mousedown_node_id(&mut self, node_id: Option<usize>) {
         self.mousedown_node_id = node_id;
     }
+
+    /// Route subsequent pointer move/up events to `node_id` instead of the hit-tested node.
+    /// The capture is released automatically after the next mouseup.
+    pub fn set_pointer_capture(&mut self, node_id: usize) {
+        self.pointer_capture_node_id = Some(node_id);
+    }
+
+    /// Release any active pointer capture
+    pub fn release_pointer_capture(&mut self) {
+        self.pointer_capture_node_id = None;
+    }
+
+    /// The node which currently has pointer capture (if any)
+    pub fn get_pointer_capture_node_id(&self) -> Option<usize> {
+        self.pointer_capture_node_id
+            .filter(|id| self.nodes.contains(*id))
+    }
+
     pub fn set_focus_to(&mut self, focus_node_id: usize) -> bool {
         if Some(focus_node_id) == self.focus_node_id {
             return false;
@@ -987,6 +1107,17 @@ impl BaseDocument {
         &mut self.devtool_settings
     }
 
+    /// The active forced-colors scheme, if any. Painters use it in place of document colors.
+    pub fn forced_colors(&self) -> Option<&ForcedColorScheme> {
+        self.forced_colors.as_ref()
+    }
+
+    /// Enable (`Some`) or disable (`None`) forced-colors painting. Takes effect on the next paint;
+    /// styles and layout are unaffected.
+    pub fn set_forced_colors(&mut self, scheme: Option<ForcedColorScheme>) {
+        self.forced_colors = scheme;
+    }
+
     pub fn is_animating(&self) -> bool {
         self.is_animating
     }
@@ -1056,11 +1187,18 @@ impl BaseDocument {
     }
 
     pub fn get_cursor(&self) -> Option<CursorIcon> {
+        self.cursor_for_node(self.get_hover_node_id()?)
+    }
+
+    /// The cursor to show over a node: its computed `cursor` unless `auto`, else a text cursor
+    /// over text and text inputs and a pointer inside links. None for `cursor: none` (or an
+    /// unstyled node). `url()` cursors use their fallback keyword.
+    pub fn cursor_for_node(&self, node_id: usize) -> Option<CursorIcon> {
         // todo: cache this on the node itself
-        let node = &self.nodes[self.get_hover_node_id()?];
+        let node = self.nodes.get(node_id)?;
 
         let style = node.primary_styles()?;
-        let keyword = stylo_to_cursor_icon(style.clone_cursor().keyword);
+        let keyword = stylo_to_cursor_icon(style.clone_cursor().keyword)?;
 
         // Return cursor from style if it is non-auto
         if keyword != CursorIcon::Default {
@@ -1114,39 +1252,15 @@ impl BaseDocument {
             })
             .unwrap_or((false, false));
 
-        let new_x = node.scroll_offset.x - x;
-        let new_y = node.scroll_offset.y - y;
-
-        let mut bubble_x = 0.0;
-        let mut bubble_y = 0.0;
-
         let scroll_width = node.final_layout.scroll_width() as f64;
         let scroll_height = node.final_layout.scroll_height() as f64;
 
-        // If we're past our scroll bounds, transfer remainder of scrolling to parent/viewport
-        if !can_x_scroll {
-            bubble_x = x
-        } else if new_x < 0.0 {
-            bubble_x = -new_x;
-            node.scroll_offset.x = 0.0;
-        } else if new_x > scroll_width {
-            bubble_x = scroll_width - new_x;
-            node.scroll_offset.x = scroll_width;
-        } else {
-            node.scroll_offset.x = new_x;
-        }
-
-        if !can_y_scroll {
-            bubble_y = y
-        } else if new_y < 0.0 {
-            bubble_y = -new_y;
-            node.scroll_offset.y = 0.0;
-        } else if new_y > scroll_height {
-            bubble_y = scroll_height - new_y;
-            node.scroll_offset.y = scroll_height;
-        } else {
-            node.scroll_offset.y = new_y;
-        }
+        // Past our scroll bounds the remainder of the delta transfers to the parent/viewport
+        let (offset_x, bubble_x) = scroll_axis(node.scroll_offset.x, x, scroll_width, can_x_scroll);
+        let (offset_y, bubble_y) =
+            scroll_axis(node.scroll_offset.y, y, scroll_height, can_y_scroll);
+        node.scroll_offset.x = offset_x;
+        node.scroll_offset.y = offset_y;
 
         if bubble_x != 0.0 || bubble_y != 0.0 {
             if let Some(parent) = node.parent {
@@ -1181,6 +1295,163 @@ impl BaseDocument {
         self.viewport_scroll = scroll;
     }
 
+    /// Scroll the viewport to the given position, clamped to the scrollable range
+    pub fn scroll_viewport_to(&mut self, x: f64, y: f64) {
+        let (max_x, max_y) = self.viewport_scroll_max();
+        self.viewport_scroll.x = x.clamp(0.0, max_x);
+        self.viewport_scroll.y = y.clamp(0.0, max_y);
+    }
+
+    /// The largest viewport scroll offset in each axis (zero when content fits the window)
+    pub fn viewport_scroll_max(&self) -> (f64, f64) {
+        let content_size = self.root_element().final_layout.size;
+        let window_width = self.viewport.window_size.0 as f64 / self.viewport.scale() as f64;
+        let window_height = self.viewport.window_size.1 as f64 / self.viewport.scale() as f64;
+        (
+            f64::max(0.0, content_size.width as f64 - window_width),
+            f64::max(0.0, content_size.height as f64 - window_height),
+        )
+    }
+
+    /// The caret (or IME composition) rect of the focused text input in window coordinates, for
+    /// positioning the platform IME candidate window
+    pub fn ime_cursor_area(&self) -> Option<kurbo::Rect> {
+        let node = self.nodes.get(self.focus_node_id?)?;
+        let input_data = node.element_data()?.text_input_data()?;
+        let area = input_data.editor.ime_cursor_area();
+        let layout = &node.final_layout;
+        let pos = node.absolute_position(
+            layout.border.left + layout.padding.left,
+            layout.border.top + layout.padding.top,
+        );
+        let offset = kurbo::Vec2::new(
+            pos.x as f64 - self.viewport_scroll.x - node.scroll_offset.x,
+            pos.y as f64 - self.viewport_scroll.y - node.scroll_offset.y,
+        );
+        Some(kurbo::Rect::new(area.x0, area.y0, area.x1, area.y1) + offset)
+    }
+
+    /// Keep the focused text input's caret visible after an edit or caret movement: first by scrolling the
+    /// input's own content (its text doesn't wrap past the box, so a long line runs off the edge), then by
+    /// scrolling the viewport if the input itself is out of view
+    pub fn scroll_caret_into_view(&mut self) {
+        let Some(node_id) = self.focus_node_id else {
+            return;
+        };
+        let scale = self.viewport.scale_f64();
+        let node = &mut self.nodes[node_id];
+        let Some(input_data) = node.element_data().and_then(|el| el.text_input_data()) else {
+            return;
+        };
+        let (Some(caret), Some(text_layout)) = (
+            input_data.editor.cursor_geometry(1.0),
+            input_data.editor.try_layout(),
+        ) else {
+            return;
+        };
+        // The editor lays out in device pixels; scroll offsets are in CSS px
+        let caret =
+            kurbo::Rect::new(caret.x0, caret.y0, caret.x1, caret.y1).scale_from_origin(1.0 / scale);
+        let text_width = text_layout.full_width() as f64 / scale;
+        let text_height = text_layout.height() as f64 / scale;
+
+        let layout = &node.final_layout;
+        let visible_width = (layout.size.width
+            - layout.padding.horizontal_components().sum()
+            - layout.border.horizontal_components().sum()) as f64;
+        let visible_height = (layout.size.height
+            - layout.padding.vertical_components().sum()
+            - layout.border.vertical_components().sum()) as f64;
+        // Deleting text scrolls back so the field isn't left showing empty space past the end
+        let max_x = (text_width.max(caret.x1) - visible_width).max(0.0);
+        let max_y = (text_height.max(caret.y1) - visible_height).max(0.0);
+        node.scroll_offset = kurbo::Point::new(
+            scroll_to_reveal(node.scroll_offset.x, caret.x0, caret.x1, visible_width).min(max_x),
+            scroll_to_reveal(node.scroll_offset.y, caret.y0, caret.y1, visible_height).min(max_y),
+        );
+
+        let pos = node.absolute_position(
+            layout.border.left + layout.padding.left,
+            layout.border.top + layout.padding.top,
+        );
+        let caret =
+            caret + kurbo::Vec2::new(pos.x as f64, pos.y as f64) - node.scroll_offset.to_vec2();
+        let window_width = self.viewport.window_size.0 as f64 / self.viewport.scale() as f64;
+        let window_height = self.viewport.window_size.1 as f64 / self.viewport.scale() as f64;
+        self.scroll_viewport_to(
+            scroll_to_reveal(self.viewport_scroll.x, caret.x0, caret.x1, window_width),
+            scroll_to_reveal(self.viewport_scroll.y, caret.y0, caret.y1, window_height),
+        );
+    }
+
+    /// The margin/border/padding/content rects of a node in window coordinates
+    pub fn box_model(&self, node_id: usize) -> Option<BoxModel> {
+        let node = self.nodes.get(node_id)?;
+        let layout = &node.final_layout;
+        let pos = node.absolute_position(0.0, 0.0);
+        let border = kurbo::Rect::new(
+            pos.x as f64 - self.viewport_scroll.x,
+            pos.y as f64 - self.viewport_scroll.y,
+            (pos.x + layout.size.width) as f64 - self.viewport_scroll.x,
+            (pos.y + layout.size.height) as f64 - self.viewport_scroll.y,
+        );
+        let inset = |rect: kurbo::Rect, edges: taffy::Rect<f32>| {
+            kurbo::Rect::new(
+                rect.x0 + edges.left as f64,
+                rect.y0 + edges.top as f64,
+                (rect.x1 - edges.right as f64).max(rect.x0 + edges.left as f64),
+                (rect.y1 - edges.bottom as f64).max(rect.y0 + edges.top as f64),
+            )
+        };
+        let padding = inset(border, layout.border);
+        Some(BoxModel {
+            margin: inset(border, layout.margin.map(|m| -m)),
+            border,
+            padding,
+            content: inset(padding, layout.padding),
+        })
+    }
+
+    /// Scroll the viewport so that the top-left of the node's border box is at the top-left of the
+    /// window (clamped to the scrollable range)
+    pub fn scroll_node_into_view(&mut self, node_id: usize) {
+        let Some(node) = self.nodes.get(node_id) else {
+            return;
+        };
+        let pos = node.absolute_position(0.0, 0.0);
+        self.scroll_viewport_to(pos.x as f64, pos.y as f64);
+    }
+
+    /// Scroll to the target of a URL fragment (without the `#`): the element with that id, else the
+    /// first `<a name>` anchor with that name. An empty fragment or `top` with no such element
+    /// scrolls to the top of the document. Returns false (leaving the scroll alone) when nothing
+    /// matches.
+    pub fn scroll_to_fragment(&mut self, fragment: &str) -> bool {
+        let decoded = percent_encoding::percent_decode_str(fragment).decode_utf8_lossy();
+        match self.find_fragment_target(&decoded) {
+            Some(node_id) => self.scroll_node_into_view(node_id),
+            None if decoded.is_empty() || decoded.eq_ignore_ascii_case("top") => {
+                self.scroll_viewport_to(self.viewport_scroll.x, 0.0)
+            }
+            None => return false,
+        }
+        true
+    }
+
+    fn find_fragment_target(&self, fragment: &str) -> Option<usize> {
+        if fragment.is_empty() {
+            return None;
+        }
+        if let Some(&node_id) = self.nodes_to_id.get(fragment) {
+            return Some(node_id);
+        }
+        TreeTraverser::new(self).find(|&node_id| {
+            self.nodes[node_id].element_data().is_some_and(|el| {
+                el.name.local == local_name!("a") && el.attr(local_name!("name")) == Some(fragment)
+            })
+        })
+    }
+
     pub fn find_title_node(&self) -> Option<&Node> {
         TreeTraverser::new(self)
             .find(|node_id| {
@@ -1191,6 +1462,72 @@ impl BaseDocument {
             .map(|node_id| &self.nodes[node_id])
     }
 
+    /// Step animated images (GIF/APNG/WebP) to the frame due at `now`. Returns whether any frame
+    /// changed, i.e. whether the document needs a repaint. Hosts call this from their render loop;
+    /// [`is_animating`](Self::is_animating) tells them whether to keep ticking.
+    pub fn advance_animated_images(&mut self, now: std::time::Instant) -> bool {
+        if !self.is_animating {
+            return false;
+        }
+        let mut changed = false;
+        for (_, node) in self.nodes.iter_mut() {
+            let Some(element) = node.data.downcast_element_mut() else {
+                continue;
+            };
+            if let SpecialElementData::Image(image) = &mut element.special_data {
+                if let ImageData::Raster(raster) = &mut **image {
+                    changed |= raster.advance(now);
+                }
+            }
+            let bg_images = element.background_images.iter_mut().flatten();
+            for bg_image in bg_images.chain(&mut element.border_image) {
+                if let ImageData::Raster(raster) = &mut bg_image.image {
+                    changed |= raster.advance(now);
+                }
+            }
+        }
+        changed
+    }
+
+    /// Whether the focused text input's caret is in the shown half of its blink cycle. Painters skip
+    /// the caret while this is false.
+    pub fn caret_visible(&self) -> bool {
+        self.caret_visible
+    }
+
+    /// Restart the caret blink cycle with the caret shown, so it stays solid while the user types or
+    /// moves it. Key, IME and pointer-down events call this.
+    pub fn reset_caret_blink(&mut self) {
+        self.caret_blink_start = std::time::Instant::now();
+        self.caret_visible = true;
+    }
+
+    /// Step the caret blink to `now`. Returns whether the caret was shown or hidden, i.e. whether the
+    /// document needs a repaint. Hosts call this from their render loop while
+    /// [`wants_animation_frame`](Self::wants_animation_frame) is true.
+    pub fn advance_caret_blink(&mut self, now: std::time::Instant) -> bool {
+        if !self.has_focused_text_input() {
+            return false;
+        }
+        let visible = caret_blink_visible(now.saturating_duration_since(self.caret_blink_start));
+        let changed = visible != self.caret_visible;
+        self.caret_visible = visible;
+        changed
+    }
+
+    /// Whether the host should keep rendering frames: animated images are playing, or the caret of
+    /// a focused text input is blinking.
+    pub fn wants_animation_frame(&self) -> bool {
+        self.is_animating || self.has_focused_text_input()
+    }
+
+    fn has_focused_text_input(&self) -> bool {
+        self.focus_node_id
+            .and_then(|id| self.nodes.get(id))
+            .and_then(|node| node.element_data())
+            .is_some_and(|element| element.text_input_data().is_some())
+    }
+
     pub(crate) fn compute_is_animating(&self) -> bool {
         TreeTraverser::new(self).any(|node_id| {
             let node = &self.nodes[node_id];
@@ -1200,12 +1537,57 @@ impl BaseDocument {
             if element.name.local == local_name!("canvas") && element.has_attr(local_name!("src")) {
                 return true;
             }
-
-            false
+            if element
+                .raster_image_data()
+                .is_some_and(|image| image.is_animated())
+            {
+                return true;
+            }
+            let bg_images = element.background_images.iter().flatten();
+            bg_images.chain(&element.border_image).any(|bg_image| {
+                matches!(&bg_image.image, ImageData::Raster(raster) if raster.is_animated())
+            })
         })
     }
 }
 
+/// How long the caret stays shown, then hidden, in each blink cycle
+const CARET_BLINK_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+// Whether the caret shows `elapsed` into its blink cycle: shown first, then alternating each interval
+fn caret_blink_visible(elapsed: std::time::Duration) -> bool {
+    (elapsed.as_millis() / CARET_BLINK_INTERVAL.as_millis()) % 2 == 0
+}
+
+// One axis of scroll_node_by: the new offset after scrolling by `delta` (content moves against the offset), and
+// the part of the delta left over for the parent once the offset hits 0 or `max` (all of it when the axis can't scroll)
+fn scroll_axis(offset: f64, delta: f64, max: f64, can_scroll: bool) -> (f64, f64) {
+    if !can_scroll {
+        return (offset, delta);
+    }
+    let new = offset - delta;
+    if new < 0.0 {
+        (0.0, -new)
+    } else if new > max {
+        (max, max - new)
+    } else {
+        (new, 0.0)
+    }
+}
+
+// The smallest change to a scroll `offset` that brings the span `start..end` into a window `visible` long: a span
+// past the far edge is aligned to it, one before the near edge to that. A span longer than the window shows its start.
+fn scroll_to_reveal(offset: f64, start: f64, end: f64, visible: f64) -> f64 {
+    let mut offset = offset;
+    if end > offset + visible {
+        offset = end - visible;
+    }
+    if start < offset {
+        offset = start;
+    }
+    offset.max(0.0)
+}
+
 impl AsRef<BaseDocument> for BaseDocument {
     fn as_ref(&self) -> &BaseDocument {
         self
@@ -1217,3 +1599,77 @@ impl AsMut<BaseDocument> for BaseDocument {
         self
     }
 }
+
+#[test]
+fn test_caret_blink_visible() {
+    use std::time::Duration;
+    assert!(caret_blink_visible(Duration::ZERO));
+    assert!(caret_blink_visible(Duration::from_millis(499)));
+    assert!(!caret_blink_visible(Duration::from_millis(500)));
+    assert!(!caret_blink_visible(Duration::from_millis(999)));
+    assert!(caret_blink_visible(Duration::from_millis(1000)));
+}
+
+#[test]
+fn test_scroll_axis() {
+    assert_eq!(scroll_axis(10.0, 5.0, 100.0, false), (10.0, 5.0));
+    assert_eq!(scroll_axis(10.0, -5.0, 100.0, true), (15.0, 0.0));
+    assert_eq!(scroll_axis(10.0, 30.0, 100.0, true), (0.0, 20.0));
+    assert_eq!(scroll_axis(90.0, -30.0, 100.0, true), (100.0, -20.0));
+}
+
+#[test]
+fn test_scroll_to_reveal() {
+    // Already visible
+    assert_eq!(scroll_to_reveal(10.0, 20.0, 22.0, 100.0), 10.0);
+    // Past the far edge: aligned to it
+    assert_eq!(scroll_to_reveal(0.0, 150.0, 152.0, 100.0), 52.0);
+    // Before the near edge: aligned to it
+    assert_eq!(scroll_to_reveal(50.0, 20.0, 22.0, 100.0), 20.0);
+    // Longer than the window: its start wins
+    assert_eq!(scroll_to_reveal(0.0, 30.0, 200.0, 100.0), 30.0);
+}
+
+#[test]
+fn animated_background_keeps_animating_across_mutations() {
+    use crate::node::{BackgroundImageData, ImageFrame};
+    use crate::{Attribute, qual_name};
+    use std::time::{Duration, Instant};
+
+    let mut doc = BaseDocument::new(DocumentConfig::default());
+    let root = doc.root_node().id;
+    let (div, canvas) = {
+        let mut mutator = doc.mutate();
+        let div = mutator.create_element(qual_name!("div", html), vec![]);
+        let src = Attribute {
+            name: qual_name!("src", html),
+            value: "1".to_string(),
+        };
+        let canvas = mutator.create_element(qual_name!("canvas", html), vec![src]);
+        mutator.append_children(root, &[div, canvas]);
+        (div, canvas)
+    };
+    assert!(doc.is_animating());
+
+    let url = ServoArc::new(Url::parse("https://example.com/spinner.gif").unwrap());
+    let element = doc.get_node_mut(div).unwrap().element_data_mut().unwrap();
+    element.background_images = vec![Some(BackgroundImageData::new(url))];
+    let frame = |byte: u8| ImageFrame {
+        data: Arc::new(vec![byte; 4]),
+        delay: Duration::from_millis(50),
+    };
+    doc.load_resource(Resource::AnimatedImage(
+        div,
+        ImageType::Background(0),
+        1,
+        1,
+        vec![frame(1), frame(2)],
+    ));
+
+    // Removing the canvas recomputes is_animating, which must still see the background GIF
+    doc.mutate().remove_node(canvas);
+    assert!(doc.is_animating());
+    let t0 = Instant::now();
+    assert!(!doc.advance_animated_images(t0));
+    assert!(doc.advance_animated_images(t0 + Duration::from_millis(60)));
+}
@@ -49,6 +49,8 @@ impl<'doc, Handler: EventHandler> EventDriver<'doc, Handler> {
 
         let mut hover_node_id = self.doc().hover_node_id;
         let focussed_node_id = self.doc().focus_node_id;
+        let captured_node_id = self.doc().get_pointer_capture_node_id();
+        let is_mouseup = matches!(event, UiEvent::MouseUp(_));
 
         // Update document input state (hover, focus, active, etc)
         match &event {
@@ -69,12 +71,13 @@ impl<'doc, Handler: EventHandler> EventDriver<'doc, Handler> {
         };
 
         let target = match event {
-            UiEvent::MouseMove(_) => hover_node_id,
-            UiEvent::MouseUp(_) => hover_node_id,
+            UiEvent::MouseMove(_) => captured_node_id.or(hover_node_id),
+            UiEvent::MouseUp(_) => captured_node_id.or(hover_node_id),
             UiEvent::MouseDown(_) => hover_node_id,
             UiEvent::KeyUp(_) => focussed_node_id,
             UiEvent::KeyDown(_) => focussed_node_id,
             UiEvent::Ime(_) => focussed_node_id,
+            UiEvent::Wheel(_) => hover_node_id,
         };
 
         let data = match event {
@@ -96,12 +99,17 @@ impl<'doc, Handler: EventHandler> EventDriver<'doc, Handler> {
             UiEvent::KeyUp(data) => DomEventData::KeyUp(data),
             UiEvent::KeyDown(data) => DomEventData::KeyDown(data),
             UiEvent::Ime(data) => DomEventData::Ime(data),
+            UiEvent::Wheel(data) => DomEventData::Wheel(data),
         };
 
         let target = target.unwrap_or_else(|| self.doc().root_element().id);
         let dom_event = DomEvent::new(target, data);
 
         self.handle_dom_event(dom_event);
+
+        if is_mouseup {
+            self.doc_mut().release_pointer_capture();
+        }
     }
 
     pub fn handle_dom_event(&mut self, event: DomEvent) {
@@ -126,3 +134,111 @@ impl<'doc, Handler: EventHandler> EventDriver<'doc, Handler> {
         }
     }
 }
+
+// Records the name and target of every event the driver dispatches
+#[cfg(test)]
+struct Recorder<'a>(&'a mut Vec<(&'static str, usize)>);
+
+#[cfg(test)]
+impl EventHandler for Recorder<'_> {
+    fn handle_event(
+        &mut self,
+        _chain: &[usize],
+        event: &mut DomEvent,
+        _mutr: &mut DocumentMutator<'_>,
+        _event_state: &mut EventState,
+    ) {
+        self.0.push((event.name(), event.target));
+    }
+}
+
+#[test]
+fn pointer_capture_routes_moves_and_up_to_the_captured_node() {
+    use crate::{DocumentConfig, qual_name};
+    use blitz_traits::events::{MouseEventButton, MouseEventButtons};
+    use keyboard_types::Modifiers;
+
+    let mut doc = BaseDocument::new(DocumentConfig::default());
+    let root = doc.root_node().id;
+    let (html, div) = {
+        let mut mutr = doc.mutate();
+        let html = mutr.create_element(qual_name!("html", html), vec![]);
+        let div = mutr.create_element(qual_name!("div", html), vec![]);
+        mutr.append_children(html, &[div]);
+        mutr.append_children(root, &[html]);
+        (html, div)
+    };
+    doc.set_pointer_capture(div);
+
+    // The document isn't laid out, so the pointer is outside the div (and every other node)
+    let mouse = |buttons| BlitzMouseButtonEvent {
+        x: 500.0,
+        y: 500.0,
+        button: MouseEventButton::Main,
+        buttons,
+        mods: Modifiers::empty(),
+    };
+    let mut events = Vec::new();
+    {
+        let mut driver = EventDriver::new(doc.mutate(), Recorder(&mut events));
+        driver.handle_ui_event(UiEvent::MouseMove(mouse(MouseEventButtons::Primary)));
+        driver.handle_ui_event(UiEvent::MouseUp(mouse(MouseEventButtons::None)));
+    }
+    let pointer_events: Vec<_> = events
+        .iter()
+        .filter(|(name, _)| matches!(*name, "mousemove" | "mouseup"))
+        .copied()
+        .collect();
+    assert_eq!(pointer_events, [("mousemove", div), ("mouseup", div)]);
+    assert_eq!(doc.get_pointer_capture_node_id(), None);
+
+    // Released on mouseup: moves are hit-tested again, landing on the root element when nothing is hit
+    events.clear();
+    EventDriver::new(doc.mutate(), Recorder(&mut events))
+        .handle_ui_event(UiEvent::MouseMove(mouse(MouseEventButtons::None)));
+    assert_eq!(events.first(), Some(&("mousemove", html)));
+}
+
+#[test]
+fn secondary_press_reported_as_main_opens_a_context_menu_without_clicking() {
+    use crate::{DocumentConfig, qual_name};
+    use blitz_traits::events::{MouseEventButton, MouseEventButtons};
+    use keyboard_types::Modifiers;
+
+    let mut doc = BaseDocument::new(DocumentConfig::default());
+    let root = doc.root_node().id;
+    {
+        let mut mutr = doc.mutate();
+        let html = mutr.create_element(qual_name!("html", html), vec![]);
+        mutr.append_children(root, &[html]);
+    }
+
+    let mouse = |buttons| BlitzMouseButtonEvent {
+        x: 0.0,
+        y: 0.0,
+        button: MouseEventButton::Main,
+        buttons,
+        mods: Modifiers::empty(),
+    };
+    let names = |events: &[(&'static str, usize)]| -> Vec<&'static str> {
+        events.iter().map(|(name, _)| *name).collect()
+    };
+
+    // The host only reports the secondary bit on press, and nothing on release
+    let mut events = Vec::new();
+    {
+        let mut driver = EventDriver::new(doc.mutate(), Recorder(&mut events));
+        driver.handle_ui_event(UiEvent::MouseDown(mouse(MouseEventButtons::Secondary)));
+        driver.handle_ui_event(UiEvent::MouseUp(mouse(MouseEventButtons::None)));
+    }
+    assert_eq!(names(&events), ["mousedown", "contextmenu", "mouseup"]);
+
+    // The next primary press clicks as usual
+    events.clear();
+    {
+        let mut driver = EventDriver::new(doc.mutate(), Recorder(&mut events));
+        driver.handle_ui_event(UiEvent::MouseDown(mouse(MouseEventButtons::Primary)));
+        driver.handle_ui_event(UiEvent::MouseUp(mouse(MouseEventButtons::None)));
+    }
+    assert_eq!(names(&events), ["mousedown", "mouseup", "click"]);
+}
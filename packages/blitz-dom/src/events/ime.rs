@@ -30,6 +30,8 @@ pub(crate) fn handle_ime_event(doc: &mut BaseDocument, event: BlitzImeEvent) {
                     }
                 }
             }
+            drop(font_ctx);
+            doc.scroll_caret_into_view();
             println!("Sent ime event to {node_id}");
         }
     }
@@ -9,6 +9,7 @@ use blitz_traits::{
 use keyboard_types::{Key, Modifiers};
 use markup5ever::local_name;
 use parley::{FontContext, LayoutContext};
+use peniko::kurbo;
 
 // TODO: support keypress events
 enum GeneratedEvent {
@@ -27,6 +28,26 @@ pub(crate) fn handle_keypress<F: FnMut(DomEvent)>(
         return;
     }
 
+    // Scrolling keys belong to a focused text input (caret movement); otherwise they scroll the page
+    let focus_is_editable = doc
+        .focus_node_id
+        .and_then(|id| doc.nodes[id].element_data())
+        .is_some_and(|el| el.text_input_data().is_some());
+    if !focus_is_editable && event.state.is_pressed() {
+        let window_height = doc.viewport.window_size.1 as f64 / doc.viewport.scale() as f64;
+        if let Some((x, y)) = keyboard_scroll_target(
+            &event.key,
+            event.modifiers,
+            doc.viewport_scroll(),
+            window_height,
+            doc.viewport_scroll_max(),
+        ) {
+            doc.scroll_viewport_to(x, y);
+            doc.shell_provider.request_redraw();
+            return;
+        }
+    }
+
     if let Some(node_id) = doc.focus_node_id {
         if target != node_id {
             return;
@@ -61,10 +82,52 @@ pub(crate) fn handle_keypress<F: FnMut(DomEvent)>(
                     }
                 }
             }
+
+            doc.scroll_caret_into_view();
         }
     }
 }
 
+// Arrow-key scroll step in CSS px
+const LINE_SCROLL: f64 = 40.0;
+// Page scrolls keep this much of the previous page in view
+const PAGE_SCROLL_OVERLAP: f64 = 40.0;
+
+// The viewport scroll position a page-scrolling key moves to: arrows by a line, PageUp/PageDown and
+// (Shift+)Space by a window height less an overlap, Home/End to the top/bottom. None for other keys
+// (or modifier combinations), which keep their normal handling.
+fn keyboard_scroll_target(
+    key: &Key,
+    mods: Modifiers,
+    current: kurbo::Point,
+    window_height: f64,
+    (max_x, max_y): (f64, f64),
+) -> Option<(f64, f64)> {
+    if mods.intersects(Modifiers::ALT | Modifiers::META) {
+        return None;
+    }
+    let ctrl = mods.contains(Modifiers::CONTROL);
+    let shift = mods.contains(Modifiers::SHIFT);
+    let page = (window_height - PAGE_SCROLL_OVERLAP).max(window_height * 0.5);
+    let (dx, dy) = match key {
+        Key::Home => return Some((current.x, 0.0)),
+        Key::End => return Some((current.x, max_y)),
+        _ if ctrl => return None,
+        Key::ArrowUp => (0.0, -LINE_SCROLL),
+        Key::ArrowDown => (0.0, LINE_SCROLL),
+        Key::ArrowLeft => (-LINE_SCROLL, 0.0),
+        Key::ArrowRight => (LINE_SCROLL, 0.0),
+        Key::PageUp => (0.0, -page),
+        Key::PageDown => (0.0, page),
+        Key::Character(c) if c == " " => (0.0, if shift { -page } else { page }),
+        _ => return None,
+    };
+    Some((
+        (current.x + dx).clamp(0.0, max_x),
+        (current.y + dy).clamp(0.0, max_y),
+    ))
+}
+
 #[cfg(target_os = "macos")]
 const ACTION_MOD: Modifiers = Modifiers::SUPER;
 #[cfg(not(target_os = "macos"))]
@@ -255,3 +318,27 @@ fn implicit_form_submission(doc: &BaseDocument, text_target: usize) {
 
     doc.submit_form(*form_owner_id, *form_owner_id);
 }
+
+#[test]
+fn scrolling_keys_move_the_viewport() {
+    let at = |y| kurbo::Point::new(0.0, y);
+    let target =
+        |key: Key, mods, y| keyboard_scroll_target(&key, mods, at(y), 600.0, (0.0, 2000.0));
+    let none = Modifiers::empty();
+    assert_eq!(target(Key::ArrowDown, none, 100.0), Some((0.0, 140.0)));
+    assert_eq!(target(Key::ArrowUp, none, 10.0), Some((0.0, 0.0)));
+    assert_eq!(target(Key::PageDown, none, 100.0), Some((0.0, 660.0)));
+    assert_eq!(
+        target(Key::Character(" ".into()), Modifiers::SHIFT, 1000.0),
+        Some((0.0, 440.0))
+    );
+    assert_eq!(target(Key::End, none, 0.0), Some((0.0, 2000.0)));
+    assert_eq!(
+        target(Key::Home, Modifiers::CONTROL, 900.0),
+        Some((0.0, 0.0))
+    );
+    // Other keys and shortcut chords are left alone
+    assert_eq!(target(Key::Character("a".into()), none, 0.0), None);
+    assert_eq!(target(Key::ArrowDown, Modifiers::ALT, 0.0), None);
+    assert_eq!(target(Key::PageDown, Modifiers::CONTROL, 0.0), None);
+}
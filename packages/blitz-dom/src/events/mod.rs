@@ -8,6 +8,7 @@ pub use driver::{EventDriver, EventHandler, NoopEventHandler};
 pub(crate) use ime::handle_ime_event;
 pub(crate) use keyboard::handle_keypress;
 use mouse::handle_mouseup;
+pub use mouse::is_context_menu_trigger;
 pub(crate) use mouse::{handle_click, handle_mousedown, handle_mousemove};
 
 use crate::BaseDocument;
@@ -19,6 +20,14 @@ pub(crate) fn handle_dom_event<F: FnMut(DomEvent)>(
 ) {
     let target_node_id = event.target;
 
+    // Typing, IME composition and clicks move or place the caret: show it solid again
+    if matches!(
+        event.data,
+        DomEventData::KeyDown(_) | DomEventData::Ime(_) | DomEventData::MouseDown(_)
+    ) {
+        doc.reset_caret_blink();
+    }
+
     match &event.data {
         DomEventData::MouseMove(mouse_event) => {
             let changed = handle_mousemove(
@@ -33,7 +42,7 @@ pub(crate) fn handle_dom_event<F: FnMut(DomEvent)>(
             }
         }
         DomEventData::MouseDown(event) => {
-            handle_mousedown(doc, target_node_id, event.x, event.y);
+            handle_mousedown(doc, target_node_id, event, dispatch_event);
         }
         DomEventData::MouseUp(event) => {
             handle_mouseup(doc, target_node_id, event, dispatch_event);
@@ -41,6 +50,9 @@ pub(crate) fn handle_dom_event<F: FnMut(DomEvent)>(
         DomEventData::Click(event) => {
             handle_click(doc, target_node_id, event, dispatch_event);
         }
+        DomEventData::ContextMenu(_) => {
+            // Do nothing (no default action). Embedders open their native menu.
+        }
         DomEventData::KeyDown(event) => {
             handle_keypress(doc, target_node_id, event.clone(), dispatch_event);
         }
@@ -56,5 +68,10 @@ pub(crate) fn handle_dom_event<F: FnMut(DomEvent)>(
         DomEventData::Input(_) => {
             // Do nothing (no default action)
         }
+        DomEventData::Wheel(event) => {
+            // Scrolls the nearest overflow: scroll/auto ancestor, bubbling leftover delta up to the viewport
+            doc.scroll_node_by(target_node_id, event.delta_x, event.delta_y);
+            doc.shell_provider.request_redraw();
+        }
     }
 }
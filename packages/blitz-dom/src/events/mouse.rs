@@ -18,6 +18,11 @@ pub(crate) fn handle_mousemove(
 ) -> bool {
     let mut changed = doc.set_hover_to(x, y);
 
+    // Dragging with the primary button extends a document text selection started on mousedown
+    if buttons.contains(MouseEventButtons::Primary) && doc.text_selection.is_some() {
+        changed |= doc.extend_text_selection(x, y);
+    }
+
     let Some(hit) = doc.hit(x, y) else {
         return changed;
     };
@@ -60,8 +65,48 @@ pub(crate) fn handle_mousemove(
     changed
 }
 
-pub(crate) fn handle_mousedown(doc: &mut BaseDocument, target: usize, x: f32, y: f32) {
-    let Some(hit) = doc.hit(x, y) else {
+/// Whether a mouse button event should open a context menu.
+///
+/// Blitz fires `contextmenu` on *press* of the secondary button (the macOS/X11 convention)
+/// rather than on release. This means a right-press that is dragged off the target before
+/// being released still opens the menu for the node that was originally pressed.
+///
+/// Some hosts report the pressed button only through the `buttons` bitmask, so a press whose
+/// only held button is the secondary one is also treated as a trigger.
+pub fn is_context_menu_trigger(event: &BlitzMouseButtonEvent) -> bool {
+    event.button == MouseEventButton::Secondary
+        || (event.button == MouseEventButton::Main && event.buttons == MouseEventButtons::Secondary)
+}
+
+pub(crate) fn handle_mousedown<F: FnMut(DomEvent)>(
+    doc: &mut BaseDocument,
+    target: usize,
+    event: &BlitzMouseButtonEvent,
+    mut dispatch_event: F,
+) {
+    // Don't return after dispatching `contextmenu`: like in browsers, a right-click still focuses
+    // (and places the caret in) the text input under the pointer
+    let context_menu = is_context_menu_trigger(event);
+    doc.context_menu_press = context_menu;
+    if context_menu {
+        dispatch_event(DomEvent::new(
+            target,
+            DomEventData::ContextMenu(BlitzMouseButtonEvent {
+                button: MouseEventButton::Secondary,
+                ..event.clone()
+            }),
+        ));
+    }
+
+    // A primary press starts a new document text selection (or clears it when not over text)
+    if event.button == MouseEventButton::Main
+        && !context_menu
+        && doc.start_text_selection(event.x, event.y)
+    {
+        doc.shell_provider.request_redraw();
+    }
+
+    let Some(hit) = doc.hit(event.x, event.y) else {
         return;
     };
     if hit.node_id != target {
@@ -101,6 +146,10 @@ pub(crate) fn handle_mouseup<F: FnMut(DomEvent)>(
     event: &BlitzMouseButtonEvent,
     mut dispatch_event: F,
 ) {
+    // Hosts may report a secondary press as a main press with only the secondary bit held, which
+    // the release no longer carries, so go by what the press did
+    let context_menu_press = std::mem::take(&mut doc.context_menu_press);
+
     if doc.devtools().highlight_hover {
         let mut node = doc.get_node(target).unwrap();
         if event.button == MouseEventButton::Secondary {
@@ -122,8 +171,8 @@ pub(crate) fn handle_mouseup<F: FnMut(DomEvent)>(
     //         || doc.non_anon_ancestor_if_anon(mouse_down_id) == doc.non_anon_ancestor_if_anon(target)
     // });
 
-    // Dispatch a click event
-    if do_click && event.button == MouseEventButton::Main {
+    // Dispatch a click event. Presses that produced a `contextmenu` event on mousedown don't click.
+    if do_click && event.button == MouseEventButton::Main && !context_menu_press {
         dispatch_event(DomEvent::new(target, DomEventData::Click(event.clone())));
     }
 }
@@ -194,11 +243,16 @@ pub(crate) fn handle_click<F: FnMut(DomEvent)>(
             local_name!("a") => {
                 if let Some(href) = el.attr(local_name!("href")) {
                     if let Some(url) = doc.url.resolve_relative(href) {
-                        doc.navigation_provider.navigate_to(NavigationOptions::new(
-                            url,
-                            String::from("text/plain"),
-                            doc.id(),
-                        ));
+                        // In-page anchors scroll to their target instead of navigating
+                        if let Some(fragment) = same_document_fragment(&doc.url, &url) {
+                            doc.scroll_to_fragment(fragment);
+                            return;
+                        }
+                        let target = el.attr(local_name!("target")).map(str::to_string);
+                        doc.navigation_provider.navigate_to(
+                            NavigationOptions::new(url, String::from("text/plain"), doc.id())
+                                .set_target(target),
+                        );
                     } else {
                         println!("{href} is not parseable as a url. : {:?}", *doc.url)
                     }
@@ -259,3 +313,30 @@ pub(crate) fn handle_click<F: FnMut(DomEvent)>(
     // If nothing is matched then clear focus
     doc.clear_focus();
 }
+
+/// The fragment of `url` if it only differs from the document's URL by its fragment
+fn same_document_fragment<'a>(doc_url: &url::Url, url: &'a url::Url) -> Option<&'a str> {
+    use url::Position::BeforeFragment;
+    let fragment = url.fragment()?;
+    (url[..BeforeFragment] == doc_url[..BeforeFragment]).then_some(fragment)
+}
+
+#[test]
+fn test_same_document_fragment() {
+    let doc_url = url::Url::parse("https://example.com/guide?v=2#intro").unwrap();
+    let join = |href: &str| doc_url.join(href).unwrap();
+    assert_eq!(
+        same_document_fragment(&doc_url, &join("#setup")),
+        Some("setup")
+    );
+    assert_eq!(
+        same_document_fragment(&doc_url, &join("guide?v=2#setup")),
+        Some("setup")
+    );
+    assert_eq!(same_document_fragment(&doc_url, &join("other#setup")), None);
+    assert_eq!(same_document_fragment(&doc_url, &join("guide?v=2")), None);
+    assert_eq!(
+        same_document_fragment(&doc_url, &join("mailto:me@example.com")),
+        None
+    );
+}
@@ -10,6 +10,7 @@ use style::{
     values::{
         computed::{Content, ContentItem, Display},
         specified::box_::{DisplayInside, DisplayOutside},
+        specified::text::TextOverflowSide,
     },
 };
 
@@ -328,19 +329,10 @@ fn flush_pseudo_elements(doc: &mut BaseDocument, node_id: usize) {
             )));
             doc.nodes[new_node_id].parent = Some(node_id);
 
-            let content = &pe_style.as_ref().get_counters().content;
-            if let Content::Items(item_data) = content {
-                let items = &item_data.items[0..item_data.alt_start];
-                match &items[0] {
-                    ContentItem::String(owned_str) => {
-                        let text_node_id = doc.create_text_node(owned_str);
-                        doc.nodes[new_node_id].children.push(text_node_id);
-                    }
-                    _ => {
-                        // TODO: other types of content
-                    }
-                }
-            }
+            let text = generated_content(&doc.nodes[node_id], pe_style);
+            let text_node_id = doc.create_text_node(&text);
+            doc.nodes[text_node_id].parent = Some(new_node_id);
+            doc.nodes[new_node_id].children.push(text_node_id);
 
             let mut element_data = StyloElementData::default();
             element_data.styles.primary = Some(pe_style.clone());
@@ -352,7 +344,15 @@ fn flush_pseudo_elements(doc: &mut BaseDocument, node_id: usize) {
 
         // Else: Update psuedo element
         if let (Some(pe_node_id), Some(pe_style)) = (pe_node_id, pe_style) {
-            // TODO: Update content
+            // The content may have changed with the style (e.g. a :hover::after rule) or an attr() source
+            let text = generated_content(&doc.nodes[node_id], &pe_style);
+            if let Some(text_node_id) = doc.nodes[pe_node_id].children.first().copied() {
+                if let Some(text_data) = doc.nodes[text_node_id].text_data_mut() {
+                    if text_data.content != text {
+                        text_data.content = text;
+                    }
+                }
+            }
 
             let mut node_styles = doc.nodes[pe_node_id].stylo_element_data.borrow_mut();
             let node_styles = &mut node_styles.as_mut().unwrap();
@@ -366,6 +366,117 @@ fn flush_pseudo_elements(doc: &mut BaseDocument, node_id: usize) {
     }
 }
 
+/// The text a ::before/::after box shows for its `content`: strings, attr() values of the originating
+/// element and quotes. Counters and images generate nothing yet.
+fn generated_content(originating: &Node, pe_style: &style::properties::ComputedValues) -> String {
+    let Content::Items(item_data) = &pe_style.get_counters().content else {
+        return String::new();
+    };
+    generated_content_text(&item_data.items[0..item_data.alt_start], |name| {
+        let element = originating.element_data()?;
+        let attr = element
+            .attrs()
+            .iter()
+            .find(|attr| *attr.name.local == *name)?;
+        Some(attr.value.clone())
+    })
+}
+
+fn generated_content_text(items: &[ContentItem], attr: impl Fn(&str) -> Option<String>) -> String {
+    let mut text = String::new();
+    for item in items {
+        match item {
+            ContentItem::String(owned_str) => text.push_str(owned_str),
+            ContentItem::Attr(attr_ref) => {
+                text.push_str(&attr(&attr_ref.attribute).unwrap_or_default())
+            }
+            // Quotes don't nest yet: the `quotes` property's outermost pair is assumed
+            ContentItem::OpenQuote => text.push('\u{201C}'),
+            ContentItem::CloseQuote => text.push('\u{201D}'),
+            _ => {}
+        }
+    }
+    text
+}
+
+// A laid out `<p data-label="..">Label: </p>` styled by `css`
+#[cfg(test)]
+fn generated_content_doc(css: &str, label: &str) -> (BaseDocument, usize) {
+    use crate::{Attribute, DocumentConfig};
+
+    let mut doc = BaseDocument::new(DocumentConfig::default());
+    doc.add_user_agent_stylesheet(css);
+    let root = doc.root_node().id;
+    let p = {
+        let mut mutr = doc.mutate();
+        let html = mutr.create_element(qual_name!("html", html), vec![]);
+        let body = mutr.create_element(qual_name!("body", html), vec![]);
+        let label = Attribute {
+            name: data_label(),
+            value: label.to_string(),
+        };
+        let p = mutr.create_element(qual_name!("p", html), vec![label]);
+        let text = mutr.create_text_node("Label: ");
+        mutr.append_children(p, &[text]);
+        mutr.append_children(body, &[p]);
+        mutr.append_children(html, &[body]);
+        mutr.append_children(root, &[html]);
+        p
+    };
+    doc.resolve();
+    (doc, p)
+}
+
+#[cfg(test)]
+fn data_label() -> QualName {
+    QualName::new(None, ns!(), "data-label".into())
+}
+
+#[test]
+fn test_generated_content_text() {
+    let attr = |name: &str| (name == "data-label").then(|| "New".to_string());
+    // Take the items from a parsed stylesheet so they include real attr() references
+    let (doc, p) = generated_content_doc(
+        r#"p::after { content: open-quote "- " attr(data-label) attr(data-missing) close-quote }"#,
+        "Old",
+    );
+    let after = doc.nodes[p].after.unwrap();
+    let style = doc.nodes[after].primary_styles().unwrap();
+    let Content::Items(item_data) = &style.get_counters().content else {
+        panic!("::after should have content items");
+    };
+    let items = &item_data.items[0..item_data.alt_start];
+    assert!(
+        items
+            .iter()
+            .any(|item| matches!(item, ContentItem::Attr(_)))
+    );
+    assert_eq!(generated_content_text(items, attr), "\u{201C}- New\u{201D}");
+    assert_eq!(generated_content_text(&[], attr), "");
+}
+
+#[test]
+fn generated_content_follows_attribute_changes() {
+    let (mut doc, p) = generated_content_doc("p::after { content: attr(data-label) }", "Old");
+    let after_text = |doc: &BaseDocument| {
+        let after = doc.nodes[p].after.unwrap();
+        let text_node = doc.nodes[after].children[0];
+        doc.nodes[text_node].text_data().unwrap().content.clone()
+    };
+    let layout_text = |doc: &BaseDocument| {
+        let element = doc.nodes[p].element_data().unwrap();
+        element.inline_layout_data.as_ref().unwrap().text.clone()
+    };
+    assert_eq!(after_text(&doc), "Old");
+    assert_eq!(layout_text(&doc), "Label: Old");
+
+    // The ::after keeps its style, but its text node and the paragraph's layout pick up the new value
+    doc.mutate().set_attribute(p, data_label(), "Updated");
+    doc.resolve();
+    assert_eq!(after_text(&doc), "Updated");
+    assert_eq!(layout_text(&doc), "Label: Updated");
+}
+
 /// Handles the cases where there are text nodes or inline nodes that need to be wrapped in an anonymous block node
 fn collect_complex_layout_children(
     doc: &mut BaseDocument,
@@ -544,6 +655,14 @@ pub(crate) fn build_inline_layout(
 
     let root_line_height = resolve_line_height(parley_style.line_height, parley_style.font_size);
 
+    // `text-overflow: ellipsis` at the end of the line
+    let wants_ellipsis = root_node_style.as_ref().is_some_and(|s| {
+        matches!(
+            s.get_text().text_overflow.second,
+            TextOverflowSide::Ellipsis
+        )
+    });
+
     // Create a parley tree builder
     let mut font_ctx = doc.font_ctx.lock().unwrap();
     let mut builder =
@@ -566,6 +685,14 @@ pub(crate) fn build_inline_layout(
         .and_then(|el| el.list_item_data.as_deref())
     {
         match marker {
+            // Shaped bullets keep the glyph's advance but aren't painted; blitz-paint draws the shape there
+            Marker::Char(char) if marker.shape().is_some() => {
+                let brush = TextBrush { placeholder: true, ..parley_style.brush.clone() };
+                builder.push_style_modification_span(&[StyleProperty::Brush(brush)]);
+                builder.push_text(&char.to_string());
+                builder.pop_style_span();
+                builder.push_text(" ");
+            }
             Marker::Char(char) => builder.push_text(&format!("{char} ")),
             Marker::String(str) => builder.push_text(str),
         }
@@ -604,6 +731,17 @@ pub(crate) fn build_inline_layout(
 
     let (layout, text) = builder.build();
 
+    // Shape the ellipsis in the root style so blitz-paint can end truncated lines with it
+    let ellipsis = wants_ellipsis.then(|| {
+        let mut builder =
+            doc.layout_ctx
+                .tree_builder(&mut font_ctx, doc.viewport.scale(), true, &parley_style);
+        builder.push_text("\u{2026}");
+        let (mut ellipsis, _) = builder.build();
+        ellipsis.break_all_lines(None);
+        ellipsis
+    });
+
     // Obtain layout children for the inline layout
     let layout_children: Vec<usize> = layout
         .inline_boxes()
@@ -611,7 +749,14 @@ pub(crate) fn build_inline_layout(
         .map(|ibox| ibox.id as usize)
         .collect();
 
-    return (TextLayout { text, layout }, layout_children);
+    return (
+        TextLayout {
+            text,
+            layout,
+            ellipsis,
+        },
+        layout_children,
+    );
 
     fn flush_inline_pseudos_recursive(doc: &mut BaseDocument, node_id: usize) {
         doc.iter_children_mut(node_id, |child_id, doc| {
@@ -65,6 +65,24 @@ impl BaseDocument {
                     // Element will always exist due to resize_with above
                     elem_bgs[idx] = new_bg_image;
                 }
+
+                // Likewise the border-image-source (drawn in place of the border once loaded)
+                elem.border_image = match &style.get_border().border_image_source {
+                    StyloImage::Url(ComputedUrl::Valid(new_url)) => {
+                        match elem.border_image.take() {
+                            Some(old) if *old.url == **new_url => Some(old),
+                            _ => {
+                                self.net_provider.fetch(
+                                    doc_id,
+                                    Request::get((**new_url).clone()),
+                                    Box::new(ImageHandler::new(node_id, ImageType::BorderImage)),
+                                );
+                                Some(BackgroundImageData::new(new_url.clone()))
+                            }
+                        }
+                    }
+                    _ => None,
+                };
             }
 
             // Clear Taffy cache
@@ -200,3 +200,14 @@ fn test_marker_for_upper_alpha() {
     assert_eq!(result_extended_1, Some(Marker::String("AA. ".to_string())));
     assert_eq!(result_extended_2, Some(Marker::String("AB. ".to_string())));
 }
+
+#[test]
+fn test_bullet_markers_paint_as_shapes() {
+    use crate::node::MarkerShape;
+    let shape = |list_style_type| marker_for_style(list_style_type, 0).and_then(|m| m.shape());
+    assert_eq!(shape(ListStyleType::Disc), Some(MarkerShape::Disc));
+    assert_eq!(shape(ListStyleType::Circle), Some(MarkerShape::Circle));
+    assert_eq!(shape(ListStyleType::Square), Some(MarkerShape::Square));
+    assert_eq!(shape(ListStyleType::Decimal), None);
+    assert_eq!(shape(ListStyleType::DisclosureOpen), None);
+}
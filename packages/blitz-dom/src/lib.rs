@@ -47,6 +47,7 @@ mod html;
 mod layout;
 mod mutator;
 mod query_selector;
+mod selection;
 /// Implementations that interact with servo's style engine
 mod stylo;
 mod stylo_to_cursor_icon;
@@ -61,7 +62,7 @@ pub mod util;
 mod accessibility;
 
 pub use config::DocumentConfig;
-pub use document::{BaseDocument, Document};
+pub use document::{BaseDocument, BoxModel, Document, ForcedColorScheme};
 pub use markup5ever::{
     LocalName, Namespace, NamespaceStaticSet, Prefix, PrefixStaticSet, QualName, local_name,
     namespace_prefix, namespace_url, ns,
@@ -69,8 +70,9 @@ pub use markup5ever::{
 pub use mutator::DocumentMutator;
 pub use node::{Attribute, ElementData, Node, NodeData, TextNodeData};
 pub use parley::FontContext;
+pub use selection::{SelectionPoint, TextSelection};
 pub use style::Atom;
 pub use style::invalidation::element::restyle_hints::RestyleHint;
 pub type SelectorList = selectors::SelectorList<style::selector_parser::SelectorImpl>;
-pub use events::{EventDriver, EventHandler, NoopEventHandler};
+pub use events::{EventDriver, EventHandler, NoopEventHandler, is_context_menu_trigger};
 pub use html::{DummyHtmlParserProvider, HtmlParserProvider};
@@ -166,6 +166,9 @@ impl DocumentMutator<'_> {
         match self.doc.nodes[node_id].text_data_mut() {
             Some(data) => {
                 data.content += text;
+                // A <style> (or <title>) whose text grows, e.g. across chunks of a streamed document, is reprocessed
+                let parent = self.doc.nodes[node_id].parent;
+                self.maybe_record_node(parent);
                 Ok(())
             }
             None => Err(AppendTextErr::NotTextNode),
@@ -550,6 +553,11 @@ impl<'doc> DocumentMutator<'doc> {
             }
         });
 
+        // The selection may have ended in the removed subtree
+        if self.doc.text_selection.is_some() {
+            self.doc.revalidate_text_selection();
+        }
+
         self.flush_eager_ops();
     }
 
@@ -20,11 +20,14 @@ use blitz_traits::net::{Bytes, NetHandler, Request, SharedCallback, SharedProvid
 
 use url::Url;
 
+use crate::node::ImageFrame;
 use crate::util::ImageType;
 
 #[derive(Clone, Debug)]
 pub enum Resource {
     Image(usize, ImageType, u32, u32, Arc<Vec<u8>>),
+    /// An image with more than one frame: width, height and the composited frames
+    AnimatedImage(usize, ImageType, u32, u32, Vec<ImageFrame>),
     #[cfg(feature = "svg")]
     Svg(usize, ImageType, Box<usvg::Tree>),
     Css(usize, DocumentStyleSheet),
@@ -308,6 +311,27 @@ impl ImageHandler {
 }
 impl NetHandler<Resource> for ImageHandler {
     fn bytes(self: Box<Self>, doc_id: usize, bytes: Bytes, callback: SharedCallback<Resource>) {
+        // Animated GIF/APNG/WebP keep all their frames
+        match decode_animation(&bytes) {
+            Some(DecodedFrames::Animated(width, height, frames)) => {
+                callback.call(
+                    doc_id,
+                    Ok(Resource::AnimatedImage(
+                        self.0, self.1, width, height, frames,
+                    )),
+                );
+                return;
+            }
+            Some(DecodedFrames::Still(width, height, data)) => {
+                callback.call(
+                    doc_id,
+                    Ok(Resource::Image(self.0, self.1, width, height, data)),
+                );
+                return;
+            }
+            None => {}
+        }
+
         // Try parse image
         if let Ok(image) = image::ImageReader::new(Cursor::new(&bytes))
             .with_guessed_format()
@@ -340,3 +364,72 @@ impl NetHandler<Resource> for ImageHandler {
         callback.call(doc_id, Err(Some(String::from("Could not parse image"))))
     }
 }
+
+/// The most RGBA frame data an animation may decode to. Frames are full-size, so long or large
+/// animations past this show just their first frame.
+const MAX_ANIMATION_BYTES: usize = 128 * 1024 * 1024;
+
+/// The frames of a GIF, APNG or WebP
+enum DecodedFrames {
+    /// Two or more frames (width, height, frames)
+    Animated(u32, u32, Vec<ImageFrame>),
+    /// A single frame (width, height, RGBA8 data): a still GIF, or the first frame of an
+    /// animation that went over [`MAX_ANIMATION_BYTES`] or failed to decode part way through
+    Still(u32, u32, Arc<Vec<u8>>),
+}
+
+/// Decode the frames of a GIF, APNG or WebP, so that still GIFs aren't decoded twice. `None` for
+/// other formats, still PNGs and WebPs, and anything whose first frame fails to decode, which then
+/// take the still path.
+fn decode_animation(bytes: &[u8]) -> Option<DecodedFrames> {
+    use image::AnimationDecoder;
+    use image::codecs::{gif::GifDecoder, png::PngDecoder, webp::WebPDecoder};
+
+    let frames = match image::guess_format(bytes).ok()? {
+        image::ImageFormat::Gif => GifDecoder::new(Cursor::new(bytes)).ok()?.into_frames(),
+        image::ImageFormat::Png => {
+            let decoder = PngDecoder::new(Cursor::new(bytes)).ok()?;
+            if !decoder.is_apng().ok()? {
+                return None;
+            }
+            decoder.apng().ok()?.into_frames()
+        }
+        image::ImageFormat::WebP => {
+            let decoder = WebPDecoder::new(Cursor::new(bytes)).ok()?;
+            if !decoder.has_animation() {
+                return None;
+            }
+            decoder.into_frames()
+        }
+        _ => return None,
+    };
+    let mut decoded: Vec<ImageFrame> = Vec::new();
+    let (mut width, mut height) = (0, 0);
+    let mut total_bytes = 0;
+    let mut first_frame_only = false;
+    for frame in frames {
+        let Ok(frame) = frame else {
+            first_frame_only = true;
+            break;
+        };
+        if decoded.is_empty() {
+            (width, height) = frame.buffer().dimensions();
+        }
+        let (numer, denom) = frame.delay().numer_denom_ms();
+        let data = frame.into_buffer().into_raw();
+        total_bytes += data.len();
+        decoded.push(ImageFrame {
+            delay: std::time::Duration::from_micros(numer as u64 * 1000 / denom.max(1) as u64),
+            data: Arc::new(data),
+        });
+        if total_bytes > MAX_ANIMATION_BYTES {
+            first_frame_only = true;
+            break;
+        }
+    }
+    if first_frame_only || decoded.len() < 2 {
+        let first = decoded.into_iter().next()?;
+        return Some(DecodedFrames::Still(width, height, first.data));
+    }
+    Some(DecodedFrames::Animated(width, height, decoded))
+}
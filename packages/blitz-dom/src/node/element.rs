@@ -5,6 +5,7 @@ use parley::{FontContext, LayoutContext};
 use selectors::matching::QuirksMode;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use style::Atom;
 use style::parser::ParserContext;
 use style::properties::{Importance, PropertyDeclaration, PropertyId, SourcePropertyDeclaration};
@@ -47,6 +48,9 @@ pub struct ElementData {
 
     pub background_images: Vec<Option<BackgroundImageData>>,
 
+    /// The `border-image-source` image, when it is a url
+    pub border_image: Option<BackgroundImageData>,
+
     /// Parley text layout (elements with inline inner display mode only)
     pub inline_layout_data: Option<Box<TextLayout>>,
 
@@ -122,6 +126,7 @@ impl ElementData {
             special_data: SpecialElementData::None,
             template_contents: None,
             background_images: Vec::new(),
+            border_image: None,
         };
         data.flush_is_focussable();
         data
@@ -382,17 +387,132 @@ pub struct RasterImageData {
     pub width: u32,
     /// The height of the image
     pub height: u32,
-    /// The raw image data in RGBA8 format
+    /// The raw image data in RGBA8 format (the current frame of an animated image)
+    pub data: Arc<Vec<u8>>,
+    /// [`peniko::Blob`] id for `data`. Painters reuse it across paints so renderers can cache the
+    /// uploaded pixels of each frame.
+    pub data_id: u64,
+    /// The frames of an animated image (GIF/APNG/WebP). Empty for still images.
+    pub frames: Arc<Vec<ImageFrame>>,
+    /// Blob ids of `frames`, in the same order
+    pub frame_ids: Arc<Vec<u64>>,
+    /// Index into `frames` of the frame in `data`
+    pub current_frame: usize,
+    /// When the current frame was first shown (set on the first `advance`)
+    pub frame_started: Option<Instant>,
+}
+
+/// One frame of an animated image, composited to the full image size
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageFrame {
+    /// RGBA8 pixels
     pub data: Arc<Vec<u8>>,
+    /// How long the frame is shown for, as authored
+    pub delay: Duration,
 }
+
 impl RasterImageData {
     pub fn new(width: u32, height: u32, data: Arc<Vec<u8>>) -> Self {
         Self {
             width,
             height,
+            data_id: new_blob_id(&data),
             data,
+            ..Default::default()
         }
     }
+
+    /// An animated image starting on its first frame
+    pub fn animated(width: u32, height: u32, frames: Vec<ImageFrame>) -> Self {
+        let frame_ids: Vec<u64> = frames.iter().map(|f| new_blob_id(&f.data)).collect();
+        Self {
+            width,
+            height,
+            data: frames.first().map(|f| f.data.clone()).unwrap_or_default(),
+            data_id: frame_ids.first().copied().unwrap_or_default(),
+            frames: Arc::new(frames),
+            frame_ids: Arc::new(frame_ids),
+            current_frame: 0,
+            frame_started: None,
+        }
+    }
+
+    pub fn is_animated(&self) -> bool {
+        self.frames.len() > 1
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len().max(1)
+    }
+
+    /// Authored per-frame delays (empty for still images)
+    pub fn frame_delays(&self) -> impl Iterator<Item = Duration> + '_ {
+        self.frames.iter().map(|f| f.delay)
+    }
+
+    /// Step to the frame that should be showing at `now`, looping forever. Returns whether the
+    /// current frame changed.
+    pub fn advance(&mut self, now: Instant) -> bool {
+        if !self.is_animated() {
+            return false;
+        }
+        let Some(started) = self.frame_started else {
+            self.frame_started = Some(now);
+            return false;
+        };
+        let delays: Vec<Duration> = self
+            .frames
+            .iter()
+            .map(|f| effective_frame_delay(f.delay))
+            .collect();
+        let (frame, started) = next_frame(&delays, self.current_frame, started, now);
+        self.frame_started = Some(started);
+        if frame == self.current_frame {
+            return false;
+        }
+        self.current_frame = frame;
+        self.data = self.frames[frame].data.clone();
+        self.data_id = self.frame_ids[frame];
+        true
+    }
+}
+
+// Take an id from peniko's blob counter, so it can't collide with the ids of other blobs
+fn new_blob_id(data: &Arc<Vec<u8>>) -> u64 {
+    peniko::Blob::new(data.clone()).id()
+}
+
+// Browsers treat near-zero GIF delays (<= 10ms) as 100ms
+fn effective_frame_delay(delay: Duration) -> Duration {
+    if delay <= Duration::from_millis(10) {
+        Duration::from_millis(100)
+    } else {
+        delay
+    }
+}
+
+// The frame showing at `now` given the current frame and when it started, plus that frame's start.
+// Whole animation loops are skipped arithmetically so a long stall doesn't step through every frame.
+fn next_frame(
+    delays: &[Duration],
+    mut frame: usize,
+    mut started: Instant,
+    now: Instant,
+) -> (usize, Instant) {
+    let total: Duration = delays.iter().sum();
+    let mut elapsed = now.saturating_duration_since(started);
+    if !total.is_zero() && elapsed >= total {
+        let loops = elapsed.as_nanos() / total.as_nanos();
+        let skipped = total * loops as u32;
+        started += skipped;
+        elapsed -= skipped;
+    }
+    while elapsed >= delays[frame] {
+        elapsed -= delays[frame];
+        started += delays[frame];
+        frame = (frame + 1) % delays.len();
+    }
+    (frame, started)
 }
 
 #[derive(Debug, Clone)]
@@ -512,6 +632,29 @@ pub enum Marker {
     String(String),
 }
 
+/// Bullets painted as geometry rather than glyphs, so they don't depend on which fonts carry them
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum MarkerShape {
+    /// `list-style-type: disc`: a filled circle
+    Disc,
+    /// `list-style-type: circle`: a hollow ring
+    Circle,
+    /// `list-style-type: square`: a filled square
+    Square,
+}
+
+impl Marker {
+    /// The shape painted in place of this marker's glyph, if it is a `disc`, `circle` or `square` bullet
+    pub fn shape(&self) -> Option<MarkerShape> {
+        match self {
+            Marker::Char('•') => Some(MarkerShape::Disc),
+            Marker::Char('◦') => Some(MarkerShape::Circle),
+            Marker::Char('▪') => Some(MarkerShape::Square),
+            _ => None,
+        }
+    }
+}
+
 //Value depends on list-style-position, determining whether a seperate layout is created for it
 #[derive(Clone)]
 pub enum ListItemLayoutPosition {
@@ -526,7 +669,7 @@ impl std::fmt::Debug for ListItemLayout {
 }
 
 #[derive(Debug, Clone, PartialEq)]
-/// Parley Brush type for Blitz which contains a `peniko::Brush`, node id, font weight, and primary family name
+/// Parley Brush type for Blitz which contains a `peniko::Brush`, node id, font weight, and font family list
 pub struct TextBrush {
     /// The node id for the span
     pub id: usize,
@@ -540,8 +683,22 @@ pub struct TextBrush {
     pub border_radius: f32,
     /// CSS numeric font weight (100..900); 400 normal.
     pub weight: u16,
-    /// Primary font family (first specified or generic keyword like "monospace")
+    /// CSS font-family list in order, comma separated (names and generic keywords like "monospace")
     pub family: std::sync::Arc<str>,
+    /// Resolved CSS `text-shadow` layers, in specified order (the first is painted on top)
+    pub shadows: std::sync::Arc<[TextShadow]>,
+    /// Text that only reserves space and is never painted (an inside list bullet drawn as a shape)
+    pub placeholder: bool,
+}
+
+/// One resolved layer of a CSS `text-shadow`. Lengths are in CSS px.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextShadow {
+    pub offset_x: f32,
+    pub offset_y: f32,
+    /// Blur radius (twice the gaussian standard deviation)
+    pub blur: f32,
+    pub color: AlphaColor<Srgb>,
 }
 
 impl Default for TextBrush {
@@ -553,20 +710,47 @@ impl Default for TextBrush {
             padding: [0.0;4],
             border_radius: 0.0,
             weight: 400,
-            family: std::sync::Arc::from("")
+            family: std::sync::Arc::from(""),
+            shadows: std::sync::Arc::from([]),
+            placeholder: false,
         }
     }
 }
 
 impl TextBrush {
     pub(crate) fn from_peniko_brush(brush: peniko::Brush) -> Self {
-        Self { id: 0, brush, background: None, padding:[0.0;4], border_radius:0.0, weight: 400, family: std::sync::Arc::from("") }
+        Self {
+            id: 0,
+            brush,
+            background: None,
+            padding: [0.0; 4],
+            border_radius: 0.0,
+            weight: 400,
+            family: std::sync::Arc::from(""),
+            shadows: std::sync::Arc::from([]),
+            placeholder: false,
+        }
     }
     pub(crate) fn from_color(color: AlphaColor<Srgb>) -> Self {
         Self::from_peniko_brush(peniko::Brush::Solid(color))
     }
-    pub(crate) fn from_id_color_weight_family(id: usize, color: AlphaColor<Srgb>, weight: u16, family: std::sync::Arc<str>) -> Self {
-        Self { id, brush: peniko::Brush::Solid(color), background: None, padding:[0.0;4], border_radius:0.0, weight, family }
+    pub(crate) fn from_id_color_weight_family(
+        id: usize,
+        color: AlphaColor<Srgb>,
+        weight: u16,
+        family: std::sync::Arc<str>,
+    ) -> Self {
+        Self {
+            id,
+            brush: peniko::Brush::Solid(color),
+            background: None,
+            padding: [0.0; 4],
+            border_radius: 0.0,
+            weight,
+            family,
+            shadows: std::sync::Arc::from([]),
+            placeholder: false,
+        }
     }
     pub(crate) fn with_background(mut self, background: Option<peniko::Brush>) -> Self {
         self.background = background;
@@ -574,12 +758,19 @@ impl TextBrush {
     }
     pub(crate) fn with_padding(mut self, pad: [f32;4]) -> Self { self.padding = pad; self }
     pub(crate) fn with_border_radius(mut self, r: f32) -> Self { self.border_radius = r; self }
+    pub(crate) fn with_shadows(mut self, shadows: Vec<TextShadow>) -> Self {
+        self.shadows = shadows.into();
+        self
+    }
 }
 
 #[derive(Clone)]
 pub struct TextLayout {
     pub text: String,
     pub layout: parley::layout::Layout<TextBrush>,
+    /// The shaped `…` drawn at the end of overflowing lines when the inline context's root has
+    /// `text-overflow: ellipsis`
+    pub ellipsis: Option<parley::layout::Layout<TextBrush>>,
 }
 
 impl std::fmt::Debug for TextLayout {
@@ -615,3 +806,28 @@ mod file_data {
 }
 #[cfg(feature = "file_input")]
 pub use file_data::FileData;
+
+#[test]
+fn animated_image_advances_by_frame_delay() {
+    let frame = |byte: u8, ms: u64| ImageFrame {
+        data: Arc::new(vec![byte; 4]),
+        delay: Duration::from_millis(ms),
+    };
+    let frames = vec![frame(1, 50), frame(2, 30), frame(3, 0)];
+    let mut image = RasterImageData::animated(1, 1, frames);
+    assert_eq!(image.frame_count(), 3);
+    let t0 = Instant::now();
+    assert!(!image.advance(t0));
+    assert!(!image.advance(t0 + Duration::from_millis(49)));
+    assert!(image.advance(t0 + Duration::from_millis(55)));
+    assert_eq!((image.current_frame, image.data[0]), (1, 2));
+    assert_eq!(image.data_id, image.frame_ids[1]);
+    assert_ne!(image.frame_ids[0], image.frame_ids[1]);
+    // The 0ms frame is shown for 100ms like in browsers
+    assert!(image.advance(t0 + Duration::from_millis(85)));
+    assert_eq!(image.current_frame, 2);
+    assert!(!image.advance(t0 + Duration::from_millis(170)));
+    // A long stall skips whole loops (180ms each) and lands on the right frame
+    assert!(image.advance(t0 + Duration::from_millis(180 * 10 + 60)));
+    assert_eq!(image.current_frame, 1);
+}
@@ -6,8 +6,8 @@ mod node;
 
 pub use attributes::{Attribute, Attributes};
 pub use element::{
-    BackgroundImageData, CanvasData, ElementData, ImageData, ListItemLayout,
-    ListItemLayoutPosition, Marker, RasterImageData, SpecialElementData, SpecialElementType,
-    Status, TextBrush, TextInputData, TextLayout,
+    BackgroundImageData, CanvasData, ElementData, ImageData, ImageFrame, ListItemLayout,
+    ListItemLayoutPosition, Marker, MarkerShape, RasterImageData, SpecialElementData,
+    SpecialElementType, Status, TextBrush, TextInputData, TextLayout, TextShadow,
 };
 pub use node::*;
@@ -0,0 +1,331 @@
+//! Document-level text selection over (non-editable) inline text.
+//!
+//! Text inputs keep their own selection inside their `PlainEditor`; this covers everything else. A
+//! selection point is a byte offset into the text of an inline root's parley layout, so a
+//! selection that spans several paragraphs is a run of inline roots in document order.
+
+use std::ops::Range;
+
+use parley::{Affinity, Cursor, Selection};
+use peniko::kurbo;
+
+use crate::BaseDocument;
+
+/// A caret position: a byte offset into the layout text of an inline root
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SelectionPoint {
+    /// The inline root node
+    pub node: usize,
+    /// Byte offset into the inline root's layout text
+    pub offset: usize,
+}
+
+/// A selection between where the pointer went down (`anchor`) and where it currently is (`focus`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TextSelection {
+    pub anchor: SelectionPoint,
+    pub focus: SelectionPoint,
+}
+
+impl TextSelection {
+    pub fn is_collapsed(&self) -> bool {
+        self.anchor == self.focus
+    }
+}
+
+impl BaseDocument {
+    /// The current document text selection, if any
+    pub fn text_selection(&self) -> Option<&TextSelection> {
+        self.text_selection.as_ref()
+    }
+
+    /// The selected byte range of an inline root's layout text, if the selection covers it
+    pub fn text_selection_range(&self, node_id: usize) -> Option<Range<usize>> {
+        self.text_selection_ranges
+            .iter()
+            .find(|(id, _)| *id == node_id)
+            .map(|(_, range)| range.clone())
+    }
+
+    /// Highlight rects (in the inline root's content box, layout units) for the selected part of
+    /// an inline root
+    pub fn text_selection_geometry(&self, node_id: usize) -> Vec<kurbo::Rect> {
+        let Some(range) = self.text_selection_range(node_id) else {
+            return Vec::new();
+        };
+        let Some(text_layout) = self
+            .get_node(node_id)
+            .and_then(|node| node.element_data())
+            .and_then(|el| el.inline_layout_data.as_ref())
+        else {
+            return Vec::new();
+        };
+        let layout = &text_layout.layout;
+        let selection = Selection::new(
+            Cursor::from_byte_index(layout, range.start, Affinity::Downstream),
+            Cursor::from_byte_index(layout, range.end, Affinity::Upstream),
+        );
+        selection
+            .geometry(layout)
+            .into_iter()
+            .map(|(bb, _line)| kurbo::Rect::new(bb.x0, bb.y0, bb.x1, bb.y1))
+            .collect()
+    }
+
+    /// The caret position under a point (in the same coordinates as [`BaseDocument::hit`])
+    pub fn selection_point_at(&self, x: f32, y: f32) -> Option<SelectionPoint> {
+        let hit = self.hit(x, y)?;
+        // The hit node is a text/inline node inside an inline root (or the root itself)
+        // (text inputs handle their own selection)
+        let mut node = &self.nodes[hit.node_id];
+        loop {
+            if node
+                .element_data()
+                .is_some_and(|el| el.text_input_data().is_some())
+            {
+                return None;
+            }
+            if node.flags.is_inline_root() {
+                break;
+            }
+            node = &self.nodes[node.layout_parent.get().or(node.parent)?];
+        }
+        let text_layout = node.element_data()?.inline_layout_data.as_ref()?;
+        let layout = &node.final_layout;
+        let origin = node.absolute_position(
+            layout.border.left + layout.padding.left,
+            layout.border.top + layout.padding.top,
+        );
+        let scale = text_layout.layout.scale();
+        let cursor = Cursor::from_point(
+            &text_layout.layout,
+            (x - origin.x) * scale,
+            (y - origin.y) * scale,
+        );
+        Some(SelectionPoint {
+            node: node.id,
+            offset: cursor.index(),
+        })
+    }
+
+    /// Start a new (collapsed) selection at a point, or clear the selection if there is no text
+    /// there. Returns whether the selection changed.
+    pub fn start_text_selection(&mut self, x: f32, y: f32) -> bool {
+        let Some(point) = self.selection_point_at(x, y) else {
+            return self.clear_text_selection();
+        };
+        self.set_text_selection(Some(TextSelection {
+            anchor: point,
+            focus: point,
+        }))
+    }
+
+    /// Move the focus of the current selection to the text under a point. Returns whether the
+    /// selection changed.
+    pub fn extend_text_selection(&mut self, x: f32, y: f32) -> bool {
+        let Some(mut selection) = self.text_selection else {
+            return false;
+        };
+        let Some(point) = self.selection_point_at(x, y) else {
+            return false;
+        };
+        selection.focus = point;
+        self.set_text_selection(Some(selection))
+    }
+
+    /// Returns whether there was a selection to clear
+    pub fn clear_text_selection(&mut self) -> bool {
+        self.set_text_selection(None)
+    }
+
+    /// The selected text, with a newline between inline roots. `None` if nothing is selected.
+    pub fn selected_text(&self) -> Option<String> {
+        if self.text_selection_ranges.is_empty() {
+            return None;
+        }
+        let parts: Vec<&str> = self
+            .text_selection_ranges
+            .iter()
+            .filter_map(|(node_id, range)| {
+                let text = &self
+                    .get_node(*node_id)?
+                    .element_data()?
+                    .inline_layout_data
+                    .as_ref()?
+                    .text;
+                text.get(range.clone())
+            })
+            .collect();
+        Some(parts.join("\n"))
+    }
+
+    /// Drop the selection if either end is no longer a live inline root, otherwise recompute its
+    /// byte ranges against the current inline layouts. Call after nodes are removed or inline
+    /// layouts are rebuilt.
+    pub(crate) fn revalidate_text_selection(&mut self) {
+        let Some(mut selection) = self.text_selection else {
+            return;
+        };
+        if !self.is_live_inline_root(selection.anchor.node)
+            || !self.is_live_inline_root(selection.focus.node)
+        {
+            self.clear_text_selection();
+            return;
+        }
+        for point in [&mut selection.anchor, &mut selection.focus] {
+            point.offset = point.offset.min(self.inline_text_len(point.node));
+        }
+        self.text_selection = Some(selection);
+        self.update_text_selection_ranges();
+    }
+
+    fn is_live_inline_root(&self, node_id: usize) -> bool {
+        let Some(node) = self.get_node(node_id) else {
+            return false;
+        };
+        // Anonymous blocks aren't in the DOM tree, so go by the node they were generated for
+        let in_document = if node.is_anonymous() {
+            node.layout_parent
+                .get()
+                .and_then(|id| self.get_node(id))
+                .is_some_and(|parent| parent.flags.is_in_document())
+        } else {
+            node.flags.is_in_document()
+        };
+        in_document
+            && node.flags.is_inline_root()
+            && node
+                .element_data()
+                .is_some_and(|el| el.inline_layout_data.is_some())
+    }
+
+    fn set_text_selection(&mut self, selection: Option<TextSelection>) -> bool {
+        if self.text_selection == selection {
+            return false;
+        }
+        self.text_selection = selection;
+        self.update_text_selection_ranges();
+        true
+    }
+
+    fn update_text_selection_ranges(&mut self) {
+        self.text_selection_ranges.clear();
+        let Some(selection) = self.text_selection.filter(|s| !s.is_collapsed()) else {
+            return;
+        };
+        let order = if selection.anchor.node == selection.focus.node {
+            vec![selection.anchor.node]
+        } else {
+            self.inline_roots_in_order()
+        };
+        self.text_selection_ranges =
+            selection_ranges(&order, selection, |id| self.inline_text_len(id));
+    }
+
+    fn inline_text_len(&self, node_id: usize) -> usize {
+        self.get_node(node_id)
+            .and_then(|node| node.element_data())
+            .and_then(|el| el.inline_layout_data.as_ref())
+            .map_or(0, |text_layout| text_layout.text.len())
+    }
+
+    // Inline roots in layout-tree order (anonymous blocks only exist in the layout tree)
+    fn inline_roots_in_order(&self) -> Vec<usize> {
+        let mut out = Vec::new();
+        let mut stack = vec![self.root_element().id];
+        while let Some(id) = stack.pop() {
+            let node = &self.nodes[id];
+            if node.flags.is_inline_root() {
+                out.push(id);
+            }
+            let layout_children = node.layout_children.borrow();
+            let children = layout_children.as_deref().unwrap_or(&node.children);
+            stack.extend(children.iter().rev());
+        }
+        out
+    }
+}
+
+// Split a selection into per-inline-root byte ranges, in document order. `order` lists the inline
+// roots in document order; roots strictly between the endpoints are selected in full. Empty ranges
+// are dropped.
+fn selection_ranges(
+    order: &[usize],
+    selection: TextSelection,
+    text_len: impl Fn(usize) -> usize,
+) -> Vec<(usize, Range<usize>)> {
+    let mut out = Vec::new();
+    let (a, f) = (selection.anchor, selection.focus);
+    if a.node == f.node {
+        if a.offset != f.offset {
+            out.push((a.node, a.offset.min(f.offset)..a.offset.max(f.offset)));
+        }
+        return out;
+    }
+    let (Some(ai), Some(fi)) = (
+        order.iter().position(|&n| n == a.node),
+        order.iter().position(|&n| n == f.node),
+    ) else {
+        return out;
+    };
+    let (start, end, si, ei) = if ai < fi {
+        (a, f, ai, fi)
+    } else {
+        (f, a, fi, ai)
+    };
+    for &node in &order[si..=ei] {
+        let len = text_len(node);
+        let range = if node == start.node {
+            start.offset.min(len)..len
+        } else if node == end.node {
+            0..end.offset.min(len)
+        } else {
+            0..len
+        };
+        if !range.is_empty() {
+            out.push((node, range));
+        }
+    }
+    out
+}
+
+#[test]
+fn selection_ranges_follow_document_order() {
+    let point = |node, offset| SelectionPoint { node, offset };
+    let select = |anchor, focus| TextSelection { anchor, focus };
+    let len = |_| 10;
+    // Backwards selection within one root
+    let same = selection_ranges(&[3], select(point(3, 7), point(3, 2)), len);
+    assert_eq!(same, vec![(3, 2..7)]);
+    // Focus before anchor across roots: the middle root is selected in full
+    let multi = selection_ranges(&[3, 5, 8, 9], select(point(9, 4), point(5, 6)), len);
+    assert_eq!(multi, vec![(5, 6..10), (8, 0..10), (9, 0..4)]);
+    // Collapsed selections select nothing
+    assert!(selection_ranges(&[3], select(point(3, 1), point(3, 1)), len).is_empty());
+}
+
+#[test]
+fn removing_the_selected_node_clears_the_selection() {
+    use crate::{DocumentConfig, qual_name};
+
+    let mut doc = BaseDocument::new(DocumentConfig::default());
+    let root = doc.root_node().id;
+    let p = {
+        let mut mutator = doc.mutate();
+        let p = mutator.create_element(qual_name!("p", html), vec![]);
+        mutator.append_children(root, &[p]);
+        p
+    };
+    let point = |offset| SelectionPoint { node: p, offset };
+    doc.set_text_selection(Some(TextSelection {
+        anchor: point(0),
+        focus: point(3),
+    }));
+    assert_eq!(doc.text_selection_range(p), Some(0..3));
+
+    doc.mutate().remove_and_drop_node(p);
+    assert!(doc.get_node(p).is_none());
+    assert_eq!(doc.text_selection(), None);
+    assert_eq!(doc.selected_text(), None);
+    assert!(doc.text_selection_geometry(p).is_empty());
+}
@@ -1,9 +1,10 @@
 use cursor_icon::CursorIcon;
 use style::values::computed::ui::CursorKind as StyloCursorKind;
 
-pub(crate) fn stylo_to_cursor_icon(cursor: StyloCursorKind) -> CursorIcon {
-    match cursor {
-        StyloCursorKind::None => todo!("set the cursor to none"),
+/// None for `cursor: none`, which has no icon
+pub(crate) fn stylo_to_cursor_icon(cursor: StyloCursorKind) -> Option<CursorIcon> {
+    let icon = match cursor {
+        StyloCursorKind::None => return None,
         StyloCursorKind::Default => CursorIcon::Default,
         StyloCursorKind::Pointer => CursorIcon::Pointer,
         StyloCursorKind::ContextMenu => CursorIcon::ContextMenu,
@@ -44,5 +45,6 @@ pub(crate) fn stylo_to_cursor_icon(cursor: StyloCursorKind) -> CursorIcon {
 
             CursorIcon::Default
         }
-    }
+    };
+    Some(icon)
 }
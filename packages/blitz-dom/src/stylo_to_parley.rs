@@ -3,7 +3,7 @@ use std::borrow::Cow;
 
 use style::values::computed::{Length, TextDecorationLine, CSSPixelLength};
 
-use crate::node::TextBrush;
+use crate::node::{TextBrush, TextShadow};
 use crate::util::ToColorColor;
 
 // Module of type aliases so we can refer to stylo types with nicer names
@@ -186,28 +186,31 @@ pub(crate) fn style(
     };
 
     let css_weight = font_styles.font_weight.value();
-    // Capture first family name (named or generic keyword) for backend selection
-    let primary_family: std::sync::Arc<str> = families
-        .get(0)
+    // Capture the family list (names and generic keywords, comma separated in CSS order) for backend
+    // selection so renderers can fall back through it
+    let family_list: std::sync::Arc<str> = families
+        .iter()
         .map(|f| match f {
-            parley::FontFamily::Named(n) => n.as_ref().into(),
+            parley::FontFamily::Named(n) => n.as_ref(),
             parley::FontFamily::Generic(g) => match g {
-                parley::GenericFamily::Monospace => "monospace".into(),
-                parley::GenericFamily::Serif => "serif".into(),
-                parley::GenericFamily::SansSerif => "sans-serif".into(),
-                parley::GenericFamily::Cursive => "cursive".into(),
-                parley::GenericFamily::Fantasy => "fantasy".into(),
-                parley::GenericFamily::SystemUi => "system-ui".into(),
-                parley::GenericFamily::UiSerif => "serif".into(),
-                parley::GenericFamily::UiSansSerif => "sans-serif".into(),
-                parley::GenericFamily::UiMonospace => "monospace".into(),
-                parley::GenericFamily::UiRounded => "sans-serif".into(),
-                parley::GenericFamily::Emoji => "emoji".into(),
-                parley::GenericFamily::Math => "Cambria Math".into(),
-                parley::GenericFamily::FangSong => "FangSong".into(),
+                parley::GenericFamily::Monospace => "monospace",
+                parley::GenericFamily::Serif => "serif",
+                parley::GenericFamily::SansSerif => "sans-serif",
+                parley::GenericFamily::Cursive => "cursive",
+                parley::GenericFamily::Fantasy => "fantasy",
+                parley::GenericFamily::SystemUi => "system-ui",
+                parley::GenericFamily::UiSerif => "serif",
+                parley::GenericFamily::UiSansSerif => "sans-serif",
+                parley::GenericFamily::UiMonospace => "monospace",
+                parley::GenericFamily::UiRounded => "sans-serif",
+                parley::GenericFamily::Emoji => "emoji",
+                parley::GenericFamily::Math => "Cambria Math",
+                parley::GenericFamily::FangSong => "FangSong",
             },
         })
-        .unwrap_or_else(|| "".into());
+        .collect::<Vec<_>>()
+        .join(", ")
+        .into();
     // Extract background color for inline elements (resolve GenericColor -> AbsoluteColor -> SRGB)
     let current_color = style.clone_color();
     let bg_color = 
@@ -234,6 +237,21 @@ pub(crate) fn style(
         let ry = tl.0.height.0.resolve(resolve_em).px() as f32;
         inline_radius = rx.min(ry).clamp(0.0, font_size * 2.0);
     }
+    // Text shadows resolve currentColor against this span's color
+    let text_shadows = itext_styles
+        .text_shadow
+        .0
+        .iter()
+        .map(|shadow| TextShadow {
+            offset_x: shadow.horizontal.px(),
+            offset_y: shadow.vertical.px(),
+            blur: shadow.blur.px(),
+            color: shadow
+                .color
+                .resolve_to_absolute(&current_color)
+                .as_color_color(),
+        })
+        .collect();
     parley::TextStyle {
         font_stack: parley::FontStack::List(Cow::Owned(families)),
         font_size,
@@ -243,10 +261,11 @@ pub(crate) fn style(
         font_variations: parley::FontSettings::List(Cow::Owned(font_variations)),
         font_features: parley::FontSettings::List(Cow::Borrowed(&[])),
         locale: Default::default(),
-        brush: TextBrush::from_id_color_weight_family(span_id, color, css_weight as u16, primary_family)
+        brush: TextBrush::from_id_color_weight_family(span_id, color, css_weight as u16, family_list)
             .with_background(bg_brush)
             .with_padding(inline_padding)
-            .with_border_radius(inline_radius),
+            .with_border_radius(inline_radius)
+            .with_shadows(text_shadows),
         has_underline: text_decoration_line.contains(TextDecorationLine::UNDERLINE),
         underline_offset: Default::default(),
         underline_size: Default::default(),
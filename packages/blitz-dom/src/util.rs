@@ -19,6 +19,7 @@ pub(crate) static FONT_DB: LazyLock<Arc<fontdb::Database>> = LazyLock::new(|| {
 pub enum ImageType {
     Image,
     Background(usize),
+    BorderImage,
 }
 
 // Debug print an RcDom
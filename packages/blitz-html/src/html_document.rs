@@ -33,17 +33,25 @@ impl Document for HtmlDocument {
 
 impl HtmlDocument {
     /// Parse HTML (or XHTML) into an [`HtmlDocument`]
-    pub fn from_html(html: &str, mut config: DocumentConfig) -> Self {
+    pub fn from_html(html: &str, config: DocumentConfig) -> Self {
+        let mut doc = Self::empty(config);
+        let mut mutr = doc.inner.mutate();
+        DocumentHtmlParser::parse_into_mutator(&mut mutr, html);
+        drop(mutr);
+        doc
+    }
+
+    /// Create an [`HtmlDocument`] with no content, e.g. to be filled in by an
+    /// [`HtmlStreamParser`](crate::HtmlStreamParser)
+    pub fn empty(mut config: DocumentConfig) -> Self {
         if let Some(ss) = &mut config.ua_stylesheets {
             if !ss.iter().any(|s| s == DEFAULT_CSS) {
                 ss.push(String::from(DEFAULT_CSS));
             }
         }
-        let mut doc = BaseDocument::new(config);
-        let mut mutr = doc.mutate();
-        DocumentHtmlParser::parse_into_mutator(&mut mutr, html);
-        drop(mutr);
-        HtmlDocument { inner: doc }
+        HtmlDocument {
+            inner: BaseDocument::new(config),
+        }
     }
 
     /// Convert the [`HtmlDocument`] into it's inner [`BaseDocument`]
@@ -0,0 +1,427 @@
+//! Incremental HTML parsing for documents that arrive in chunks (e.g. from the network).
+//!
+//! html5ever's parser keeps its sink for the whole parse, while the document has to stay available for
+//! styling and painting between chunks. So the parser feeds a [`RecordingSink`], which only hands out
+//! handles and records the tree operations, and [`HtmlStreamParser::push`] replays them into the document
+//! through a regular [`DocumentHtmlParser`] once each chunk is tokenized.
+
+use std::borrow::Cow;
+use std::cell::{Cell, Ref, RefCell};
+use std::rc::Rc;
+
+use blitz_dom::BaseDocument;
+use encoding_rs::{Decoder, Encoding, UTF_8, UTF_16BE, UTF_16LE, WINDOWS_1252, X_USER_DEFINED};
+use html5ever::ParseOpts;
+use html5ever::tokenizer::TokenizerOpts;
+use html5ever::tree_builder::TreeBuilderOpts;
+use html5ever::{
+    QualName,
+    tendril::{StrTendril, TendrilSink},
+    tree_builder::{ElementFlags, NodeOrText, QuirksMode, TreeSink},
+};
+
+use crate::DocumentHtmlParser;
+
+/// How many leading bytes are buffered to look for a BOM or a `<meta charset>` before parsing starts
+const PRESCAN_BYTES: usize = 1024;
+
+/// Parses an HTML document from byte chunks, adding nodes to a [`BaseDocument`] as each chunk arrives so
+/// the partial document can be laid out and painted before the rest is received.
+///
+/// The encoding is taken from a byte order mark or a `<meta charset>` in the first 1024 bytes and
+/// defaults to UTF-8. Unlike [`HtmlDocument::from_html`](crate::HtmlDocument::from_html), XHTML is
+/// parsed as HTML.
+pub struct HtmlStreamParser {
+    parser: html5ever::Parser<RecordingSink>,
+    ops: Rc<RefCell<Vec<TreeOp>>>,
+    /// Node id of each recorded handle (handle 0 is the document)
+    nodes: Vec<usize>,
+    decoder: StreamDecoder,
+}
+
+impl Default for HtmlStreamParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HtmlStreamParser {
+    pub fn new() -> Self {
+        let ops = Rc::new(RefCell::new(Vec::new()));
+        let sink = RecordingSink {
+            ops: ops.clone(),
+            names: RefCell::new(vec![None]),
+            next_handle: Cell::new(1),
+        };
+        let opts = ParseOpts {
+            tokenizer: TokenizerOpts::default(),
+            tree_builder: TreeBuilderOpts {
+                exact_errors: false,
+                scripting_enabled: false, // Enables parsing of <noscript> tags
+                iframe_srcdoc: false,
+                drop_doctype: true,
+                quirks_mode: QuirksMode::NoQuirks,
+            },
+        };
+        Self {
+            parser: html5ever::parse_document(sink, opts),
+            ops,
+            nodes: vec![0],
+            decoder: StreamDecoder::default(),
+        }
+    }
+
+    /// Parse the next chunk of the document and add the nodes it completes to `doc`. Chunks may split
+    /// tags and multi-byte characters anywhere.
+    pub fn push(&mut self, doc: &mut BaseDocument, bytes: &[u8]) {
+        let text = self.decoder.decode(bytes, false);
+        if !text.is_empty() {
+            self.parser.process(StrTendril::from(text));
+        }
+        apply_ops(&self.ops, &mut self.nodes, doc);
+    }
+
+    /// Parse any buffered input and end the document. Elements that are still open are closed as at the
+    /// end of any HTML document, so truncated or unbalanced markup still produces a complete tree.
+    pub fn finish(self, doc: &mut BaseDocument) {
+        let Self {
+            mut parser,
+            ops,
+            mut nodes,
+            mut decoder,
+        } = self;
+        let text = decoder.decode(&[], true);
+        if !text.is_empty() {
+            parser.process(StrTendril::from(text));
+        }
+        parser.finish();
+        apply_ops(&ops, &mut nodes, doc);
+    }
+}
+
+fn apply_ops(ops: &RefCell<Vec<TreeOp>>, nodes: &mut Vec<usize>, doc: &mut BaseDocument) {
+    let ops = std::mem::take(&mut *ops.borrow_mut());
+    if ops.is_empty() {
+        return;
+    }
+    let mut mutr = doc.mutate();
+    let sink = DocumentHtmlParser::new(&mut mutr);
+    let child = |nodes: &[usize], child: NodeOrText<usize>| match child {
+        NodeOrText::AppendNode(handle) => NodeOrText::AppendNode(nodes[handle]),
+        NodeOrText::AppendText(text) => NodeOrText::AppendText(text),
+    };
+    for op in ops {
+        match op {
+            TreeOp::CreateElement { name, attrs, flags } => {
+                nodes.push(sink.create_element(name, attrs, flags))
+            }
+            TreeOp::CreateComment => nodes.push(sink.create_comment(StrTendril::new())),
+            TreeOp::Append { parent, node } => sink.append(&nodes[parent], child(nodes, node)),
+            TreeOp::AppendBeforeSibling { sibling, node } => {
+                sink.append_before_sibling(&nodes[sibling], child(nodes, node))
+            }
+            TreeOp::AppendBasedOnParentNode {
+                element,
+                prev_element,
+                node,
+            } => sink.append_based_on_parent_node(
+                &nodes[element],
+                &nodes[prev_element],
+                child(nodes, node),
+            ),
+            TreeOp::AddAttrsIfMissing { target, attrs } => {
+                sink.add_attrs_if_missing(&nodes[target], attrs)
+            }
+            TreeOp::RemoveFromParent { target } => sink.remove_from_parent(&nodes[target]),
+            TreeOp::ReparentChildren {
+                old_parent,
+                new_parent,
+            } => sink.reparent_children(&nodes[old_parent], &nodes[new_parent]),
+        }
+    }
+}
+
+/// A [`TreeSink`] call, with recorded handles in place of node ids
+enum TreeOp {
+    CreateElement {
+        name: QualName,
+        attrs: Vec<html5ever::Attribute>,
+        flags: ElementFlags,
+    },
+    CreateComment,
+    Append {
+        parent: usize,
+        node: NodeOrText<usize>,
+    },
+    AppendBeforeSibling {
+        sibling: usize,
+        node: NodeOrText<usize>,
+    },
+    AppendBasedOnParentNode {
+        element: usize,
+        prev_element: usize,
+        node: NodeOrText<usize>,
+    },
+    AddAttrsIfMissing {
+        target: usize,
+        attrs: Vec<html5ever::Attribute>,
+    },
+    RemoveFromParent {
+        target: usize,
+    },
+    ReparentChildren {
+        old_parent: usize,
+        new_parent: usize,
+    },
+}
+
+/// Hands out sequential handles and records tree operations for [`apply_ops`]. Queries that depend on the
+/// tree (whether a node has a parent, text merging) are answered when the operations are replayed.
+struct RecordingSink {
+    ops: Rc<RefCell<Vec<TreeOp>>>,
+    /// Element name by handle (`None` for the document and comments)
+    names: RefCell<Vec<Option<QualName>>>,
+    next_handle: Cell<usize>,
+}
+
+impl RecordingSink {
+    fn push(&self, op: TreeOp) {
+        self.ops.borrow_mut().push(op);
+    }
+
+    fn new_handle(&self, name: Option<QualName>) -> usize {
+        let handle = self.next_handle.get();
+        self.next_handle.set(handle + 1);
+        self.names.borrow_mut().push(name);
+        handle
+    }
+}
+
+impl TreeSink for RecordingSink {
+    type Output = ();
+    type Handle = usize;
+    type ElemName<'a>
+        = Ref<'a, QualName>
+    where
+        Self: 'a;
+
+    fn finish(self) -> Self::Output {}
+
+    fn parse_error(&self, _msg: Cow<'static, str>) {}
+
+    fn get_document(&self) -> Self::Handle {
+        0
+    }
+
+    fn elem_name<'a>(&'a self, target: &'a Self::Handle) -> Self::ElemName<'a> {
+        Ref::map(self.names.borrow(), |names| {
+            names[*target]
+                .as_ref()
+                .expect("TreeSink::elem_name called on a node which is not an element!")
+        })
+    }
+
+    fn create_element(
+        &self,
+        name: QualName,
+        attrs: Vec<html5ever::Attribute>,
+        flags: ElementFlags,
+    ) -> Self::Handle {
+        let handle = self.new_handle(Some(name.clone()));
+        self.push(TreeOp::CreateElement { name, attrs, flags });
+        handle
+    }
+
+    fn create_comment(&self, _text: StrTendril) -> Self::Handle {
+        let handle = self.new_handle(None);
+        self.push(TreeOp::CreateComment);
+        handle
+    }
+
+    fn create_pi(&self, _target: StrTendril, _data: StrTendril) -> Self::Handle {
+        let handle = self.new_handle(None);
+        self.push(TreeOp::CreateComment);
+        handle
+    }
+
+    fn append(&self, parent: &Self::Handle, child: NodeOrText<Self::Handle>) {
+        self.push(TreeOp::Append {
+            parent: *parent,
+            node: child,
+        });
+    }
+
+    fn append_before_sibling(&self, sibling: &Self::Handle, new_node: NodeOrText<Self::Handle>) {
+        self.push(TreeOp::AppendBeforeSibling {
+            sibling: *sibling,
+            node: new_node,
+        });
+    }
+
+    fn append_based_on_parent_node(
+        &self,
+        element: &Self::Handle,
+        prev_element: &Self::Handle,
+        child: NodeOrText<Self::Handle>,
+    ) {
+        self.push(TreeOp::AppendBasedOnParentNode {
+            element: *element,
+            prev_element: *prev_element,
+            node: child,
+        });
+    }
+
+    fn append_doctype_to_document(
+        &self,
+        _name: StrTendril,
+        _public_id: StrTendril,
+        _system_id: StrTendril,
+    ) {
+        // Ignore. We don't care about the DOCTYPE for now.
+    }
+
+    fn get_template_contents(&self, target: &Self::Handle) -> Self::Handle {
+        *target
+    }
+
+    fn same_node(&self, x: &Self::Handle, y: &Self::Handle) -> bool {
+        x == y
+    }
+
+    fn set_quirks_mode(&self, _mode: QuirksMode) {}
+
+    fn add_attrs_if_missing(&self, target: &Self::Handle, attrs: Vec<html5ever::Attribute>) {
+        self.push(TreeOp::AddAttrsIfMissing {
+            target: *target,
+            attrs,
+        });
+    }
+
+    fn remove_from_parent(&self, target: &Self::Handle) {
+        self.push(TreeOp::RemoveFromParent { target: *target });
+    }
+
+    fn reparent_children(&self, old_parent: &Self::Handle, new_parent: &Self::Handle) {
+        self.push(TreeOp::ReparentChildren {
+            old_parent: *old_parent,
+            new_parent: *new_parent,
+        });
+    }
+}
+
+/// Turns byte chunks into text, holding back sequences split between chunks
+#[derive(Default)]
+struct StreamDecoder {
+    /// `None` while the first bytes are buffered for sniffing
+    decoder: Option<Decoder>,
+    /// Bytes buffered for sniffing until the encoding is known
+    pending: Vec<u8>,
+}
+
+impl StreamDecoder {
+    fn decode(&mut self, bytes: &[u8], last: bool) -> String {
+        let Some(decoder) = &mut self.decoder else {
+            self.pending.extend_from_slice(bytes);
+            if self.pending.len() < PRESCAN_BYTES && !last {
+                return String::new();
+            }
+            let (encoding, bom_len) = sniff_encoding(&self.pending);
+            self.decoder = Some(encoding.new_decoder_without_bom_handling());
+            let prefix = std::mem::take(&mut self.pending);
+            return self.decode(&prefix[bom_len..], last);
+        };
+        let capacity = decoder
+            .max_utf8_buffer_length(bytes.len())
+            .unwrap_or(bytes.len());
+        let mut out = String::with_capacity(capacity);
+        let _ = decoder.decode_to_string(bytes, &mut out, last);
+        out
+    }
+}
+
+/// The document's encoding and the length of its byte order mark, from a BOM or else a `<meta charset>`
+/// (or `http-equiv` content type) in `prefix`. See https://html.spec.whatwg.org/#determining-the-character-encoding
+fn sniff_encoding(prefix: &[u8]) -> (&'static Encoding, usize) {
+    if let Some(bom) = Encoding::for_bom(prefix) {
+        return bom;
+    }
+    let prefix = prefix[..prefix.len().min(PRESCAN_BYTES)].to_ascii_lowercase();
+    let mut search = &prefix[..];
+    while let Some(start) = find(search, b"<meta") {
+        let tag = &search[start..];
+        let tag = &tag[..find(tag, b">").unwrap_or(tag.len())];
+        if let Some(at) = find(tag, b"charset=") {
+            let label: Vec<u8> = tag[at + 8..]
+                .iter()
+                .skip_while(|b| matches!(b, b'"' | b'\'' | b' '))
+                .take_while(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b':' | b'.'))
+                .copied()
+                .collect();
+            if let Some(encoding) = Encoding::for_label(&label) {
+                // UTF-16 declared in markup can't be right (the markup was read as ASCII), so it means UTF-8
+                return match encoding {
+                    e if e == UTF_16BE || e == UTF_16LE => (UTF_8, 0),
+                    e if e == X_USER_DEFINED => (WINDOWS_1252, 0),
+                    e => (e, 0),
+                };
+            }
+        }
+        search = &search[start + 5..];
+    }
+    (UTF_8, 0)
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[test]
+fn streamed_chunks_add_nodes_before_the_document_ends() {
+    use blitz_dom::DocumentConfig;
+
+    // The comment fills the encoding prescan window so parsing starts with the first chunk. Chunk boundaries
+    // split a tag, an attribute and the two bytes of 'é'; the last element is never closed.
+    let html = format!(
+        "<!DOCTYPE html><!--{}--><html><head><style>p {{ color: red }}</style></head><body><p id=\"a\">Café</p><div><span>open",
+        " ".repeat(PRESCAN_BYTES)
+    );
+    let bytes = html.as_bytes();
+    let cafe = html.find('é').unwrap() + 1;
+    let mut doc = BaseDocument::new(DocumentConfig::default());
+    let mut parser = HtmlStreamParser::new();
+    parser.push(&mut doc, &bytes[..cafe - 20]);
+    parser.push(&mut doc, &bytes[cafe - 20..cafe]);
+    parser.push(&mut doc, &bytes[cafe..html.find("<div>").unwrap()]);
+    let p = doc.query_selector("#a").unwrap().unwrap();
+    assert_eq!(doc.get_node(p).unwrap().text_content(), "Café");
+    assert!(doc.query_selector("span").unwrap().is_none());
+
+    parser.push(&mut doc, &bytes[html.find("<div>").unwrap()..]);
+    parser.finish(&mut doc);
+    let span = doc.query_selector("div > span").unwrap().unwrap();
+    assert_eq!(doc.get_node(span).unwrap().text_content(), "open");
+}
+
+#[test]
+fn stream_encoding_comes_from_the_bom_or_meta_charset() {
+    assert_eq!(sniff_encoding(b"\xEF\xBB\xBF<p>"), (UTF_8, 3));
+    assert_eq!(sniff_encoding(b"\xFF\xFE<\0"), (UTF_16LE, 2));
+    assert_eq!(
+        sniff_encoding(b"<meta charset=\"ISO-8859-1\"><p>"),
+        (WINDOWS_1252, 0)
+    );
+    assert_eq!(
+        sniff_encoding(b"<meta http-equiv=content-type content='text/html; charset=windows-1252'>"),
+        (WINDOWS_1252, 0)
+    );
+    assert_eq!(sniff_encoding(b"<meta charset=utf-16>"), (UTF_8, 0));
+    assert_eq!(sniff_encoding(b"<p>no declaration</p>"), (UTF_8, 0));
+
+    let mut decoder = StreamDecoder::default();
+    assert_eq!(
+        decoder.decode(b"<meta charset=latin1>caf\xE9 \x93q\x94", true),
+        "<meta charset=latin1>café “q”"
+    );
+}
@@ -1,6 +1,8 @@
 mod html_document;
 mod html_sink;
+mod html_stream;
 
 pub use html_document::HtmlDocument;
 pub use html_sink::DocumentHtmlParser;
 pub use html_sink::HtmlProvider;
+pub use html_stream::HtmlStreamParser;
@@ -1,4 +1,5 @@
 //! Shared metric types for Blitz instrumentation.
+use std::fmt;
 use std::sync::Mutex;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
@@ -43,37 +44,87 @@ impl FrameTimings {
             _ => {}
         }
     }
+    /// Pipeline phases as `"name":value` JSON members (no surrounding braces) so callers can
+    /// splice in their own fields.
+    pub fn json_fields(&self) -> String {
+        format!(
+            "\"html_parse_ms\":{},\"style_ms\":{},\"layout_ms\":{},\"text_shaping_ms\":{},\"scene_build_ms\":{},\"device_init_ms\":{},\"backbuffer_ms\":{},\"playback_ms\":{},\"frame_total_ms\":{}",
+            JsonNumber(self.html_parse_ms),
+            JsonNumber(self.style_ms),
+            JsonNumber(self.layout_ms),
+            JsonNumber(self.text_shaping_ms),
+            JsonNumber(self.scene_build_ms),
+            JsonNumber(self.device_init_ms),
+            JsonNumber(self.backbuffer_ms),
+            JsonNumber(self.playback_ms),
+            JsonNumber(self.frame_total_ms),
+        )
+    }
+    pub fn to_json(&self) -> String {
+        format!("{{{}}}", self.json_fields())
+    }
+}
+
+/// Formats an `f32` as a JSON number, or `null` when it is NaN or infinite (which JSON can't express)
+pub struct JsonNumber(pub f32);
+
+impl fmt::Display for JsonNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_finite() {
+            write!(f, "{}", self.0)
+        } else {
+            f.write_str("null")
+        }
+    }
 }
 
 static PHASE_TIMINGS: once_cell::sync::Lazy<Mutex<FrameTimings>> = once_cell::sync::Lazy::new(|| Mutex::new(FrameTimings::default()));
 static FROZEN: AtomicBool = AtomicBool::new(false);
 
-pub struct PhaseGuard { name: &'static str, start: Option<Instant> }
+pub struct PhaseGuard {
+    name: &'static str,
+    start: Option<Instant>,
+    fixed: Option<Duration>,
+}
 impl PhaseGuard {
     pub fn end(mut self) {
+        self.finish();
+    }
+    fn finish(&mut self) {
         if let Some(st) = self.start.take() {
-            let dur = st.elapsed();
-            if !FROZEN.load(Ordering::SeqCst) {
-                let mut g = PHASE_TIMINGS.lock().unwrap();
-                g.add(self.name, dur);
-            }
+            record_phase(self.name, self.fixed.unwrap_or_else(|| st.elapsed()));
         }
     }
 }
 
 impl Drop for PhaseGuard {
     fn drop(&mut self) {
-        if let Some(st) = self.start.take() {
-            if !FROZEN.load(Ordering::SeqCst) {
-                let dur = st.elapsed();
-                let mut g = PHASE_TIMINGS.lock().unwrap();
-                g.add(self.name, dur);
-            }
-        }
+        self.finish();
     }
 }
 
-pub fn start_phase(name: &'static str) -> PhaseGuard { PhaseGuard { name, start: Some(Instant::now()) } }
+pub fn start_phase(name: &'static str) -> PhaseGuard {
+    PhaseGuard {
+        name,
+        start: Some(Instant::now()),
+        fixed: None,
+    }
+}
+/// Test hook: a guard that records `duration` for `name` when it ends, instead of the wall-clock time.
+pub fn start_phase_with_duration(name: &'static str, duration: Duration) -> PhaseGuard {
+    PhaseGuard {
+        name,
+        start: Some(Instant::now()),
+        fixed: Some(duration),
+    }
+}
+/// Add `duration` to a phase unless metrics are frozen. Guards report through this; tests can call
+/// it directly with fixed durations.
+pub fn record_phase(name: &str, duration: Duration) {
+    if !FROZEN.load(Ordering::SeqCst) {
+        PHASE_TIMINGS.lock().unwrap().add(name, duration);
+    }
+}
 pub fn snapshot() -> FrameTimings { PHASE_TIMINGS.lock().unwrap().clone() }
 pub fn reset_frame() { *PHASE_TIMINGS.lock().unwrap() = FrameTimings::default(); }
 pub fn freeze() { FROZEN.store(true, Ordering::SeqCst); }
@@ -83,3 +134,69 @@ pub fn unfreeze_and_reset() { FROZEN.store(false, Ordering::SeqCst); *PHASE_TIMI
 pub fn begin_init_window(_start: Instant) { /* no-op with always-active gating */ }
 pub fn end_init_window() { /* no-op; freeze() stops recording */ }
 pub fn init_active() -> bool { !FROZEN.load(Ordering::SeqCst) }
+
+#[test]
+fn to_json_lists_all_phases() {
+    let t = FrameTimings {
+        layout_ms: 1.5,
+        ..Default::default()
+    };
+    let j = t.to_json();
+    assert!(j.starts_with('{') && j.ends_with('}'));
+    assert!(j.contains("\"layout_ms\":1.5"));
+    assert_eq!(j.matches(':').count(), 9);
+
+    let t = FrameTimings {
+        layout_ms: f32::NAN,
+        playback_ms: f32::INFINITY,
+        ..Default::default()
+    };
+    let j = t.to_json();
+    assert!(j.contains("\"layout_ms\":null") && j.contains("\"playback_ms\":null"));
+}
+
+// The metrics state is process-global; serialize the tests that touch it.
+#[cfg(test)]
+static TEST_LOCK: Mutex<()> = Mutex::new(());
+
+#[test]
+fn recording_stops_at_freeze_and_resumes_after_reset() {
+    let _lock = TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    reset_for_testing();
+    let ms = Duration::from_millis;
+    record_phase("layout", ms(2));
+    record_phase("layout", ms(3));
+    start_phase_with_duration("style", ms(4)).end();
+    assert!(init_active());
+    assert_eq!((snapshot().layout_ms, snapshot().style_ms), (5.0, 4.0));
+
+    freeze();
+    assert!(is_frozen() && !init_active());
+    record_phase("layout", ms(7));
+    start_phase_with_duration("style", ms(7)).end();
+    assert_eq!((snapshot().layout_ms, snapshot().style_ms), (5.0, 4.0));
+
+    unfreeze_and_reset();
+    assert!(!is_frozen());
+    assert_eq!(snapshot().layout_ms, 0.0);
+    record_phase("layout", ms(1));
+    assert_eq!(snapshot().layout_ms, 1.0);
+    reset_for_testing();
+}
+
+#[test]
+fn phase_guard_dropped_while_frozen_records_nothing() {
+    let _lock = TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    reset_for_testing();
+    let guard = start_phase_with_duration("parse", Duration::from_millis(9));
+    freeze();
+    drop(guard);
+    assert_eq!(snapshot().html_parse_ms, 0.0);
+    // A guard dropped while active records its (injected) duration exactly once
+    reset_for_testing();
+    {
+        let _guard = start_phase_with_duration("parse", Duration::from_millis(9));
+    }
+    assert_eq!(snapshot().html_parse_ms, 9.0);
+    reset_for_testing();
+}
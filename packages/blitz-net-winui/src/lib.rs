@@ -1,11 +1,13 @@
 //! WinUI host-driven networking provider placeholder.
 //! This crate defines a NetProvider implementation that delegates actual network IO to a
 //! host-provided WinRT INetworkFetcher (see blitz-shell-winui IDL). It focuses on request ID
-//! tracking and mapping handler completions.
+//! tracking and mapping handler completions. `file:` URLs are read from disk on a background thread
+//! instead, limited to one directory (see [`WinUiNetProvider::set_file_root`]).
+use blitz_traits::net::{Body, BoxedHandler, NetProvider, Request};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::{Arc, Mutex};
-use std::sync::atomic::{AtomicU32, Ordering};
-use blitz_traits::net::{NetProvider, Request, BoxedHandler};
 
 // Lightweight logging hook (the shell exposes debug_log; we gate behind feature-less fn pointer lookup).
 #[inline(always)]
@@ -14,45 +16,325 @@ fn host_debug_log(msg: &str) {
     unsafe { let _ = std::panic::catch_unwind(|| __blitz_host_debug_log(msg.as_ptr(), msg.len())); }
 }
 
+// Per-request logging is off by default: a page with hundreds of resources would otherwise flood the debugger
+// output and format a string per fetch even when nobody is listening.
+static VERBOSE_LOG: AtomicBool = AtomicBool::new(false);
+
+/// Enable or disable the per-request net-bridge logging (mirrors `anyrender_d2d::set_verbose_logging`).
+pub fn set_verbose_logging(enabled: bool) {
+    VERBOSE_LOG.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether per-request logging is on, for callers that want to skip building log messages entirely.
+#[inline]
+pub fn is_verbose_logging() -> bool {
+    VERBOSE_LOG.load(Ordering::Relaxed)
+}
+
+// Only formats the message when verbose logging is on.
+macro_rules! vlog { ($($t:tt)*) => { if is_verbose_logging() { host_debug_log(&format!($($t)*)); } } }
+
+/// The parts of a `Request` the host needs to issue it faithfully.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostRequest {
+    pub url: String,
+    pub method: String,
+    /// `Name: value` header lines joined with CRLF, including Content-Type when the request has one
+    pub headers: String,
+    /// Request body; form bodies are url-encoded (multipart isn't supported by the host bridge)
+    pub body: Vec<u8>,
+}
+
+impl HostRequest {
+    pub fn from_request(request: &Request) -> Self {
+        let mut lines: Vec<String> = request
+            .headers
+            .iter()
+            .filter_map(|(name, value)| {
+                Some(format!("{}: {}", name.as_str(), value.to_str().ok()?))
+            })
+            .collect();
+        if !request.content_type.is_empty() && !request.headers.contains_key("content-type") {
+            lines.push(format!("Content-Type: {}", request.content_type));
+        }
+        let body = match &request.body {
+            Body::Bytes(bytes) => bytes.to_vec(),
+            Body::Form(form) if request.content_type == "application/x-www-form-urlencoded" => {
+                let mut serializer = url::form_urlencoded::Serializer::new(String::new());
+                for entry in form.iter() {
+                    if let blitz_traits::net::EntryValue::String(value) = &entry.value {
+                        serializer.append_pair(&entry.name, value);
+                    }
+                }
+                serializer.finish().into_bytes()
+            }
+            Body::Form(_) => {
+                host_debug_log(&format!(
+                    "HostRequest: unsupported form encoding '{}'; sending empty body",
+                    request.content_type
+                ));
+                Vec::new()
+            }
+            Body::Empty => Vec::new(),
+        };
+        Self {
+            url: request.url.as_str().to_string(),
+            method: request.method.as_str().to_string(),
+            headers: lines.join("\r\n"),
+            body,
+        }
+    }
+}
+
+/// Parse CRLF (or LF) separated `Name: value` lines, as carried by `HostRequest::headers`, skipping malformed ones.
+pub fn parse_header_lines(lines: &str) -> blitz_traits::net::HeaderMap {
+    use blitz_traits::net::http::{HeaderName, HeaderValue};
+    let mut headers = blitz_traits::net::HeaderMap::new();
+    for line in lines.lines() {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.trim().as_bytes()),
+            HeaderValue::from_str(value.trim()),
+        ) {
+            headers.append(name, value);
+        }
+    }
+    headers
+}
+
 // Trait the shell implements to let the provider ask the host to start a fetch.
 pub trait HostFetcher: Send + Sync {
     // Return true if dispatch accepted; false if host not ready.
-    fn request_url(&self, doc_id: usize, url: &str, request_id: u32) -> bool;
+    fn request_url(&self, doc_id: usize, request: &HostRequest, request_id: u32) -> bool;
 }
 
+/// Outcome of a `file:` request: the file's bytes, or why it wasn't read.
+pub type FileReadResult = Result<Vec<u8>, String>;
+
 pub struct WinUiNetProvider<D: 'static> {
-    host: Arc<dyn HostFetcher>,
+    host: Mutex<Arc<dyn HostFetcher>>,
     next_id: AtomicU32,
     // request_id -> (doc_id, handler)
     pending: Mutex<HashMap<u32, (usize, BoxedHandler<D>)>>,
+    // Directory file: requests may read from; None refuses them all
+    file_root: Mutex<Option<PathBuf>>,
+    // Finished file: reads, pushed by the reader threads and completed by the host on its own thread
+    // (take_completed_files)
+    completed_files: Arc<Mutex<Vec<(u32, FileReadResult)>>>,
 }
 
 impl<D: 'static> WinUiNetProvider<D> {
     pub fn new(host: Arc<dyn HostFetcher>) -> Self {
-    host_debug_log("WinUiNetProvider: created");
-    Self { host, next_id: AtomicU32::new(1), pending: Mutex::new(HashMap::new()) }
+        vlog!("WinUiNetProvider: created");
+        Self {
+            host: Mutex::new(host),
+            next_id: AtomicU32::new(1),
+            pending: Mutex::new(HashMap::new()),
+            file_root: Mutex::new(None),
+            completed_files: Arc::new(Mutex::new(Vec::new())),
+        }
     }
 
     pub fn shared(host: Arc<dyn HostFetcher>) -> Arc<Self> { Arc::new(Self::new(host)) }
 
+    /// Replace the fetcher that http(s) requests are dispatched to.
+    pub fn set_host(&self, host: Arc<dyn HostFetcher>) {
+        if let Ok(mut guard) = self.host.lock() {
+            *guard = host;
+        }
+    }
+
+    /// Allow `file:` requests to read files inside `root` (and its subdirectories), or refuse them all with `None`.
+    pub fn set_file_root(&self, root: Option<PathBuf>) {
+        if let Ok(mut guard) = self.file_root.lock() {
+            *guard = root;
+        }
+    }
+
     pub fn take_handler(&self, id: u32) -> Option<(usize, BoxedHandler<D>)> {
         self.pending.lock().ok().and_then(|mut m| m.remove(&id))
     }
+
+    /// Drop the handlers of requests issued for `doc_id`, e.g. once that document has been replaced. Their
+    /// responses then find no handler (`take_handler` returns None) and are ignored.
+    pub fn cancel_document(&self, doc_id: usize) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.retain(|_, (request_doc, _)| *request_doc != doc_id);
+        }
+    }
+
+    /// File reads finished since the last call, as (request id, result). The host completes each one like a
+    /// host fetch (take_handler, then the handler or the error callback).
+    pub fn take_completed_files(&self) -> Vec<(u32, FileReadResult)> {
+        self.completed_files
+            .lock()
+            .map(|mut done| std::mem::take(&mut *done))
+            .unwrap_or_default()
+    }
+}
+
+/// The path a `file:` URL names, provided it lies inside `root` once both are canonicalized, so `..` segments,
+/// symlinks and absolute URLs can't reach files elsewhere.
+pub fn local_file_path(root: &Path, url: &url::Url) -> Result<PathBuf, String> {
+    let path = url
+        .to_file_path()
+        .map_err(|_| format!("not a local file URL: {}", url))?;
+    let root = root
+        .canonicalize()
+        .map_err(|err| format!("file root {}: {}", root.display(), err))?;
+    let path = path
+        .canonicalize()
+        .map_err(|err| format!("{}: {}", path.display(), err))?;
+    if path.starts_with(&root) {
+        Ok(path)
+    } else {
+        Err(format!("{} is outside {}", path.display(), root.display()))
+    }
 }
 
 impl<D: 'static> NetProvider<D> for WinUiNetProvider<D> {
     fn fetch(&self, doc_id: usize, request: Request, handler: BoxedHandler<D>) {
         let id = self.next_id.fetch_add(1, Ordering::Relaxed);
-        let url_str = request.url.as_str().to_string();
+        let host_request = HostRequest::from_request(&request);
         let pending_len = {
             let mut guard_opt = self.pending.lock().ok();
             if let Some(ref mut guard) = guard_opt { guard.insert(id, (doc_id, handler)); guard.len() } else { 0 }
         };
-        host_debug_log(&format!("WinUiNetProvider.fetch: id={} doc_id={} url={} pending={} (dispatching)", id, doc_id, url_str, pending_len));
-        if !self.host.request_url(doc_id, &url_str, id) {
+        if request.url.scheme() == "file" {
+            let root = self.file_root.lock().ok().and_then(|root| root.clone());
+            let completed_files = self.completed_files.clone();
+            let url = request.url;
+            // fetch runs on the UI thread (resolve, load_html), so large or numerous files are read off it
+            std::thread::spawn(move || {
+                let result = match root {
+                    Some(root) => local_file_path(&root, &url).and_then(|path| {
+                        std::fs::read(&path).map_err(|err| format!("{}: {}", path.display(), err))
+                    }),
+                    None => Err(format!("file access not allowed: {}", url)),
+                };
+                vlog!(
+                    "WinUiNetProvider.fetch: id={} doc_id={} url={} file read ok={}",
+                    id,
+                    doc_id,
+                    url,
+                    result.is_ok()
+                );
+                if let Ok(mut done) = completed_files.lock() {
+                    done.push((id, result));
+                }
+            });
+            return;
+        }
+        vlog!(
+            "WinUiNetProvider.fetch: id={} doc_id={} url={} method={} pending={} (dispatching)",
+            id,
+            doc_id,
+            host_request.url,
+            host_request.method,
+            pending_len
+        );
+        let host = self.host.lock().ok().map(|host| host.clone());
+        if !host.is_some_and(|host| host.request_url(doc_id, &host_request, id)) {
             // Host rejected; remove handler and (best-effort) drop silently. Upstream can add error callback here.
             let _ = self.take_handler(id);
             host_debug_log(&format!("WinUiNetProvider.fetch: id={} rejected by host", id));
         }
     }
 }
+
+// A scratch directory under the system temp dir, removed on drop even if the test panics
+#[cfg(test)]
+struct TempDir(PathBuf);
+
+#[cfg(test)]
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let dir =
+            std::env::temp_dir().join(format!("blitz-net-winui-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+}
+
+#[cfg(test)]
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+#[test]
+fn file_urls_stay_inside_the_file_root() {
+    let temp = TempDir::new("file-root");
+    let base = &temp.0;
+    let site = base.join("site");
+    std::fs::create_dir_all(site.join("css")).unwrap();
+    std::fs::write(site.join("css").join("app.css"), "body{}").unwrap();
+    std::fs::write(base.join("secret.txt"), "no").unwrap();
+    let url = |path: &Path| url::Url::from_file_path(path).unwrap();
+
+    assert!(local_file_path(&site, &url(&site.join("css").join("app.css"))).is_ok());
+    // `..` and absolute paths outside the root are refused, as are missing files
+    let escape = url::Url::from_directory_path(&site)
+        .unwrap()
+        .join("../secret.txt")
+        .unwrap();
+    assert!(local_file_path(&site, &escape).is_err());
+    assert!(local_file_path(&site, &url(&base.join("secret.txt"))).is_err());
+    assert!(local_file_path(&site, &url(&site.join("missing.png"))).is_err());
+}
+
+// Test doubles: a host that accepts every request, and a handler that drops the response
+#[cfg(test)]
+struct AcceptAll;
+#[cfg(test)]
+impl HostFetcher for AcceptAll {
+    fn request_url(&self, _: usize, _: &HostRequest, _: u32) -> bool {
+        true
+    }
+}
+#[cfg(test)]
+struct Ignore;
+#[cfg(test)]
+impl blitz_traits::net::NetHandler<()> for Ignore {
+    fn bytes(
+        self: Box<Self>,
+        _: usize,
+        _: blitz_traits::net::Bytes,
+        _: blitz_traits::net::SharedCallback<()>,
+    ) {
+    }
+}
+
+#[test]
+fn file_reads_complete_off_the_calling_thread() {
+    let temp = TempDir::new("file-read");
+    std::fs::write(temp.0.join("app.css"), "body{}").unwrap();
+    let provider = WinUiNetProvider::<()>::new(Arc::new(AcceptAll));
+    provider.set_file_root(Some(temp.0.clone()));
+    let url = url::Url::from_file_path(temp.0.join("app.css")).unwrap();
+    provider.fetch(1, Request::get(url), Box::new(Ignore));
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+    let mut done = Vec::new();
+    while done.is_empty() && std::time::Instant::now() < deadline {
+        std::thread::sleep(std::time::Duration::from_millis(1));
+        done = provider.take_completed_files();
+    }
+    assert_eq!(done, [(1, Ok(b"body{}".to_vec()))]);
+    assert!(provider.take_handler(1).is_some());
+}
+
+#[test]
+fn cancel_document_drops_only_that_documents_handlers() {
+    let provider = WinUiNetProvider::<()>::new(Arc::new(AcceptAll));
+    let request = || Request::get(url::Url::parse("https://example.com/a.png").unwrap());
+    provider.fetch(1, request(), Box::new(Ignore));
+    provider.fetch(2, request(), Box::new(Ignore));
+    provider.cancel_document(1);
+    assert!(provider.take_handler(1).is_none());
+    assert_eq!(provider.take_handler(2).map(|(doc_id, _)| doc_id), Some(2));
+}
@@ -1,8 +1,9 @@
 use crate::color::{Color, ToColorColor};
-use color::DynamicColor;
+use color::{ColorSpaceTag, DynamicColor, HueDirection};
 use kurbo::{self, Affine, Point, Rect, Vec2};
 use peniko::{self, ColorStop, Gradient};
-use style::color::AbsoluteColor;
+use style::color::mix::{ColorInterpolationMethod, HueInterpolationMethod};
+use style::color::{AbsoluteColor, ColorSpace};
 use style::{
     OwnedSlice,
     values::{
@@ -52,7 +53,7 @@ pub(crate) fn to_peniko_gradient(
     scale: f64,
     current_color: &AbsoluteColor,
 ) -> (peniko::Gradient, Option<Affine>) {
-    match gradient {
+    let (mut peniko_gradient, transform) = match gradient {
         // https://developer.mozilla.org/en-US/docs/Web/CSS/gradient/linear-gradient
         GenericGradient::Linear {
             direction,
@@ -82,7 +83,50 @@ pub(crate) fn to_peniko_gradient(
             flags,
             ..
         } => conic_gradient((angle, position, items, *flags), origin_rect, current_color),
+    };
+    let (GenericGradient::Linear {
+        color_interpolation_method,
+        ..
+    }
+    | GenericGradient::Radial {
+        color_interpolation_method,
+        ..
     }
+    | GenericGradient::Conic {
+        color_interpolation_method,
+        ..
+    }) = gradient;
+    (
+        peniko_gradient.interpolation_cs,
+        peniko_gradient.hue_direction,
+    ) = interpolation_space(color_interpolation_method);
+    (peniko_gradient, transform)
+}
+
+// https://drafts.csswg.org/css-color-4/#interpolation-space
+fn interpolation_space(method: &ColorInterpolationMethod) -> (ColorSpaceTag, HueDirection) {
+    let cs = match method.space {
+        ColorSpace::Srgb => ColorSpaceTag::Srgb,
+        ColorSpace::SrgbLinear => ColorSpaceTag::LinearSrgb,
+        ColorSpace::Lab => ColorSpaceTag::Lab,
+        ColorSpace::Lch => ColorSpaceTag::Lch,
+        ColorSpace::Oklab => ColorSpaceTag::Oklab,
+        ColorSpace::Oklch => ColorSpaceTag::Oklch,
+        ColorSpace::Hsl => ColorSpaceTag::Hsl,
+        ColorSpace::Hwb => ColorSpaceTag::Hwb,
+        ColorSpace::DisplayP3 => ColorSpaceTag::DisplayP3,
+        ColorSpace::XyzD50 => ColorSpaceTag::XyzD50,
+        ColorSpace::XyzD65 => ColorSpaceTag::XyzD65,
+        // Remaining wide-gamut spaces interpolate like the default
+        _ => ColorSpaceTag::Srgb,
+    };
+    let hue = match method.hue {
+        HueInterpolationMethod::Longer => HueDirection::Longer,
+        HueInterpolationMethod::Increasing => HueDirection::Increasing,
+        HueInterpolationMethod::Decreasing => HueDirection::Decreasing,
+        _ => HueDirection::Shorter,
+    };
+    (cs, hue)
 }
 
 fn linear_gradient(
@@ -183,26 +227,31 @@ fn radial_gradient(
             .px() as f64,
     );
 
+    // Distances from the center to the nearest/farthest vertical and horizontal edges
+    let (near_x, far_x) = (
+        width_px.min(rect.width() - width_px),
+        width_px.max(rect.width() - width_px),
+    );
+    let (near_y, far_y) = (
+        height_px.min(rect.height() - height_px),
+        height_px.max(rect.height() - height_px),
+    );
+    // `contain` and `cover` are legacy aliases for `closest-side` and `farthest-corner`
+    let normalize_extent = |extent: &ShapeExtent| match extent {
+        ShapeExtent::Contain => ShapeExtent::ClosestSide,
+        ShapeExtent::Cover => ShapeExtent::FarthestCorner,
+        other => *other,
+    };
+
     let gradient_scale: Option<Vec2> = match shape {
         GenericEndingShape::Circle(circle) => {
             let scale = match circle {
-                GenericCircle::Extent(extent) => match extent {
-                    ShapeExtent::FarthestSide => width_px
-                        .max(rect.width() - width_px)
-                        .max(height_px.max(rect.height() - height_px)),
-                    ShapeExtent::ClosestSide => width_px
-                        .min(rect.width() - width_px)
-                        .min(height_px.min(rect.height() - height_px)),
-                    ShapeExtent::FarthestCorner => {
-                        (width_px.max(rect.width() - width_px)
-                            + height_px.max(rect.height() - height_px))
-                            * 0.5_f64.sqrt()
-                    }
-                    ShapeExtent::ClosestCorner => {
-                        (width_px.min(rect.width() - width_px)
-                            + height_px.min(rect.height() - height_px))
-                            * 0.5_f64.sqrt()
-                    }
+                GenericCircle::Extent(extent) => match normalize_extent(extent) {
+                    ShapeExtent::FarthestSide => far_x.max(far_y),
+                    ShapeExtent::ClosestSide => near_x.min(near_y),
+                    // A circle through the corner: its radius is the distance to that corner
+                    ShapeExtent::FarthestCorner => far_x.hypot(far_y),
+                    ShapeExtent::ClosestCorner => near_x.hypot(near_y),
                     _ => 0.0,
                 },
                 GenericCircle::Radius(radius) => radius.0.px() as f64,
@@ -210,23 +259,18 @@ fn radial_gradient(
             Some(Vec2::new(scale, scale))
         }
         GenericEndingShape::Ellipse(ellipse) => match ellipse {
-            GenericEllipse::Extent(extent) => match extent {
-                ShapeExtent::FarthestCorner | ShapeExtent::FarthestSide => {
-                    let mut scale = Vec2::new(
-                        width_px.max(rect.width() - width_px),
-                        height_px.max(rect.height() - height_px),
-                    );
-                    if *extent == ShapeExtent::FarthestCorner {
+            GenericEllipse::Extent(extent) => match normalize_extent(extent) {
+                extent @ (ShapeExtent::FarthestCorner | ShapeExtent::FarthestSide) => {
+                    let mut scale = Vec2::new(far_x, far_y);
+                    // Same aspect ratio as the side ellipse, scaled to pass through the corner
+                    if extent == ShapeExtent::FarthestCorner {
                         scale *= 2.0_f64.sqrt();
                     }
                     Some(scale)
                 }
-                ShapeExtent::ClosestCorner | ShapeExtent::ClosestSide => {
-                    let mut scale = Vec2::new(
-                        width_px.min(rect.width() - width_px),
-                        height_px.min(rect.height() - height_px),
-                    );
-                    if *extent == ShapeExtent::ClosestCorner {
+                extent @ (ShapeExtent::ClosestCorner | ShapeExtent::ClosestSide) => {
+                    let mut scale = Vec2::new(near_x, near_y);
+                    if extent == ShapeExtent::ClosestCorner {
                         scale *= 2.0_f64.sqrt();
                     }
                     Some(scale)
@@ -336,32 +380,34 @@ fn resolve_color_stops<T>(
     repeating: bool,
     item_resolver: impl Fn(CSSPixelLength, &T) -> Option<f32>,
 ) -> (f32, f32) {
+    // Resolve every color stop up front: colors (including currentColor) against the element's
+    // computed color, and offsets with the missing ones filled in. Interpolation hints don't count
+    // as stops when spacing unpositioned stops.
+    let (colors, offsets): (Vec<DynamicColor>, Vec<Option<f32>>) = items
+        .iter()
+        .filter_map(|item| match item {
+            GenericGradientItem::SimpleColorStop(color) => Some((
+                color.resolve_to_absolute(current_color).as_dynamic_color(),
+                None,
+            )),
+            GenericGradientItem::ComplexColorStop { color, position } => Some((
+                color.resolve_to_absolute(current_color).as_dynamic_color(),
+                item_resolver(gradient_length, position),
+            )),
+            GenericGradientItem::InterpolationHint(_) => None,
+        })
+        .unzip();
+    let mut stops = colors.into_iter().zip(fixup_stop_offsets(&offsets));
+
     let mut hint: Option<f32> = None;
 
     for (idx, item) in items.iter().enumerate() {
         let (color, offset) = match item {
-            GenericGradientItem::SimpleColorStop(color) => {
-                let step = 1.0 / (items.len() as f32 - 1.0);
-                (
-                    color.resolve_to_absolute(current_color).as_dynamic_color(),
-                    step * idx as f32,
-                )
-            }
-            GenericGradientItem::ComplexColorStop { color, position } => {
-                let offset = item_resolver(gradient_length, position);
-                if let Some(offset) = offset {
-                    (
-                        color.resolve_to_absolute(current_color).as_dynamic_color(),
-                        offset,
-                    )
-                } else {
-                    continue;
-                }
-            }
             GenericGradientItem::InterpolationHint(position) => {
                 hint = item_resolver(gradient_length, position);
                 continue;
             }
+            _ => stops.next().unwrap(),
         };
 
         if idx == 0 && !repeating && offset != 0.0 {
@@ -370,7 +416,8 @@ fn resolve_color_stops<T>(
                 .push(peniko::ColorStop { color, offset: 0.0 });
         }
 
-        match hint {
+        // A hint only applies to the transition into the next stop
+        match hint.take() {
             None => gradient.stops.push(peniko::ColorStop { color, offset }),
             Some(hint) => {
                 let &last_stop = gradient.stops.last().unwrap();
@@ -473,6 +520,45 @@ fn resolve_color_stops<T>(
     }
 }
 
+/// Fill in unpositioned color stops per https://drafts.csswg.org/css-images-4/#color-stop-fixup:
+/// the first defaults to 0 and the last to 1, positions never go backwards, and runs of missing
+/// positions are spread evenly between their positioned neighbours.
+fn fixup_stop_offsets(offsets: &[Option<f32>]) -> Vec<f32> {
+    let mut fixed: Vec<Option<f32>> = offsets.to_vec();
+    if let Some(first @ None) = fixed.first_mut() {
+        *first = Some(0.0);
+    }
+    if let Some(last @ None) = fixed.last_mut() {
+        *last = Some(1.0);
+    }
+
+    let mut max_so_far = f32::MIN;
+    for offset in fixed.iter_mut().flatten() {
+        max_so_far = max_so_far.max(*offset);
+        *offset = max_so_far;
+    }
+
+    let mut result = Vec::with_capacity(fixed.len());
+    let mut idx = 0;
+    while idx < fixed.len() {
+        if let Some(offset) = fixed[idx] {
+            result.push(offset);
+            idx += 1;
+            continue;
+        }
+        // The first and last stops are always positioned, so a run has neighbours on both sides
+        let start = result[idx - 1];
+        let run_end = (idx..fixed.len()).find(|&i| fixed[i].is_some()).unwrap();
+        let end = fixed[run_end].unwrap();
+        let steps = (run_end - idx + 1) as f32;
+        for i in idx..run_end {
+            result.push(start + (end - start) * (i - idx + 1) as f32 / steps);
+        }
+        idx = run_end;
+    }
+    result
+}
+
 #[inline]
 fn resolve_angle_color_stops(
     current_color: &AbsoluteColor,
@@ -516,3 +602,76 @@ fn get_translation(
                 .px() as f64,
     )
 }
+
+#[test]
+fn stop_offsets_follow_css_fixup() {
+    assert_eq!(fixup_stop_offsets(&[None, None]), [0.0, 1.0]);
+    assert_eq!(fixup_stop_offsets(&[None, None, None]), [0.0, 0.5, 1.0]);
+    assert_eq!(
+        fixup_stop_offsets(&[None, Some(0.2), None, None]),
+        [0.0, 0.2, 0.6, 1.0]
+    );
+    // Positions never go backwards
+    assert_eq!(
+        fixup_stop_offsets(&[Some(0.5), Some(0.3), None]),
+        [0.5, 0.5, 1.0]
+    );
+    assert_eq!(fixup_stop_offsets(&[None]), [0.0]);
+}
+
+#[test]
+fn current_color_stops_resolve_against_the_element_color() {
+    let red = AbsoluteColor::srgb_legacy(255, 0, 0, 1.0);
+    let blue = AbsoluteColor::srgb_legacy(0, 0, 255, 1.0);
+    // linear-gradient(currentColor, blue) with color: red
+    let items: [GradientItem<LengthPercentage>; 2] = [
+        GenericGradientItem::SimpleColorStop(GenericColor::CurrentColor),
+        GenericGradientItem::SimpleColorStop(GenericColor::Absolute(blue)),
+    ];
+    let mut gradient = Gradient::new_linear((0.0, 0.0), (0.0, 100.0));
+    resolve_length_color_stops(
+        &red,
+        &items,
+        CSSPixelLength::new(100.0),
+        &mut gradient,
+        false,
+    );
+
+    let stops: Vec<(f32, [f32; 4])> = gradient
+        .stops
+        .iter()
+        .map(|stop| (stop.offset, stop.color.components))
+        .collect();
+    assert_eq!(
+        stops,
+        [(0.0, [1.0, 0.0, 0.0, 1.0]), (1.0, [0.0, 0.0, 1.0, 1.0])]
+    );
+}
+
+#[test]
+fn interpolation_method_selects_the_peniko_color_space() {
+    let method = |space| ColorInterpolationMethod {
+        space,
+        hue: HueInterpolationMethod::Shorter,
+    };
+    assert_eq!(
+        interpolation_space(&method(ColorSpace::Srgb)).0,
+        ColorSpaceTag::Srgb
+    );
+    assert_eq!(
+        interpolation_space(&method(ColorSpace::SrgbLinear)).0,
+        ColorSpaceTag::LinearSrgb
+    );
+    assert_eq!(
+        interpolation_space(&method(ColorSpace::Oklab)).0,
+        ColorSpaceTag::Oklab
+    );
+    let longer = ColorInterpolationMethod {
+        space: ColorSpace::Oklch,
+        hue: HueInterpolationMethod::Longer,
+    };
+    assert_eq!(
+        interpolation_space(&longer),
+        (ColorSpaceTag::Oklch, HueDirection::Longer)
+    );
+}
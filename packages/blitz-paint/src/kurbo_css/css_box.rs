@@ -34,10 +34,13 @@ pub struct CssBox {
     pub padding_box: Rect,
     pub content_box: Rect,
     pub outline_box: Rect,
+    /// The border box outset by `outline-offset`: the inner edge of the outline
+    pub outline_offset_box: Rect,
 
     pub padding_width: Insets,
     pub border_width: Insets,
     pub outline_width: f64,
+    pub outline_offset: f64,
 
     pub border_radii: NonUniformRoundedRectRadii,
 }
@@ -48,11 +51,13 @@ impl CssBox {
         border: Insets,
         padding: Insets,
         outline_width: f64,
+        outline_offset: f64,
         mut border_radii: NonUniformRoundedRectRadii,
     ) -> Self {
         let padding_box = border_box - border;
         let content_box = padding_box - padding;
-        let outline_box = border_box.inset(outline_width);
+        let outline_offset_box = border_box.inset(outline_offset);
+        let outline_box = outline_offset_box.inset(outline_width);
 
         // Correct the border radii if they are too big if two border radii would intersect, then we need to shrink
         // ALL border radii by the same factor such that they do not
@@ -79,7 +84,9 @@ impl CssBox {
             border_box,
             content_box,
             outline_box,
+            outline_offset_box,
             outline_width,
+            outline_offset,
             padding_width: padding,
             border_width: border,
             border_radii,
@@ -139,16 +146,51 @@ impl CssBox {
         path
     }
 
+    /// The band of the border between `start` and `end`, given as fractions of each border width
+    /// (0.0 = outer edge, 1.0 = inner edge), as a box whose border is just that band. Used to paint
+    /// multi-part border styles (`double`, `groove`, `ridge`) with `border_edge_shape`.
+    pub fn border_band(&self, start: f64, end: f64) -> CssBox {
+        let scale = |insets: Insets, f: f64| Insets {
+            x0: insets.x0 * f,
+            y0: insets.y0 * f,
+            x1: insets.x1 * f,
+            y1: insets.y1 * f,
+        };
+        let outer = scale(self.border_width, start);
+        let shrink = |radii: Vec2, corner: Corner| {
+            let inset = get_corner_insets(outer, corner);
+            Vec2::new((radii.x - inset.x).max(0.0), (radii.y - inset.y).max(0.0))
+        };
+        let radii = self.border_radii;
+        CssBox::new(
+            self.border_box - outer,
+            scale(self.border_width, end - start),
+            Insets::ZERO,
+            0.0,
+            0.0,
+            NonUniformRoundedRectRadii {
+                top_left: shrink(radii.top_left, Corner::TopLeft),
+                top_right: shrink(radii.top_right, Corner::TopRight),
+                bottom_right: shrink(radii.bottom_right, Corner::BottomRight),
+                bottom_left: shrink(radii.bottom_left, Corner::BottomLeft),
+            },
+        )
+    }
+
     /// Construct a bezpath drawing the outline
     pub fn outline(&self) -> BezPath {
         let mut path = BezPath::new();
 
         // TODO: this has been known to produce quirky outputs with hugely rounded edges
         self.shape(&mut path, CssBoxKind::OutlineBox, Direction::Clockwise);
-        path.move_to(self.corner(Corner::TopLeft, CssBoxKind::BorderBox));
+        path.move_to(self.corner(Corner::TopLeft, CssBoxKind::OutlineOffsetBox));
 
-        self.shape(&mut path, CssBoxKind::BorderBox, Direction::Anticlockwise);
-        path.move_to(self.corner(Corner::TopLeft, CssBoxKind::BorderBox));
+        self.shape(
+            &mut path,
+            CssBoxKind::OutlineOffsetBox,
+            Direction::Anticlockwise,
+        );
+        path.move_to(self.corner(Corner::TopLeft, CssBoxKind::OutlineOffsetBox));
 
         path
     }
@@ -230,6 +272,7 @@ impl CssBox {
     fn corner(&self, corner: Corner, css_box: CssBoxKind) -> Point {
         let Rect { x0, y0, x1, y1 } = match css_box {
             CssBoxKind::OutlineBox => self.outline_box,
+            CssBoxKind::OutlineOffsetBox => self.outline_offset_box,
             CssBoxKind::BorderBox => self.border_box,
             CssBoxKind::PaddingBox => self.padding_box,
             CssBoxKind::ContentBox => self.content_box,
@@ -410,8 +453,19 @@ impl CssBox {
             return true;
         }
 
+        // Outline corners follow the border radius grown by the offset (and width); a negative
+        // offset can shrink them down to a sharp corner
+        let outline_outset = match side {
+            OutlineBox => Some(self.outline_offset + self.outline_width),
+            OutlineOffsetBox => Some(self.outline_offset),
+            _ => None,
+        };
+        if let Some(outset) = outline_outset {
+            return (corner_radii.x + outset <= 0.0) | (corner_radii.y + outset <= 0.0);
+        }
+
         let css_box: Insets = match side {
-            OutlineBox => return false,
+            OutlineBox | OutlineOffsetBox => unreachable!(),
             BorderBox => return false,
             PaddingBox => self.border_width,
             ContentBox => add_insets(self.border_width, self.padding_width),
@@ -459,7 +513,11 @@ impl CssBox {
 
         let radii: Vec2 = match side {
             BorderBox => corner_radii,
-            OutlineBox => corner_radii + Vec2::new(self.outline_width, self.outline_width),
+            OutlineBox => {
+                let outset = self.outline_offset + self.outline_width;
+                corner_radii + Vec2::new(outset, outset)
+            }
+            OutlineOffsetBox => corner_radii + Vec2::new(self.outline_offset, self.outline_offset),
             PaddingBox => corner_radii - get_corner_insets(*border_width, corner),
             ContentBox => {
                 corner_radii - get_corner_insets(add_insets(*border_width, *padding_width), corner)
@@ -546,3 +604,71 @@ fn should_solve_properly() {
     // 0.643501
     dbg!(start_angle(4.0, 1.0, Vec2 { x: 1.0, y: 2.0 }));
 }
+
+#[test]
+fn outline_follows_offset_and_radii() {
+    let radii = NonUniformRoundedRectRadii {
+        top_left: Vec2::new(8.0, 8.0),
+        top_right: Vec2::new(8.0, 8.0),
+        bottom_right: Vec2::ZERO,
+        bottom_left: Vec2::ZERO,
+    };
+    let frame = CssBox::new(
+        Rect::new(0.0, 0.0, 100.0, 40.0),
+        Insets::ZERO,
+        Insets::ZERO,
+        2.0,
+        3.0,
+        radii,
+    );
+    assert_eq!(frame.outline_offset_box, Rect::new(-3.0, -3.0, 103.0, 43.0));
+    assert_eq!(frame.outline_box, Rect::new(-5.0, -5.0, 105.0, 45.0));
+
+    // Rounded corners stay concentric with the border radius, grown by the offset (and width)
+    let inner = frame.ellipse(Corner::TopLeft, CssBoxKind::OutlineOffsetBox);
+    let outer = frame.ellipse(Corner::TopLeft, CssBoxKind::OutlineBox);
+    assert_eq!(inner.center(), Point::new(8.0, 8.0));
+    assert_eq!(inner.radii(), Vec2::new(11.0, 11.0));
+    assert_eq!(outer.radii(), Vec2::new(13.0, 13.0));
+    assert!(frame.is_sharp(Corner::BottomLeft, CssBoxKind::OutlineBox));
+
+    // A negative offset insets the outline and can collapse small radii to sharp corners
+    let inset = CssBox::new(
+        Rect::new(0.0, 0.0, 100.0, 40.0),
+        Insets::ZERO,
+        Insets::ZERO,
+        2.0,
+        -10.0,
+        radii,
+    );
+    assert_eq!(inset.outline_offset_box, Rect::new(10.0, 10.0, 90.0, 30.0));
+    assert!(inset.is_sharp(Corner::TopLeft, CssBoxKind::OutlineOffsetBox));
+}
+
+#[test]
+fn border_bands_split_the_border_width() {
+    let radii = NonUniformRoundedRectRadii {
+        top_left: Vec2::new(10.0, 10.0),
+        ..Default::default()
+    };
+    let frame = CssBox::new(
+        Rect::new(0.0, 0.0, 100.0, 40.0),
+        Insets::new(6.0, 3.0, 6.0, 3.0),
+        Insets::ZERO,
+        0.0,
+        0.0,
+        radii,
+    );
+
+    // Outer and inner halves, as for groove and ridge
+    let outer = frame.border_band(0.0, 0.5);
+    assert_eq!(outer.border_box, frame.border_box);
+    assert_eq!(outer.padding_box, Rect::new(3.0, 1.5, 97.0, 38.5));
+    let inner = frame.border_band(0.5, 1.0);
+    assert_eq!(inner.border_box, Rect::new(3.0, 1.5, 97.0, 38.5));
+    assert_eq!(inner.padding_box, frame.padding_box);
+
+    // Radii shrink with the band so the curves stay concentric
+    assert_eq!(inner.border_radii.top_left, Vec2::new(7.0, 8.5));
+    assert_eq!(inner.border_radii.top_right, Vec2::ZERO);
+}
@@ -34,6 +34,7 @@ pub(crate) enum Corner {
 #[allow(clippy::enum_variant_names, reason = "Use CSS standard terminology")]
 pub(crate) enum CssBoxKind {
     OutlineBox,
+    OutlineOffsetBox,
     BorderBox,
     PaddingBox,
     ContentBox,
@@ -6,12 +6,16 @@ mod debug_overlay;
 mod gradient;
 mod kurbo_css;
 mod layers;
+pub mod recording;
 mod render;
 mod sizing;
 mod text;
 
+use std::cell::Cell;
+
 use anyrender::PaintScene;
 use blitz_dom::BaseDocument;
+use kurbo::Rect;
 use layers::reset_layer_stats;
 use render::BlitzDomPainter;
 
@@ -40,6 +44,12 @@ pub fn paint_scene(
         width,
         height,
         devtools,
+        scrollport: Cell::new(Rect::new(
+            0.0,
+            0.0,
+            width as f64 / scale,
+            height as f64 / scale,
+        )),
     };
     generator.paint_scene(scene);
 
@@ -50,3 +60,13 @@ pub fn paint_scene(
     //     CLIPS_WANTED.load(atomic::Ordering::SeqCst)
     // );
 }
+
+/// The background color of the document's canvas, as painted by [`paint_scene`].
+///
+/// This is the `<html>` element's background color or, if that is transparent, the `<body>`
+/// element's background color. Renderers can use it to clear their surface so that areas
+/// outside of the painted scene match the document. Returns `None` if styles have not been
+/// resolved yet.
+pub fn root_background_color(dom: &BaseDocument) -> Option<peniko::Color> {
+    render::root_background_color(dom)
+}
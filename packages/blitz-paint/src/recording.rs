@@ -0,0 +1,326 @@
+//! A [`PaintScene`] that records what is painted into it, for asserting on the output of [`paint_scene`]
+//! without a GPU. [`paint_to_commands`] paints a resolved document and returns the recorded commands.
+
+use anyrender::{Glyph, NormalizedCoord, Paint, PaintScene};
+use blitz_dom::BaseDocument;
+use kurbo::{Affine, BezPath, Rect, Shape, Stroke};
+use peniko::{BlendMode, Brush, BrushRef, Color, Fill, Font, StyleRef};
+
+use crate::paint_scene;
+
+// Tolerance used to flatten non-path shapes into paths when recording
+const TOLERANCE: f64 = 0.1;
+
+/// One call made on a [`RecordingScene`]. Shapes keep their own coordinates; `transform` maps them to
+/// device pixels (see [`SceneCommand::bounds`]).
+#[derive(Clone, Debug)]
+pub enum SceneCommand {
+    PushLayer {
+        blend: BlendMode,
+        alpha: f32,
+        transform: Affine,
+        clip: BezPath,
+    },
+    PopLayer,
+    Fill {
+        style: Fill,
+        transform: Affine,
+        /// `None` for a backend-specific custom paint
+        brush: Option<Brush>,
+        brush_transform: Option<Affine>,
+        shape: BezPath,
+    },
+    Stroke {
+        style: Stroke,
+        transform: Affine,
+        brush: Brush,
+        brush_transform: Option<Affine>,
+        shape: BezPath,
+    },
+    Glyphs {
+        font_family: String,
+        font_size: f32,
+        font_weight: u16,
+        brush: Brush,
+        brush_alpha: f32,
+        transform: Affine,
+        glyphs: Vec<Glyph>,
+    },
+    BoxShadow {
+        transform: Affine,
+        /// The shadowed rect, or the unshadowed hole of an inset shadow
+        rect: Rect,
+        color: Color,
+        radius: f64,
+        std_dev: f64,
+        inset: bool,
+    },
+}
+
+impl SceneCommand {
+    /// The device-space bounding box of what the command draws (or clips to). `None` for
+    /// [`PopLayer`](Self::PopLayer) and glyph runs.
+    pub fn bounds(&self) -> Option<Rect> {
+        match self {
+            Self::PushLayer {
+                transform,
+                clip: shape,
+                ..
+            }
+            | Self::Fill {
+                transform, shape, ..
+            }
+            | Self::Stroke {
+                transform, shape, ..
+            } => Some((*transform * shape.clone()).bounding_box()),
+            Self::BoxShadow {
+                transform, rect, ..
+            } => Some(transform.transform_rect_bbox(*rect)),
+            Self::PopLayer | Self::Glyphs { .. } => None,
+        }
+    }
+
+    /// The color of a solid fill, stroke or glyph run (or a box shadow's color)
+    pub fn solid_color(&self) -> Option<Color> {
+        match self {
+            Self::Fill {
+                brush: Some(Brush::Solid(color)),
+                ..
+            }
+            | Self::Stroke {
+                brush: Brush::Solid(color),
+                ..
+            }
+            | Self::Glyphs {
+                brush: Brush::Solid(color),
+                ..
+            }
+            | Self::BoxShadow { color, .. } => Some(*color),
+            _ => None,
+        }
+    }
+}
+
+/// A [`PaintScene`] that keeps a list of the commands painted into it instead of drawing them
+#[derive(Clone, Debug, Default)]
+pub struct RecordingScene {
+    pub commands: Vec<SceneCommand>,
+}
+
+impl PaintScene for RecordingScene {
+    fn reset(&mut self) {
+        self.commands.clear();
+    }
+
+    fn push_layer(
+        &mut self,
+        blend: impl Into<BlendMode>,
+        alpha: f32,
+        transform: Affine,
+        clip: &impl Shape,
+    ) {
+        self.commands.push(SceneCommand::PushLayer {
+            blend: blend.into(),
+            alpha,
+            transform,
+            clip: clip.into_path(TOLERANCE),
+        });
+    }
+
+    fn pop_layer(&mut self) {
+        self.commands.push(SceneCommand::PopLayer);
+    }
+
+    fn stroke<'a>(
+        &mut self,
+        style: &Stroke,
+        transform: Affine,
+        brush: impl Into<BrushRef<'a>>,
+        brush_transform: Option<Affine>,
+        shape: &impl Shape,
+    ) {
+        self.commands.push(SceneCommand::Stroke {
+            style: style.clone(),
+            transform,
+            brush: brush.into().to_owned(),
+            brush_transform,
+            shape: shape.into_path(TOLERANCE),
+        });
+    }
+
+    fn fill<'a>(
+        &mut self,
+        style: Fill,
+        transform: Affine,
+        brush: impl Into<Paint<'a>>,
+        brush_transform: Option<Affine>,
+        shape: &impl Shape,
+    ) {
+        let brush = match brush.into() {
+            Paint::Solid(color) => Some(Brush::Solid(color)),
+            Paint::Gradient(gradient) => Some(Brush::Gradient(gradient.clone())),
+            Paint::Image(image) => Some(Brush::Image(image.clone())),
+            Paint::Custom(_) => None,
+        };
+        self.commands.push(SceneCommand::Fill {
+            style,
+            transform,
+            brush,
+            brush_transform,
+            shape: shape.into_path(TOLERANCE),
+        });
+    }
+
+    fn draw_glyphs<'a, 's: 'a>(
+        &'s mut self,
+        _font: &'a Font,
+        font_family: &str,
+        font_size: f32,
+        font_weight: u16,
+        _hint: bool,
+        _normalized_coords: &'a [NormalizedCoord],
+        _style: impl Into<StyleRef<'a>>,
+        brush: impl Into<BrushRef<'a>>,
+        brush_alpha: f32,
+        transform: Affine,
+        _glyph_transform: Option<Affine>,
+        glyphs: impl Iterator<Item = Glyph>,
+    ) {
+        self.commands.push(SceneCommand::Glyphs {
+            font_family: font_family.to_string(),
+            font_size,
+            font_weight,
+            brush: brush.into().to_owned(),
+            brush_alpha,
+            transform,
+            glyphs: glyphs.collect(),
+        });
+    }
+
+    fn draw_box_shadow(
+        &mut self,
+        transform: Affine,
+        rect: Rect,
+        brush: Color,
+        radius: f64,
+        std_dev: f64,
+    ) {
+        self.commands.push(SceneCommand::BoxShadow {
+            transform,
+            rect,
+            color: brush,
+            radius,
+            std_dev,
+            inset: false,
+        });
+    }
+
+    fn draw_inset_box_shadow(
+        &mut self,
+        transform: Affine,
+        rect: Rect,
+        brush: Color,
+        radius: f64,
+        std_dev: f64,
+    ) {
+        self.commands.push(SceneCommand::BoxShadow {
+            transform,
+            rect,
+            color: brush,
+            radius,
+            std_dev,
+            inset: true,
+        });
+    }
+}
+
+/// Paint `dom` (whose styles and layout must already be resolved, as for [`paint_scene`]) into a
+/// [`RecordingScene`] and return the recorded commands
+pub fn paint_to_commands(
+    dom: &BaseDocument,
+    scale: f64,
+    width: u32,
+    height: u32,
+) -> Vec<SceneCommand> {
+    let mut scene = RecordingScene::default();
+    paint_scene(&mut scene, dom, scale, width, height);
+    scene.commands
+}
+
+// Parse and lay out `html` in a `width` x `height` window at scale 1, then paint it
+#[cfg(test)]
+fn paint_html(html: &str, width: u32, height: u32) -> Vec<SceneCommand> {
+    use blitz_dom::DocumentConfig;
+    use blitz_html::HtmlDocument;
+    use blitz_traits::shell::{ColorScheme, Viewport};
+
+    let mut document = HtmlDocument::from_html(html, DocumentConfig::default());
+    document
+        .as_mut()
+        .set_viewport(Viewport::new(width, height, 1.0, ColorScheme::Light));
+    document.as_mut().resolve();
+    paint_to_commands(document.as_ref(), 1.0, width, height)
+}
+
+#[test]
+fn background_color_paints_a_fill_of_the_border_box() {
+    let commands = paint_html(
+        r#"<body style="margin: 0"><div style="background: red; width: 100px; height: 50px"></div></body>"#,
+        800,
+        600,
+    );
+    let red_fills: Vec<Rect> = commands
+        .iter()
+        .filter(|cmd| matches!(cmd, SceneCommand::Fill { .. }))
+        .filter(|cmd| cmd.solid_color() == Some(Color::from_rgb8(255, 0, 0)))
+        .filter_map(SceneCommand::bounds)
+        .collect();
+    assert_eq!(red_fills, [Rect::new(0.0, 0.0, 100.0, 50.0)]);
+}
+
+#[test]
+fn inset_box_shadows_are_drawn_with_draw_inset_box_shadow() {
+    let commands = paint_html(
+        r#"<body style="margin: 0"><div style="width: 100px; height: 50px; box-shadow: inset 0 0 0 5px black"></div></body>"#,
+        800,
+        600,
+    );
+    let shadows: Vec<(Rect, f64, bool)> = commands
+        .iter()
+        .filter_map(|cmd| match cmd {
+            SceneCommand::BoxShadow {
+                rect,
+                std_dev,
+                inset,
+                ..
+            } => Some((*rect, *std_dev, *inset)),
+            _ => None,
+        })
+        .collect();
+    // An unblurred inset shadow is still inset, with the spread shrinking its hole
+    assert_eq!(shadows, [(Rect::new(5.0, 5.0, 95.0, 45.0), 0.0, true)]);
+}
+
+#[test]
+fn premultiplied_images_are_drawn_straight_by_default() {
+    use anyrender::ImageAlphaType;
+    use std::sync::Arc;
+
+    // 50% red: premultiplied (128, 0, 0, 128) is straight (255, 0, 0, 128)
+    let image = peniko::Image::new(
+        peniko::Blob::new(Arc::new(vec![128u8, 0, 0, 128, 0, 0, 0, 0])),
+        peniko::ImageFormat::Rgba8,
+        2,
+        1,
+    );
+    let mut scene = RecordingScene::default();
+    scene.draw_image_with_alpha_type(&image, Affine::IDENTITY, ImageAlphaType::AlphaPremultiplied);
+    let Some(SceneCommand::Fill {
+        brush: Some(Brush::Image(drawn)),
+        ..
+    }) = scene.commands.first()
+    else {
+        panic!("expected an image fill");
+    };
+    assert_eq!(drawn.data.data(), [255, 0, 0, 128, 0, 0, 0, 0]);
+}
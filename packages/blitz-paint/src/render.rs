@@ -1,19 +1,23 @@
+mod backdrop_filter;
 mod background;
+mod border_image;
 mod box_shadow;
+mod forced_colors;
 mod form_controls;
 
+use std::cell::Cell;
 use std::sync::Arc;
 
 use super::kurbo_css::{CssBox, Edge};
 use crate::color::{Color, ToColorColor};
 use crate::debug_overlay::render_debug_overlay;
 use crate::kurbo_css::NonUniformRoundedRectRadii;
-use crate::layers::maybe_with_layer;
+use crate::layers::{maybe_pop_layer, maybe_push_layer, maybe_with_layer};
 use crate::sizing::compute_object_fit;
 use anyrender::{CustomPaint, Paint, PaintScene};
 use blitz_dom::node::{
-    ListItemLayout, ListItemLayoutPosition, Marker, NodeData, RasterImageData, TextInputData,
-    TextNodeData,
+    ListItemLayout, ListItemLayoutPosition, Marker, MarkerShape, NodeData, RasterImageData,
+    TextInputData, TextNodeData,
 };
 use blitz_dom::{BaseDocument, ElementData, Node, local_name};
 use blitz_traits::devtools::DevtoolSettings;
@@ -27,16 +31,57 @@ use style::{
         style_structs::Font,
     },
     values::{
-        computed::{CSSPixelLength, Overflow},
+        computed::{CSSPixelLength, LengthPercentage, Overflow, Percentage},
         specified::{BorderStyle, OutlineStyle, image::ImageRendering},
     },
 };
 
 use kurbo::{self, Affine, Insets, Point, Rect, Stroke, Vec2};
+use parley::PositionedLayoutItem;
 use peniko::{self, Fill};
-use style::values::generics::color::GenericColor;
+use style::properties::longhands::position::computed_value::T as Position;
+use style::values::generics::color::{GenericColor, GenericColorOrAuto};
+use style::values::generics::position::GenericInset;
 use taffy::Layout;
 
+type Inset = GenericInset<Percentage, LengthPercentage>;
+
+/// Resolve the background color of the canvas: the root element's background, or the `<body>`
+/// background propagated to the canvas when the root element's background is transparent.
+///
+/// Returns `None` if styles have not been resolved yet. When forced colors are active, the canvas is
+/// always the scheme's `canvas` color.
+pub(crate) fn root_background_color(dom: &BaseDocument) -> Option<Color> {
+    if let Some(scheme) = dom.forced_colors() {
+        return Some(scheme.canvas);
+    }
+    let root_element = dom.try_root_element()?;
+    let html_color = root_element
+        .primary_styles()
+        .map(|s| s.clone_background_color())
+        .unwrap_or(GenericColor::TRANSPARENT_BLACK);
+    let color = if html_color == GenericColor::TRANSPARENT_BLACK {
+        root_element
+            .children
+            .iter()
+            .find_map(|id| {
+                dom.get_node(*id)
+                    .filter(|node| node.data.is_element_with_tag_name(&local_name!("body")))
+            })
+            .and_then(|body| body.primary_styles())
+            .map(|style| {
+                let current_color = style.clone_color();
+                style
+                    .clone_background_color()
+                    .resolve_to_absolute(&current_color)
+            })
+    } else {
+        let current_color = root_element.primary_styles()?.clone_color();
+        Some(html_color.resolve_to_absolute(&current_color))
+    };
+    color.map(|c| c.as_srgb_color())
+}
+
 /// A short-lived struct which holds a bunch of parameters for rendering a scene so
 /// that we don't have to pass them down as parameters
 pub struct BlitzDomPainter<'dom> {
@@ -46,6 +91,9 @@ pub struct BlitzDomPainter<'dom> {
     pub(crate) width: u32,
     pub(crate) height: u32,
     pub(crate) devtools: DevtoolSettings,
+    /// Padding box of the nearest scroll container (initially the viewport) in CSS pixels, in the same
+    /// coordinates as element positions; `position: sticky` boxes are clamped inside it
+    pub(crate) scrollport: Cell<Rect>,
 }
 
 impl BlitzDomPainter<'_> {
@@ -76,36 +124,9 @@ impl BlitzDomPainter<'_> {
         let bg_width = (self.width as f32).max(root_element.final_layout.size.width);
         let bg_height = (self.height as f32).max(root_element.final_layout.size.height);
 
-        let background_color = {
-            let html_color = root_element
-                .primary_styles()
-                .map(|s| s.clone_background_color())
-                .unwrap_or(GenericColor::TRANSPARENT_BLACK);
-            if html_color == GenericColor::TRANSPARENT_BLACK {
-                root_element
-                    .children
-                    .iter()
-                    .find_map(|id| {
-                        self.dom
-                            .as_ref()
-                            .get_node(*id)
-                            .filter(|node| node.data.is_element_with_tag_name(&local_name!("body")))
-                    })
-                    .and_then(|body| body.primary_styles())
-                    .map(|style| {
-                        let current_color = style.clone_color();
-                        style
-                            .clone_background_color()
-                            .resolve_to_absolute(&current_color)
-                    })
-            } else {
-                let current_color = root_element.primary_styles().unwrap().clone_color();
-                Some(html_color.resolve_to_absolute(&current_color))
-            }
-        };
+        let background_color = root_background_color(self.dom.as_ref());
 
         if let Some(bg_color) = background_color {
-            let bg_color = bg_color.as_srgb_color();
             let rect = Rect::from_origin_size((0.0, 0.0), (bg_width as f64, bg_height as f64));
             scene.fill(Fill::NonZero, Affine::IDENTITY, bg_color, None, &rect);
         }
@@ -167,7 +188,8 @@ impl BlitzDomPainter<'_> {
             return;
         }
 
-        // We can't fully support opacity yet, but we can hide elements with opacity 0
+        // Elements with opacity 0 are skipped entirely; fractional opacity composites the element and
+        // its subtree as a group (see the opacity layer below)
         let opacity = node.primary_styles().unwrap().get_effects().opacity;
         if opacity == 0.0 {
             return;
@@ -182,10 +204,23 @@ impl BlitzDomPainter<'_> {
             .element_data()
             .and_then(|e| e.raster_image_data())
             .is_some();
+        // Text inputs scroll their text to keep the caret in view, so whatever runs past the box is clipped
+        let is_text_input = node
+            .element_data()
+            .and_then(|e| e.text_input_data())
+            .is_some();
         let should_clip = is_image
+            || is_text_input
             || !matches!(overflow_x, Overflow::Visible)
             || !matches!(overflow_y, Overflow::Visible);
 
+        // Sticky boxes keep their in-flow layout and are shifted at paint time to stay in the scrollport
+        let location = if matches!(styles.get_box().position, Position::Sticky) {
+            location + self.sticky_offset(node, location)
+        } else {
+            location
+        };
+
         // Apply padding/border offset to inline root
         let (layout, box_position) = self.node_position(node_id, location);
         let taffy::Layout {
@@ -218,17 +253,40 @@ impl BlitzDomPainter<'_> {
             return;
         }
 
+        // The group opacity layer wraps the element's own box as well as its children, and only clips to
+        // the viewport so overflowing content stays visible; overflow clipping is a separate layer inside it
+        let viewport = Rect::new(0.0, 0.0, self.width as f64, self.height as f64);
+        let opacity_layer =
+            maybe_push_layer(scene, has_opacity, opacity, Affine::IDENTITY, &viewport);
+
         let mut cx = self.element_cx(node, layout, box_position);
         cx.draw_outline(scene);
         cx.draw_outset_box_shadow(scene);
+        cx.draw_backdrop_filter(scene);
         cx.draw_background(scene);
         cx.draw_border(scene);
 
-        // TODO: allow layers with opacity to be unclipped (overflow: visible)
-        let wants_layer = should_clip | has_opacity;
         let clip = &cx.frame.padding_box_path();
 
-        maybe_with_layer(scene, wants_layer, opacity, cx.transform, clip, |scene| {
+        // Scroll containers become the scrollport of their sticky descendants
+        let is_scroll_container = [overflow_x, overflow_y].iter().any(|overflow| {
+            matches!(
+                overflow,
+                Overflow::Hidden | Overflow::Scroll | Overflow::Auto
+            )
+        });
+        let outer_scrollport = self.scrollport.get();
+        if is_scroll_container {
+            let border = node.final_layout.border.map(f64::from);
+            self.scrollport.set(Rect::new(
+                box_position.x + border.left,
+                box_position.y + border.top,
+                box_position.x + size.width as f64 - border.right,
+                box_position.y + size.height as f64 - border.bottom,
+            ));
+        }
+
+        maybe_with_layer(scene, should_clip, 1.0, cx.transform, clip, |scene| {
             cx.draw_inset_box_shadow(scene);
             cx.stroke_devtools(scene);
 
@@ -256,6 +314,80 @@ impl BlitzDomPainter<'_> {
             cx.draw_marker(scene, content_position);
             cx.draw_children(scene);
         });
+        self.scrollport.set(outer_scrollport);
+
+        maybe_pop_layer(scene, opacity_layer);
+    }
+
+    /// How far the `position: sticky` element `node` moves from its in-flow position, given `location`, the
+    /// (scrolled) position of its parent. The containing block is the parent's content box, or its whole
+    /// scrollable content when the parent is itself the scroll container.
+    fn sticky_offset(&self, node: &Node, location: Point) -> Vec2 {
+        let tree = self.dom.as_ref().tree();
+        let (Some(parent_id), Some(styles)) = (node.layout_parent.get(), node.primary_styles())
+        else {
+            return Vec2::ZERO;
+        };
+        let parent = &tree[parent_id];
+        let parent_scrolls = parent.primary_styles().is_some_and(|s| {
+            !matches!(s.get_box().overflow_y, Overflow::Visible | Overflow::Clip)
+                || !matches!(s.get_box().overflow_x, Overflow::Visible | Overflow::Clip)
+        });
+        let parent_layout = parent.final_layout;
+        let pb = (parent_layout.padding + parent_layout.border).map(f64::from);
+        let (width, height) = if parent_scrolls {
+            (
+                parent_layout
+                    .content_size
+                    .width
+                    .max(parent_layout.size.width) as f64,
+                parent_layout
+                    .content_size
+                    .height
+                    .max(parent_layout.size.height) as f64,
+            )
+        } else {
+            (
+                parent_layout.size.width as f64,
+                parent_layout.size.height as f64,
+            )
+        };
+        let container = Rect::new(
+            location.x + pb.left,
+            location.y + pb.top,
+            location.x + width - pb.right,
+            location.y + height - pb.bottom,
+        );
+
+        let layout = self.layout(node.id);
+        let border_box = Rect::from_origin_size(
+            location + Vec2::new(layout.location.x as f64, layout.location.y as f64),
+            (layout.size.width as f64, layout.size.height as f64),
+        );
+        let scrollport = self.scrollport.get();
+        let resolve = |inset: &Inset, basis: f64| match inset {
+            GenericInset::LengthPercentage(lp) => {
+                Some(lp.resolve(CSSPixelLength::new(basis as f32)).px() as f64)
+            }
+            _ => None,
+        };
+        let pos = styles.get_position();
+        Vec2::new(
+            sticky_axis_offset(
+                (border_box.x0, border_box.x1),
+                (container.x0, container.x1),
+                (scrollport.x0, scrollport.x1),
+                resolve(&pos.left, scrollport.width()),
+                resolve(&pos.right, scrollport.width()),
+            ),
+            sticky_axis_offset(
+                (border_box.y0, border_box.y1),
+                (container.y0, container.y1),
+                (scrollport.y0, scrollport.y1),
+                resolve(&pos.top, scrollport.height()),
+                resolve(&pos.bottom, scrollport.height()),
+            ),
+        )
     }
 
     fn render_node(&self, scene: &mut impl PaintScene, node_id: usize, location: Point) {
@@ -311,12 +443,21 @@ impl BlitzDomPainter<'_> {
             .transform
             .to_transform_3d_matrix(None)
             .unwrap_or((Transform3D::default(), false));
-        if !has_3d {
+        let kurbo_transform = if *has_3d {
+            // Flatten onto the z=0 plane. Affine-equivalent 3D transforms (rotateZ, translateZ, ...) are
+            // exact; perspective is approximated by dropping its terms.
+            #[cfg(feature = "tracing")]
+            if !is_affine_2d(t) {
+                tracing::debug!("perspective transform flattened to 2D on node {}", node.id);
+            }
+            flatten_transform(t)
+        } else {
             // See: https://drafts.csswg.org/css-transforms-2/#two-dimensional-subset
             // And https://docs.rs/kurbo/latest/kurbo/struct.Affine.html#method.new
-            let kurbo_transform =
-                Affine::new([t.m11, t.m12, t.m21, t.m22, t.m41, t.m42].map(|v| v as f64));
+            Affine::new([t.m11, t.m12, t.m21, t.m22, t.m41, t.m42].map(|v| v as f64))
+        };
 
+        if kurbo_transform != Affine::IDENTITY {
             // Apply the transform origin by:
             //   - Translating by the origin offset
             //   - Applying our transform
@@ -329,7 +470,7 @@ impl BlitzDomPainter<'_> {
                     .px() as f64,
                 y: transform_origin
                     .vertical
-                    .resolve(CSSPixelLength::new(frame.border_box.width() as f32))
+                    .resolve(CSSPixelLength::new(frame.border_box.height() as f32))
                     .px() as f64,
             });
             let kurbo_transform =
@@ -358,6 +499,19 @@ impl BlitzDomPainter<'_> {
     }
 }
 
+// A 3D transform whose action on the z=0 plane is affine (no perspective row)
+#[cfg(any(test, feature = "tracing"))]
+fn is_affine_2d<Src, Dst>(t: &Transform3D<f32, Src, Dst>) -> bool {
+    t.m14.abs() < 1e-6 && t.m24.abs() < 1e-6 && t.m44 != 0.0
+}
+
+/// Project a CSS 3D transform onto the z=0 plane: the z row and column drop out and the w column
+/// (perspective) is ignored apart from a uniform divide
+fn flatten_transform<Src, Dst>(t: &Transform3D<f32, Src, Dst>) -> Affine {
+    let w = if t.m44 != 0.0 { t.m44 as f64 } else { 1.0 };
+    Affine::new([t.m11, t.m12, t.m21, t.m22, t.m41, t.m42].map(|v| v as f64 / w))
+}
+
 fn to_image_quality(image_rendering: ImageRendering) -> peniko::ImageQuality {
     match image_rendering {
         ImageRendering::Auto => peniko::ImageQuality::Medium,
@@ -369,7 +523,8 @@ fn to_image_quality(image_rendering: ImageRendering) -> peniko::ImageQuality {
 /// Ensure that the `resized_image` field has a correctly sized image
 fn to_peniko_image(image: &RasterImageData, quality: peniko::ImageQuality) -> peniko::Image {
     peniko::Image {
-        data: peniko::Blob::new(image.data.clone()),
+        // Keep the blob id stable so renderers reuse their upload of this frame
+        data: peniko::Blob::from_raw_parts(image.data.clone(), image.data_id),
         format: peniko::ImageFormat::Rgba8,
         width: image.width,
         height: image.height,
@@ -407,8 +562,38 @@ impl ElementCx<'_> {
                     panic!("Tried to render node marked as inline root that does not have an inline layout: {:?}", self.node);
                 });
 
-            // Render text
-            crate::text::stroke_text(self.scale, scene, text_layout.layout.lines(), pos);
+            // Render the document text selection behind the glyphs
+            let transform = self.inline_layout_transform(pos);
+            let selection_color = self.selection_color();
+            for rect in self.context.dom.text_selection_geometry(self.node.id) {
+                scene.fill(Fill::NonZero, transform, selection_color, None, &rect);
+            }
+
+            // Render text. Runs take the forced color of their own span, so links inside a
+            // paragraph still get the link color.
+            let forced_text_color = self
+                .forced_colors()
+                .map(|scheme| move |id: usize| self.forced_color_role(id).foreground(scheme));
+            // text-overflow: ellipsis only applies where overflow is clipped, and lines are horizontal
+            let ellipsis = text_layout
+                .ellipsis
+                .as_ref()
+                .filter(|_| {
+                    !matches!(self.style.get_box().overflow_x, Overflow::Visible)
+                        && !self.style.writing_mode.is_vertical()
+                })
+                .map(|layout| crate::text::Ellipsis {
+                    layout,
+                    clip_x: self.frame.content_box.width() as f32,
+                });
+            crate::text::stroke_text(
+                self.scale,
+                scene,
+                text_layout.layout.lines(),
+                transform,
+                forced_text_color.as_ref().map(|f| f as &dyn Fn(usize) -> Color),
+                ellipsis.as_ref(),
+            );
         }
     }
 
@@ -419,64 +604,183 @@ impl ElementCx<'_> {
 
             if self.node.is_focussed() {
                 // Render selection/caret
+                let selection_color = self.selection_color();
                 for (rect, _line_idx) in input_data.editor.selection_geometry().iter() {
-                    scene.fill(
-                        Fill::NonZero,
-                        transform,
-                        color::palette::css::STEEL_BLUE,
-                        None,
-                        &rect,
-                    );
+                    scene.fill(Fill::NonZero, transform, selection_color, None, &rect);
                 }
-                if let Some(cursor) = input_data.editor.cursor_geometry(1.5) {
-                    scene.fill(Fill::NonZero, transform, Color::BLACK, None, &cursor);
+                // The caret blinks; the document tracks the phase (see BaseDocument::advance_caret_blink)
+                if let Some(cursor) = input_data.editor.cursor_geometry(1.5).filter(|_| self.dom.caret_visible()) {
+                    scene.fill(Fill::NonZero, transform, self.caret_color(), None, &cursor);
                 };
             }
 
             // Render text
+            let forced_text_color = self.forced_foreground().map(|color| move |_: usize| color);
             crate::text::stroke_text(
                 self.scale,
                 scene,
                 input_data.editor.try_layout().unwrap().lines(),
-                pos,
+                transform,
+                forced_text_color.as_ref().map(|f| f as &dyn Fn(usize) -> Color),
+                None,
             );
         }
     }
 
+    /// Placement of the element's inline layout. Parley only lays text out horizontally, so
+    /// vertical-rl (and sideways-rl) text is turned a quarter clockwise about the content box's
+    /// top-right corner: lines then run top to bottom and stack right to left. Line breaking still
+    /// uses the box's width as the line length.
+    fn inline_layout_transform(&self, pos: Point) -> Affine {
+        let transform = Affine::translate((pos.x * self.scale, pos.y * self.scale));
+        let writing_mode = self.style.writing_mode;
+        if writing_mode.is_vertical() && !writing_mode.is_vertical_lr() {
+            transform
+                * Affine::translate((self.frame.content_box.width(), 0.0))
+                * Affine::rotate(std::f64::consts::FRAC_PI_2)
+        } else {
+            transform
+        }
+    }
+
     fn draw_marker(&self, scene: &mut impl PaintScene, pos: Point) {
-        if let Some(ListItemLayout {
-            marker,
-            position: ListItemLayoutPosition::Outside(layout),
-        }) = self.list_item
-        {
-            // Right align and pad the bullet when rendering outside
-            let x_padding = match marker {
-                Marker::Char(_) => 8.0,
-                Marker::String(_) => 0.0,
-            };
-            let x_offset = -(layout.full_width() / layout.scale() + x_padding);
+        let Some(ListItemLayout { marker, position }) = self.list_item else {
+            return;
+        };
+        let layout = match position {
+            ListItemLayoutPosition::Inside => {
+                self.draw_inside_bullet(scene, pos, marker);
+                return;
+            }
+            ListItemLayoutPosition::Outside(layout) => layout,
+        };
 
-            // Align the marker with the baseline of the first line of text in the list item
-            let y_offset = if let Some(first_text_line) = &self
-                .element
-                .inline_layout_data
+        // Align the marker with the baseline of the first line of text in the list item
+        let Some(marker_line) = layout.lines().next() else {
+            return;
+        };
+        let marker_baseline = marker_line.metrics().baseline;
+        let text_baseline = self
+            .element
+            .inline_layout_data
+            .as_ref()
+            .and_then(|text_layout| text_layout.layout.lines().next())
+            .map(|first_text_line| first_text_line.metrics().baseline);
+
+        if let Some(shape) = marker.shape() {
+            // Bullets end half an em left of the content box
+            let font_size = self.style.get_font().font_size.used_size.0.px() as f64;
+            let radius = font_size * BULLET_SIZE / 2.0;
+            let baseline =
+                pos.y + (text_baseline.unwrap_or(marker_baseline) / layout.scale()) as f64;
+            let center = Point::new(
+                pos.x - font_size * 0.5 - radius,
+                baseline - font_size * BULLET_RAISE,
+            );
+            let color = self.marker_color();
+            draw_bullet(
+                scene,
+                Affine::scale(self.scale),
+                shape,
+                center,
+                font_size,
+                color,
+            );
+            return;
+        }
+
+        // Right align and pad the bullet when rendering outside
+        let x_padding = match marker {
+            Marker::Char(_) => 8.0,
+            Marker::String(_) => 0.0,
+        };
+        let x_offset = -(layout.full_width() / layout.scale() + x_padding);
+        let y_offset = text_baseline.map_or(0.0, |baseline| {
+            (baseline - marker_baseline) / layout.scale()
+        });
+
+        let pos = Point {
+            x: pos.x + x_offset as f64,
+            y: pos.y + y_offset as f64,
+        };
+
+        let forced_text_color = self.forced_foreground().map(|color| move |_: usize| color);
+        crate::text::stroke_text(
+            self.scale,
+            scene,
+            layout.lines(),
+            Affine::translate((pos.x * self.scale, pos.y * self.scale)),
+            forced_text_color
                 .as_ref()
-                .and_then(|text_layout| text_layout.layout.lines().next())
-            {
-                (first_text_line.metrics().baseline
-                    - layout.lines().next().unwrap().metrics().baseline)
-                    / layout.scale()
-            } else {
-                0.0
-            };
+                .map(|f| f as &dyn Fn(usize) -> Color),
+            None,
+        );
+    }
 
-            let pos = Point {
-                x: pos.x + x_offset as f64,
-                y: pos.y + y_offset as f64,
-            };
+    /// Inside markers are laid out as the first text of the inline layout, so only shaped bullets need
+    /// painting: into the placeholder run they left at the start of the first line.
+    fn draw_inside_bullet(&self, scene: &mut impl PaintScene, pos: Point, marker: &Marker) {
+        let Some(shape) = marker.shape() else {
+            return;
+        };
+        let Some(first_line) = self
+            .element
+            .inline_layout_data
+            .as_ref()
+            .and_then(|text_layout| text_layout.layout.lines().next())
+        else {
+            return;
+        };
+        // Layout units (device px): centred in the bullet glyph's advance
+        let placeholder = first_line.items().find_map(|item| match item {
+            PositionedLayoutItem::GlyphRun(run) if run.style().brush.placeholder => {
+                let font_size = run.run().font_size() as f64;
+                let x = (run.offset() + run.advance() / 2.0) as f64;
+                Some((
+                    Point::new(x, run.baseline() as f64 - font_size * BULLET_RAISE),
+                    font_size,
+                ))
+            }
+            _ => None,
+        });
+        if let Some((center, font_size)) = placeholder {
+            let color = self.marker_color();
+            draw_bullet(
+                scene,
+                self.inline_layout_transform(pos),
+                shape,
+                center,
+                font_size,
+                color,
+            );
+        }
+    }
 
-            crate::text::stroke_text(self.scale, scene, layout.lines(), pos);
+    /// CSS `caret-color`, where `auto` is the text color
+    fn caret_color(&self) -> Color {
+        if let Some(color) = self.forced_foreground() {
+            return color;
         }
+        let current_color = self.style.clone_color();
+        match &self.style.get_inherited_ui().caret_color.0 {
+            GenericColorOrAuto::Color(color) => {
+                color.resolve_to_absolute(&current_color).as_srgb_color()
+            }
+            GenericColorOrAuto::Auto => current_color.as_srgb_color(),
+        }
+    }
+
+    /// List markers take the list item's text color
+    fn marker_color(&self) -> Color {
+        self.forced_foreground()
+            .unwrap_or_else(|| self.style.clone_color().as_srgb_color())
+    }
+
+    /// Background of selected text: the scheme's highlight color under forced colors
+    fn selection_color(&self) -> Color {
+        self.forced_colors()
+            .map(|scheme| scheme.highlight)
+            .unwrap_or(color::palette::css::STEEL_BLUE)
     }
 
     fn draw_children(&self, scene: &mut impl PaintScene) {
@@ -571,12 +875,22 @@ impl ElementCx<'_> {
 
             let x_scale = paint_size.width as f64 / object_size.width as f64;
             let y_scale = paint_size.height as f64 / object_size.height as f64;
-            let transform = self
-                .transform
-                .pre_scale_non_uniform(x_scale, y_scale)
-                .then_translate(Vec2 { x, y });
-
-            scene.draw_image(&to_peniko_image(image, quality), transform);
+            let image_transform = Affine::translate((x, y)).pre_scale_non_uniform(x_scale, y_scale);
+
+            // Fill only the part of the positioned image inside the content box, so `cover` (and
+            // oversized `none`/`scale-down`) crop instead of spilling out of the element
+            let paint_rect = Rect::from_origin_size((x, y), (paint_size.width as f64, paint_size.height as f64));
+            let visible = paint_rect.intersect(self.frame.content_box);
+            if visible.width() <= 0.0 || visible.height() <= 0.0 {
+                return;
+            }
+            scene.fill(
+                Fill::NonZero,
+                self.transform,
+                &to_peniko_image(image, quality),
+                Some(image_transform),
+                &visible,
+            );
         }
     }
 
@@ -629,16 +943,19 @@ impl ElementCx<'_> {
     /// ❌ dotted - Defines a dotted border
     /// ❌ dashed - Defines a dashed border
     /// ✅ solid - Defines a solid border
-    /// ❌ double - Defines a double border
-    /// ❌ groove - Defines a 3D grooved border.
-    /// ❌ ridge - Defines a 3D ridged border.
-    /// ❌ inset - Defines a 3D inset border.
-    /// ❌ outset - Defines a 3D outset border.
+    /// ✅ double - Defines a double border
+    /// ✅ groove - Defines a 3D grooved border.
+    /// ✅ ridge - Defines a 3D ridged border.
+    /// ✅ inset - Defines a 3D inset border.
+    /// ✅ outset - Defines a 3D outset border.
     /// ✅ none - Defines no border
     /// ✅ hidden - Defines a hidden border
     ///
     /// The border-style property can have from one to four values (for the top border, right border, bottom border, and the left border).
     fn draw_border(&self, sb: &mut impl PaintScene) {
+        if self.draw_border_image(sb) {
+            return;
+        }
         for edge in [Edge::Top, Edge::Right, Edge::Bottom, Edge::Left] {
             self.draw_border_edge(sb, edge);
         }
@@ -652,11 +969,11 @@ impl ElementCx<'_> {
     /// - ❌ dotted: Defines a dotted border
     /// - ❌ dashed: Defines a dashed border
     /// - ✅ solid: Defines a solid border
-    /// - ❌ double: Defines a double border
-    /// - ❌ groove: Defines a 3D grooved border*
-    /// - ❌ ridge: Defines a 3D ridged border*
-    /// - ❌ inset: Defines a 3D inset border*
-    /// - ❌ outset: Defines a 3D outset border*
+    /// - ✅ double: Defines a double border
+    /// - ✅ groove: Defines a 3D grooved border*
+    /// - ✅ ridge: Defines a 3D ridged border*
+    /// - ✅ inset: Defines a 3D inset border*
+    /// - ✅ outset: Defines a 3D outset border*
     /// - ✅ none: Defines no border
     /// - ✅ hidden: Defines a hidden border
     ///
@@ -664,7 +981,6 @@ impl ElementCx<'_> {
     fn draw_border_edge(&self, sb: &mut impl PaintScene, edge: Edge) {
         let style = &*self.style;
         let border = style.get_border();
-        let path = self.frame.border_edge_shape(edge);
 
         let current_color = style.clone_color();
         let color = match edge {
@@ -687,8 +1003,64 @@ impl ElementCx<'_> {
         };
 
         let alpha = color.components[3];
-        if alpha != 0.0 {
-            sb.fill(Fill::NonZero, self.transform, color, None, &path);
+        if alpha == 0.0 {
+            return;
+        }
+        let color = self.forced_foreground().unwrap_or(color);
+
+        let border_style = match edge {
+            Edge::Top => border.border_top_style,
+            Edge::Right => border.border_right_style,
+            Edge::Bottom => border.border_bottom_style,
+            Edge::Left => border.border_left_style,
+        };
+        let width = match edge {
+            Edge::Top => self.frame.border_width.y0,
+            Edge::Right => self.frame.border_width.x1,
+            Edge::Bottom => self.frame.border_width.y1,
+            Edge::Left => self.frame.border_width.x0,
+        };
+        // Top and left edges are the ones in shadow for inset/groove (lit for outset/ridge)
+        let top_left = matches!(edge, Edge::Top | Edge::Left);
+        let (light, dark) = bevel_colors(color);
+
+        match border_style {
+            // Two lines with a gap, each a third of the width; too thin to split below 3px
+            BorderStyle::Double if width >= 3.0 => {
+                for (start, end) in [(0.0, 1.0 / 3.0), (2.0 / 3.0, 1.0)] {
+                    let path = self.frame.border_band(start, end).border_edge_shape(edge);
+                    sb.fill(Fill::NonZero, self.transform, color, None, &path);
+                }
+            }
+            // Two-tone bevels: the outer and inner halves of the edge in opposite shades
+            BorderStyle::Groove | BorderStyle::Ridge => {
+                let outer_dark = top_left == matches!(border_style, BorderStyle::Groove);
+                let (outer, inner) = if outer_dark {
+                    (dark, light)
+                } else {
+                    (light, dark)
+                };
+                for (start, end, color) in [(0.0, 0.5, outer), (0.5, 1.0, inner)] {
+                    let path = self.frame.border_band(start, end).border_edge_shape(edge);
+                    sb.fill(Fill::NonZero, self.transform, color, None, &path);
+                }
+            }
+            BorderStyle::Inset | BorderStyle::Outset => {
+                let is_dark = top_left == matches!(border_style, BorderStyle::Inset);
+                let path = self.frame.border_edge_shape(edge);
+                sb.fill(
+                    Fill::NonZero,
+                    self.transform,
+                    if is_dark { dark } else { light },
+                    None,
+                    &path,
+                );
+            }
+            // TODO: dotted and dashed are still painted solid
+            _ => {
+                let path = self.frame.border_edge_shape(edge);
+                sb.fill(Fill::NonZero, self.transform, color, None, &path);
+            }
         }
     }
 
@@ -710,6 +1082,7 @@ impl ElementCx<'_> {
             .outline_color
             .resolve_to_absolute(&current_color)
             .as_srgb_color();
+        let color = self.forced_foreground().unwrap_or(color);
 
         let style = match outline.outline_style {
             OutlineStyle::Auto => return,
@@ -740,6 +1113,54 @@ impl<'a> std::ops::Deref for ElementCx<'a> {
     }
 }
 
+// Diameter of a list bullet, and how far above the baseline its centre sits (about the middle of the
+// x-height), as fractions of the font size
+const BULLET_SIZE: f64 = 0.35;
+const BULLET_RAISE: f64 = 0.25;
+
+/// Paint a `disc`, `circle` or `square` list bullet centred on `center`, sized to `font_size`
+fn draw_bullet(
+    scene: &mut impl PaintScene,
+    transform: Affine,
+    shape: MarkerShape,
+    center: Point,
+    font_size: f64,
+    color: Color,
+) {
+    let radius = font_size * BULLET_SIZE / 2.0;
+    match shape {
+        MarkerShape::Disc => {
+            scene.fill(
+                Fill::NonZero,
+                transform,
+                color,
+                None,
+                &kurbo::Circle::new(center, radius),
+            );
+        }
+        MarkerShape::Circle => {
+            // The ring's outer edge matches the disc's
+            let width = font_size * 0.07;
+            let ring = kurbo::Circle::new(center, radius - width / 2.0);
+            scene.stroke(&Stroke::new(width), transform, color, None, &ring);
+        }
+        MarkerShape::Square => {
+            let square = Rect::from_center_size(center, (radius * 2.0, radius * 2.0));
+            scene.fill(Fill::NonZero, transform, color, None, &square);
+        }
+    }
+}
+
+/// The lit and shadowed tones of a 3D (groove/ridge/inset/outset) border: the color itself and the
+/// color at half brightness. Black can't get darker, so it is lit with a dark gray instead.
+fn bevel_colors(color: Color) -> (Color, Color) {
+    let [r, g, b, a] = color.components;
+    if r.max(g).max(b) < 0.05 {
+        return (Color::new([0.33, 0.33, 0.33, a]), color);
+    }
+    (color, Color::new([r * 0.5, g * 0.5, b * 0.5, a]))
+}
+
 fn insets_from_taffy_rect(input: taffy::Rect<f64>) -> Insets {
     Insets {
         x0: input.left,
@@ -759,6 +1180,7 @@ fn create_css_rect(style: &ComputedValues, layout: &Layout, scale: f64) -> CssBo
     let border = insets_from_taffy_rect(layout.border.map(|p| p as f64 * scale));
     let padding = insets_from_taffy_rect(layout.padding.map(|p| p as f64 * scale));
     let outline_width = style.get_outline().outline_width.to_f64_px() * scale;
+    let outline_offset = style.get_outline().outline_offset.px() as f64 * scale;
 
     // Resolve the radii to a length. need to downscale since the radii are in document pixels
     let resolve_w = CSSPixelLength::new(width as _);
@@ -777,5 +1199,94 @@ fn create_css_rect(style: &ComputedValues, layout: &Layout, scale: f64) -> CssBo
         bottom_left: resolve_radii(&s_border.border_bottom_left_radius),
     };
 
-    CssBox::new(border_box, border, padding, outline_width, border_radii)
+    CssBox::new(
+        border_box,
+        border,
+        padding,
+        outline_width,
+        outline_offset,
+        border_radii,
+    )
+}
+
+/// Sticky shift along one axis (https://drafts.csswg.org/css-position/#stickypos-insets). The box moves
+/// just enough to stay `inset_start`/`inset_end` inside the scrollport, but never out of its containing
+/// block; when both insets apply and conflict, the start inset wins.
+fn sticky_axis_offset(
+    (start, end): (f64, f64),
+    (container_start, container_end): (f64, f64),
+    (port_start, port_end): (f64, f64),
+    inset_start: Option<f64>,
+    inset_end: Option<f64>,
+) -> f64 {
+    let mut delta = 0.0;
+    if let Some(inset) = inset_end {
+        let limit = port_end - inset;
+        if end > limit {
+            delta = (limit - end).max(container_start - start).min(0.0);
+        }
+    }
+    if let Some(inset) = inset_start {
+        let limit = port_start + inset;
+        if start + delta < limit {
+            delta = (limit - start).min(container_end - end).max(delta);
+        }
+    }
+    delta
+}
+
+#[test]
+fn sticky_boxes_pin_within_their_containing_block() {
+    // A 40px `top: 0` header at the start of a 500px section, in a 600px viewport scrolled by `s`
+    let header_offset = |s: f64| {
+        sticky_axis_offset(
+            (-s, 40.0 - s),
+            (-s, 500.0 - s),
+            (0.0, 600.0),
+            Some(0.0),
+            None,
+        )
+    };
+    // Not scrolled yet: stays in flow
+    assert_eq!(header_offset(0.0), 0.0);
+    // Section partly scrolled out: moved down by the scrolled distance, pinned to the viewport top
+    assert_eq!(header_offset(100.0), 100.0);
+    // Section almost gone: the header is pushed out with the section's bottom edge
+    assert_eq!(header_offset(480.0), 460.0);
+    // `bottom: 10px` footer still below the fold is pulled up to the viewport bottom
+    assert_eq!(
+        sticky_axis_offset((700.0, 720.0), (0.0, 800.0), (0.0, 600.0), None, Some(10.0)),
+        -130.0
+    );
+}
+
+#[test]
+fn flattened_3d_transforms_keep_their_2d_action() {
+    use euclid::Angle;
+    type T = Transform3D<f32, euclid::UnknownUnit, euclid::UnknownUnit>;
+
+    // rotateZ is a plain 2D rotation
+    let rotate = flatten_transform(&T::rotation(0.0, 0.0, 1.0, Angle::degrees(90.0)));
+    let p = rotate * Point::new(1.0, 0.0);
+    assert!((p.x - 0.0).abs() < 1e-6 && (p.y - 1.0).abs() < 1e-6);
+
+    // translateZ and rotateX(0) leave the plane untouched
+    assert_eq!(
+        flatten_transform(&T::translation(0.0, 0.0, 50.0)),
+        Affine::IDENTITY
+    );
+    assert!(is_affine_2d(&T::rotation(
+        1.0,
+        0.0,
+        0.0,
+        Angle::degrees(0.0)
+    )));
+
+    // translate3d keeps its x/y part
+    let translate = flatten_transform(&T::translation(10.0, 20.0, 30.0));
+    assert_eq!(translate * Point::ORIGIN, Point::new(10.0, 20.0));
+    // perspective only matters once something tilts the plane out of z=0
+    assert!(is_affine_2d(&T::perspective(100.0)));
+    let tilted = T::rotation(0.0, 1.0, 0.0, Angle::degrees(45.0)).then(&T::perspective(100.0));
+    assert!(!is_affine_2d(&tilted));
 }
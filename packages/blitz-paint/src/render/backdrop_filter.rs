@@ -0,0 +1,26 @@
+use super::ElementCx;
+use anyrender::PaintScene;
+use style::values::generics::effects::GenericFilter;
+
+impl ElementCx<'_> {
+    /// `backdrop-filter`: blur what is already drawn behind the border box. Only `blur()` is
+    /// supported; several blurs combine into one of the summed variance.
+    pub(super) fn draw_backdrop_filter(&self, scene: &mut impl PaintScene) {
+        let filters = &self.style.get_effects().backdrop_filter.0;
+        let variance: f64 = filters
+            .iter()
+            .filter_map(|filter| match filter {
+                GenericFilter::Blur(length) => Some(length.0.px() as f64 * self.scale),
+                _ => None,
+            })
+            .map(|std_dev| std_dev * std_dev)
+            .sum();
+        if variance > 0.0 {
+            scene.draw_backdrop_blur(
+                self.transform,
+                &self.frame.border_box_path(),
+                variance.sqrt(),
+            );
+        }
+    }
+}
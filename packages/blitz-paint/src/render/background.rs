@@ -63,6 +63,8 @@ impl ElementCx<'_> {
                         None => {
                             // Do nothing
                         }
+                        // Gradients are decoration rather than content, so forced colors drop them
+                        Gradient(_) if self.forced_colors().is_some() => {}
                         Gradient(gradient) => {
                             self.draw_gradient_bg(scene, gradient, idx, *background_clip)
                         }
@@ -101,6 +103,8 @@ impl ElementCx<'_> {
             .as_srgb_color();
 
         if bg_color != Color::TRANSPARENT {
+            // Forced colors keep whether an element has a background, but not its color
+            let bg_color = self.forced_background().unwrap_or(bg_color);
             // Fill the color
             scene.fill(Fill::NonZero, self.transform, bg_color, None, shape);
         }
@@ -0,0 +1,355 @@
+use super::{ElementCx, to_image_quality, to_peniko_image};
+use anyrender::PaintScene;
+use blitz_dom::node::ImageData;
+use kurbo::{Affine, Insets, Rect, Size};
+use peniko::{self, Fill};
+use style::values::{
+    computed::{
+        BorderImageSideWidth, Length, NonNegativeLengthOrNumber, NonNegativeNumberOrPercentage,
+        NumberOrPercentage,
+    },
+    generics::{border::GenericBorderImageSideWidth, length::GenericLengthOrNumber},
+    specified::border::BorderImageRepeatKeyword,
+};
+
+/// One of the nine parts of a border image: the part of the source image it shows, the part of the border
+/// image area it fills, and the size of one tile of it there (the whole of `dst` when stretched).
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct BorderImageRegion {
+    src: Rect,
+    dst: Rect,
+    tile: Size,
+    repeat_x: BorderImageRepeatKeyword,
+    repeat_y: BorderImageRepeatKeyword,
+}
+
+impl ElementCx<'_> {
+    /// `border-image`: draw the nine-slice of the `border-image-source` in place of the border's own
+    /// style. Returns false, leaving the regular border to be drawn, until a raster source has loaded.
+    pub(super) fn draw_border_image(&self, scene: &mut impl PaintScene) -> bool {
+        let Some(border_image) = &self.element.border_image else {
+            return false;
+        };
+        let ImageData::Raster(image_data) = &border_image.image else {
+            return false;
+        };
+        let border = self.style.get_border();
+        let image_size = Size::new(image_data.width as f64, image_data.height as f64);
+        let border_width = self.frame.border_width;
+
+        // border-image-slice: numbers are image pixels, percentages of the image's size
+        let slice = |value: &NonNegativeNumberOrPercentage, extent: f64| {
+            let offset = match value.0 {
+                NumberOrPercentage::Number(number) => number as f64,
+                NumberOrPercentage::Percentage(percentage) => percentage.0 as f64 * extent,
+            };
+            offset.min(extent)
+        };
+        let offsets = &border.border_image_slice.offsets;
+        let slices = Insets::new(
+            slice(&offsets.3, image_size.width),
+            slice(&offsets.0, image_size.height),
+            slice(&offsets.1, image_size.width),
+            slice(&offsets.2, image_size.height),
+        );
+
+        // border-image-outset: lengths, or multiples of the border width
+        let outset = |value: &NonNegativeLengthOrNumber, width: f64| match value {
+            GenericLengthOrNumber::Length(length) => length.0.px() as f64 * self.scale,
+            GenericLengthOrNumber::Number(number) => number.0 as f64 * width,
+        };
+        let outsets = &border.border_image_outset;
+        let area = self.frame.border_box
+            + Insets::new(
+                outset(&outsets.3, border_width.x0),
+                outset(&outsets.0, border_width.y0),
+                outset(&outsets.1, border_width.x1),
+                outset(&outsets.2, border_width.y1),
+            );
+
+        // border-image-width: percentages of the area, multiples of the border width, or the slice's own size
+        let side_width =
+            |value: &BorderImageSideWidth, width: f64, slice: f64, extent: f64| match value {
+                GenericBorderImageSideWidth::LengthPercentage(lp) => {
+                    lp.0.resolve(Length::new((extent / self.scale) as f32)).px() as f64 * self.scale
+                }
+                GenericBorderImageSideWidth::Number(number) => number.0 as f64 * width,
+                GenericBorderImageSideWidth::Auto => slice * self.scale,
+            };
+        let widths = &border.border_image_width;
+        let widths = Insets::new(
+            side_width(&widths.3, border_width.x0, slices.x0, area.width()),
+            side_width(&widths.0, border_width.y0, slices.y0, area.height()),
+            side_width(&widths.1, border_width.x1, slices.x1, area.width()),
+            side_width(&widths.2, border_width.y1, slices.y1, area.height()),
+        );
+
+        let repeat = &border.border_image_repeat;
+        let regions = border_image_regions(
+            image_size,
+            slices,
+            area,
+            widths,
+            border.border_image_slice.fill,
+            (repeat.0, repeat.1),
+        );
+
+        let quality = to_image_quality(self.style.clone_image_rendering());
+        // Pad rather than repeat so that a tile's edge doesn't sample the opposite side of the image
+        let image = to_peniko_image(image_data, quality).with_extend(peniko::Extend::Pad);
+        for region in regions {
+            let xs = border_image_tiles(
+                region.dst.x0,
+                region.dst.width(),
+                region.tile.width,
+                region.repeat_x,
+            );
+            let ys = border_image_tiles(
+                region.dst.y0,
+                region.dst.height(),
+                region.tile.height,
+                region.repeat_y,
+            );
+            for &(y, height) in &ys {
+                for &(x, width) in &xs {
+                    let tile = Rect::new(x, y, x + width, y + height);
+                    let shape = tile.intersect(region.dst);
+                    if shape.is_zero_area() {
+                        continue;
+                    }
+                    // Map the source slice onto this tile
+                    let brush_transform = Affine::translate((tile.x0, tile.y0))
+                        * Affine::scale_non_uniform(
+                            tile.width() / region.src.width(),
+                            tile.height() / region.src.height(),
+                        )
+                        * Affine::translate((-region.src.x0, -region.src.y0));
+                    scene.fill(
+                        Fill::NonZero,
+                        self.transform,
+                        &image,
+                        Some(brush_transform),
+                        &shape,
+                    );
+                }
+            }
+        }
+
+        true
+    }
+}
+
+// Carve the image into corners, edges and middle per the slices, and place them in the border image area per
+// the (device pixel) widths. Corners are stretched to fit; edges keep their aspect ratio across the border
+// and tile along it; the middle (only drawn for `fill`) is scaled like the top and left edges.
+fn border_image_regions(
+    image: Size,
+    slices: Insets,
+    area: Rect,
+    widths: Insets,
+    fill: bool,
+    (repeat_x, repeat_y): (BorderImageRepeatKeyword, BorderImageRepeatKeyword),
+) -> Vec<BorderImageRegion> {
+    // Opposite widths that overlap are scaled down together until they fit
+    let horizontal = widths.x0 + widths.x1;
+    let vertical = widths.y0 + widths.y1;
+    let mut reduce = 1.0_f64;
+    if horizontal > area.width() {
+        reduce = reduce.min(area.width() / horizontal);
+    }
+    if vertical > area.height() {
+        reduce = reduce.min(area.height() / vertical);
+    }
+    let widths = Insets::new(
+        widths.x0 * reduce,
+        widths.y0 * reduce,
+        widths.x1 * reduce,
+        widths.y1 * reduce,
+    );
+
+    let src_x = [0.0, slices.x0, image.width - slices.x1, image.width];
+    let src_y = [0.0, slices.y0, image.height - slices.y1, image.height];
+    let dst_x = [area.x0, area.x0 + widths.x0, area.x1 - widths.x1, area.x1];
+    let dst_y = [area.y0, area.y0 + widths.y0, area.y1 - widths.y1, area.y1];
+    let rect = |xs: &[f64; 4], ys: &[f64; 4], col: usize, row: usize| {
+        Rect::new(xs[col], ys[row], xs[col + 1], ys[row + 1])
+    };
+    // How much an edge is scaled to fit across the border, if it is drawn at all
+    let edge_scale = |dst: f64, src: f64| (src > 0.0 && dst > 0.0).then(|| dst / src);
+    let top = edge_scale(widths.y0, slices.y0);
+    let bottom = edge_scale(widths.y1, slices.y1);
+    let left = edge_scale(widths.x0, slices.x0);
+    let right = edge_scale(widths.x1, slices.x1);
+
+    let mut regions = Vec::with_capacity(9);
+    for row in 0..3 {
+        for col in 0..3 {
+            if row == 1 && col == 1 && !fill {
+                continue;
+            }
+            let src = rect(&src_x, &src_y, col, row);
+            let dst = rect(&dst_x, &dst_y, col, row);
+            if src.width() <= 0.0
+                || src.height() <= 0.0
+                || dst.width() <= 0.0
+                || dst.height() <= 0.0
+            {
+                continue;
+            }
+            let scale_x = match (col, row) {
+                (1, 0) => top,
+                (1, 2) => bottom,
+                (1, 1) => top.or(bottom),
+                _ => None,
+            };
+            let scale_y = match (col, row) {
+                (0, 1) => left,
+                (2, 1) => right,
+                (1, 1) => left.or(right),
+                _ => None,
+            };
+            // Corners stretch, as does any axis an edge runs across
+            let (tile_width, repeat_x) = match (col, scale_x) {
+                (1, Some(scale)) => (src.width() * scale, repeat_x),
+                _ => (dst.width(), BorderImageRepeatKeyword::Stretch),
+            };
+            let (tile_height, repeat_y) = match (row, scale_y) {
+                (1, Some(scale)) => (src.height() * scale, repeat_y),
+                _ => (dst.height(), BorderImageRepeatKeyword::Stretch),
+            };
+            regions.push(BorderImageRegion {
+                src,
+                dst,
+                tile: Size::new(tile_width, tile_height),
+                repeat_x,
+                repeat_y,
+            });
+        }
+    }
+    regions
+}
+
+// The (start, length) of each tile laid along `start..start + length` per `border-image-repeat`
+fn border_image_tiles(
+    start: f64,
+    length: f64,
+    tile: f64,
+    mode: BorderImageRepeatKeyword,
+) -> Vec<(f64, f64)> {
+    use BorderImageRepeatKeyword::*;
+    if tile <= 0.0 || length <= 0.0 {
+        return Vec::new();
+    }
+    match mode {
+        Stretch => vec![(start, length)],
+        // Whole tiles centered in the area, cut off at both ends
+        Repeat => {
+            let lead = (length - tile) / 2.0;
+            let before = (lead / tile).ceil().max(0.0);
+            let first = start + lead - before * tile;
+            let count = ((start + length - first) / tile).ceil() as usize;
+            (0..count)
+                .map(|i| (first + i as f64 * tile, tile))
+                .collect()
+        }
+        // Tiles resized so that a whole number of them fits
+        Round => {
+            let count = (length / tile).round().max(1.0);
+            let tile = length / count;
+            (0..count as usize)
+                .map(|i| (start + i as f64 * tile, tile))
+                .collect()
+        }
+        // As many whole tiles as fit, with the leftover space spread evenly around them
+        Space => {
+            let count = (length / tile).floor();
+            let gap = (length - count * tile) / (count + 1.0);
+            (0..count as usize)
+                .map(|i| (start + gap + i as f64 * (tile + gap), tile))
+                .collect()
+        }
+    }
+}
+
+#[test]
+fn border_image_tiles_follow_the_repeat_mode() {
+    use BorderImageRepeatKeyword::*;
+    assert_eq!(
+        border_image_tiles(10.0, 100.0, 30.0, Stretch),
+        [(10.0, 100.0)]
+    );
+    // 100 / 30: a centered tile, a whole one either side of it and cut-off ones beyond
+    assert_eq!(
+        border_image_tiles(0.0, 100.0, 30.0, Repeat),
+        [
+            (-25.0, 30.0),
+            (5.0, 30.0),
+            (35.0, 30.0),
+            (65.0, 30.0),
+            (95.0, 30.0)
+        ]
+    );
+    assert_eq!(
+        border_image_tiles(0.0, 100.0, 30.0, Round),
+        [
+            (0.0, 100.0 / 3.0),
+            (100.0 / 3.0, 100.0 / 3.0),
+            (200.0 / 3.0, 100.0 / 3.0)
+        ]
+    );
+    assert_eq!(
+        border_image_tiles(0.0, 100.0, 30.0, Space),
+        [(2.5, 30.0), (35.0, 30.0), (67.5, 30.0)]
+    );
+    assert!(border_image_tiles(0.0, 20.0, 30.0, Space).is_empty());
+}
+
+#[test]
+fn border_image_regions_keep_corners_and_tile_edges() {
+    use BorderImageRepeatKeyword::*;
+    // A 30x30 image sliced 10px in from each side, drawn into a 100x60 box with 20px borders
+    let regions = border_image_regions(
+        Size::new(30.0, 30.0),
+        Insets::uniform(10.0),
+        Rect::new(0.0, 0.0, 100.0, 60.0),
+        Insets::uniform(20.0),
+        false,
+        (Repeat, Round),
+    );
+    assert_eq!(regions.len(), 8);
+    let top_left = regions[0];
+    assert_eq!(
+        (top_left.src, top_left.dst),
+        (
+            Rect::new(0.0, 0.0, 10.0, 10.0),
+            Rect::new(0.0, 0.0, 20.0, 20.0)
+        )
+    );
+    assert_eq!((top_left.repeat_x, top_left.repeat_y), (Stretch, Stretch));
+    // The top edge doubles in height to fit the border, so its tiles double in width
+    let top = regions[1];
+    assert_eq!(top.dst, Rect::new(20.0, 0.0, 80.0, 20.0));
+    assert_eq!(
+        (top.tile, top.repeat_x, top.repeat_y),
+        (Size::new(20.0, 20.0), Repeat, Stretch)
+    );
+    let left = regions[3];
+    assert_eq!(left.dst, Rect::new(0.0, 20.0, 20.0, 40.0));
+    assert_eq!(
+        (left.tile, left.repeat_x, left.repeat_y),
+        (Size::new(20.0, 20.0), Stretch, Round)
+    );
+
+    // Widths wider than the box are scaled down together
+    let regions = border_image_regions(
+        Size::new(30.0, 30.0),
+        Insets::uniform(10.0),
+        Rect::new(0.0, 0.0, 100.0, 60.0),
+        Insets::uniform(40.0),
+        true,
+        (Stretch, Stretch),
+    );
+    // Only the top and bottom rows are left
+    assert_eq!(regions.len(), 6);
+    assert_eq!(regions[0].dst, Rect::new(0.0, 0.0, 30.0, 30.0));
+}
@@ -12,7 +12,7 @@ impl ElementCx<'_> {
 
         // TODO: Only apply clip if element has transparency
         let has_outset_shadow = box_shadow.iter().any(|s| !s.inset);
-        if !has_outset_shadow {
+        if !has_outset_shadow || self.forced_colors().is_some() {
             return;
         }
 
@@ -74,7 +74,7 @@ impl ElementCx<'_> {
         let current_color = self.style.clone_color();
         let box_shadow = &self.style.get_effects().box_shadow.0;
         let has_inset_shadow = box_shadow.iter().any(|s| s.inset);
-        if !has_inset_shadow {
+        if !has_inset_shadow || self.forced_colors().is_some() {
             return;
         }
 
@@ -93,21 +93,28 @@ impl ElementCx<'_> {
                         .as_srgb_color();
                     if shadow_color != Color::TRANSPARENT {
                         let transform = self.transform.then_translate(Vec2 {
-                            x: shadow.base.horizontal.px() as f64,
-                            y: shadow.base.vertical.px() as f64,
+                            x: shadow.base.horizontal.px() as f64 * self.scale,
+                            y: shadow.base.vertical.px() as f64 * self.scale,
                         });
 
                         //TODO draw shadows with matching individual radii instead of averaging
                         let radius = self.frame.border_radii.average();
 
+                        // Inset spread shrinks the unshadowed "hole" that the shadow is cast around.
+                        let spread = shadow.spread.px() as f64 * self.scale;
+                        let pb = self.frame.padding_box;
+                        let sx = spread.min(pb.width() / 2.0);
+                        let sy = spread.min(pb.height() / 2.0);
+                        let hole = Rect::new(pb.x0 + sx, pb.y0 + sy, pb.x1 - sx, pb.y1 - sy);
+                        let radius = (radius - spread).max(0.0);
+
                         // Fill the color
-                        // Encode inset by negating std_dev so renderer can distinguish without new trait API.
-                        scene.draw_box_shadow(
+                        scene.draw_inset_box_shadow(
                             transform,
-                            self.frame.border_box,
+                            hole,
                             shadow_color,
                             radius,
-                            -(shadow.base.blur.px() as f64 * self.scale),
+                            shadow.base.blur.px() as f64 * self.scale,
                         );
                     }
                 }
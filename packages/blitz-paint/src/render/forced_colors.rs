@@ -0,0 +1,76 @@
+use super::{BlitzDomPainter, ElementCx};
+use crate::color::Color;
+use blitz_dom::{ForcedColorScheme, Node, local_name};
+
+/// Which pair of system colors an element is painted with when forced colors are active
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ForcedColorRole {
+    Text,
+    Link,
+    Button,
+}
+
+impl ForcedColorRole {
+    pub(crate) fn foreground(self, scheme: &ForcedColorScheme) -> Color {
+        match self {
+            ForcedColorRole::Text => scheme.canvas_text,
+            ForcedColorRole::Link => scheme.link_text,
+            ForcedColorRole::Button => scheme.button_text,
+        }
+    }
+
+    pub(crate) fn background(self, scheme: &ForcedColorScheme) -> Color {
+        match self {
+            ForcedColorRole::Button => scheme.button_face,
+            ForcedColorRole::Text | ForcedColorRole::Link => scheme.canvas,
+        }
+    }
+}
+
+fn own_role(node: &Node) -> Option<ForcedColorRole> {
+    let element = node.element_data()?;
+    match element.name.local.as_ref() {
+        "a" if element.attr(local_name!("href")).is_some() => Some(ForcedColorRole::Link),
+        "button" => Some(ForcedColorRole::Button),
+        "input" => matches!(
+            element.attr(local_name!("type")),
+            Some("button" | "submit" | "reset")
+        )
+        .then_some(ForcedColorRole::Button),
+        _ => None,
+    }
+}
+
+impl BlitzDomPainter<'_> {
+    pub(super) fn forced_colors(&self) -> Option<&ForcedColorScheme> {
+        self.dom.forced_colors()
+    }
+
+    /// The role of the nearest link or button enclosing `node_id` (inclusive)
+    pub(super) fn forced_color_role(&self, node_id: usize) -> ForcedColorRole {
+        let mut current = self.dom.get_node(node_id);
+        while let Some(node) = current {
+            if let Some(role) = own_role(node) {
+                return role;
+            }
+            current = node.parent.and_then(|id| self.dom.get_node(id));
+        }
+        ForcedColorRole::Text
+    }
+}
+
+impl ElementCx<'_> {
+    /// The system color replacing this element's text, border and outline colors, if forced
+    /// colors are active
+    pub(super) fn forced_foreground(&self) -> Option<Color> {
+        let scheme = self.forced_colors()?;
+        Some(self.forced_color_role(self.node.id).foreground(scheme))
+    }
+
+    /// The system color replacing this element's (non-transparent) background color, if forced
+    /// colors are active
+    pub(super) fn forced_background(&self) -> Option<Color> {
+        let scheme = self.forced_colors()?;
+        Some(self.forced_color_role(self.node.id).background(scheme))
+    }
+}
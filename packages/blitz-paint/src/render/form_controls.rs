@@ -24,6 +24,10 @@ impl ElementCx<'_> {
         } else {
             self.style.clone_color().as_srgb_color()
         };
+        let (accent_color, surface_color) = match self.forced_colors() {
+            Some(scheme) => (scheme.canvas_text, scheme.canvas),
+            None => (accent_color, Color::WHITE),
+        };
 
         let width = self.frame.border_box.width();
         let height = self.frame.border_box.height();
@@ -34,11 +38,27 @@ impl ElementCx<'_> {
 
         match type_attr {
             Some("checkbox") => {
-                draw_checkbox(scene, checked, frame, self.transform, accent_color, scale);
+                draw_checkbox(
+                    scene,
+                    checked,
+                    frame,
+                    self.transform,
+                    accent_color,
+                    surface_color,
+                    scale,
+                );
             }
             Some("radio") => {
                 let center = frame.center();
-                draw_radio_button(scene, checked, center, self.transform, accent_color, scale);
+                draw_radio_button(
+                    scene,
+                    checked,
+                    center,
+                    self.transform,
+                    accent_color,
+                    surface_color,
+                    scale,
+                );
             }
             _ => {}
         }
@@ -51,6 +71,7 @@ fn draw_checkbox(
     frame: RoundedRect,
     transform: Affine,
     accent_color: Color,
+    surface_color: Color,
     scale: f64,
 ) {
     if checked {
@@ -73,9 +94,9 @@ fn draw_checkbox(
             dash_offset: 0.0,
         };
 
-        scene.stroke(&style, transform, Color::WHITE, None, &path);
+        scene.stroke(&style, transform, surface_color, None, &path);
     } else {
-        scene.fill(Fill::NonZero, transform, Color::WHITE, None, &frame);
+        scene.fill(Fill::NonZero, transform, surface_color, None, &frame);
         scene.stroke(&Stroke::default(), transform, accent_color, None, &frame);
     }
 }
@@ -86,6 +107,7 @@ fn draw_radio_button(
     center: Point,
     transform: Affine,
     accent_color: Color,
+    surface_color: Color,
     scale: f64,
 ) {
     let outer_ring = Circle::new(center, 8.0 * scale);
@@ -93,11 +115,11 @@ fn draw_radio_button(
     let inner_circle = Circle::new(center, 4.0 * scale);
     if checked {
         scene.fill(Fill::NonZero, transform, accent_color, None, &outer_ring);
-        scene.fill(Fill::NonZero, transform, Color::WHITE, None, &gap);
+        scene.fill(Fill::NonZero, transform, surface_color, None, &gap);
         scene.fill(Fill::NonZero, transform, accent_color, None, &inner_circle);
     } else {
         const GRAY: Color = color::palette::css::GRAY;
         scene.fill(Fill::NonZero, transform, GRAY, None, &outer_ring);
-        scene.fill(Fill::NonZero, transform, Color::WHITE, None, &gap);
+        scene.fill(Fill::NonZero, transform, surface_color, None, &gap);
     }
 }
@@ -1,19 +1,43 @@
 use anyrender::PaintScene;
 use blitz_dom::node::TextBrush;
-use kurbo::{Affine, Point, Stroke, RoundedRect};
-use parley::{Line, PositionedLayoutItem};
-use peniko::Fill;
+use kurbo::{Affine, RoundedRect};
+use parley::{Layout, Line, PositionedLayoutItem};
+use peniko::{Brush, Fill};
 
+use crate::color::Color;
+
+/// `text-overflow: ellipsis` for the lines passed to [`stroke_text`]
+pub(crate) struct Ellipsis<'a> {
+    /// The shaped `…`
+    pub layout: &'a Layout<TextBrush>,
+    /// The end edge of the content box, in layout coordinates. Glyphs of a line reaching past it
+    /// are cut so that the ellipsis fits before it.
+    pub clip_x: f32,
+}
+
+/// Draw the glyph runs (and their inline backgrounds, text shadows and decorations) of `lines`.
+///
+/// `forced_color` maps a run's span node id to the color it is painted with when forced colors
+/// are active; inline backgrounds are dropped in that case.
 pub(crate) fn stroke_text<'a>(
     scale: f64,
     scene: &mut impl PaintScene,
     lines: impl Iterator<Item = Line<'a, TextBrush>>,
-    pos: Point,
+    transform: Affine,
+    forced_color: Option<&dyn Fn(usize) -> Color>,
+    ellipsis: Option<&Ellipsis>,
 ) {
-    let transform = Affine::translate((pos.x * scale, pos.y * scale));
     for line in lines {
+        // Where glyphs of an overflowing line stop to leave room for the ellipsis
+        let limit = ellipsis.and_then(|ellipsis| truncation_limit(&line, ellipsis));
+        // End of the last glyph drawn on a truncated line, where the ellipsis goes
+        let mut kept_end = line.metrics().offset;
         for item in line.items() {
             if let PositionedLayoutItem::GlyphRun(glyph_run) = item {
+                // Space reserved for a shaped list bullet; draw_marker paints it
+                if glyph_run.style().brush.placeholder {
+                    continue;
+                }
                 let mut x = glyph_run.offset();
                 let y = glyph_run.baseline();
 
@@ -29,8 +53,10 @@ pub(crate) fn stroke_text<'a>(
 
                 // Use weight propagated via TextBrush; treat 0 as normal (400)
                 let weight: u16 = if style.brush.weight == 0 { 400 } else { style.brush.weight };
+                let forced_brush = forced_color.map(|f| Brush::Solid(f(style.brush.id)));
                 // Draw background rect for inline background if present
-                if let Some(bg_brush) = &style.brush.background {
+                let background = style.brush.background.as_ref();
+                if let Some(bg_brush) = background.filter(|_| forced_brush.is_none()) {
                     if let peniko::Brush::Solid(color) = bg_brush {
                         if color.components[3] > 0.0 {
                             // Base glyph run box
@@ -55,6 +81,54 @@ pub(crate) fn stroke_text<'a>(
                         }
                     }
                 }
+                let mut glyphs: Vec<anyrender::Glyph> = glyph_run
+                    .glyphs()
+                    .map(|glyph| {
+                        let gx = x + glyph.x;
+                        let gy = y - glyph.y;
+                        x += glyph.advance;
+
+                        anyrender::Glyph::new(glyph.id as _, gx, gy).with_advance(glyph.advance)
+                    })
+                    .filter(|glyph| limit.is_none_or(|limit| glyph.x + glyph.advance <= limit))
+                    .collect();
+                // Width actually drawn, for decorations on a truncated run
+                let run_advance = match limit {
+                    Some(_) => {
+                        let end = glyphs.last().map_or(glyph_run.offset(), |g| g.x + g.advance);
+                        kept_end = kept_end.max(end);
+                        end - glyph_run.offset()
+                    }
+                    None => glyph_run.advance(),
+                };
+                if glyphs.is_empty() {
+                    continue;
+                }
+                // Glyphs come in visual order; hand right-to-left runs over in logical order so the
+                // backend sees x decreasing and can shape the run with an odd bidi level
+                if run.is_rtl() {
+                    glyphs.reverse();
+                }
+
+                // Text shadows go under the glyphs, the first specified on top. Forced colors drop them.
+                let shadows = style.brush.shadows.iter().rev().filter(|_| forced_brush.is_none());
+                for shadow in shadows {
+                    let offset = Affine::translate((shadow.offset_x as f64 * scale, shadow.offset_y as f64 * scale));
+                    scene.draw_glyph_shadow(
+                        font,
+                        &style.brush.family,
+                        font_size,
+                        weight,
+                        run.normalized_coords(),
+                        shadow.color,
+                        // The blur radius is twice the gaussian's standard deviation
+                        shadow.blur as f64 * scale / 2.0,
+                        offset * transform,
+                        glyph_xform,
+                        glyphs.iter().copied(),
+                    );
+                }
+
                 scene.draw_glyphs(
                     font,
                     &style.brush.family,
@@ -63,34 +137,23 @@ pub(crate) fn stroke_text<'a>(
                     true, // hint
                     run.normalized_coords(),
                     Fill::NonZero,
-                    &style.brush.brush,
+                    forced_brush.as_ref().unwrap_or(&style.brush.brush),
                     1.0, // alpha
                     transform,
                     glyph_xform,
-                    glyph_run.glyphs().map(|glyph| {
-                        let gx = x + glyph.x;
-                        let gy = y - glyph.y;
-                        x += glyph.advance;
-
-                        anyrender::Glyph {
-                            id: glyph.id as _,
-                            x: gx,
-                            y: gy,
-                        }
-                    }),
+                    glyphs.into_iter(),
                 );
 
                 let mut draw_decoration_line = |offset: f32, size: f32, brush: &TextBrush| {
                     let x = glyph_run.offset() as f64;
-                    let w = glyph_run.advance() as f64;
+                    let w = run_advance as f64;
                     let y = (glyph_run.baseline() - offset + size / 2.0) as f64;
                     let line = kurbo::Line::new((x, y), (x + w, y));
-                    scene.stroke(
-                        &Stroke::new(size as f64),
+                    scene.draw_text_decoration(
                         transform,
-                        &brush.brush,
-                        None,
-                        &line,
+                        forced_brush.as_ref().unwrap_or(&brush.brush),
+                        line,
+                        size as f64,
                     )
                 };
 
@@ -109,5 +172,30 @@ pub(crate) fn stroke_text<'a>(
                 }
             }
         }
+        if let (Some(ellipsis), Some(_)) = (ellipsis, limit) {
+            // Place the ellipsis' own baseline on the line's
+            let ellipsis_baseline = ellipsis.layout.lines().next().map_or(0.0, |l| l.metrics().baseline);
+            let offset = Affine::translate((kept_end as f64, (line.metrics().baseline - ellipsis_baseline) as f64));
+            stroke_text(scale, scene, ellipsis.layout.lines(), transform * offset, forced_color, None);
+        }
+    }
+}
+
+/// Where glyphs of `line` must end for the ellipsis to fit before the clip edge, or None if the line
+/// fits. Only left-to-right lines are truncated.
+fn truncation_limit(line: &Line<'_, TextBrush>, ellipsis: &Ellipsis) -> Option<f32> {
+    let mut line_end = line.metrics().offset;
+    for item in line.items() {
+        if let PositionedLayoutItem::GlyphRun(glyph_run) = item {
+            if glyph_run.run().is_rtl() {
+                return None;
+            }
+            line_end = line_end.max(glyph_run.offset() + glyph_run.advance());
+        }
+    }
+    // Allow for rounding of the content box width
+    if line_end <= ellipsis.clip_x + 0.5 {
+        return None;
     }
+    Some(ellipsis.clip_x - ellipsis.layout.full_width())
 }
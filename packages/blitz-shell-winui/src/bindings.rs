@@ -223,231 +223,1514 @@ impl Host {
             .ok()
         }
     }
-    pub fn CreateInstance<P0>(
-        attacher: P0,
-        width: u32,
-        height: u32,
-        scale: f32,
-        initialhtml: &windows_core::HSTRING,
-    ) -> windows_core::Result<Host>
-    where
-        P0: windows_core::Param<windows_core::IInspectable>,
-    {
-        Self::IHostFactory(|this| unsafe {
+    pub fn Copy(&self) -> windows_core::Result<windows_core::HSTRING> {
+        let this = self;
+        unsafe {
             let mut result__ = core::mem::zeroed();
-            (windows_core::Interface::vtable(this).CreateInstance)(
+            (windows_core::Interface::vtable(this).Copy)(
                 windows_core::Interface::as_raw(this),
-                attacher.param().abi(),
-                width,
-                height,
-                scale,
-                core::mem::transmute_copy(initialhtml),
                 &mut result__,
             )
-            .and_then(|| windows_core::Type::from_abi(result__))
-        })
+            .map(|| core::mem::transmute(result__))
+        }
     }
-    fn IHostFactory<R, F: FnOnce(&IHostFactory) -> windows_core::Result<R>>(
-        callback: F,
-    ) -> windows_core::Result<R> {
-        static SHARED: windows_core::imp::FactoryCache<Host, IHostFactory> =
-            windows_core::imp::FactoryCache::new();
-        SHARED.call(callback)
+    pub fn Cut(&self) -> windows_core::Result<windows_core::HSTRING> {
+        let this = self;
+        unsafe {
+            let mut result__ = core::mem::zeroed();
+            (windows_core::Interface::vtable(this).Cut)(
+                windows_core::Interface::as_raw(this),
+                &mut result__,
+            )
+            .map(|| core::mem::transmute(result__))
+        }
     }
-}
-impl windows_core::RuntimeType for Host {
-    const SIGNATURE: windows_core::imp::ConstBuffer =
-        windows_core::imp::ConstBuffer::for_class::<Self, IHost>();
-}
-unsafe impl windows_core::Interface for Host {
-    type Vtable = <IHost as windows_core::Interface>::Vtable;
-    const IID: windows_core::GUID = <IHost as windows_core::Interface>::IID;
-}
-impl windows_core::RuntimeName for Host {
-    const NAME: &'static str = "BlitzWinUI.Host";
-}
-unsafe impl Send for Host {}
-unsafe impl Sync for Host {}
-windows_core::imp::define_interface!(IHost, IHost_Vtbl, 0xaa6fb944_a0b6_5aa2_b960_b8fbdb2394c9);
-impl windows_core::RuntimeType for IHost {
-    const SIGNATURE: windows_core::imp::ConstBuffer =
-        windows_core::imp::ConstBuffer::for_interface::<Self>();
-}
-impl windows_core::RuntimeName for IHost {
-    const NAME: &'static str = "BlitzWinUI.IHost";
-}
-pub trait IHost_Impl: windows_core::IUnknownImpl {
-    fn SetPanel(
-        &self,
-        attacher: windows_core::Ref<'_, windows_core::IInspectable>,
-    ) -> windows_core::Result<()>;
-    fn Resize(&self, width: u32, height: u32, scale: f32) -> windows_core::Result<()>;
-    fn RenderOnce(&self) -> windows_core::Result<()>;
-    fn LoadHtml(&self, html: &windows_core::HSTRING) -> windows_core::Result<()>;
-    fn SetVerboseLogging(&self, enabled: bool) -> windows_core::Result<()>;
-    fn SetDebugOverlay(&self, enabled: bool) -> windows_core::Result<()>;
-    fn SetNetworkFetcher(
-        &self,
-        fetcher: windows_core::Ref<'_, windows_core::IInspectable>,
-    ) -> windows_core::Result<()>;
-    fn CompleteFetch(
+    pub fn Paste(&self, text: &windows_core::HSTRING) -> windows_core::Result<()> {
+        let this = self;
+        unsafe {
+            (windows_core::Interface::vtable(this).Paste)(
+                windows_core::Interface::as_raw(this),
+                core::mem::transmute_copy(text),
+            )
+            .ok()
+        }
+    }
+    pub fn GetFrameTimingsJson(&self) -> windows_core::Result<windows_core::HSTRING> {
+        let this = self;
+        unsafe {
+            let mut result__ = core::mem::zeroed();
+            (windows_core::Interface::vtable(this).GetFrameTimingsJson)(
+                windows_core::Interface::as_raw(this),
+                &mut result__,
+            )
+            .map(|| core::mem::transmute(result__))
+        }
+    }
+    pub fn ScrollToNode(&self, nodeid: u32) -> windows_core::Result<()> {
+        let this = self;
+        unsafe {
+            (windows_core::Interface::vtable(this).ScrollToNode)(
+                windows_core::Interface::as_raw(this),
+                nodeid,
+            )
+            .ok()
+        }
+    }
+    pub fn ScrollToTop(&self) -> windows_core::Result<()> {
+        let this = self;
+        unsafe {
+            (windows_core::Interface::vtable(this).ScrollToTop)(windows_core::Interface::as_raw(
+                this,
+            ))
+            .ok()
+        }
+    }
+    pub fn ScrollToBottom(&self) -> windows_core::Result<()> {
+        let this = self;
+        unsafe {
+            (windows_core::Interface::vtable(this).ScrollToBottom)(windows_core::Interface::as_raw(
+                this,
+            ))
+            .ok()
+        }
+    }
+    pub fn ScrollBy(&self, dx: f64, dy: f64) -> windows_core::Result<()> {
+        let this = self;
+        unsafe {
+            (windows_core::Interface::vtable(this).ScrollBy)(
+                windows_core::Interface::as_raw(this),
+                dx,
+                dy,
+            )
+            .ok()
+        }
+    }
+    pub fn ScrollByPage(&self, pages: i32) -> windows_core::Result<()> {
+        let this = self;
+        unsafe {
+            (windows_core::Interface::vtable(this).ScrollByPage)(
+                windows_core::Interface::as_raw(this),
+                pages,
+            )
+            .ok()
+        }
+    }
+    pub fn ImeSetComposition(
         &self,
-        requestId: u32,
-        docId: u32,
-        success: bool,
-        data: &[u8],
-        errorMessage: &windows_core::HSTRING,
-    ) -> windows_core::Result<()>;
-    fn RequestUrl(
+        text: &windows_core::HSTRING,
+        cursorstart: u32,
+        cursorend: u32,
+    ) -> windows_core::Result<()> {
+        let this = self;
+        unsafe {
+            (windows_core::Interface::vtable(this).ImeSetComposition)(
+                windows_core::Interface::as_raw(this),
+                core::mem::transmute_copy(text),
+                cursorstart,
+                cursorend,
+            )
+            .ok()
+        }
+    }
+    pub fn ImeCommit(&self, text: &windows_core::HSTRING) -> windows_core::Result<()> {
+        let this = self;
+        unsafe {
+            (windows_core::Interface::vtable(this).ImeCommit)(
+                windows_core::Interface::as_raw(this),
+                core::mem::transmute_copy(text),
+            )
+            .ok()
+        }
+    }
+    pub fn ImeCancel(&self) -> windows_core::Result<()> {
+        let this = self;
+        unsafe {
+            (windows_core::Interface::vtable(this).ImeCancel)(windows_core::Interface::as_raw(this))
+                .ok()
+        }
+    }
+    pub fn GetImeCaretRect(&self) -> windows_core::Result<windows_core::HSTRING> {
+        let this = self;
+        unsafe {
+            let mut result__ = core::mem::zeroed();
+            (windows_core::Interface::vtable(this).GetImeCaretRect)(
+                windows_core::Interface::as_raw(this),
+                &mut result__,
+            )
+            .map(|| core::mem::transmute(result__))
+        }
+    }
+    pub fn DumpLastScene(&self) -> windows_core::Result<windows_core::HSTRING> {
+        let this = self;
+        unsafe {
+            let mut result__ = core::mem::zeroed();
+            (windows_core::Interface::vtable(this).DumpLastScene)(
+                windows_core::Interface::as_raw(this),
+                &mut result__,
+            )
+            .map(|| core::mem::transmute(result__))
+        }
+    }
+    pub fn SetRasterizationScale(&self, scale: f32) -> windows_core::Result<()> {
+        let this = self;
+        unsafe {
+            (windows_core::Interface::vtable(this).SetRasterizationScale)(
+                windows_core::Interface::as_raw(this),
+                scale,
+            )
+            .ok()
+        }
+    }
+    pub fn SetInspectOverlay(&self, enabled: bool) -> windows_core::Result<()> {
+        let this = self;
+        unsafe {
+            (windows_core::Interface::vtable(this).SetInspectOverlay)(
+                windows_core::Interface::as_raw(this),
+                enabled,
+            )
+            .ok()
+        }
+    }
+    pub fn SetFirstFrameListener<P0>(&self, listener: P0) -> windows_core::Result<()>
+    where
+        P0: windows_core::Param<windows_core::IInspectable>,
+    {
+        let this = self;
+        unsafe {
+            (windows_core::Interface::vtable(this).SetFirstFrameListener)(
+                windows_core::Interface::as_raw(this),
+                listener.param().abi(),
+            )
+            .ok()
+        }
+    }
+    pub fn SetForcedColors(
         &self,
-        docId: u32,
-        url: &windows_core::HSTRING,
-        requestId: u32,
-    ) -> windows_core::Result<()>;
-    fn TestAttacherConnection(&self) -> windows_core::Result<bool>;
-    fn WheelScroll(&self, dx: f64, dy: f64) -> windows_core::Result<()>;
-    fn PointerMove(&self, x: f32, y: f32, buttons: u32, modifiers: u32)
-        -> windows_core::Result<()>;
-    fn PointerDown(
+        enabled: bool,
+        canvas: u32,
+        canvastext: u32,
+        linktext: u32,
+        buttonface: u32,
+        buttontext: u32,
+        highlight: u32,
+    ) -> windows_core::Result<()> {
+        let this = self;
+        unsafe {
+            (windows_core::Interface::vtable(this).SetForcedColors)(
+                windows_core::Interface::as_raw(this),
+                enabled,
+                canvas,
+                canvastext,
+                linktext,
+                buttonface,
+                buttontext,
+                highlight,
+            )
+            .ok()
+        }
+    }
+    pub fn WheelScrollWithModifiers(
         &self,
-        x: f32,
-        y: f32,
-        button: u8,
-        buttons: u32,
+        dx: f64,
+        dy: f64,
         modifiers: u32,
-    ) -> windows_core::Result<()>;
-    fn PointerUp(
+    ) -> windows_core::Result<()> {
+        let this = self;
+        unsafe {
+            (windows_core::Interface::vtable(this).WheelScrollWithModifiers)(
+                windows_core::Interface::as_raw(this),
+                dx,
+                dy,
+                modifiers,
+            )
+            .ok()
+        }
+    }
+    pub fn SetZoom(&self, zoom: f32) -> windows_core::Result<()> {
+        let this = self;
+        unsafe {
+            (windows_core::Interface::vtable(this).SetZoom)(
+                windows_core::Interface::as_raw(this),
+                zoom,
+            )
+            .ok()
+        }
+    }
+    pub fn GetZoom(&self) -> windows_core::Result<f32> {
+        let this = self;
+        unsafe {
+            let mut result__ = core::mem::zeroed();
+            (windows_core::Interface::vtable(this).GetZoom)(
+                windows_core::Interface::as_raw(this),
+                &mut result__,
+            )
+            .map(|| result__)
+        }
+    }
+    pub fn CapturePng(
         &self,
-        x: f32,
-        y: f32,
-        button: u8,
-        buttons: u32,
-        modifiers: u32,
-    ) -> windows_core::Result<()>;
-    fn ReportAttachSubPhase(&self, kind: u8, ms: f32) -> windows_core::Result<()>;
-}
-impl IHost_Vtbl {
-    pub const fn new<Identity: IHost_Impl, const OFFSET: isize>() -> Self {
-        unsafe extern "system" fn SetPanel<Identity: IHost_Impl, const OFFSET: isize>(
+        width: u32,
+        height: u32,
+        fullpage: bool,
+    ) -> windows_core::Result<windows_core::Array<u8>> {
+        let this = self;
+        unsafe {
+            let mut result__ = core::mem::MaybeUninit::zeroed();
+            (windows_core::Interface::vtable(this).CapturePng)(
+                windows_core::Interface::as_raw(this),
+                width,
+                height,
+                fullpage,
+                windows_core::Array::<u8>::set_abi_len(core::mem::transmute(&mut result__)),
+                result__.as_mut_ptr() as *mut _ as _,
+            )
+            .map(|| result__.assume_init())
+        }
+    }
+    pub fn RequestUrlWithOptions(
+        &self,
+        docid: u32,
+        url: &windows_core::HSTRING,
+        requestid: u32,
+        method: &windows_core::HSTRING,
+        headers: &windows_core::HSTRING,
+        body: &[u8],
+    ) -> windows_core::Result<()> {
+        let this = self;
+        unsafe {
+            (windows_core::Interface::vtable(this).RequestUrlWithOptions)(
+                windows_core::Interface::as_raw(this),
+                docid,
+                core::mem::transmute_copy(url),
+                requestid,
+                core::mem::transmute_copy(method),
+                core::mem::transmute_copy(headers),
+                body.len().try_into().unwrap(),
+                body.as_ptr(),
+            )
+            .ok()
+        }
+    }
+    pub fn IsContentLoaded(&self) -> windows_core::Result<bool> {
+        let this = self;
+        unsafe {
+            let mut result__ = core::mem::zeroed();
+            (windows_core::Interface::vtable(this).IsContentLoaded)(
+                windows_core::Interface::as_raw(this),
+                &mut result__,
+            )
+            .map(|| result__)
+        }
+    }
+    pub fn SetTargetFps(&self, fps: f32) -> windows_core::Result<()> {
+        let this = self;
+        unsafe {
+            (windows_core::Interface::vtable(this).SetTargetFps)(
+                windows_core::Interface::as_raw(this),
+                fps,
+            )
+            .ok()
+        }
+    }
+    pub fn HitTest(&self, x: f32, y: f32) -> windows_core::Result<windows_core::HSTRING> {
+        let this = self;
+        unsafe {
+            let mut result__ = core::mem::zeroed();
+            (windows_core::Interface::vtable(this).HitTest)(
+                windows_core::Interface::as_raw(this),
+                x,
+                y,
+                &mut result__,
+            )
+            .map(|| core::mem::transmute(result__))
+        }
+    }
+    pub fn SetNavigationListener<P0>(&self, listener: P0) -> windows_core::Result<()>
+    where
+        P0: windows_core::Param<windows_core::IInspectable>,
+    {
+        let this = self;
+        unsafe {
+            (windows_core::Interface::vtable(this).SetNavigationListener)(
+                windows_core::Interface::as_raw(this),
+                listener.param().abi(),
+            )
+            .ok()
+        }
+    }
+    pub fn ScrollToFragment(&self, fragment: &windows_core::HSTRING) -> windows_core::Result<bool> {
+        let this = self;
+        unsafe {
+            let mut result__ = core::mem::zeroed();
+            (windows_core::Interface::vtable(this).ScrollToFragment)(
+                windows_core::Interface::as_raw(this),
+                core::mem::transmute_copy(fragment),
+                &mut result__,
+            )
+            .map(|| result__)
+        }
+    }
+    pub fn GetCommandStatsJson(&self) -> windows_core::Result<windows_core::HSTRING> {
+        let this = self;
+        unsafe {
+            let mut result__ = core::mem::zeroed();
+            (windows_core::Interface::vtable(this).GetCommandStatsJson)(
+                windows_core::Interface::as_raw(this),
+                &mut result__,
+            )
+            .map(|| core::mem::transmute(result__))
+        }
+    }
+    pub fn Unload(&self) -> windows_core::Result<()> {
+        let this = self;
+        unsafe {
+            (windows_core::Interface::vtable(this).Unload)(windows_core::Interface::as_raw(this))
+                .ok()
+        }
+    }
+    pub fn WantsAnimationFrame(&self) -> windows_core::Result<bool> {
+        let this = self;
+        unsafe {
+            let mut result__ = core::mem::zeroed();
+            (windows_core::Interface::vtable(this).WantsAnimationFrame)(
+                windows_core::Interface::as_raw(this),
+                &mut result__,
+            )
+            .map(|| result__)
+        }
+    }
+    pub fn SetBaseUrl(&self, url: &windows_core::HSTRING) -> windows_core::Result<()> {
+        let this = self;
+        unsafe {
+            (windows_core::Interface::vtable(this).SetBaseUrl)(
+                windows_core::Interface::as_raw(this),
+                core::mem::transmute_copy(url),
+            )
+            .ok()
+        }
+    }
+    pub fn BeginStream(&self) -> windows_core::Result<()> {
+        let this = self;
+        unsafe {
+            (windows_core::Interface::vtable(this).BeginStream)(windows_core::Interface::as_raw(
+                this,
+            ))
+            .ok()
+        }
+    }
+    pub fn PushHtmlChunk(&self, bytes: &[u8]) -> windows_core::Result<bool> {
+        let this = self;
+        unsafe {
+            let mut result__ = core::mem::zeroed();
+            (windows_core::Interface::vtable(this).PushHtmlChunk)(
+                windows_core::Interface::as_raw(this),
+                bytes.len().try_into().unwrap(),
+                bytes.as_ptr(),
+                &mut result__,
+            )
+            .map(|| result__)
+        }
+    }
+    pub fn EndStream(&self) -> windows_core::Result<bool> {
+        let this = self;
+        unsafe {
+            let mut result__ = core::mem::zeroed();
+            (windows_core::Interface::vtable(this).EndStream)(
+                windows_core::Interface::as_raw(this),
+                &mut result__,
+            )
+            .map(|| result__)
+        }
+    }
+    pub fn GetCursor(&self, x: f32, y: f32) -> windows_core::Result<i32> {
+        let this = self;
+        unsafe {
+            let mut result__ = core::mem::zeroed();
+            (windows_core::Interface::vtable(this).GetCursor)(
+                windows_core::Interface::as_raw(this),
+                x,
+                y,
+                &mut result__,
+            )
+            .map(|| result__)
+        }
+    }
+    pub fn SetDebugOverlayOptions(
+        &self,
+        lines: u32,
+        corner: u8,
+        opacity: f32,
+    ) -> windows_core::Result<()> {
+        let this = self;
+        unsafe {
+            (windows_core::Interface::vtable(this).SetDebugOverlayOptions)(
+                windows_core::Interface::as_raw(this),
+                lines,
+                corner,
+                opacity,
+            )
+            .ok()
+        }
+    }
+    pub fn LoadUrl(&self, url: &windows_core::HSTRING) -> windows_core::Result<bool> {
+        let this = self;
+        unsafe {
+            let mut result__ = core::mem::zeroed();
+            (windows_core::Interface::vtable(this).LoadUrl)(
+                windows_core::Interface::as_raw(this),
+                core::mem::transmute_copy(url),
+                &mut result__,
+            )
+            .map(|| result__)
+        }
+    }
+    pub fn SetColorScheme(&self, scheme: u8) -> windows_core::Result<()> {
+        let this = self;
+        unsafe {
+            (windows_core::Interface::vtable(this).SetColorScheme)(
+                windows_core::Interface::as_raw(this),
+                scheme,
+            )
+            .ok()
+        }
+    }
+    pub fn TrimMemory(&self, level: u8) -> windows_core::Result<()> {
+        let this = self;
+        unsafe {
+            (windows_core::Interface::vtable(this).TrimMemory)(
+                windows_core::Interface::as_raw(this),
+                level,
+            )
+            .ok()
+        }
+    }
+    pub fn CreateInstance<P0>(
+        attacher: P0,
+        width: u32,
+        height: u32,
+        scale: f32,
+        initialhtml: &windows_core::HSTRING,
+    ) -> windows_core::Result<Host>
+    where
+        P0: windows_core::Param<windows_core::IInspectable>,
+    {
+        Self::IHostFactory(|this| unsafe {
+            let mut result__ = core::mem::zeroed();
+            (windows_core::Interface::vtable(this).CreateInstance)(
+                windows_core::Interface::as_raw(this),
+                attacher.param().abi(),
+                width,
+                height,
+                scale,
+                core::mem::transmute_copy(initialhtml),
+                &mut result__,
+            )
+            .and_then(|| windows_core::Type::from_abi(result__))
+        })
+    }
+    fn IHostFactory<R, F: FnOnce(&IHostFactory) -> windows_core::Result<R>>(
+        callback: F,
+    ) -> windows_core::Result<R> {
+        static SHARED: windows_core::imp::FactoryCache<Host, IHostFactory> =
+            windows_core::imp::FactoryCache::new();
+        SHARED.call(callback)
+    }
+}
+impl windows_core::RuntimeType for Host {
+    const SIGNATURE: windows_core::imp::ConstBuffer =
+        windows_core::imp::ConstBuffer::for_class::<Self, IHost>();
+}
+unsafe impl windows_core::Interface for Host {
+    type Vtable = <IHost as windows_core::Interface>::Vtable;
+    const IID: windows_core::GUID = <IHost as windows_core::Interface>::IID;
+}
+impl windows_core::RuntimeName for Host {
+    const NAME: &'static str = "BlitzWinUI.Host";
+}
+unsafe impl Send for Host {}
+unsafe impl Sync for Host {}
+windows_core::imp::define_interface!(
+    IFirstFrameListener,
+    IFirstFrameListener_Vtbl,
+    0x7c1e5a42_3b8d_4f6e_9a21_d4c8b0f37e65
+);
+impl windows_core::RuntimeType for IFirstFrameListener {
+    const SIGNATURE: windows_core::imp::ConstBuffer =
+        windows_core::imp::ConstBuffer::for_interface::<Self>();
+}
+windows_core::imp::interface_hierarchy!(
+    IFirstFrameListener,
+    windows_core::IUnknown,
+    windows_core::IInspectable
+);
+impl IFirstFrameListener {
+    pub fn OnFirstFrame(&self, timingsjson: &windows_core::HSTRING) -> windows_core::Result<()> {
+        let this = self;
+        unsafe {
+            (windows_core::Interface::vtable(this).OnFirstFrame)(
+                windows_core::Interface::as_raw(this),
+                core::mem::transmute_copy(timingsjson),
+            )
+            .ok()
+        }
+    }
+}
+impl windows_core::RuntimeName for IFirstFrameListener {
+    const NAME: &'static str = "BlitzWinUI.IFirstFrameListener";
+}
+pub trait IFirstFrameListener_Impl: windows_core::IUnknownImpl {
+    fn OnFirstFrame(&self, timingsJson: &windows_core::HSTRING) -> windows_core::Result<()>;
+}
+impl IFirstFrameListener_Vtbl {
+    pub const fn new<Identity: IFirstFrameListener_Impl, const OFFSET: isize>() -> Self {
+        unsafe extern "system" fn OnFirstFrame<
+            Identity: IFirstFrameListener_Impl,
+            const OFFSET: isize,
+        >(
+            this: *mut core::ffi::c_void,
+            timingsjson: *mut core::ffi::c_void,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                IFirstFrameListener_Impl::OnFirstFrame(this, core::mem::transmute(&timingsjson))
+                    .into()
+            }
+        }
+        Self {
+            base__: windows_core::IInspectable_Vtbl::new::<Identity, IFirstFrameListener, OFFSET>(),
+            OnFirstFrame: OnFirstFrame::<Identity, OFFSET>,
+        }
+    }
+    pub fn matches(iid: &windows_core::GUID) -> bool {
+        iid == &<IFirstFrameListener as windows_core::Interface>::IID
+    }
+}
+#[repr(C)]
+#[doc(hidden)]
+pub struct IFirstFrameListener_Vtbl {
+    pub base__: windows_core::IInspectable_Vtbl,
+    pub OnFirstFrame: unsafe extern "system" fn(
+        *mut core::ffi::c_void,
+        *mut core::ffi::c_void,
+    ) -> windows_core::HRESULT,
+}
+windows_core::imp::define_interface!(
+    INavigationListener,
+    INavigationListener_Vtbl,
+    0x3e9f6c1d_82a4_4b57_b0d3_6f1a9c2e4d87
+);
+impl windows_core::RuntimeType for INavigationListener {
+    const SIGNATURE: windows_core::imp::ConstBuffer =
+        windows_core::imp::ConstBuffer::for_interface::<Self>();
+}
+windows_core::imp::interface_hierarchy!(
+    INavigationListener,
+    windows_core::IUnknown,
+    windows_core::IInspectable
+);
+impl INavigationListener {
+    pub fn OnNavigationRequested(
+        &self,
+        url: &windows_core::HSTRING,
+        target: &windows_core::HSTRING,
+    ) -> windows_core::Result<()> {
+        let this = self;
+        unsafe {
+            (windows_core::Interface::vtable(this).OnNavigationRequested)(
+                windows_core::Interface::as_raw(this),
+                core::mem::transmute_copy(url),
+                core::mem::transmute_copy(target),
+            )
+            .ok()
+        }
+    }
+}
+impl windows_core::RuntimeName for INavigationListener {
+    const NAME: &'static str = "BlitzWinUI.INavigationListener";
+}
+pub trait INavigationListener_Impl: windows_core::IUnknownImpl {
+    fn OnNavigationRequested(
+        &self,
+        url: &windows_core::HSTRING,
+        target: &windows_core::HSTRING,
+    ) -> windows_core::Result<()>;
+}
+impl INavigationListener_Vtbl {
+    pub const fn new<Identity: INavigationListener_Impl, const OFFSET: isize>() -> Self {
+        unsafe extern "system" fn OnNavigationRequested<
+            Identity: INavigationListener_Impl,
+            const OFFSET: isize,
+        >(
+            this: *mut core::ffi::c_void,
+            url: *mut core::ffi::c_void,
+            target: *mut core::ffi::c_void,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                INavigationListener_Impl::OnNavigationRequested(
+                    this,
+                    core::mem::transmute(&url),
+                    core::mem::transmute(&target),
+                )
+                .into()
+            }
+        }
+        Self {
+            base__: windows_core::IInspectable_Vtbl::new::<Identity, INavigationListener, OFFSET>(),
+            OnNavigationRequested: OnNavigationRequested::<Identity, OFFSET>,
+        }
+    }
+    pub fn matches(iid: &windows_core::GUID) -> bool {
+        iid == &<INavigationListener as windows_core::Interface>::IID
+    }
+}
+#[repr(C)]
+#[doc(hidden)]
+pub struct INavigationListener_Vtbl {
+    pub base__: windows_core::IInspectable_Vtbl,
+    pub OnNavigationRequested: unsafe extern "system" fn(
+        *mut core::ffi::c_void,
+        *mut core::ffi::c_void,
+        *mut core::ffi::c_void,
+    ) -> windows_core::HRESULT,
+}
+windows_core::imp::define_interface!(IHost, IHost_Vtbl, 0xaa6fb944_a0b6_5aa2_b960_b8fbdb2394c9);
+impl windows_core::RuntimeType for IHost {
+    const SIGNATURE: windows_core::imp::ConstBuffer =
+        windows_core::imp::ConstBuffer::for_interface::<Self>();
+}
+impl windows_core::RuntimeName for IHost {
+    const NAME: &'static str = "BlitzWinUI.IHost";
+}
+pub trait IHost_Impl: windows_core::IUnknownImpl {
+    fn SetPanel(
+        &self,
+        attacher: windows_core::Ref<'_, windows_core::IInspectable>,
+    ) -> windows_core::Result<()>;
+    fn Resize(&self, width: u32, height: u32, scale: f32) -> windows_core::Result<()>;
+    fn RenderOnce(&self) -> windows_core::Result<()>;
+    fn LoadHtml(&self, html: &windows_core::HSTRING) -> windows_core::Result<()>;
+    fn SetVerboseLogging(&self, enabled: bool) -> windows_core::Result<()>;
+    fn SetDebugOverlay(&self, enabled: bool) -> windows_core::Result<()>;
+    fn SetNetworkFetcher(
+        &self,
+        fetcher: windows_core::Ref<'_, windows_core::IInspectable>,
+    ) -> windows_core::Result<()>;
+    fn CompleteFetch(
+        &self,
+        requestId: u32,
+        docId: u32,
+        success: bool,
+        data: &[u8],
+        errorMessage: &windows_core::HSTRING,
+    ) -> windows_core::Result<()>;
+    fn RequestUrl(
+        &self,
+        docId: u32,
+        url: &windows_core::HSTRING,
+        requestId: u32,
+    ) -> windows_core::Result<()>;
+    fn TestAttacherConnection(&self) -> windows_core::Result<bool>;
+    fn WheelScroll(&self, dx: f64, dy: f64) -> windows_core::Result<()>;
+    fn PointerMove(&self, x: f32, y: f32, buttons: u32, modifiers: u32)
+    -> windows_core::Result<()>;
+    fn PointerDown(
+        &self,
+        x: f32,
+        y: f32,
+        button: u8,
+        buttons: u32,
+        modifiers: u32,
+    ) -> windows_core::Result<()>;
+    fn PointerUp(
+        &self,
+        x: f32,
+        y: f32,
+        button: u8,
+        buttons: u32,
+        modifiers: u32,
+    ) -> windows_core::Result<()>;
+    fn ReportAttachSubPhase(&self, kind: u8, ms: f32) -> windows_core::Result<()>;
+    fn Copy(&self) -> windows_core::Result<windows_core::HSTRING>;
+    fn Cut(&self) -> windows_core::Result<windows_core::HSTRING>;
+    fn Paste(&self, text: &windows_core::HSTRING) -> windows_core::Result<()>;
+    fn GetFrameTimingsJson(&self) -> windows_core::Result<windows_core::HSTRING>;
+    fn ScrollToNode(&self, nodeid: u32) -> windows_core::Result<()>;
+    fn ScrollToTop(&self) -> windows_core::Result<()>;
+    fn ScrollToBottom(&self) -> windows_core::Result<()>;
+    fn ScrollBy(&self, dx: f64, dy: f64) -> windows_core::Result<()>;
+    fn ScrollByPage(&self, pages: i32) -> windows_core::Result<()>;
+    fn ImeSetComposition(
+        &self,
+        text: &windows_core::HSTRING,
+        cursorstart: u32,
+        cursorend: u32,
+    ) -> windows_core::Result<()>;
+    fn ImeCommit(&self, text: &windows_core::HSTRING) -> windows_core::Result<()>;
+    fn ImeCancel(&self) -> windows_core::Result<()>;
+    fn GetImeCaretRect(&self) -> windows_core::Result<windows_core::HSTRING>;
+    fn DumpLastScene(&self) -> windows_core::Result<windows_core::HSTRING>;
+    fn SetRasterizationScale(&self, scale: f32) -> windows_core::Result<()>;
+    fn SetInspectOverlay(&self, enabled: bool) -> windows_core::Result<()>;
+    fn SetFirstFrameListener(
+        &self,
+        listener: windows_core::Ref<'_, windows_core::IInspectable>,
+    ) -> windows_core::Result<()>;
+    fn SetForcedColors(
+        &self,
+        enabled: bool,
+        canvas: u32,
+        canvastext: u32,
+        linktext: u32,
+        buttonface: u32,
+        buttontext: u32,
+        highlight: u32,
+    ) -> windows_core::Result<()>;
+    fn WheelScrollWithModifiers(
+        &self,
+        dx: f64,
+        dy: f64,
+        modifiers: u32,
+    ) -> windows_core::Result<()>;
+    fn SetZoom(&self, zoom: f32) -> windows_core::Result<()>;
+    fn GetZoom(&self) -> windows_core::Result<f32>;
+    fn CapturePng(
+        &self,
+        width: u32,
+        height: u32,
+        fullpage: bool,
+    ) -> windows_core::Result<windows_core::Array<u8>>;
+    fn RequestUrlWithOptions(
+        &self,
+        docId: u32,
+        url: &windows_core::HSTRING,
+        requestId: u32,
+        method: &windows_core::HSTRING,
+        headers: &windows_core::HSTRING,
+        body: &[u8],
+    ) -> windows_core::Result<()>;
+    fn IsContentLoaded(&self) -> windows_core::Result<bool>;
+    fn SetTargetFps(&self, fps: f32) -> windows_core::Result<()>;
+    fn HitTest(&self, x: f32, y: f32) -> windows_core::Result<windows_core::HSTRING>;
+    fn SetNavigationListener(
+        &self,
+        listener: windows_core::Ref<'_, windows_core::IInspectable>,
+    ) -> windows_core::Result<()>;
+    fn ScrollToFragment(&self, fragment: &windows_core::HSTRING) -> windows_core::Result<bool>;
+    fn GetCommandStatsJson(&self) -> windows_core::Result<windows_core::HSTRING>;
+    fn Unload(&self) -> windows_core::Result<()>;
+    fn WantsAnimationFrame(&self) -> windows_core::Result<bool>;
+    fn SetBaseUrl(&self, url: &windows_core::HSTRING) -> windows_core::Result<()>;
+    fn BeginStream(&self) -> windows_core::Result<()>;
+    fn PushHtmlChunk(&self, bytes: &[u8]) -> windows_core::Result<bool>;
+    fn EndStream(&self) -> windows_core::Result<bool>;
+    fn GetCursor(&self, x: f32, y: f32) -> windows_core::Result<i32>;
+    fn SetDebugOverlayOptions(
+        &self,
+        lines: u32,
+        corner: u8,
+        opacity: f32,
+    ) -> windows_core::Result<()>;
+    fn LoadUrl(&self, url: &windows_core::HSTRING) -> windows_core::Result<bool>;
+    fn SetColorScheme(&self, scheme: u8) -> windows_core::Result<()>;
+    fn TrimMemory(&self, level: u8) -> windows_core::Result<()>;
+}
+impl IHost_Vtbl {
+    pub const fn new<Identity: IHost_Impl, const OFFSET: isize>() -> Self {
+        unsafe extern "system" fn SetPanel<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+            attacher: *mut core::ffi::c_void,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                IHost_Impl::SetPanel(this, core::mem::transmute_copy(&attacher)).into()
+            }
+        }
+        unsafe extern "system" fn Resize<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+            width: u32,
+            height: u32,
+            scale: f32,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                IHost_Impl::Resize(this, width, height, scale).into()
+            }
+        }
+        unsafe extern "system" fn RenderOnce<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                IHost_Impl::RenderOnce(this).into()
+            }
+        }
+        unsafe extern "system" fn LoadHtml<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+            html: *mut core::ffi::c_void,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                IHost_Impl::LoadHtml(this, core::mem::transmute(&html)).into()
+            }
+        }
+        unsafe extern "system" fn SetVerboseLogging<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+            enabled: bool,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                IHost_Impl::SetVerboseLogging(this, enabled).into()
+            }
+        }
+        unsafe extern "system" fn SetDebugOverlay<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+            enabled: bool,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                IHost_Impl::SetDebugOverlay(this, enabled).into()
+            }
+        }
+        unsafe extern "system" fn SetNetworkFetcher<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+            fetcher: *mut core::ffi::c_void,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                IHost_Impl::SetNetworkFetcher(this, core::mem::transmute_copy(&fetcher)).into()
+            }
+        }
+        unsafe extern "system" fn CompleteFetch<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+            requestid: u32,
+            docid: u32,
+            success: bool,
+            data_array_size: u32,
+            data: *const u8,
+            errormessage: *mut core::ffi::c_void,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                IHost_Impl::CompleteFetch(
+                    this,
+                    requestid,
+                    docid,
+                    success,
+                    core::slice::from_raw_parts(
+                        core::mem::transmute_copy(&data),
+                        data_array_size as usize,
+                    ),
+                    core::mem::transmute(&errormessage),
+                )
+                .into()
+            }
+        }
+        unsafe extern "system" fn RequestUrl<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+            docid: u32,
+            url: *mut core::ffi::c_void,
+            requestid: u32,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                IHost_Impl::RequestUrl(this, docid, core::mem::transmute(&url), requestid).into()
+            }
+        }
+        unsafe extern "system" fn TestAttacherConnection<
+            Identity: IHost_Impl,
+            const OFFSET: isize,
+        >(
+            this: *mut core::ffi::c_void,
+            result__: *mut bool,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                match IHost_Impl::TestAttacherConnection(this) {
+                    Ok(ok__) => {
+                        result__.write(core::mem::transmute_copy(&ok__));
+                        windows_core::HRESULT(0)
+                    }
+                    Err(err) => err.into(),
+                }
+            }
+        }
+        unsafe extern "system" fn WheelScroll<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+            dx: f64,
+            dy: f64,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                IHost_Impl::WheelScroll(this, dx, dy).into()
+            }
+        }
+        unsafe extern "system" fn PointerMove<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+            x: f32,
+            y: f32,
+            buttons: u32,
+            modifiers: u32,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                IHost_Impl::PointerMove(this, x, y, buttons, modifiers).into()
+            }
+        }
+        unsafe extern "system" fn PointerDown<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+            x: f32,
+            y: f32,
+            button: u8,
+            buttons: u32,
+            modifiers: u32,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                IHost_Impl::PointerDown(this, x, y, button, buttons, modifiers).into()
+            }
+        }
+        unsafe extern "system" fn PointerUp<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+            x: f32,
+            y: f32,
+            button: u8,
+            buttons: u32,
+            modifiers: u32,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                IHost_Impl::PointerUp(this, x, y, button, buttons, modifiers).into()
+            }
+        }
+        unsafe extern "system" fn ReportAttachSubPhase<
+            Identity: IHost_Impl,
+            const OFFSET: isize,
+        >(
+            this: *mut core::ffi::c_void,
+            kind: u8,
+            ms: f32,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                IHost_Impl::ReportAttachSubPhase(this, kind, ms).into()
+            }
+        }
+        unsafe extern "system" fn Copy<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+            result__: *mut *mut core::ffi::c_void,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                match IHost_Impl::Copy(this) {
+                    Ok(ok__) => {
+                        result__.write(core::mem::transmute_copy(&ok__));
+                        core::mem::forget(ok__);
+                        windows_core::HRESULT(0)
+                    }
+                    Err(err) => err.into(),
+                }
+            }
+        }
+        unsafe extern "system" fn Cut<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+            result__: *mut *mut core::ffi::c_void,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                match IHost_Impl::Cut(this) {
+                    Ok(ok__) => {
+                        result__.write(core::mem::transmute_copy(&ok__));
+                        core::mem::forget(ok__);
+                        windows_core::HRESULT(0)
+                    }
+                    Err(err) => err.into(),
+                }
+            }
+        }
+        unsafe extern "system" fn Paste<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+            text: *mut core::ffi::c_void,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                IHost_Impl::Paste(this, core::mem::transmute(&text)).into()
+            }
+        }
+        unsafe extern "system" fn GetFrameTimingsJson<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+            result__: *mut *mut core::ffi::c_void,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                match IHost_Impl::GetFrameTimingsJson(this) {
+                    Ok(ok__) => {
+                        result__.write(core::mem::transmute_copy(&ok__));
+                        core::mem::forget(ok__);
+                        windows_core::HRESULT(0)
+                    }
+                    Err(err) => err.into(),
+                }
+            }
+        }
+        unsafe extern "system" fn ScrollToNode<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+            nodeid: u32,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                IHost_Impl::ScrollToNode(this, nodeid).into()
+            }
+        }
+        unsafe extern "system" fn ScrollToTop<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                IHost_Impl::ScrollToTop(this).into()
+            }
+        }
+        unsafe extern "system" fn ScrollToBottom<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                IHost_Impl::ScrollToBottom(this).into()
+            }
+        }
+        unsafe extern "system" fn ScrollBy<Identity: IHost_Impl, const OFFSET: isize>(
             this: *mut core::ffi::c_void,
-            attacher: *mut core::ffi::c_void,
+            dx: f64,
+            dy: f64,
         ) -> windows_core::HRESULT {
             unsafe {
                 let this: &Identity =
                     &*((this as *const *const ()).offset(OFFSET) as *const Identity);
-                IHost_Impl::SetPanel(this, core::mem::transmute_copy(&attacher)).into()
+                IHost_Impl::ScrollBy(this, dx, dy).into()
             }
         }
-        unsafe extern "system" fn Resize<Identity: IHost_Impl, const OFFSET: isize>(
+        unsafe extern "system" fn ScrollByPage<Identity: IHost_Impl, const OFFSET: isize>(
             this: *mut core::ffi::c_void,
-            width: u32,
-            height: u32,
-            scale: f32,
+            pages: i32,
         ) -> windows_core::HRESULT {
             unsafe {
                 let this: &Identity =
                     &*((this as *const *const ()).offset(OFFSET) as *const Identity);
-                IHost_Impl::Resize(this, width, height, scale).into()
+                IHost_Impl::ScrollByPage(this, pages).into()
             }
         }
-        unsafe extern "system" fn RenderOnce<Identity: IHost_Impl, const OFFSET: isize>(
+        unsafe extern "system" fn ImeSetComposition<Identity: IHost_Impl, const OFFSET: isize>(
             this: *mut core::ffi::c_void,
+            text: *mut core::ffi::c_void,
+            cursorstart: u32,
+            cursorend: u32,
         ) -> windows_core::HRESULT {
             unsafe {
                 let this: &Identity =
                     &*((this as *const *const ()).offset(OFFSET) as *const Identity);
-                IHost_Impl::RenderOnce(this).into()
+                IHost_Impl::ImeSetComposition(
+                    this,
+                    core::mem::transmute(&text),
+                    cursorstart,
+                    cursorend,
+                )
+                .into()
             }
         }
-        unsafe extern "system" fn LoadHtml<Identity: IHost_Impl, const OFFSET: isize>(
+        unsafe extern "system" fn ImeCommit<Identity: IHost_Impl, const OFFSET: isize>(
             this: *mut core::ffi::c_void,
-            html: *mut core::ffi::c_void,
+            text: *mut core::ffi::c_void,
         ) -> windows_core::HRESULT {
             unsafe {
                 let this: &Identity =
                     &*((this as *const *const ()).offset(OFFSET) as *const Identity);
-                IHost_Impl::LoadHtml(this, core::mem::transmute(&html)).into()
+                IHost_Impl::ImeCommit(this, core::mem::transmute(&text)).into()
             }
         }
-        unsafe extern "system" fn SetVerboseLogging<Identity: IHost_Impl, const OFFSET: isize>(
+        unsafe extern "system" fn ImeCancel<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                IHost_Impl::ImeCancel(this).into()
+            }
+        }
+        unsafe extern "system" fn GetImeCaretRect<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+            result__: *mut *mut core::ffi::c_void,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                match IHost_Impl::GetImeCaretRect(this) {
+                    Ok(ok__) => {
+                        result__.write(core::mem::transmute_copy(&ok__));
+                        core::mem::forget(ok__);
+                        windows_core::HRESULT(0)
+                    }
+                    Err(err) => err.into(),
+                }
+            }
+        }
+        unsafe extern "system" fn DumpLastScene<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+            result__: *mut *mut core::ffi::c_void,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                match IHost_Impl::DumpLastScene(this) {
+                    Ok(ok__) => {
+                        result__.write(core::mem::transmute_copy(&ok__));
+                        core::mem::forget(ok__);
+                        windows_core::HRESULT(0)
+                    }
+                    Err(err) => err.into(),
+                }
+            }
+        }
+        unsafe extern "system" fn SetRasterizationScale<
+            Identity: IHost_Impl,
+            const OFFSET: isize,
+        >(
+            this: *mut core::ffi::c_void,
+            scale: f32,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                IHost_Impl::SetRasterizationScale(this, scale).into()
+            }
+        }
+        unsafe extern "system" fn SetInspectOverlay<Identity: IHost_Impl, const OFFSET: isize>(
             this: *mut core::ffi::c_void,
             enabled: bool,
         ) -> windows_core::HRESULT {
             unsafe {
                 let this: &Identity =
                     &*((this as *const *const ()).offset(OFFSET) as *const Identity);
-                IHost_Impl::SetVerboseLogging(this, enabled).into()
+                IHost_Impl::SetInspectOverlay(this, enabled).into()
             }
         }
-        unsafe extern "system" fn SetDebugOverlay<Identity: IHost_Impl, const OFFSET: isize>(
+        unsafe extern "system" fn SetFirstFrameListener<
+            Identity: IHost_Impl,
+            const OFFSET: isize,
+        >(
+            this: *mut core::ffi::c_void,
+            listener: *mut core::ffi::c_void,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                IHost_Impl::SetFirstFrameListener(this, core::mem::transmute_copy(&listener)).into()
+            }
+        }
+        unsafe extern "system" fn SetForcedColors<Identity: IHost_Impl, const OFFSET: isize>(
             this: *mut core::ffi::c_void,
             enabled: bool,
+            canvas: u32,
+            canvastext: u32,
+            linktext: u32,
+            buttonface: u32,
+            buttontext: u32,
+            highlight: u32,
         ) -> windows_core::HRESULT {
             unsafe {
                 let this: &Identity =
                     &*((this as *const *const ()).offset(OFFSET) as *const Identity);
-                IHost_Impl::SetDebugOverlay(this, enabled).into()
+                IHost_Impl::SetForcedColors(
+                    this, enabled, canvas, canvastext, linktext, buttonface, buttontext, highlight,
+                )
+                .into()
             }
         }
-        unsafe extern "system" fn SetNetworkFetcher<Identity: IHost_Impl, const OFFSET: isize>(
+        unsafe extern "system" fn WheelScrollWithModifiers<
+            Identity: IHost_Impl,
+            const OFFSET: isize,
+        >(
             this: *mut core::ffi::c_void,
-            fetcher: *mut core::ffi::c_void,
+            dx: f64,
+            dy: f64,
+            modifiers: u32,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                IHost_Impl::WheelScrollWithModifiers(this, dx, dy, modifiers).into()
+            }
+        }
+        unsafe extern "system" fn SetZoom<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+            zoom: f32,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                IHost_Impl::SetZoom(this, zoom).into()
+            }
+        }
+        unsafe extern "system" fn GetZoom<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+            result__: *mut f32,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                match IHost_Impl::GetZoom(this) {
+                    Ok(ok__) => {
+                        result__.write(core::mem::transmute_copy(&ok__));
+                        windows_core::HRESULT(0)
+                    }
+                    Err(err) => err.into(),
+                }
+            }
+        }
+        unsafe extern "system" fn CapturePng<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+            width: u32,
+            height: u32,
+            fullpage: bool,
+            result_size__: *mut u32,
+            result__: *mut *mut u8,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                match IHost_Impl::CapturePng(this, width, height, fullpage) {
+                    Ok(ok__) => {
+                        let (ok_data__, ok_data_len__) = ok__.into_abi();
+                        result__.write(core::mem::transmute(ok_data__));
+                        result_size__.write(ok_data_len__);
+                        windows_core::HRESULT(0)
+                    }
+                    Err(err) => err.into(),
+                }
+            }
+        }
+        unsafe extern "system" fn RequestUrlWithOptions<
+            Identity: IHost_Impl,
+            const OFFSET: isize,
+        >(
+            this: *mut core::ffi::c_void,
+            docid: u32,
+            url: *mut core::ffi::c_void,
+            requestid: u32,
+            method: *mut core::ffi::c_void,
+            headers: *mut core::ffi::c_void,
+            body_array_size: u32,
+            body: *const u8,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                IHost_Impl::RequestUrlWithOptions(
+                    this,
+                    docid,
+                    core::mem::transmute(&url),
+                    requestid,
+                    core::mem::transmute(&method),
+                    core::mem::transmute(&headers),
+                    core::slice::from_raw_parts(
+                        core::mem::transmute_copy(&body),
+                        body_array_size as usize,
+                    ),
+                )
+                .into()
+            }
+        }
+        unsafe extern "system" fn IsContentLoaded<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+            result__: *mut bool,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                match IHost_Impl::IsContentLoaded(this) {
+                    Ok(ok__) => {
+                        result__.write(core::mem::transmute_copy(&ok__));
+                        windows_core::HRESULT(0)
+                    }
+                    Err(err) => err.into(),
+                }
+            }
+        }
+        unsafe extern "system" fn SetTargetFps<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+            fps: f32,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                IHost_Impl::SetTargetFps(this, fps).into()
+            }
+        }
+        unsafe extern "system" fn HitTest<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+            x: f32,
+            y: f32,
+            result__: *mut *mut core::ffi::c_void,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                match IHost_Impl::HitTest(this, x, y) {
+                    Ok(ok__) => {
+                        result__.write(core::mem::transmute_copy(&ok__));
+                        core::mem::forget(ok__);
+                        windows_core::HRESULT(0)
+                    }
+                    Err(err) => err.into(),
+                }
+            }
+        }
+        unsafe extern "system" fn SetNavigationListener<
+            Identity: IHost_Impl,
+            const OFFSET: isize,
+        >(
+            this: *mut core::ffi::c_void,
+            listener: *mut core::ffi::c_void,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                IHost_Impl::SetNavigationListener(this, core::mem::transmute_copy(&listener)).into()
+            }
+        }
+        unsafe extern "system" fn ScrollToFragment<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+            fragment: *mut core::ffi::c_void,
+            result__: *mut bool,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                match IHost_Impl::ScrollToFragment(this, core::mem::transmute(&fragment)) {
+                    Ok(ok__) => {
+                        result__.write(core::mem::transmute_copy(&ok__));
+                        windows_core::HRESULT(0)
+                    }
+                    Err(err) => err.into(),
+                }
+            }
+        }
+        unsafe extern "system" fn GetCommandStatsJson<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+            result__: *mut *mut core::ffi::c_void,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                match IHost_Impl::GetCommandStatsJson(this) {
+                    Ok(ok__) => {
+                        result__.write(core::mem::transmute_copy(&ok__));
+                        core::mem::forget(ok__);
+                        windows_core::HRESULT(0)
+                    }
+                    Err(err) => err.into(),
+                }
+            }
+        }
+        unsafe extern "system" fn Unload<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                IHost_Impl::Unload(this).into()
+            }
+        }
+        unsafe extern "system" fn WantsAnimationFrame<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+            result__: *mut bool,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                match IHost_Impl::WantsAnimationFrame(this) {
+                    Ok(ok__) => {
+                        result__.write(core::mem::transmute_copy(&ok__));
+                        windows_core::HRESULT(0)
+                    }
+                    Err(err) => err.into(),
+                }
+            }
+        }
+        unsafe extern "system" fn SetBaseUrl<Identity: IHost_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+            url: *mut core::ffi::c_void,
         ) -> windows_core::HRESULT {
             unsafe {
                 let this: &Identity =
                     &*((this as *const *const ()).offset(OFFSET) as *const Identity);
-                IHost_Impl::SetNetworkFetcher(this, core::mem::transmute_copy(&fetcher)).into()
+                IHost_Impl::SetBaseUrl(this, core::mem::transmute(&url)).into()
             }
         }
-        unsafe extern "system" fn CompleteFetch<Identity: IHost_Impl, const OFFSET: isize>(
+        unsafe extern "system" fn BeginStream<Identity: IHost_Impl, const OFFSET: isize>(
             this: *mut core::ffi::c_void,
-            requestid: u32,
-            docid: u32,
-            success: bool,
-            data_array_size: u32,
-            data: *const u8,
-            errormessage: *mut core::ffi::c_void,
         ) -> windows_core::HRESULT {
             unsafe {
                 let this: &Identity =
                     &*((this as *const *const ()).offset(OFFSET) as *const Identity);
-                IHost_Impl::CompleteFetch(
-                    this,
-                    requestid,
-                    docid,
-                    success,
-                    core::slice::from_raw_parts(
-                        core::mem::transmute_copy(&data),
-                        data_array_size as usize,
-                    ),
-                    core::mem::transmute(&errormessage),
-                )
-                .into()
+                IHost_Impl::BeginStream(this).into()
             }
         }
-        unsafe extern "system" fn RequestUrl<Identity: IHost_Impl, const OFFSET: isize>(
+        unsafe extern "system" fn PushHtmlChunk<Identity: IHost_Impl, const OFFSET: isize>(
             this: *mut core::ffi::c_void,
-            docid: u32,
-            url: *mut core::ffi::c_void,
-            requestid: u32,
+            bytes_array_size: u32,
+            bytes: *const u8,
+            result__: *mut bool,
         ) -> windows_core::HRESULT {
             unsafe {
                 let this: &Identity =
                     &*((this as *const *const ()).offset(OFFSET) as *const Identity);
-                IHost_Impl::RequestUrl(this, docid, core::mem::transmute(&url), requestid).into()
+                match IHost_Impl::PushHtmlChunk(
+                    this,
+                    core::slice::from_raw_parts(
+                        core::mem::transmute_copy(&bytes),
+                        bytes_array_size as usize,
+                    ),
+                ) {
+                    Ok(ok__) => {
+                        result__.write(core::mem::transmute_copy(&ok__));
+                        windows_core::HRESULT(0)
+                    }
+                    Err(err) => err.into(),
+                }
             }
         }
-        unsafe extern "system" fn TestAttacherConnection<
-            Identity: IHost_Impl,
-            const OFFSET: isize,
-        >(
+        unsafe extern "system" fn EndStream<Identity: IHost_Impl, const OFFSET: isize>(
             this: *mut core::ffi::c_void,
             result__: *mut bool,
         ) -> windows_core::HRESULT {
             unsafe {
                 let this: &Identity =
                     &*((this as *const *const ()).offset(OFFSET) as *const Identity);
-                match IHost_Impl::TestAttacherConnection(this) {
+                match IHost_Impl::EndStream(this) {
                     Ok(ok__) => {
                         result__.write(core::mem::transmute_copy(&ok__));
                         windows_core::HRESULT(0)
@@ -456,70 +1739,74 @@ impl IHost_Vtbl {
                 }
             }
         }
-        unsafe extern "system" fn WheelScroll<Identity: IHost_Impl, const OFFSET: isize>(
+        unsafe extern "system" fn GetCursor<Identity: IHost_Impl, const OFFSET: isize>(
             this: *mut core::ffi::c_void,
-            dx: f64,
-            dy: f64,
+            x: f32,
+            y: f32,
+            result__: *mut i32,
         ) -> windows_core::HRESULT {
             unsafe {
                 let this: &Identity =
                     &*((this as *const *const ()).offset(OFFSET) as *const Identity);
-                IHost_Impl::WheelScroll(this, dx, dy).into()
+                match IHost_Impl::GetCursor(this, x, y) {
+                    Ok(ok__) => {
+                        result__.write(core::mem::transmute_copy(&ok__));
+                        windows_core::HRESULT(0)
+                    }
+                    Err(err) => err.into(),
+                }
             }
         }
-        unsafe extern "system" fn PointerMove<Identity: IHost_Impl, const OFFSET: isize>(
+        unsafe extern "system" fn SetDebugOverlayOptions<
+            Identity: IHost_Impl,
+            const OFFSET: isize,
+        >(
             this: *mut core::ffi::c_void,
-            x: f32,
-            y: f32,
-            buttons: u32,
-            modifiers: u32,
+            lines: u32,
+            corner: u8,
+            opacity: f32,
         ) -> windows_core::HRESULT {
             unsafe {
                 let this: &Identity =
                     &*((this as *const *const ()).offset(OFFSET) as *const Identity);
-                IHost_Impl::PointerMove(this, x, y, buttons, modifiers).into()
+                IHost_Impl::SetDebugOverlayOptions(this, lines, corner, opacity).into()
             }
         }
-        unsafe extern "system" fn PointerDown<Identity: IHost_Impl, const OFFSET: isize>(
+        unsafe extern "system" fn LoadUrl<Identity: IHost_Impl, const OFFSET: isize>(
             this: *mut core::ffi::c_void,
-            x: f32,
-            y: f32,
-            button: u8,
-            buttons: u32,
-            modifiers: u32,
+            url: *mut core::ffi::c_void,
+            result__: *mut bool,
         ) -> windows_core::HRESULT {
             unsafe {
                 let this: &Identity =
                     &*((this as *const *const ()).offset(OFFSET) as *const Identity);
-                IHost_Impl::PointerDown(this, x, y, button, buttons, modifiers).into()
+                match IHost_Impl::LoadUrl(this, core::mem::transmute(&url)) {
+                    Ok(ok__) => {
+                        result__.write(core::mem::transmute_copy(&ok__));
+                        windows_core::HRESULT(0)
+                    }
+                    Err(err) => err.into(),
+                }
             }
         }
-        unsafe extern "system" fn PointerUp<Identity: IHost_Impl, const OFFSET: isize>(
+        unsafe extern "system" fn SetColorScheme<Identity: IHost_Impl, const OFFSET: isize>(
             this: *mut core::ffi::c_void,
-            x: f32,
-            y: f32,
-            button: u8,
-            buttons: u32,
-            modifiers: u32,
+            scheme: u8,
         ) -> windows_core::HRESULT {
             unsafe {
                 let this: &Identity =
                     &*((this as *const *const ()).offset(OFFSET) as *const Identity);
-                IHost_Impl::PointerUp(this, x, y, button, buttons, modifiers).into()
+                IHost_Impl::SetColorScheme(this, scheme).into()
             }
         }
-        unsafe extern "system" fn ReportAttachSubPhase<
-            Identity: IHost_Impl,
-            const OFFSET: isize,
-        >(
+        unsafe extern "system" fn TrimMemory<Identity: IHost_Impl, const OFFSET: isize>(
             this: *mut core::ffi::c_void,
-            kind: u8,
-            ms: f32,
+            level: u8,
         ) -> windows_core::HRESULT {
             unsafe {
                 let this: &Identity =
                     &*((this as *const *const ()).offset(OFFSET) as *const Identity);
-                IHost_Impl::ReportAttachSubPhase(this, kind, ms).into()
+                IHost_Impl::TrimMemory(this, level).into()
             }
         }
         Self {
@@ -539,6 +1826,46 @@ impl IHost_Vtbl {
             PointerDown: PointerDown::<Identity, OFFSET>,
             PointerUp: PointerUp::<Identity, OFFSET>,
             ReportAttachSubPhase: ReportAttachSubPhase::<Identity, OFFSET>,
+            Copy: Copy::<Identity, OFFSET>,
+            Cut: Cut::<Identity, OFFSET>,
+            Paste: Paste::<Identity, OFFSET>,
+            GetFrameTimingsJson: GetFrameTimingsJson::<Identity, OFFSET>,
+            ScrollToNode: ScrollToNode::<Identity, OFFSET>,
+            ScrollToTop: ScrollToTop::<Identity, OFFSET>,
+            ScrollToBottom: ScrollToBottom::<Identity, OFFSET>,
+            ScrollBy: ScrollBy::<Identity, OFFSET>,
+            ScrollByPage: ScrollByPage::<Identity, OFFSET>,
+            ImeSetComposition: ImeSetComposition::<Identity, OFFSET>,
+            ImeCommit: ImeCommit::<Identity, OFFSET>,
+            ImeCancel: ImeCancel::<Identity, OFFSET>,
+            GetImeCaretRect: GetImeCaretRect::<Identity, OFFSET>,
+            DumpLastScene: DumpLastScene::<Identity, OFFSET>,
+            SetRasterizationScale: SetRasterizationScale::<Identity, OFFSET>,
+            SetInspectOverlay: SetInspectOverlay::<Identity, OFFSET>,
+            SetFirstFrameListener: SetFirstFrameListener::<Identity, OFFSET>,
+            SetForcedColors: SetForcedColors::<Identity, OFFSET>,
+            WheelScrollWithModifiers: WheelScrollWithModifiers::<Identity, OFFSET>,
+            SetZoom: SetZoom::<Identity, OFFSET>,
+            GetZoom: GetZoom::<Identity, OFFSET>,
+            CapturePng: CapturePng::<Identity, OFFSET>,
+            RequestUrlWithOptions: RequestUrlWithOptions::<Identity, OFFSET>,
+            IsContentLoaded: IsContentLoaded::<Identity, OFFSET>,
+            SetTargetFps: SetTargetFps::<Identity, OFFSET>,
+            HitTest: HitTest::<Identity, OFFSET>,
+            SetNavigationListener: SetNavigationListener::<Identity, OFFSET>,
+            ScrollToFragment: ScrollToFragment::<Identity, OFFSET>,
+            GetCommandStatsJson: GetCommandStatsJson::<Identity, OFFSET>,
+            Unload: Unload::<Identity, OFFSET>,
+            WantsAnimationFrame: WantsAnimationFrame::<Identity, OFFSET>,
+            SetBaseUrl: SetBaseUrl::<Identity, OFFSET>,
+            BeginStream: BeginStream::<Identity, OFFSET>,
+            PushHtmlChunk: PushHtmlChunk::<Identity, OFFSET>,
+            EndStream: EndStream::<Identity, OFFSET>,
+            GetCursor: GetCursor::<Identity, OFFSET>,
+            SetDebugOverlayOptions: SetDebugOverlayOptions::<Identity, OFFSET>,
+            LoadUrl: LoadUrl::<Identity, OFFSET>,
+            SetColorScheme: SetColorScheme::<Identity, OFFSET>,
+            TrimMemory: TrimMemory::<Identity, OFFSET>,
         }
     }
     pub fn matches(iid: &windows_core::GUID) -> bool {
@@ -612,6 +1939,145 @@ pub struct IHost_Vtbl {
     ) -> windows_core::HRESULT,
     pub ReportAttachSubPhase:
         unsafe extern "system" fn(*mut core::ffi::c_void, u8, f32) -> windows_core::HRESULT,
+    pub Copy: unsafe extern "system" fn(
+        *mut core::ffi::c_void,
+        *mut *mut core::ffi::c_void,
+    ) -> windows_core::HRESULT,
+    pub Cut: unsafe extern "system" fn(
+        *mut core::ffi::c_void,
+        *mut *mut core::ffi::c_void,
+    ) -> windows_core::HRESULT,
+    pub Paste: unsafe extern "system" fn(
+        *mut core::ffi::c_void,
+        *mut core::ffi::c_void,
+    ) -> windows_core::HRESULT,
+    pub GetFrameTimingsJson: unsafe extern "system" fn(
+        *mut core::ffi::c_void,
+        *mut *mut core::ffi::c_void,
+    ) -> windows_core::HRESULT,
+    pub ScrollToNode:
+        unsafe extern "system" fn(*mut core::ffi::c_void, u32) -> windows_core::HRESULT,
+    pub ScrollToTop: unsafe extern "system" fn(*mut core::ffi::c_void) -> windows_core::HRESULT,
+    pub ScrollToBottom: unsafe extern "system" fn(*mut core::ffi::c_void) -> windows_core::HRESULT,
+    pub ScrollBy:
+        unsafe extern "system" fn(*mut core::ffi::c_void, f64, f64) -> windows_core::HRESULT,
+    pub ScrollByPage:
+        unsafe extern "system" fn(*mut core::ffi::c_void, i32) -> windows_core::HRESULT,
+    pub ImeSetComposition: unsafe extern "system" fn(
+        *mut core::ffi::c_void,
+        *mut core::ffi::c_void,
+        u32,
+        u32,
+    ) -> windows_core::HRESULT,
+    pub ImeCommit: unsafe extern "system" fn(
+        *mut core::ffi::c_void,
+        *mut core::ffi::c_void,
+    ) -> windows_core::HRESULT,
+    pub ImeCancel: unsafe extern "system" fn(*mut core::ffi::c_void) -> windows_core::HRESULT,
+    pub GetImeCaretRect: unsafe extern "system" fn(
+        *mut core::ffi::c_void,
+        *mut *mut core::ffi::c_void,
+    ) -> windows_core::HRESULT,
+    pub DumpLastScene: unsafe extern "system" fn(
+        *mut core::ffi::c_void,
+        *mut *mut core::ffi::c_void,
+    ) -> windows_core::HRESULT,
+    pub SetRasterizationScale:
+        unsafe extern "system" fn(*mut core::ffi::c_void, f32) -> windows_core::HRESULT,
+    pub SetInspectOverlay:
+        unsafe extern "system" fn(*mut core::ffi::c_void, bool) -> windows_core::HRESULT,
+    pub SetFirstFrameListener: unsafe extern "system" fn(
+        *mut core::ffi::c_void,
+        *mut core::ffi::c_void,
+    ) -> windows_core::HRESULT,
+    pub SetForcedColors: unsafe extern "system" fn(
+        *mut core::ffi::c_void,
+        bool,
+        u32,
+        u32,
+        u32,
+        u32,
+        u32,
+        u32,
+    ) -> windows_core::HRESULT,
+    pub WheelScrollWithModifiers:
+        unsafe extern "system" fn(*mut core::ffi::c_void, f64, f64, u32) -> windows_core::HRESULT,
+    pub SetZoom: unsafe extern "system" fn(*mut core::ffi::c_void, f32) -> windows_core::HRESULT,
+    pub GetZoom:
+        unsafe extern "system" fn(*mut core::ffi::c_void, *mut f32) -> windows_core::HRESULT,
+    pub CapturePng: unsafe extern "system" fn(
+        *mut core::ffi::c_void,
+        u32,
+        u32,
+        bool,
+        *mut u32,
+        *mut *mut u8,
+    ) -> windows_core::HRESULT,
+    pub RequestUrlWithOptions: unsafe extern "system" fn(
+        *mut core::ffi::c_void,
+        u32,
+        *mut core::ffi::c_void,
+        u32,
+        *mut core::ffi::c_void,
+        *mut core::ffi::c_void,
+        u32,
+        *const u8,
+    ) -> windows_core::HRESULT,
+    pub IsContentLoaded:
+        unsafe extern "system" fn(*mut core::ffi::c_void, *mut bool) -> windows_core::HRESULT,
+    pub SetTargetFps:
+        unsafe extern "system" fn(*mut core::ffi::c_void, f32) -> windows_core::HRESULT,
+    pub HitTest: unsafe extern "system" fn(
+        *mut core::ffi::c_void,
+        f32,
+        f32,
+        *mut *mut core::ffi::c_void,
+    ) -> windows_core::HRESULT,
+    pub SetNavigationListener: unsafe extern "system" fn(
+        *mut core::ffi::c_void,
+        *mut core::ffi::c_void,
+    ) -> windows_core::HRESULT,
+    pub ScrollToFragment: unsafe extern "system" fn(
+        *mut core::ffi::c_void,
+        *mut core::ffi::c_void,
+        *mut bool,
+    ) -> windows_core::HRESULT,
+    pub GetCommandStatsJson: unsafe extern "system" fn(
+        *mut core::ffi::c_void,
+        *mut *mut core::ffi::c_void,
+    ) -> windows_core::HRESULT,
+    pub Unload: unsafe extern "system" fn(*mut core::ffi::c_void) -> windows_core::HRESULT,
+    pub WantsAnimationFrame:
+        unsafe extern "system" fn(*mut core::ffi::c_void, *mut bool) -> windows_core::HRESULT,
+    pub SetBaseUrl: unsafe extern "system" fn(
+        *mut core::ffi::c_void,
+        *mut core::ffi::c_void,
+    ) -> windows_core::HRESULT,
+    pub BeginStream: unsafe extern "system" fn(*mut core::ffi::c_void) -> windows_core::HRESULT,
+    pub PushHtmlChunk: unsafe extern "system" fn(
+        *mut core::ffi::c_void,
+        u32,
+        *const u8,
+        *mut bool,
+    ) -> windows_core::HRESULT,
+    pub EndStream:
+        unsafe extern "system" fn(*mut core::ffi::c_void, *mut bool) -> windows_core::HRESULT,
+    pub GetCursor: unsafe extern "system" fn(
+        *mut core::ffi::c_void,
+        f32,
+        f32,
+        *mut i32,
+    ) -> windows_core::HRESULT,
+    pub SetDebugOverlayOptions:
+        unsafe extern "system" fn(*mut core::ffi::c_void, u32, u8, f32) -> windows_core::HRESULT,
+    pub LoadUrl: unsafe extern "system" fn(
+        *mut core::ffi::c_void,
+        *mut core::ffi::c_void,
+        *mut bool,
+    ) -> windows_core::HRESULT,
+    pub SetColorScheme:
+        unsafe extern "system" fn(*mut core::ffi::c_void, u8) -> windows_core::HRESULT,
+    pub TrimMemory: unsafe extern "system" fn(*mut core::ffi::c_void, u8) -> windows_core::HRESULT,
 }
 windows_core::imp::define_interface!(
     IHostFactory,
@@ -782,6 +2248,114 @@ pub struct INetworkFetcher_Vtbl {
         *mut core::ffi::c_void,
     ) -> windows_core::HRESULT,
 }
+windows_core::imp::define_interface!(
+    INetworkFetcher2,
+    INetworkFetcher2_Vtbl,
+    0xa9a8fcaa_5d9e_4230_9f36_e445cf90875f
+);
+impl windows_core::RuntimeType for INetworkFetcher2 {
+    const SIGNATURE: windows_core::imp::ConstBuffer =
+        windows_core::imp::ConstBuffer::for_interface::<Self>();
+}
+windows_core::imp::interface_hierarchy!(
+    INetworkFetcher2,
+    windows_core::IUnknown,
+    windows_core::IInspectable
+);
+impl INetworkFetcher2 {
+    pub fn Fetch(
+        &self,
+        requestid: u32,
+        docid: u32,
+        url: &windows_core::HSTRING,
+        method: &windows_core::HSTRING,
+        headers: &windows_core::HSTRING,
+        body: &[u8],
+    ) -> windows_core::Result<()> {
+        let this = self;
+        unsafe {
+            (windows_core::Interface::vtable(this).Fetch)(
+                windows_core::Interface::as_raw(this),
+                requestid,
+                docid,
+                core::mem::transmute_copy(url),
+                core::mem::transmute_copy(method),
+                core::mem::transmute_copy(headers),
+                body.len().try_into().unwrap(),
+                body.as_ptr(),
+            )
+            .ok()
+        }
+    }
+}
+impl windows_core::RuntimeName for INetworkFetcher2 {
+    const NAME: &'static str = "BlitzWinUI.INetworkFetcher2";
+}
+pub trait INetworkFetcher2_Impl: windows_core::IUnknownImpl {
+    fn Fetch(
+        &self,
+        requestId: u32,
+        docId: u32,
+        url: &windows_core::HSTRING,
+        method: &windows_core::HSTRING,
+        headers: &windows_core::HSTRING,
+        body: &[u8],
+    ) -> windows_core::Result<()>;
+}
+impl INetworkFetcher2_Vtbl {
+    pub const fn new<Identity: INetworkFetcher2_Impl, const OFFSET: isize>() -> Self {
+        unsafe extern "system" fn Fetch<Identity: INetworkFetcher2_Impl, const OFFSET: isize>(
+            this: *mut core::ffi::c_void,
+            requestid: u32,
+            docid: u32,
+            url: *mut core::ffi::c_void,
+            method: *mut core::ffi::c_void,
+            headers: *mut core::ffi::c_void,
+            body_array_size: u32,
+            body: *const u8,
+        ) -> windows_core::HRESULT {
+            unsafe {
+                let this: &Identity =
+                    &*((this as *const *const ()).offset(OFFSET) as *const Identity);
+                INetworkFetcher2_Impl::Fetch(
+                    this,
+                    requestid,
+                    docid,
+                    core::mem::transmute(&url),
+                    core::mem::transmute(&method),
+                    core::mem::transmute(&headers),
+                    core::slice::from_raw_parts(
+                        core::mem::transmute_copy(&body),
+                        body_array_size as usize,
+                    ),
+                )
+                .into()
+            }
+        }
+        Self {
+            base__: windows_core::IInspectable_Vtbl::new::<Identity, INetworkFetcher2, OFFSET>(),
+            Fetch: Fetch::<Identity, OFFSET>,
+        }
+    }
+    pub fn matches(iid: &windows_core::GUID) -> bool {
+        iid == &<INetworkFetcher2 as windows_core::Interface>::IID
+    }
+}
+#[repr(C)]
+#[doc(hidden)]
+pub struct INetworkFetcher2_Vtbl {
+    pub base__: windows_core::IInspectable_Vtbl,
+    pub Fetch: unsafe extern "system" fn(
+        *mut core::ffi::c_void,
+        u32,
+        u32,
+        *mut core::ffi::c_void,
+        *mut core::ffi::c_void,
+        *mut core::ffi::c_void,
+        u32,
+        *const u8,
+    ) -> windows_core::HRESULT,
+}
 windows_core::imp::define_interface!(
     ISwapChainAttacher,
     ISwapChainAttacher_Vtbl,
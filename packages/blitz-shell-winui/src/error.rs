@@ -0,0 +1,75 @@
+use windows_core::HRESULT;
+
+// DXGI_ERROR_UNSUPPORTED: no device supports the requested feature levels
+const DXGI_ERROR_UNSUPPORTED: HRESULT = HRESULT(0x887A0004u32 as i32);
+const E_NOINTERFACE: HRESULT = HRESULT(0x80004002u32 as i32);
+const E_FAIL: HRESULT = HRESULT(0x80004005u32 as i32);
+
+/// Why a host could not be created or attached to its panel.
+///
+/// At the WinRT boundary it converts into a `windows_core::Error` carrying an HRESULT and this
+/// error's message, so C# callers see a COMException with a meaningful code.
+#[derive(Debug)]
+pub enum BlitzHostError {
+    /// No Direct3D 11 device could be created, or Direct2D could not run on it (no compatible GPU).
+    DeviceInit,
+    /// DXGI could not create the composition swapchain, or the panel rejected `AttachSwapChain`.
+    SwapchainAttach(windows_core::Error),
+    /// The DirectWrite factory could not be created, so no text would render.
+    FontInit,
+    /// The panel object passed by the host does not implement `ISwapChainAttacher`.
+    PanelCast(windows_core::Error),
+}
+
+impl BlitzHostError {
+    /// HRESULT reported across the WinRT boundary.
+    pub fn code(&self) -> HRESULT {
+        match self {
+            BlitzHostError::DeviceInit => DXGI_ERROR_UNSUPPORTED,
+            BlitzHostError::SwapchainAttach(err) => err.code(),
+            BlitzHostError::FontInit => E_FAIL,
+            BlitzHostError::PanelCast(_) => E_NOINTERFACE,
+        }
+    }
+}
+
+impl std::fmt::Display for BlitzHostError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BlitzHostError::DeviceInit => write!(f, "no compatible Direct3D 11 / Direct2D device"),
+            BlitzHostError::SwapchainAttach(err) => {
+                write!(f, "swapchain creation or attach failed: {err}")
+            }
+            BlitzHostError::FontInit => write!(f, "DirectWrite initialization failed"),
+            BlitzHostError::PanelCast(err) => {
+                write!(f, "panel does not implement ISwapChainAttacher: {err}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BlitzHostError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BlitzHostError::SwapchainAttach(err) | BlitzHostError::PanelCast(err) => Some(err),
+            BlitzHostError::DeviceInit | BlitzHostError::FontInit => None,
+        }
+    }
+}
+
+impl From<BlitzHostError> for windows_core::Error {
+    fn from(err: BlitzHostError) -> Self {
+        windows_core::Error::new(err.code(), err.to_string())
+    }
+}
+
+#[test]
+fn errors_marshal_with_distinct_hresults() {
+    let device: windows_core::Error = BlitzHostError::DeviceInit.into();
+    let panel: windows_core::Error =
+        BlitzHostError::PanelCast(windows_core::Error::from(E_NOINTERFACE)).into();
+    assert_eq!(device.code(), DXGI_ERROR_UNSUPPORTED);
+    assert_eq!(panel.code(), E_NOINTERFACE);
+    assert_ne!(device.code(), panel.code());
+    assert!(panel.message().contains("ISwapChainAttacher"));
+}
@@ -1,4 +1,4 @@
-use std::sync::OnceLock;
+use std::sync::Mutex;
 use windows::Win32::Graphics::Direct3D11::{
     D3D11CreateDevice, ID3D11Device, ID3D11DeviceContext, D3D11_CREATE_DEVICE_BGRA_SUPPORT,
     D3D11_CREATE_DEVICE_DEBUG, D3D11_SDK_VERSION,
@@ -15,7 +15,7 @@ struct GlobalDevice {
     _thread_id: std::thread::ThreadId,
 }
 
-static GLOBAL_DEVICE: OnceLock<GlobalDevice> = OnceLock::new();
+static GLOBAL_DEVICE: Mutex<Option<GlobalDevice>> = Mutex::new(None);
 
 pub(crate) struct DeviceAcquireResult {
     pub device: ID3D11Device,
@@ -25,7 +25,8 @@ pub(crate) struct DeviceAcquireResult {
 }
 
 pub(crate) fn get_or_create_d3d_device() -> Option<DeviceAcquireResult> {
-    if let Some(glob) = GLOBAL_DEVICE.get() {
+    let mut global = GLOBAL_DEVICE.lock().unwrap();
+    if let Some(glob) = global.as_ref() {
         return Some(DeviceAcquireResult { device: glob.device.clone(), context: glob.context.clone(), feature_level: glob.feature_level, created: false });
     }
     let start = std::time::Instant::now();
@@ -67,8 +68,13 @@ pub(crate) fn get_or_create_d3d_device() -> Option<DeviceAcquireResult> {
         let context = context.unwrap();
         let create_ms = start.elapsed().as_secs_f32()*1000.0;
         let _thread_id = std::thread::current().id();
-        let _ = GLOBAL_DEVICE.set(GlobalDevice { device: device.clone(), context: context.clone(), feature_level: chosen, _thread_id });
+        *global = Some(GlobalDevice { device: device.clone(), context: context.clone(), feature_level: chosen, _thread_id });
         debug_log(&format!("global_gfx: created shared D3D device (feature {:?}) in {:.2} ms", chosen, create_ms));
     Some(DeviceAcquireResult { device, context, feature_level: chosen, created: true })
     }
 }
+
+// Forget the shared device after it was removed (GPU reset, driver update) so the next caller creates a new one.
+pub(crate) fn release_d3d_device() {
+    GLOBAL_DEVICE.lock().unwrap().take();
+}
@@ -8,10 +8,13 @@
 //!
 //! Status: initial scaffold. Surface creation and event wiring are stubs that need real handles.
 
-mod winrt_component;
-mod global_gfx;
 mod bindings;
+mod error;
+mod global_gfx;
 mod net_bridge;
+mod winrt_component;
+
+pub use error::BlitzHostError;
 
 #[derive(Clone, Copy)]
 pub struct SwapChainPanelHandle {
@@ -27,13 +30,14 @@ pub use anyrender_d2d::D2DWindowRenderer as WindowRenderer;
 /// Contract:
 /// - Inputs: a platform handle (DXGI/D3D interop) describing the target surface + size/scale.
 /// - Output: an opaque handle that the host can drive (resize, redraw, send input).
-/// - Errors: returns a string on failure for easy marshaling across WinRT.
+/// - Errors: a [`BlitzHostError`] naming the failed stage; it converts into an HRESULT-carrying
+///   `windows_core::Error` for marshaling across WinRT.
 pub fn initialize_for_swapchain_panel(
     panel: SwapChainPanelHandle,
     width: u32,
     height: u32,
     scale: f32,
-) -> Result<winrt_component::BlitzHost, String> {
+) -> Result<winrt_component::BlitzHost, BlitzHostError> {
     winrt_component::BlitzHost::new_for_swapchain(panel, width, height, scale)
 }
 
@@ -99,7 +103,7 @@ pub unsafe extern "C" fn blitz_winui_pointer_up(ptr: *mut winrt_component::Blitz
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn blitz_winui_wheel(ptr: *mut winrt_component::BlitzHost, dx: f64, dy: f64) {
     if let Some(host) = unsafe { ptr.as_mut() } {
-        host.wheel_scroll(dx, dy);
+        host.wheel_scroll(dx, dy, 0);
     }
 }
 
@@ -125,6 +129,28 @@ use windows_core::IUnknownImpl;
 use crate::bindings::{IHost, IHostFactory, IHost_Impl, IHostFactory_Impl};
 // Note: We expose a custom factory (IHostFactory) via DllGetActivationFactory.
 
+// Live WinRT objects (hosts + activation factories) handed out by this module; DllCanUnloadNow
+// only allows unloading once this drops back to zero.
+static LIVE_OBJECTS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+fn object_created() {
+    LIVE_OBJECTS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+}
+
+fn object_destroyed() {
+    LIVE_OBJECTS.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
+}
+
+fn live_object_count() -> usize {
+    LIVE_OBJECTS.load(std::sync::atomic::Ordering::SeqCst)
+}
+
+// Windows.UI.Color values cross the ABI packed as 0xAARRGGBB
+fn color_from_argb(argb: u32) -> peniko::Color {
+    let [a, r, g, b] = argb.to_be_bytes();
+    peniko::Color::from_rgba8(r, g, b, a)
+}
+
 // HostRuntime implements only IHost; factory provided separately via HostActivationFactory
 #[implement(IHost)]
 pub struct HostRuntime {
@@ -134,17 +160,24 @@ pub struct HostRuntime {
 #[allow(non_snake_case)]
 impl HostRuntime {
     fn new() -> HostRuntime {
+        object_created();
         HostRuntime { inner: std::sync::Mutex::new(None) }
     }
 }
 
+impl Drop for HostRuntime {
+    fn drop(&mut self) {
+        object_destroyed();
+    }
+}
+
 // Implement the generated traits for the macro-generated identity type
 #[allow(non_snake_case)]
 impl IHost_Impl for HostRuntime_Impl {
     fn SetPanel(&self, panel: windows_core::Ref<'_, IInspectable>) -> windows_core::Result<()> {
         let imp = self.get_impl();
         if let Some(inner) = imp.inner.lock().unwrap().as_mut() {
-            inner.set_panel(panel, 0, 0);
+            inner.set_panel(panel, 0, 0)?;
         }
         Ok(())
     }
@@ -157,6 +190,14 @@ impl IHost_Impl for HostRuntime_Impl {
         Ok(())
     }
 
+    fn SetRasterizationScale(&self, scale: f32) -> windows_core::Result<()> {
+        let imp = self.get_impl();
+        if let Some(inner) = imp.inner.lock().unwrap().as_mut() {
+            inner.set_rasterization_scale(scale);
+        }
+        Ok(())
+    }
+
     fn RenderOnce(&self) -> windows_core::Result<()> {
         let imp = self.get_impl();
         if let Some(inner) = imp.inner.lock().unwrap().as_mut() {
@@ -175,8 +216,9 @@ impl IHost_Impl for HostRuntime_Impl {
     }
 
     fn SetVerboseLogging(&self, enabled: bool) -> windows_core::Result<()> {
-        // Toggle global verbose logging in the D2D backend.
+        // Toggle global verbose logging in the D2D backend and the net bridge.
         anyrender_d2d::set_verbose_logging(enabled);
+        blitz_net_winui::set_verbose_logging(enabled);
         crate::winrt_component::debug_log(&format!("HostRuntime::SetVerboseLogging: enabled={}", enabled));
         Ok(())
     }
@@ -189,6 +231,39 @@ impl IHost_Impl for HostRuntime_Impl {
         Ok(())
     }
 
+    fn SetInspectOverlay(&self, enabled: bool) -> windows_core::Result<()> {
+        let imp = self.get_impl();
+        if let Some(inner) = imp.inner.lock().unwrap().as_mut() {
+            inner.set_inspect_overlay(enabled);
+        }
+        Ok(())
+    }
+
+    fn SetForcedColors(
+        &self,
+        enabled: bool,
+        canvas: u32,
+        canvas_text: u32,
+        link_text: u32,
+        button_face: u32,
+        button_text: u32,
+        highlight: u32,
+    ) -> windows_core::Result<()> {
+        let imp = self.get_impl();
+        if let Some(inner) = imp.inner.lock().unwrap().as_mut() {
+            let scheme = enabled.then(|| blitz_dom::ForcedColorScheme {
+                canvas: color_from_argb(canvas),
+                canvas_text: color_from_argb(canvas_text),
+                link_text: color_from_argb(link_text),
+                button_face: color_from_argb(button_face),
+                button_text: color_from_argb(button_text),
+                highlight: color_from_argb(highlight),
+            });
+            inner.set_forced_colors(scheme);
+        }
+        Ok(())
+    }
+
     fn SetNetworkFetcher(&self, fetcher: windows_core::Ref<'_, IInspectable>) -> windows_core::Result<()> {
         let imp = self.get_impl();
         if let Some(inner) = imp.inner.lock().unwrap().as_mut() {
@@ -197,6 +272,19 @@ impl IHost_Impl for HostRuntime_Impl {
         Ok(())
     }
 
+    fn SetFirstFrameListener(
+        &self,
+        listener: windows_core::Ref<'_, IInspectable>,
+    ) -> windows_core::Result<()> {
+        let imp = self.get_impl();
+        if let Some(inner) = imp.inner.lock().unwrap().as_mut() {
+            if let Some(obj) = listener.as_ref() {
+                inner.set_first_frame_listener(obj.clone());
+            }
+        }
+        Ok(())
+    }
+
     fn CompleteFetch(&self, request_id: u32, doc_id: u32, success: bool, data: &[u8], error_message: &HSTRING) -> windows_core::Result<()> {
         let imp = self.get_impl();
         if let Some(inner) = imp.inner.lock().unwrap().as_mut() {
@@ -206,13 +294,53 @@ impl IHost_Impl for HostRuntime_Impl {
         Ok(())
     }
 
-    fn RequestUrl(&self, doc_id: u32, url: &::windows::core::HSTRING, _request_id: u32) -> ::windows::core::Result<()> {
+    fn RequestUrl(
+        &self,
+        doc_id: u32,
+        url: &::windows::core::HSTRING,
+        _request_id: u32,
+    ) -> ::windows::core::Result<()> {
         use blitz_dom::net::Resource;
         let imp = self.get_impl();
         if let Some(inner) = imp.inner.lock().unwrap().as_mut() {
             struct NoopHandler; impl blitz_traits::net::NetHandler<Resource> for NoopHandler { fn bytes(self: Box<Self>, _doc: usize, _b: blitz_traits::net::Bytes, _cb: blitz_traits::net::SharedCallback<Resource>) {} }
             let handler: blitz_traits::net::BoxedHandler<Resource> = Box::new(NoopHandler);
-            inner.request_url(doc_id as usize, &url.to_string(), handler);
+            inner.request_url(doc_id as usize, &url.to_string(), "", "", &[], handler);
+        }
+        Ok(())
+    }
+
+    fn RequestUrlWithOptions(
+        &self,
+        doc_id: u32,
+        url: &::windows::core::HSTRING,
+        _request_id: u32,
+        method: &::windows::core::HSTRING,
+        headers: &::windows::core::HSTRING,
+        body: &[u8],
+    ) -> ::windows::core::Result<()> {
+        use blitz_dom::net::Resource;
+        let imp = self.get_impl();
+        if let Some(inner) = imp.inner.lock().unwrap().as_mut() {
+            struct NoopHandler;
+            impl blitz_traits::net::NetHandler<Resource> for NoopHandler {
+                fn bytes(
+                    self: Box<Self>,
+                    _doc: usize,
+                    _b: blitz_traits::net::Bytes,
+                    _cb: blitz_traits::net::SharedCallback<Resource>,
+                ) {
+                }
+            }
+            let handler: blitz_traits::net::BoxedHandler<Resource> = Box::new(NoopHandler);
+            inner.request_url(
+                doc_id as usize,
+                &url.to_string(),
+                &method.to_string(),
+                &headers.to_string(),
+                body,
+                handler,
+            );
         }
         Ok(())
     }
@@ -234,11 +362,43 @@ impl IHost_Impl for HostRuntime_Impl {
     fn WheelScroll(&self, dx: f64, dy: f64) -> windows_core::Result<()> {
         let imp = self.get_impl();
         if let Some(inner) = imp.inner.lock().unwrap().as_mut() {
-            inner.wheel_scroll(dx, dy);
+            inner.wheel_scroll(dx, dy, 0);
         }
         Ok(())
     }
 
+    fn WheelScrollWithModifiers(
+        &self,
+        dx: f64,
+        dy: f64,
+        modifiers: u32,
+    ) -> windows_core::Result<()> {
+        let imp = self.get_impl();
+        if let Some(inner) = imp.inner.lock().unwrap().as_mut() {
+            inner.wheel_scroll(dx, dy, modifiers);
+        }
+        Ok(())
+    }
+
+    fn SetZoom(&self, zoom: f32) -> windows_core::Result<()> {
+        let imp = self.get_impl();
+        if let Some(inner) = imp.inner.lock().unwrap().as_mut() {
+            inner.set_zoom(zoom);
+        }
+        Ok(())
+    }
+
+    fn GetZoom(&self) -> windows_core::Result<f32> {
+        let imp = self.get_impl();
+        Ok(imp
+            .inner
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|inner| inner.zoom())
+            .unwrap_or(1.0))
+    }
+
     fn PointerMove(&self, x: f32, y: f32, buttons: u32, modifiers: u32) -> windows_core::Result<()> {
         let imp = self.get_impl();
         if let Some(inner) = imp.inner.lock().unwrap().as_mut() {
@@ -270,17 +430,367 @@ impl IHost_Impl for HostRuntime_Impl {
         }
         Ok(())
     }
+
+    fn Copy(&self) -> windows_core::Result<HSTRING> {
+        let imp = self.get_impl();
+        let text = imp
+            .inner
+            .lock()
+            .unwrap()
+            .as_mut()
+            .and_then(|inner| inner.copy_selection());
+        Ok(HSTRING::from(text.unwrap_or_default()))
+    }
+
+    fn Cut(&self) -> windows_core::Result<HSTRING> {
+        let imp = self.get_impl();
+        let text = imp
+            .inner
+            .lock()
+            .unwrap()
+            .as_mut()
+            .and_then(|inner| inner.cut_selection());
+        Ok(HSTRING::from(text.unwrap_or_default()))
+    }
+
+    fn Paste(&self, text: &HSTRING) -> windows_core::Result<()> {
+        let imp = self.get_impl();
+        if let Some(inner) = imp.inner.lock().unwrap().as_mut() {
+            inner.paste_text(&text.to_string());
+        }
+        Ok(())
+    }
+
+    fn GetFrameTimingsJson(&self) -> windows_core::Result<HSTRING> {
+        let imp = self.get_impl();
+        let json = imp
+            .inner
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|inner| inner.frame_timings_json());
+        Ok(HSTRING::from(json.unwrap_or_else(|| "{}".to_string())))
+    }
+
+    fn ScrollToNode(&self, nodeid: u32) -> windows_core::Result<()> {
+        let imp = self.get_impl();
+        if let Some(inner) = imp.inner.lock().unwrap().as_mut() {
+            inner.scroll_to(nodeid as usize);
+        }
+        Ok(())
+    }
+
+    fn ScrollToTop(&self) -> windows_core::Result<()> {
+        let imp = self.get_impl();
+        if let Some(inner) = imp.inner.lock().unwrap().as_mut() {
+            inner.scroll_to_top();
+        }
+        Ok(())
+    }
+
+    fn ScrollToBottom(&self) -> windows_core::Result<()> {
+        let imp = self.get_impl();
+        if let Some(inner) = imp.inner.lock().unwrap().as_mut() {
+            inner.scroll_to_bottom();
+        }
+        Ok(())
+    }
+
+    fn ScrollBy(&self, dx: f64, dy: f64) -> windows_core::Result<()> {
+        let imp = self.get_impl();
+        if let Some(inner) = imp.inner.lock().unwrap().as_mut() {
+            inner.scroll_by(dx, dy);
+        }
+        Ok(())
+    }
+
+    fn ScrollByPage(&self, pages: i32) -> windows_core::Result<()> {
+        let imp = self.get_impl();
+        if let Some(inner) = imp.inner.lock().unwrap().as_mut() {
+            inner.scroll_by_page(pages);
+        }
+        Ok(())
+    }
+
+    fn ImeSetComposition(
+        &self,
+        text: &HSTRING,
+        cursorstart: u32,
+        cursorend: u32,
+    ) -> windows_core::Result<()> {
+        let imp = self.get_impl();
+        if let Some(inner) = imp.inner.lock().unwrap().as_mut() {
+            inner.ime_set_composition(&text.to_string(), cursorstart, cursorend);
+        }
+        Ok(())
+    }
+
+    fn ImeCommit(&self, text: &HSTRING) -> windows_core::Result<()> {
+        let imp = self.get_impl();
+        if let Some(inner) = imp.inner.lock().unwrap().as_mut() {
+            inner.ime_commit(&text.to_string());
+        }
+        Ok(())
+    }
+
+    fn ImeCancel(&self) -> windows_core::Result<()> {
+        let imp = self.get_impl();
+        if let Some(inner) = imp.inner.lock().unwrap().as_mut() {
+            inner.ime_cancel();
+        }
+        Ok(())
+    }
+
+    fn GetImeCaretRect(&self) -> windows_core::Result<HSTRING> {
+        let imp = self.get_impl();
+        let rect = imp
+            .inner
+            .lock()
+            .unwrap()
+            .as_ref()
+            .and_then(|inner| inner.ime_caret_rect());
+        let json = rect
+            .map(|r| {
+                format!(
+                    "{{\"x\":{},\"y\":{},\"width\":{},\"height\":{}}}",
+                    r.x0,
+                    r.y0,
+                    r.width(),
+                    r.height()
+                )
+            })
+            .unwrap_or_default();
+        Ok(HSTRING::from(json))
+    }
+
+    fn IsContentLoaded(&self) -> windows_core::Result<bool> {
+        let imp = self.get_impl();
+        Ok(imp
+            .inner
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|inner| inner.is_content_loaded()))
+    }
+
+    fn HitTest(&self, x: f32, y: f32) -> windows_core::Result<HSTRING> {
+        let imp = self.get_impl();
+        let hit = imp
+            .inner
+            .lock()
+            .unwrap()
+            .as_ref()
+            .and_then(|inner| inner.hit_test(x, y));
+        Ok(HSTRING::from(
+            hit.map(|hit| hit.to_json()).unwrap_or_default(),
+        ))
+    }
+
+    fn SetTargetFps(&self, fps: f32) -> windows_core::Result<()> {
+        let imp = self.get_impl();
+        if let Some(inner) = imp.inner.lock().unwrap().as_mut() {
+            inner.set_target_fps((fps > 0.0).then_some(fps));
+        }
+        Ok(())
+    }
+
+    fn ScrollToFragment(&self, fragment: &HSTRING) -> windows_core::Result<bool> {
+        let imp = self.get_impl();
+        let found = imp
+            .inner
+            .lock()
+            .unwrap()
+            .as_mut()
+            .is_some_and(|inner| inner.scroll_to_fragment(&fragment.to_string()));
+        Ok(found)
+    }
+
+    fn Unload(&self) -> windows_core::Result<()> {
+        let imp = self.get_impl();
+        if let Some(inner) = imp.inner.lock().unwrap().as_mut() {
+            inner.unload();
+        }
+        Ok(())
+    }
+
+    fn WantsAnimationFrame(&self) -> windows_core::Result<bool> {
+        let imp = self.get_impl();
+        Ok(imp
+            .inner
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|inner| inner.wants_animation_frame()))
+    }
+
+    fn GetCommandStatsJson(&self) -> windows_core::Result<HSTRING> {
+        let imp = self.get_impl();
+        let json = imp
+            .inner
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|inner| inner.command_stats_json());
+        Ok(HSTRING::from(json.unwrap_or_else(|| "{}".to_string())))
+    }
+
+    fn SetBaseUrl(&self, url: &HSTRING) -> windows_core::Result<()> {
+        let imp = self.get_impl();
+        if let Some(inner) = imp.inner.lock().unwrap().as_mut() {
+            inner.set_base_url(&url.to_string());
+        }
+        Ok(())
+    }
+
+    fn BeginStream(&self) -> windows_core::Result<()> {
+        let imp = self.get_impl();
+        if let Some(inner) = imp.inner.lock().unwrap().as_mut() {
+            inner.begin_stream();
+        }
+        Ok(())
+    }
+
+    fn PushHtmlChunk(&self, bytes: &[u8]) -> windows_core::Result<bool> {
+        let imp = self.get_impl();
+        Ok(imp
+            .inner
+            .lock()
+            .unwrap()
+            .as_mut()
+            .is_some_and(|inner| inner.push_html_chunk(bytes)))
+    }
+
+    fn EndStream(&self) -> windows_core::Result<bool> {
+        let imp = self.get_impl();
+        Ok(imp
+            .inner
+            .lock()
+            .unwrap()
+            .as_mut()
+            .is_some_and(|inner| inner.end_stream()))
+    }
+
+    fn GetCursor(&self, x: f32, y: f32) -> windows_core::Result<i32> {
+        let imp = self.get_impl();
+        let cursor = imp
+            .inner
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|inner| inner.cursor_at(x, y))
+            .unwrap_or_default();
+        Ok(cursor as i32)
+    }
+
+    fn SetDebugOverlayOptions(
+        &self,
+        lines: u32,
+        corner: u8,
+        opacity: f32,
+    ) -> windows_core::Result<()> {
+        let imp = self.get_impl();
+        if let Some(inner) = imp.inner.lock().unwrap().as_mut() {
+            inner.set_debug_overlay_options(lines, corner, opacity);
+        }
+        Ok(())
+    }
+
+    fn LoadUrl(&self, url: &HSTRING) -> windows_core::Result<bool> {
+        let imp = self.get_impl();
+        Ok(imp
+            .inner
+            .lock()
+            .unwrap()
+            .as_mut()
+            .is_some_and(|inner| inner.load_url(&url.to_string())))
+    }
+
+    fn SetColorScheme(&self, scheme: u8) -> windows_core::Result<()> {
+        let imp = self.get_impl();
+        if let Some(inner) = imp.inner.lock().unwrap().as_mut() {
+            inner.set_color_scheme(winrt_component::color_scheme_from_abi(scheme));
+        }
+        Ok(())
+    }
+
+    fn TrimMemory(&self, level: u8) -> windows_core::Result<()> {
+        let imp = self.get_impl();
+        if let Some(inner) = imp.inner.lock().unwrap().as_mut() {
+            let level = if level == 0 {
+                anyrender_d2d::TrimLevel::Mild
+            } else {
+                anyrender_d2d::TrimLevel::Aggressive
+            };
+            inner.trim_memory(level);
+        }
+        Ok(())
+    }
+
+    fn SetNavigationListener(
+        &self,
+        listener: windows_core::Ref<'_, IInspectable>,
+    ) -> windows_core::Result<()> {
+        let imp = self.get_impl();
+        if let Some(inner) = imp.inner.lock().unwrap().as_mut() {
+            if let Some(obj) = listener.as_ref() {
+                inner.set_navigation_listener(obj.clone());
+            }
+        }
+        Ok(())
+    }
+
+    fn CapturePng(
+        &self,
+        width: u32,
+        height: u32,
+        full_page: bool,
+    ) -> windows_core::Result<windows_core::Array<u8>> {
+        let imp = self.get_impl();
+        let png = imp
+            .inner
+            .lock()
+            .unwrap()
+            .as_mut()
+            .map(|inner| inner.capture_png(width, height, full_page))
+            .unwrap_or_default();
+        Ok(windows_core::Array::from_slice(&png))
+    }
+
+    fn DumpLastScene(&self) -> windows_core::Result<HSTRING> {
+        let imp = self.get_impl();
+        let dump = imp
+            .inner
+            .lock()
+            .unwrap()
+            .as_mut()
+            .map(|inner| inner.dump_last_scene());
+        Ok(HSTRING::from(dump.unwrap_or_default()))
+    }
 }
 
 
 // --- WinRT Activation Factory ---
 // Provide a factory object that implements IHostFactory; the runtime will QI for this interface.
 #[implement(IHostFactory)]
-pub struct HostActivationFactory;
+pub struct HostActivationFactory {
+    _private: (),
+}
+
+impl HostActivationFactory {
+    fn new() -> HostActivationFactory {
+        object_created();
+        HostActivationFactory { _private: () }
+    }
+}
+
+impl Drop for HostActivationFactory {
+    fn drop(&mut self) {
+        object_destroyed();
+    }
+}
 
 #[allow(non_snake_case)]
 impl IHostFactory_Impl for HostActivationFactory_Impl {
-
     fn CreateInstance(
         &self,
         attacher: windows_core::Ref<'_, IInspectable>,
@@ -298,13 +808,15 @@ impl IHostFactory_Impl for HostActivationFactory_Impl {
             match insp.cast::<ISwapChainAttacher>() {
                 Ok(att) => {
                     crate::winrt_component::debug_log("HostActivationFactory::CreateInstance: cast to ISwapChainAttacher succeeded");
-                    if let Ok(mut shell) = winrt_component::BlitzHost::new_with_attacher(att, width, height, scale) {
-                        if !html_str.is_empty() { shell.load_html(&html_str); }
-                        *runtime.inner.lock().unwrap() = Some(Box::new(shell));
-                        let insp: IInspectable = runtime.into();
-                        let host: bindings::Host = Interface::cast(&insp)?;
-                        return Ok(host);
-                    }
+                    let mut shell = winrt_component::BlitzHost::new_with_attacher(att, width, height, scale).map_err(|e| {
+                        crate::winrt_component::debug_log(&format!("HostActivationFactory::CreateInstance: host creation failed: {e}"));
+                        windows_core::Error::from(e)
+                    })?;
+                    if !html_str.is_empty() { shell.load_html(&html_str); }
+                    *runtime.inner.lock().unwrap() = Some(Box::new(shell));
+                    let insp: IInspectable = runtime.into();
+                    let host: bindings::Host = Interface::cast(&insp)?;
+                    return Ok(host);
                 }
                 Err(err) => {
                     crate::winrt_component::debug_log(&format!("HostActivationFactory::CreateInstance: cast to ISwapChainAttacher FAILED hr={:?}", err.code()));
@@ -313,6 +825,7 @@ impl IHostFactory_Impl for HostActivationFactory_Impl {
                     }
                     crate::winrt_component::debug_log(&format!("  Expected ISwapChainAttacher IID={:?}", <ISwapChainAttacher as Interface>::IID));
                     crate::winrt_component::debug_log("  Likely cause: object is a plain C# class not emitted as a WinRT runtime class implementing the interface.");
+                    return Err(BlitzHostError::PanelCast(err).into());
                 }
             }
         }
@@ -322,7 +835,7 @@ impl IHostFactory_Impl for HostActivationFactory_Impl {
             width,
             height,
             scale,
-        ).map_err(|_| windows_core::Error::new(windows_core::HRESULT(0x80004005u32 as i32), "Host creation failed"))?;
+        )?;
         if !html_str.is_empty() { shell.load_html(&html_str); }
         *runtime.inner.lock().unwrap() = Some(Box::new(shell));
         let insp: IInspectable = runtime.into();
@@ -334,7 +847,10 @@ impl IHostFactory_Impl for HostActivationFactory_Impl {
 
 // Exported activation entrypoint returning our activation factory for Blitz.WinUI.Host
 #[unsafe(no_mangle)]
-pub extern "system" fn DllGetActivationFactory(name: HSTRING, factory: *mut *mut c_void) -> windows_core::HRESULT {
+pub extern "system" fn DllGetActivationFactory(
+    name: HSTRING,
+    factory: *mut *mut c_void,
+) -> windows_core::HRESULT {
     // E_INVALIDARG if no out parameter
     if factory.is_null() {
         return windows_core::HRESULT(0x80070057u32 as i32);
@@ -345,7 +861,7 @@ pub extern "system" fn DllGetActivationFactory(name: HSTRING, factory: *mut *mut
     let class_name = name.to_string();
     if class_name == "BlitzWinUI.Host" {
         // Create factory object and hand out IHostFactory
-        let fac = HostActivationFactory;
+        let fac = HostActivationFactory::new();
         let insp: IInspectable = fac.into();
         match Interface::cast::<IHostFactory>(&insp) {
             Ok(host_factory) => {
@@ -359,3 +875,37 @@ pub extern "system" fn DllGetActivationFactory(name: HSTRING, factory: *mut *mut
         windows_core::HRESULT(0x80040154u32 as i32)
     }
 }
+
+// Exported unload probe: S_OK once no hosts or factories are alive, S_FALSE otherwise.
+#[unsafe(no_mangle)]
+pub extern "system" fn DllCanUnloadNow() -> windows_core::HRESULT {
+    if live_object_count() == 0 {
+        windows_core::HRESULT(0) // S_OK
+    } else {
+        windows_core::HRESULT(1) // S_FALSE
+    }
+}
+
+#[test]
+fn test_live_object_count() {
+    let before = live_object_count();
+    let runtime = HostRuntime::new();
+    let factory = HostActivationFactory::new();
+    assert_eq!(live_object_count(), before + 2);
+    drop(runtime);
+    drop(factory);
+    assert_eq!(live_object_count(), before);
+}
+
+#[test]
+fn test_color_from_argb() {
+    assert_eq!(
+        color_from_argb(0xFF1A2B3C).to_rgba8(),
+        peniko::color::Rgba8 {
+            r: 0x1A,
+            g: 0x2B,
+            b: 0x3C,
+            a: 0xFF
+        }
+    );
+}
@@ -1,8 +1,8 @@
+use crate::bindings::{INetworkFetcher, INetworkFetcher2};
+use crate::winrt_component::debug_log;
+use blitz_net_winui::{HostFetcher, HostRequest};
 use std::sync::Arc;
 use windows::core::{IInspectable, Interface};
-use crate::bindings::INetworkFetcher;
-use blitz_net_winui::HostFetcher;
-use crate::winrt_component::debug_log;
 
 pub struct HostNetworkDispatcher {
     pub fetcher: IInspectable,
@@ -14,17 +14,39 @@ unsafe impl Send for HostNetworkDispatcher {}
 unsafe impl Sync for HostNetworkDispatcher {}
 
 impl HostFetcher for HostNetworkDispatcher {
-    fn request_url(&self, doc_id: usize, url: &str, request_id: u32) -> bool {
-        debug_log(&format!("HostNetworkDispatcher.request_url: req_id={} doc_id={} url={}", request_id, doc_id, url));
-        if let Ok(f) = self.fetcher.cast::<INetworkFetcher>() {
-            use windows::core::HSTRING;
-            let url_h = HSTRING::from(url);
-            let method_h = HSTRING::from("GET");
-            let ok = f.Fetch(request_id, doc_id as u32, &url_h, &method_h).is_ok();
-            if ok { debug_log(&format!("HostNetworkDispatcher.request_url: dispatched req_id={}", request_id)); }
-            else { debug_log(&format!("HostNetworkDispatcher.request_url: Fetch call failed req_id={}", request_id)); }
-            ok
-        } else { debug_log("HostNetworkDispatcher.request_url: cast to INetworkFetcher failed"); false }
+    fn request_url(&self, doc_id: usize, request: &HostRequest, request_id: u32) -> bool {
+        if blitz_net_winui::is_verbose_logging() {
+            debug_log(&format!(
+                "HostNetworkDispatcher.request_url: req_id={} doc_id={} {} {}",
+                request_id, doc_id, request.method, request.url
+            ));
+        }
+        use windows::core::HSTRING;
+        let url_h = HSTRING::from(request.url.as_str());
+        let method_h = HSTRING::from(request.method.as_str());
+        let result = if let Ok(f) = self.fetcher.cast::<INetworkFetcher2>() {
+            let headers_h = HSTRING::from(request.headers.as_str());
+            f.Fetch(request_id, doc_id as u32, &url_h, &method_h, &headers_h, &request.body)
+        } else if let Ok(f) = self.fetcher.cast::<INetworkFetcher>() {
+            // Older fetchers only take the URL and method
+            f.Fetch(request_id, doc_id as u32, &url_h, &method_h)
+        } else { debug_log("HostNetworkDispatcher.request_url: cast to INetworkFetcher failed"); return false; };
+        let ok = result.is_ok();
+        if !ok { debug_log(&format!("HostNetworkDispatcher.request_url: Fetch call failed req_id={}", request_id)); }
+        ok
+    }
+}
+
+// Stand-in until the host supplies an INetworkFetcher: only file: requests (served by the provider itself) succeed
+pub struct OfflineFetcher;
+
+impl HostFetcher for OfflineFetcher {
+    fn request_url(&self, _doc_id: usize, request: &HostRequest, request_id: u32) -> bool {
+        debug_log(&format!(
+            "OfflineFetcher.request_url: req_id={} {} (no network fetcher)",
+            request_id, request.url
+        ));
+        false
     }
 }
 
@@ -32,3 +54,39 @@ pub fn make_provider(fetcher: IInspectable) -> Arc<blitz_net_winui::WinUiNetProv
     let dispatcher = HostNetworkDispatcher { fetcher };
     blitz_net_winui::WinUiNetProvider::shared(Arc::new(dispatcher))
 }
+
+pub fn make_offline_provider() -> Arc<blitz_net_winui::WinUiNetProvider<blitz_dom::net::Resource>> {
+    blitz_net_winui::WinUiNetProvider::shared(Arc::new(OfflineFetcher))
+}
+
+#[test]
+fn host_requests_carry_method_headers_and_body() {
+    use blitz_net_winui::parse_header_lines;
+    use blitz_traits::net::{Body, Entry, EntryValue, FormData, HeaderMap, Method, Request, Url};
+    let mut headers = HeaderMap::new();
+    headers.insert("x-api-key", "secret".parse().unwrap());
+    let mut request = Request::get(Url::parse("https://example.com/submit").unwrap());
+    request.method = Method::POST;
+    request.headers = headers;
+    request.content_type = "application/x-www-form-urlencoded".to_string();
+    request.body = Body::Form(FormData(vec![Entry {
+        name: "q".into(),
+        value: EntryValue::String("a b&c".into()),
+    }]));
+
+    let host = HostRequest::from_request(&request);
+    assert_eq!(host.method, "POST");
+    assert_eq!(
+        host.headers,
+        "x-api-key: secret\r\nContent-Type: application/x-www-form-urlencoded"
+    );
+    assert_eq!(host.body, b"q=a+b%26c");
+
+    let parsed = parse_header_lines(&host.headers);
+    assert_eq!(parsed.get("x-api-key").unwrap(), "secret");
+    assert_eq!(
+        parsed.get("content-type").unwrap(),
+        "application/x-www-form-urlencoded"
+    );
+    assert_eq!(parse_header_lines("no colon\n: empty name").len(), 0);
+}
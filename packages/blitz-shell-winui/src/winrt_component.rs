@@ -1,29 +1,28 @@
 use anyrender::WindowRenderer as _;
-use std::sync::Arc;
 use anyrender_d2d::D2DWindowRenderer;
-use blitz_dom::{Document, DocumentConfig};
-use blitz_html::HtmlDocument;
-use blitz_paint::paint_scene;
+use blitz_dom::{Document, DocumentConfig, ForcedColorScheme, local_name};
+use blitz_html::{HtmlDocument, HtmlStreamParser};
+use blitz_paint::{paint_scene, root_background_color};
 use blitz_traits::shell::{ColorScheme, Viewport};
+use cursor_icon::CursorIcon;
+use std::sync::Arc;
 
-use crate::bindings::ISwapChainAttacher;
+use crate::BlitzHostError;
+use crate::bindings::{IFirstFrameListener, INavigationListener, ISwapChainAttacher};
 use crate::net_bridge;
 use blitz_dom::net::Resource;
-use windows::core::{IInspectable, Interface};
 use windows::Win32::Graphics::Direct3D11::{
-    ID3D11Device, ID3D11DeviceContext, ID3D11Texture2D,
-    ID3D11Resource,
+    ID3D11Device, ID3D11DeviceContext, ID3D11Resource, ID3D11Texture2D,
 };
+use windows::Win32::Graphics::Dxgi::Common::{DXGI_FORMAT, DXGI_SAMPLE_DESC};
 use windows::Win32::Graphics::Dxgi::{
-    CreateDXGIFactory2, IDXGIFactory2, IDXGISwapChain1, DXGI_CREATE_FACTORY_FLAGS,
-    DXGI_SWAP_CHAIN_DESC1, DXGI_USAGE_RENDER_TARGET_OUTPUT, DXGI_PRESENT,
-    DXGI_SWAP_EFFECT_FLIP_SEQUENTIAL,
-};
-use windows::Win32::Graphics::Dxgi::Common::{
-    DXGI_FORMAT, DXGI_SAMPLE_DESC,
+    CreateDXGIFactory2, DXGI_CREATE_FACTORY_FLAGS, DXGI_PRESENT, DXGI_PRESENT_PARAMETERS,
+    DXGI_SWAP_CHAIN_DESC1, DXGI_SWAP_EFFECT_FLIP_SEQUENTIAL, DXGI_USAGE_RENDER_TARGET_OUTPUT,
+    IDXGIFactory2, IDXGISwapChain1,
 };
 use windows::Win32::System::Diagnostics::Debug::OutputDebugStringA;
 use windows::core::PCSTR;
+use windows::core::{IInspectable, Interface};
 
 pub(crate) fn debug_log(msg: &str) {
     // Append newline for readability in DebugView.
@@ -37,6 +36,7 @@ fn resource_kind_name(r: &Resource) -> &'static str {
     match r {
         Resource::Css(..) => "Css",
         Resource::Image(..) => "Image",
+        Resource::AnimatedImage(..) => "AnimatedImage",
         Resource::Font(..) => "Font",
         Resource::Navigation { .. } => "Navigation",
     Resource::None => "None",
@@ -55,6 +55,64 @@ pub unsafe extern "C" fn __blitz_host_debug_log(ptr: *const u8, len: usize) {
 
 // Use generated ISwapChainAttacher from bindings.rs
 
+// Document-side clipboard. The WinUI host owns the real Windows clipboard: it handles Ctrl+C/X/V by calling
+// Copy/Cut (and placing the result on the clipboard) or Paste with the clipboard's text, so this only mirrors
+// the last exchanged text. Shortcuts sent as plain key events act on the mirror alone.
+#[derive(Default)]
+struct HostShellProvider {
+    clipboard: std::sync::Mutex<Option<String>>,
+}
+
+impl HostShellProvider {
+    fn clipboard_text(&self) -> Option<String> {
+        self.clipboard.lock().unwrap().clone()
+    }
+}
+
+impl blitz_traits::shell::ShellProvider for HostShellProvider {
+    fn get_clipboard_text(&self) -> Result<String, blitz_traits::shell::ClipboardError> {
+        self.clipboard_text()
+            .ok_or(blitz_traits::shell::ClipboardError)
+    }
+    fn set_clipboard_text(&self, text: String) -> Result<(), blitz_traits::shell::ClipboardError> {
+        *self.clipboard.lock().unwrap() = Some(text);
+        Ok(())
+    }
+}
+
+// Link clicks and form submissions the document asked to navigate to. The document calls this from inside
+// event dispatch, so requests are only queued here and handed to the host's INavigationListener afterwards.
+#[derive(Default)]
+struct HostNavigationProvider {
+    pending: std::sync::Mutex<Vec<blitz_traits::navigation::NavigationOptions>>,
+}
+
+impl blitz_traits::navigation::NavigationProvider for HostNavigationProvider {
+    fn navigate_to(&self, options: blitz_traits::navigation::NavigationOptions) {
+        self.pending.lock().unwrap().push(options);
+    }
+}
+
+// Receives the body of a page requested with load_url; complete_fetch then loads it as the new document
+struct PageHandler {
+    body: Arc<std::sync::Mutex<Option<blitz_traits::net::Bytes>>>,
+}
+
+impl blitz_traits::net::NetHandler<Resource> for PageHandler {
+    fn bytes(
+        self: Box<Self>,
+        _doc_id: usize,
+        bytes: blitz_traits::net::Bytes,
+        _callback: blitz_traits::net::SharedCallback<Resource>,
+    ) {
+        *self.body.lock().unwrap() = Some(bytes);
+    }
+}
+
+// Empty transparent page used before the first load_html and after unload
+const BLANK_HTML: &str =
+    "<html><head></head><body style=\"margin:0;padding:0;background:transparent;\"></body></html>";
+
 /// Public host object backing the WinRT class. Keeps the document and renderer alive and exposes
 /// methods called from C# to drive rendering and input.
 pub struct BlitzHost {
@@ -88,19 +146,57 @@ pub struct BlitzHost {
     // Shared callback used by provider to deliver parsed resources back to DOM once handler finishes.
     resource_callback: Option<blitz_traits::net::SharedCallback<Resource>>,
     provider: Option<std::sync::Arc<blitz_net_winui::WinUiNetProvider<Resource>>>,
+    // Base URL (SetBaseUrl) that relative resource, navigation and RequestUrl URLs resolve against; applies to the
+    // current document and every later load_html
+    base_url: Option<blitz_traits::net::Url>,
+    // http(s) page requested by load_url: its URL and the slot its body lands in when the fetch completes
+    pending_page: Option<(
+        blitz_traits::net::Url,
+        Arc<std::sync::Mutex<Option<blitz_traits::net::Bytes>>>,
+    )>,
+    // Parser of the document being streamed in (begin_stream .. end_stream)
+    stream: Option<HtmlStreamParser>,
     // Device (rasterization) scale captured from XamlRoot; we force viewport scale=1.0 (CSS px == logical DIP)
     // but allocate swapchain/backbuffer at logical * device_scale for crisp text.
     device_scale: f32,
+    // Page zoom (SetZoom / Ctrl+wheel), kept in the viewport so layout reflows at zoom; composes with
+    // device_scale (applied by the renderer) into the final transform
+    zoom: f32,
+    // Color scheme (SetColorScheme) for prefers-color-scheme; kept in the viewport across resizes
+    color_scheme: ColorScheme,
+    // Clipboard mirror shared with the document's ShellProvider
+    shell: Arc<HostShellProvider>,
+    // Navigation requests queued by the document, and the host object implementing INavigationListener
+    navigation: Arc<HostNavigationProvider>,
+    navigation_listener: Option<IInspectable>,
+    // Active overlay-scrollbar drag: pointer y offset from the thumb top at grab time
+    scrollbar_drag: Option<f64>,
+    // Host object implementing IFirstFrameListener, told when each document's first frame is painted. The
+    // renderer's callback only raises the flag; the listener is called from render_once on the UI thread.
+    first_frame_listener: Option<IInspectable>,
+    first_frame_painted: Arc<std::sync::atomic::AtomicBool>,
+    // The current document finished its initial layout and presented its first frame (reset by load_html)
+    document_ready: bool,
+    // Frame pacing (set_target_fps): minimum time between presented frames, when the last one went out, and
+    // whether the next frame must bypass the cap (resize, new content)
+    frame_interval: Option<std::time::Duration>,
+    last_frame_at: Option<std::time::Instant>,
+    force_frame: bool,
 }
 
 impl BlitzHost {
-    pub fn new_for_swapchain(_panel: crate::SwapChainPanelHandle, width: u32, height: u32, scale: f32) -> Result<Self, String> {
+    pub fn new_for_swapchain(
+        _panel: crate::SwapChainPanelHandle,
+        width: u32,
+        height: u32,
+        scale: f32,
+    ) -> Result<Self, BlitzHostError> {
         // No HWND usage in WinUI path. We strictly render into the provided SwapChainPanel swapchain.
         // Option A DPI policy (WinUI): Treat incoming width/height as logical DIPs and ignore external scale.
         // Rationale: WinUI XAML talks in DIPs already; we keep CSS px == DIP for clarity.
-        // Multi-monitor: future improvement will listen for XamlRoot RasterizationScale changes and if they differ
-        // significantly from 1.0 we could either (a) upscale swapchain or (b) introduce a zoom factor distinct from CSS px.
-        // For now we force scale = 1.0 to avoid double device-pixel-ratio application (causing oversized content).
+        // The device scale only sizes the swapchain (logical * scale) and the renderer's playback transform; the
+        // viewport scale stays 1.0 to avoid double device-pixel-ratio application (causing oversized content).
+        // Multi-monitor: hosts forward XamlRoot RasterizationScale changes through set_rasterization_scale.
         let device_scale = if scale <= 0.0 { 1.0 } else { scale };
         if (device_scale - 1.0).abs() > 0.01 {
             debug_log(&format!("new_for_swapchain: storing device_scale={} while forcing viewport scale=1.0", device_scale));
@@ -110,17 +206,21 @@ impl BlitzHost {
         // Start with an empty document so we don't flash placeholder content before real HTML loads.
         // Prepare a config that will later receive a real net provider when the host supplies
         // an INetworkFetcher. Until then it falls back to DummyNetProvider.
-        let cfg = DocumentConfig::default();
-        let mut doc = HtmlDocument::from_html(
-            "<html><head></head><body style=\"margin:0;padding:0;background:transparent;\"></body></html>",
-            cfg,
-        );
+        let shell = Arc::new(HostShellProvider::default());
+        let navigation = Arc::new(HostNavigationProvider::default());
+        let cfg = DocumentConfig {
+            shell_provider: Some(shell.clone() as _),
+            navigation_provider: Some(navigation.clone() as _),
+            ..Default::default()
+        };
+        let mut doc = HtmlDocument::from_html(BLANK_HTML, cfg);
 
         // Initialize viewport so first swapchain uses real size instead of 1x1.
     // Treat provided width/height as logical CSS px (WinUI gives DIPs). Viewport scale forced 1.0.
     let viewport = Viewport::new(width.max(1), height.max(1), 1.0, ColorScheme::Light);
         doc.set_viewport(viewport);
 
+        // All host calls (and resource callbacks) arrive on the UI thread, so the default single-threaded factory applies
         let renderer = D2DWindowRenderer::new();
     Ok(Self { 
             renderer, 
@@ -141,26 +241,46 @@ impl BlitzHost {
             network_fetcher: None,
             resource_callback: None,
             provider: None,
+            base_url: None,
+            pending_page: None,
+            stream: None,
             device_scale: device_scale,
+            zoom: 1.0,
+            color_scheme: ColorScheme::Light,
+            shell,
+            navigation,
+            navigation_listener: None,
+            scrollbar_drag: None,
+            first_frame_listener: None,
+            first_frame_painted: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            document_ready: false,
+            frame_interval: None,
+            last_frame_at: None,
+            force_frame: false,
         })
     }
     
     // New method that takes an attacher directly
-    pub fn new_with_attacher(attacher: ISwapChainAttacher, width: u32, height: u32, scale: f32) -> Result<Self, String> {
+    pub fn new_with_attacher(
+        attacher: ISwapChainAttacher,
+        width: u32,
+        height: u32,
+        scale: f32,
+    ) -> Result<Self, BlitzHostError> {
         let mut host = Self::new_for_swapchain(crate::SwapChainPanelHandle { swapchain: 0 }, width, height, scale)?;
         host.attacher = Some(attacher);
-        host.create_and_attach_swapchain();
+        host.create_and_attach_swapchain()?;
         Ok(host)
     }
 
     // Associate a WinRT INetworkFetcher implementation.
     pub fn set_network_fetcher(&mut self, fetcher: windows::core::IInspectable) {
-        self.network_fetcher = Some(fetcher);
-        // Lazily create provider if not already created
-        if self.provider.is_none() {
-            if let Some(f) = &self.network_fetcher {
-                let provider = net_bridge::make_provider(f.clone());
-                self.provider = Some(provider);
+        self.network_fetcher = Some(fetcher.clone());
+        // Lazily create provider if not already created; one made for file: loads (load_url) gets the fetcher
+        match &self.provider {
+            Some(p) => p.set_host(Arc::new(net_bridge::HostNetworkDispatcher { fetcher })),
+            None => {
+                self.provider = Some(net_bridge::make_provider(fetcher));
                 debug_log("set_network_fetcher: provider created");
             }
         }
@@ -178,25 +298,55 @@ impl BlitzHost {
     }
     }
 
-    // Simplified path invoked from WinRT RequestUrl (host passes in request_id already allocated on C# side)
-    pub fn request_url(&mut self, doc_id: usize, url: &str, handler: blitz_traits::net::BoxedHandler<Resource>) {
-        if blitz_traits::net::Url::parse(url).is_err() { debug_log(&format!("request_url: invalid url '{}'", url)); return; }
+    // Simplified path invoked from WinRT RequestUrl (host passes in request_id already allocated on C# side).
+    // `method` empty means GET; `headers` are CRLF separated `Name: value` lines.
+    pub fn request_url(
+        &mut self,
+        doc_id: usize,
+        url: &str,
+        method: &str,
+        headers: &str,
+        body: &[u8],
+        handler: blitz_traits::net::BoxedHandler<Resource>,
+    ) {
+        let Some(parsed) = resolve_against_base(self.base_url.as_ref(), url) else {
+            debug_log(&format!("request_url: invalid url '{}'", url));
+            return;
+        };
         if let Some(p) = &self.provider {
-            use blitz_traits::net::{Request, NetProvider};
-            if let Ok(parsed) = blitz_traits::net::Url::parse(url) {
+            use blitz_traits::net::{Body, Method, NetProvider, Request};
+            if blitz_net_winui::is_verbose_logging() {
                 debug_log(&format!("request_url: dispatching doc_id={} url={} (provider ok)", doc_id, parsed));
-                p.fetch(doc_id, Request::get(parsed), handler);
             }
+            let mut request = Request::get(parsed);
+            if !method.is_empty() {
+                match Method::from_bytes(method.as_bytes()) {
+                    Ok(m) => request.method = m,
+                    Err(_) => { debug_log(&format!("request_url: invalid method '{}'", method)); return; }
+                }
+            }
+            request.headers = blitz_net_winui::parse_header_lines(headers);
+            if !body.is_empty() { request.body = Body::Bytes(blitz_traits::net::Bytes::copy_from_slice(body)); }
+            p.fetch(doc_id, request, handler);
         } else { debug_log("request_url: no provider available"); }
     }
 
     // Completion path invoked by HostRuntime from WinRT CompleteFetch
-    pub fn complete_fetch(&mut self, request_id: u32, _doc_id: u32, success: bool, data: &[u8], error: &str) {
+    pub fn complete_fetch(
+        &mut self,
+        request_id: u32,
+        _doc_id: u32,
+        success: bool,
+        data: &[u8],
+        error: &str,
+    ) {
         if let Some(p) = &self.provider {
             if let Some((orig_doc, handler)) = p.take_handler(request_id) {
                 if let Some(cb) = &self.resource_callback {
                     if success {
-                        debug_log(&format!("complete_fetch: request_id={} doc_id={} success bytes={}", request_id, orig_doc, data.len()));
+                        if blitz_net_winui::is_verbose_logging() {
+                            debug_log(&format!("complete_fetch: request_id={} doc_id={} success bytes={}", request_id, orig_doc, data.len()));
+                        }
                         let bytes = blitz_traits::net::Bytes::from(data.to_vec());
                         handler.bytes(orig_doc, bytes, cb.clone());
                     } else {
@@ -204,12 +354,52 @@ impl BlitzHost {
                         cb.call(orig_doc, Err(Some(error.to_string())));
                     }
                 }
+                self.load_pending_page();
                 return;
             }
         }
         debug_log(&format!("complete_fetch: unknown request id {} (no provider match)", request_id));
     }
 
+    // Complete the file: reads the provider's background threads have finished, including ones issued while
+    // completing earlier reads (e.g. a stylesheet's @import). Reads still in progress complete on a later render_once.
+    fn complete_local_file_reads(&mut self) {
+        let Some(p) = self.provider.clone() else {
+            return;
+        };
+        loop {
+            let done = p.take_completed_files();
+            if done.is_empty() {
+                break;
+            }
+            for (request_id, result) in done {
+                match result {
+                    Ok(data) => self.complete_fetch(request_id, 0, true, &data, ""),
+                    Err(err) => self.complete_fetch(request_id, 0, false, &[], &err),
+                }
+            }
+        }
+    }
+
+    // Load the page body a load_url fetch delivered, if it has arrived
+    fn load_pending_page(&mut self) {
+        let arrived = self
+            .pending_page
+            .as_ref()
+            .is_some_and(|(_, body)| body.lock().is_ok_and(|b| b.is_some()));
+        if !arrived {
+            return;
+        }
+        let Some((url, body)) = self.pending_page.take() else {
+            return;
+        };
+        let Some(bytes) = body.lock().ok().and_then(|mut b| b.take()) else {
+            return;
+        };
+        self.set_base_url(url.as_str());
+        self.load_html(&String::from_utf8_lossy(&bytes));
+    }
+
     pub fn set_resource_callback(&mut self, cb: blitz_traits::net::SharedCallback<Resource>) { self.resource_callback = Some(cb); }
 
     // If the embedding hasn't provided a resource callback, install a default one that loads
@@ -225,8 +415,15 @@ impl BlitzHost {
                 unsafe {
                     if let Some(host) = self.host.as_mut() {
                         match result {
+                            // A response for a document that has since been replaced (load_html, unload) names nodes
+                            // the current document doesn't have
+                            Ok(_) if doc_id != host.doc.id() => {
+                                debug_log(&format!("resource_callback: doc_id={} dropped (document replaced)", doc_id));
+                            }
                             Ok(res) => {
-                                debug_log(&format!("resource_callback: doc_id={} kind={}", doc_id, resource_kind_name(&res)));
+                                if blitz_net_winui::is_verbose_logging() {
+                                    debug_log(&format!("resource_callback: doc_id={} kind={}", doc_id, resource_kind_name(&res)));
+                                }
                                 host.doc.load_resource(res);
                                 host.needs_render = true;
                                 host.render_once();
@@ -254,6 +451,7 @@ impl BlitzHost {
 
     pub fn set_verbose_logging(&mut self, enabled: bool) {
         anyrender_d2d::set_verbose_logging(enabled);
+        blitz_net_winui::set_verbose_logging(enabled);
         debug_log(&format!("SetVerboseLogging: enabled={}", enabled));
     }
 
@@ -262,8 +460,213 @@ impl BlitzHost {
         debug_log(&format!("SetDebugOverlay: enabled={}", enabled));
     }
 
+    /// Choose the debug overlay's lines, corner and opacity; the bits and codes are documented on IHost.SetDebugOverlayOptions.
+    pub fn set_debug_overlay_options(&mut self, lines: u32, corner: u8, opacity: f32) {
+        let options = overlay_options_from_abi(
+            lines,
+            corner,
+            opacity,
+            self.renderer.overlay_options().max_width,
+        );
+        self.renderer.set_overlay_options(options);
+        self.needs_render = true;
+        debug_log(&format!(
+            "SetDebugOverlayOptions: lines={:#x} corner={} opacity={}",
+            lines, corner, opacity
+        ));
+    }
+
+    pub fn set_inspect_overlay(&mut self, enabled: bool) {
+        self.renderer.set_inspect_overlay(enabled);
+        self.needs_render = true;
+        debug_log(&format!("SetInspectOverlay: enabled={}", enabled));
+    }
+
+    /// Paint with system colors (high contrast) instead of document colors, or stop doing so with `None`.
+    /// Carried over to documents loaded later; the clear color follows the scheme's canvas on the next render.
+    pub fn set_forced_colors(&mut self, scheme: Option<ForcedColorScheme>) {
+        self.doc.set_forced_colors(scheme);
+        self.needs_render = true;
+        debug_log(&format!("SetForcedColors: enabled={}", scheme.is_some()));
+    }
+
+    /// Shed renderer caches under memory pressure; the next frame rebuilds what it uses.
+    pub fn trim_memory(&mut self, level: anyrender_d2d::TrimLevel) {
+        self.renderer.trim_memory(level);
+        debug_log(&format!("TrimMemory: level={:?}", level));
+    }
+
+    /// Set the color scheme `@media (prefers-color-scheme)` queries see; `None` follows the Windows app theme as it
+    /// is now (call again when the system theme changes). The document restyles and repaints on the next render.
+    pub fn set_color_scheme(&mut self, scheme: Option<ColorScheme>) {
+        let scheme = scheme.unwrap_or_else(system_color_scheme);
+        self.color_scheme = scheme;
+        let mut viewport = self.doc.viewport().clone();
+        viewport.color_scheme = scheme;
+        self.doc.set_viewport(viewport);
+        self.needs_render = true;
+        debug_log(&format!("SetColorScheme: {:?}", scheme));
+    }
+
+    /// Associate a WinRT IFirstFrameListener notified after the first frame of the current document (if not yet
+    /// painted) and of each document loaded later.
+    pub fn set_first_frame_listener(&mut self, listener: IInspectable) {
+        self.first_frame_listener = Some(listener);
+        self.arm_first_frame_listener();
+    }
+
+    // Armed for every document (not only with a listener) so is_content_loaded can track the first frame too
+    fn arm_first_frame_listener(&mut self) {
+        let painted = self.first_frame_painted.clone();
+        self.renderer.on_first_frame(Box::new(move |_| {
+            painted.store(true, std::sync::atomic::Ordering::SeqCst)
+        }));
+    }
+
+    // Deliver a pending first-frame notification with the frozen timings
+    fn notify_first_frame(&mut self) {
+        if !self
+            .first_frame_painted
+            .swap(false, std::sync::atomic::Ordering::SeqCst)
+        {
+            return;
+        }
+        self.document_ready = true;
+        let Some(listener) = self
+            .first_frame_listener
+            .as_ref()
+            .and_then(|l| l.cast::<IFirstFrameListener>().ok())
+        else {
+            return;
+        };
+        let json = self.renderer.frame_timings_json();
+        if let Err(e) = listener.OnFirstFrame(&windows::core::HSTRING::from(json)) {
+            debug_log(&format!("notify_first_frame: OnFirstFrame failed {:?}", e));
+        }
+    }
+
+    /// Associate a WinRT INavigationListener told about link clicks and form submissions. Blitz never navigates
+    /// on its own; the host decides whether to load the URL, open it elsewhere or ignore it.
+    pub fn set_navigation_listener(&mut self, listener: IInspectable) {
+        self.navigation_listener = Some(listener);
+    }
+
+    // Hand navigation requests queued during event dispatch to the listener (dropped if none is set)
+    fn deliver_navigation_requests(&mut self) {
+        let requests = std::mem::take(&mut *self.navigation.pending.lock().unwrap());
+        if requests.is_empty() {
+            return;
+        }
+        let listener = self
+            .navigation_listener
+            .as_ref()
+            .and_then(|l| l.cast::<INavigationListener>().ok());
+        for request in requests {
+            debug_log(&format!(
+                "navigation requested: {} target={:?}",
+                request.url, request.target
+            ));
+            let Some(listener) = listener.as_ref() else {
+                continue;
+            };
+            let url = windows::core::HSTRING::from(request.url.as_str());
+            let target = windows::core::HSTRING::from(request.target.as_deref().unwrap_or(""));
+            if let Err(e) = listener.OnNavigationRequested(&url, &target) {
+                debug_log(&format!(
+                    "deliver_navigation_requests: OnNavigationRequested failed {:?}",
+                    e
+                ));
+            }
+        }
+    }
+
+    /// Whether the current document has finished parsing and initial layout and presented its first frame. Goes
+    /// false again while a newly loaded document is pending; the same moment IFirstFrameListener is notified.
+    pub fn is_content_loaded(&self) -> bool {
+        self.document_ready
+    }
+
+    /// Current frame timings (pipeline phases + D2D/host instrumentation) as a JSON object.
+    pub fn frame_timings_json(&self) -> String {
+        self.renderer.frame_timings_json()
+    }
+
+    /// Per-kind command counts of the last frame (fills, strokes, glyphs, shadows, layers, clip depth) as JSON.
+    pub fn command_stats_json(&self) -> String {
+        self.renderer.last_command_stats().to_json()
+    }
+
+    /// Whether the document keeps changing on its own (animated images, caret blink) so RenderOnce should keep
+    /// being called.
+    pub fn wants_animation_frame(&self) -> bool {
+        self.content_loaded && self.doc.wants_animation_frame()
+    }
+
+    /// Render one frame with scene capture on and return the renderer's listing of the commands it replayed.
+    pub fn dump_last_scene(&mut self) -> String {
+        self.renderer.set_scene_capture(true);
+        self.needs_render = true;
+        self.render_once();
+        let dump = self.renderer.last_scene_dump().to_string();
+        self.renderer.set_scene_capture(false);
+        dump
+    }
+
+    /// Render the current document offscreen and return it PNG-encoded, `width` px wide. `height` 0 keeps the
+    /// view's aspect ratio (a larger height extends the capture below the view). With `full_page` the whole
+    /// scrollable height is captured from the top; otherwise the visible view at the current scroll position.
+    /// Works without a swapchain (a WARP device is created) and leaves the panel's frame untouched.
+    pub fn capture_png(&mut self, width: u32, height: u32, full_page: bool) -> Vec<u8> {
+        if width == 0 {
+            return Vec::new();
+        }
+        self.doc.resolve();
+        let (lw, lh) = self.doc.viewport().window_size;
+        let (lw, lh) = (lw.max(1), lh.max(1));
+        let scale = self.doc.viewport().scale_f64();
+        let saved_scroll = self.doc.viewport_scroll();
+        let mut source_h = lh;
+        if full_page {
+            let (_, max_y) = self.doc.viewport_scroll_max();
+            source_h = (lh as f64 + max_y * scale).ceil() as u32;
+            self.doc.set_viewport_scroll(kurbo::Point::ZERO);
+        }
+        let factor = width as f32 / lw as f32;
+        let height = if height > 0 {
+            height
+        } else {
+            ((source_h as f32 * factor).round() as u32).max(1)
+        };
+        let paint_h = source_h.max((height as f32 / factor).ceil() as u32);
+        self.renderer
+            .set_clear_color(root_background_color(&self.doc));
+        let rgba = self
+            .renderer
+            .capture_to_rgba(width, height, factor, |scene| {
+                paint_scene(scene, &self.doc, scale, lw, paint_h)
+            });
+        if full_page {
+            self.doc.set_viewport_scroll(saved_scroll);
+        }
+        self.needs_render = true;
+        debug_log(&format!(
+            "CapturePng: {}x{} full_page={} ({} bytes rgba)",
+            width,
+            height,
+            full_page,
+            rgba.len()
+        ));
+        encode_png(rgba, width, height).unwrap_or_default()
+    }
+
     // SwapChainPanel interop: detect if the provided Object is an attacher callback; if so, store it and, if possible, create and attach swapchain now.
-    pub fn set_panel(&mut self, panel: windows_core::Ref<'_, IInspectable>, _width: u32, _height: u32) {
+    // A null panel is accepted (headless hosts); any other object must implement ISwapChainAttacher.
+    pub fn set_panel(
+        &mut self,
+        panel: windows_core::Ref<'_, IInspectable>,
+        _width: u32,
+        _height: u32,
+    ) -> Result<(), BlitzHostError> {
         // Try casting to our attacher interface
         if let Some(insp) = panel.as_ref() {
         debug_log(&format!("set_panel: received panel object: {:?}", insp));
@@ -272,18 +675,20 @@ impl BlitzHost {
             debug_log("set_panel: successfully cast to ISwapChainAttacher");
                     self.attacher = Some(att);
                     // Always create and attach the swapchain when we get an attacher
-                    self.create_and_attach_swapchain();
+                    self.create_and_attach_swapchain()
                 }
                 Err(e) => {
             debug_log(&format!("set_panel: failed to cast to ISwapChainAttacher: {:?}", e));
+                    Err(BlitzHostError::PanelCast(e))
                 }
             }
         } else {
         debug_log("set_panel: no panel object received");
+            Ok(())
         }
     }
 
-    fn create_and_attach_swapchain(&mut self) {
+    fn create_and_attach_swapchain(&mut self) -> Result<(), BlitzHostError> {
         debug_log("create_and_attach_swapchain: entering (async queued mode)");
         let host_t0 = std::time::Instant::now();
         self.host_init_start = Some(host_t0);
@@ -296,7 +701,7 @@ impl BlitzHost {
             }, 
             None => {
                 debug_log("create_and_attach_swapchain: no attacher available");
-                return;
+                return Ok(());
             } 
         };
         
@@ -317,7 +722,10 @@ impl BlitzHost {
     debug_log(&format!("create_and_attach_swapchain: logical {}x{} device_scale {:.3} -> physical {}x{}", logical_w, logical_h, self.device_scale, phys_w, phys_h));
         unsafe {
             let acquire = crate::global_gfx::get_or_create_d3d_device();
-            if acquire.is_none() { debug_log("create_and_attach_swapchain: failed to acquire global device"); return; }
+            if acquire.is_none() {
+                debug_log("create_and_attach_swapchain: failed to acquire global device");
+                return Err(BlitzHostError::DeviceInit);
+            }
             let acquire = acquire.unwrap();
             let device = acquire.device.clone();
             let context = acquire.context.clone();
@@ -337,7 +745,7 @@ impl BlitzHost {
                 },
                 Err(e) => {
                     debug_log(&format!("create_and_attach_swapchain: CreateDXGIFactory2 failed: {:?}", e));
-                    return;
+                    return Err(BlitzHostError::SwapchainAttach(e));
                 },
             };
             
@@ -362,51 +770,51 @@ impl BlitzHost {
                 "create_and_attach_swapchain: Attempting swapchain ({}x{}, fmt={:?}, swap_effect={:?}, alpha={:?}, buffers={}, usage=0x{:X})",
                 desc.Width, desc.Height, desc.Format, desc.SwapEffect, desc.AlphaMode, desc.BufferCount, desc.BufferUsage.0
             ));
-            let mut sc_attempt: Option<IDXGISwapChain1> = match factory.CreateSwapChainForComposition(&device, &desc, None) {
-                Ok(s) => Some(s),
-                Err(e) => {
-                    debug_log(&format!("create_and_attach_swapchain: initial CreateSwapChainForComposition failed: {:?}", e));
-                    None
-                }
-            };
+            let mut sc_attempt = factory.CreateSwapChainForComposition(&device, &desc, None);
+            if let Err(e) = &sc_attempt {
+                debug_log(&format!(
+                    "create_and_attach_swapchain: initial CreateSwapChainForComposition failed: {:?}",
+                    e
+                ));
+            }
 
-            if sc_attempt.is_none() {
+            if sc_attempt.is_err() {
                 // Fallback 1: straight alpha
                 desc.AlphaMode = windows::Win32::Graphics::Dxgi::Common::DXGI_ALPHA_MODE_STRAIGHT;
                 debug_log(&format!("create_and_attach_swapchain: retry with STRAIGHT alpha (alpha={:?})", desc.AlphaMode));
-                sc_attempt = match factory.CreateSwapChainForComposition(&device, &desc, None) {
-                    Ok(s) => Some(s),
-                    Err(e) => { debug_log(&format!("fallback1 failed: {:?}", e)); None }
-                };
+                sc_attempt = factory.CreateSwapChainForComposition(&device, &desc, None);
+                if let Err(e) = &sc_attempt {
+                    debug_log(&format!("fallback1 failed: {:?}", e));
+                }
             }
-            if sc_attempt.is_none() {
+            if sc_attempt.is_err() {
                 // Fallback 2: ignore alpha (opaque)
                 desc.AlphaMode = windows::Win32::Graphics::Dxgi::Common::DXGI_ALPHA_MODE_IGNORE;
                 debug_log(&format!("create_and_attach_swapchain: retry with IGNORE alpha (alpha={:?})", desc.AlphaMode));
-                sc_attempt = match factory.CreateSwapChainForComposition(&device, &desc, None) {
-                    Ok(s) => Some(s),
-                    Err(e) => { debug_log(&format!("fallback2 failed: {:?}", e)); None }
-                };
+                sc_attempt = factory.CreateSwapChainForComposition(&device, &desc, None);
+                if let Err(e) = &sc_attempt {
+                    debug_log(&format!("fallback2 failed: {:?}", e));
+                }
             }
-            if sc_attempt.is_none() {
+            if sc_attempt.is_err() {
                 // Fallback 3: change swap effect to FLIP_DISCARD
                 desc.SwapEffect = windows::Win32::Graphics::Dxgi::DXGI_SWAP_EFFECT_FLIP_DISCARD;
                 desc.AlphaMode = windows::Win32::Graphics::Dxgi::Common::DXGI_ALPHA_MODE_PREMULTIPLIED; // reset to premultiplied
                 debug_log(&format!("create_and_attach_swapchain: retry with FLIP_DISCARD (swap_effect={:?}, alpha={:?})", desc.SwapEffect, desc.AlphaMode));
-                sc_attempt = match factory.CreateSwapChainForComposition(&device, &desc, None) {
-                    Ok(s) => Some(s),
-                    Err(e) => { debug_log(&format!("fallback3 failed: {:?}", e)); None }
-                };
+                sc_attempt = factory.CreateSwapChainForComposition(&device, &desc, None);
+                if let Err(e) = &sc_attempt {
+                    debug_log(&format!("fallback3 failed: {:?}", e));
+                }
             }
             let sc: IDXGISwapChain1 = match sc_attempt {
-                Some(s) => {
+                Ok(s) => {
                     debug_log("create_and_attach_swapchain: Created swap chain successfully (after possible fallbacks)");
                     if let Ok(desc1) = s.GetDesc1() { debug_log(&format!("create_and_attach_swapchain: actual desc {}x{} fmt={:?} alpha={:?} buffers={} scaling={:?}", desc1.Width, desc1.Height, desc1.Format, desc1.AlphaMode, desc1.BufferCount, desc1.Scaling)); }
                     s
                 },
-                None => {
+                Err(e) => {
                     debug_log("create_and_attach_swapchain: All swapchain creation attempts failed");
-                    return;
+                    return Err(BlitzHostError::SwapchainAttach(e));
                 }
             };
             let sc_elapsed = t_phase.elapsed().as_secs_f32()*1000.0; // t_phase no longer reused
@@ -435,35 +843,70 @@ impl BlitzHost {
             self.attach_pending = true;
             debug_log("create_and_attach_swapchain: queued panel AttachSwapChain (executing immediately to minimize wait)");
             // Execute immediately to keep queue_ms near-zero for better overlap accounting
-            self.maybe_execute_queued_attach();
+            self.maybe_execute_queued_attach()
         }
     }
 
     // Execute pending panel attach when appropriate (first poll after queue) measuring queue vs exec time.
-    fn maybe_execute_queued_attach(&mut self) {
-        if !self.attach_pending { return; }
-        if self.swapchain.is_some() { self.attach_pending = false; return; }
+    // The swapchain is handed to the renderer even when the attach fails; the first failure is still reported.
+    fn maybe_execute_queued_attach(&mut self) -> Result<(), BlitzHostError> {
+        if !self.attach_pending {
+            return Ok(());
+        }
+        if self.swapchain.is_some() {
+            self.attach_pending = false;
+            return Ok(());
+        }
         // Safe to proceed now; measure queue_ms
         let queue_ms = self.attach_queue_start.map(|t| t.elapsed().as_secs_f32()*1000.0).unwrap_or(0.0);
         // Perform the real attach now
-        let Some(attacher) = self.attacher.clone() else { debug_log("maybe_execute_queued_attach: no attacher (aborting)" ); self.attach_pending = false; return; };
-        let Some(sc) = self.pending_swapchain.take() else { debug_log("maybe_execute_queued_attach: no pending swapchain" ); self.attach_pending = false; return; };
+        let Some(attacher) = self.attacher.clone() else {
+            debug_log("maybe_execute_queued_attach: no attacher (aborting)");
+            self.attach_pending = false;
+            return Ok(());
+        };
+        let Some(sc) = self.pending_swapchain.take() else {
+            debug_log("maybe_execute_queued_attach: no pending swapchain");
+            self.attach_pending = false;
+            return Ok(());
+        };
         // Recreate raw pointer for swapchain (COM pointer still valid)
         let raw_ptr = windows::core::Interface::as_raw(&sc) as usize as u64;
         let exec_start = std::time::Instant::now();
         let result = attacher.AttachSwapChain(raw_ptr);
         let exec_ms = exec_start.elapsed().as_secs_f32()*1000.0;
         if let Some(r) = self.renderer_mut() { r.add_host_panel_attach_queue_ms(queue_ms); r.add_host_panel_attach_exec_ms(exec_ms); }
-        match result {
-            Ok(_) => debug_log(&format!("maybe_execute_queued_attach: AttachSwapChain succeeded queue_ms={:.2} exec_ms={:.2}", queue_ms, exec_ms)),
-            Err(e) => { debug_log(&format!("maybe_execute_queued_attach: AttachSwapChain failed queue_ms={:.2} exec_ms={:.2} err={:?}", queue_ms, exec_ms, e)); }
-        }
+        let mut status = match result {
+            Ok(_) => {
+                debug_log(&format!(
+                    "maybe_execute_queued_attach: AttachSwapChain succeeded queue_ms={:.2} exec_ms={:.2}",
+                    queue_ms, exec_ms
+                ));
+                Ok(())
+            }
+            Err(e) => {
+                debug_log(&format!(
+                    "maybe_execute_queued_attach: AttachSwapChain failed queue_ms={:.2} exec_ms={:.2} err={:?}",
+                    queue_ms, exec_ms, e
+                ));
+                Err(BlitzHostError::SwapchainAttach(e))
+            }
+        };
         // Finalize swapchain into renderer
     let (logical_w, logical_h) = self.doc.viewport().window_size;
     let phys_w = ((logical_w as f32) * self.device_scale).round().max(1.0) as u32;
     let phys_h = ((logical_h as f32) * self.device_scale).round().max(1.0) as u32;
     self.renderer.set_swapchain(sc.clone(), phys_w, phys_h);
         self.swapchain = Some(sc);
+        if status.is_ok() && !self.renderer.is_device_ready() {
+            debug_log(
+                "maybe_execute_queued_attach: Direct2D did not initialize on the swapchain device",
+            );
+            status = Err(BlitzHostError::DeviceInit);
+        } else if status.is_ok() && !self.renderer.is_text_ready() {
+            debug_log("maybe_execute_queued_attach: DirectWrite did not initialize");
+            status = Err(BlitzHostError::FontInit);
+        }
         // Accumulate host init total after full attach completes, excluding queue wait (we only want non-overlapped exec + prior setup)
         if let Some(start) = self.host_init_start.take() {
             let total_elapsed = start.elapsed().as_secs_f32()*1000.0;
@@ -481,6 +924,7 @@ impl BlitzHost {
             self.render_once();
         }
         self.attach_pending = false;
+        status
     }
 
     // TODO: Enable when implementing CPU-GPU texture bridge
@@ -491,7 +935,13 @@ impl BlitzHost {
 
     // Alternative interop: host passes an already-created IDXGISwapChain1* pointer.
     // Safety: swapchain_ptr must be a valid, AddRef'd IDXGISwapChain1 pointer. We take ownership of a reference.
-    pub fn set_swapchain(&mut self, swapchain_ptr: *mut core::ffi::c_void, width: u32, height: u32, scale: f32) {
+    pub fn set_swapchain(
+        &mut self,
+        swapchain_ptr: *mut core::ffi::c_void,
+        width: u32,
+        height: u32,
+        scale: f32,
+    ) {
         if scale > 0.0 { self.device_scale = scale; }
         if swapchain_ptr.is_null() { return; }
         unsafe {
@@ -500,7 +950,8 @@ impl BlitzHost {
             // Store swapchain and reset D3D device/context for render path that just clears/presents
             self.swapchain = Some(sc);
             // Update viewport and renderer size
-            let viewport = Viewport::new(width, height, 1.0, ColorScheme::Light);
+            let mut viewport = Viewport::new(width, height, 1.0, self.color_scheme);
+            viewport.set_zoom(self.zoom);
             self.doc.set_viewport(viewport);
             let phys_w = ((width as f32) * self.device_scale).round().max(1.0) as u32;
             let phys_h = ((height as f32) * self.device_scale).round().max(1.0) as u32;
@@ -514,7 +965,8 @@ impl BlitzHost {
 
     pub fn resize(&mut self, width: u32, height: u32, scale: f32) {
         if scale > 0.0 { self.device_scale = scale; }
-        let viewport = Viewport::new(width, height, 1.0, ColorScheme::Light);
+        let mut viewport = Viewport::new(width, height, 1.0, self.color_scheme);
+        viewport.set_zoom(self.zoom);
         self.doc.set_viewport(viewport);
         let phys_w = ((width as f32) * self.device_scale).round().max(1.0) as u32;
         let phys_h = ((height as f32) * self.device_scale).round().max(1.0) as u32;
@@ -532,25 +984,89 @@ impl BlitzHost {
         }
         // Mark for redraw (layout may depend on viewport size)
         self.needs_render = true;
+        self.force_frame = true;
         // Eagerly render once to avoid blank gap after resize
         if self.content_loaded { self.render_once(); }
     }
 
-    pub fn render_once(&mut self) {
-        // Execute pending attach if any first
-        self.maybe_execute_queued_attach();
-        if !self.content_loaded && !self.needs_render { return; }
-        if self.content_loaded && !self.needs_render { return; }
+    /// Update the device (rasterization) scale, e.g. after XamlRoot.RasterizationScale changes when the window
+    /// moves to a monitor with different scaling. CSS px stay 1:1 with DIPs; only the swapchain allocation
+    /// (logical * scale) and the renderer's playback transform change. Goes through the resize path, which
+    /// re-renders right after ResizeBuffers so no stretched or blank frame is presented.
+    pub fn set_rasterization_scale(&mut self, scale: f32) {
+        if !(scale > 0.0) || (scale - self.device_scale).abs() < 0.001 {
+            return;
+        }
+        debug_log(&format!(
+            "set_rasterization_scale: {:.3} -> {:.3}",
+            self.device_scale, scale
+        ));
+        let (width, height) = self.doc.viewport().window_size;
+        self.resize(width, height, scale);
+    }
+
+    /// Cap how often render_once presents, for hosts that call it from a tight loop; `None` (or a non-positive
+    /// rate) renders on every call. Resizes and newly loaded content always render immediately.
+    pub fn set_target_fps(&mut self, fps: Option<f32>) {
+        self.frame_interval = fps
+            .filter(|f| f.is_finite() && *f > 0.0)
+            .map(|f| std::time::Duration::from_secs_f32(1.0 / f));
+        debug_log(&format!("SetTargetFps: {:?}", fps));
+    }
+
+    /// Render and present a frame if anything changed. Returns false when the call was skipped: nothing was
+    /// dirty, or the previous frame was presented less than a target frame interval ago.
+    pub fn render_once(&mut self) -> bool {
+        // Execute pending attach if any first; failures were logged where they happened
+        let _ = self.maybe_execute_queued_attach();
+        self.complete_local_file_reads();
+        if !self.content_loaded && !self.needs_render {
+            return false;
+        }
+        // Animated images (GIF/APNG/WebP) invalidate the frame when they step to a new frame. The host keeps
+        // calling RenderOnce from its render loop while the document is animating.
+        if self.content_loaded && self.doc.advance_animated_images(std::time::Instant::now()) {
+            self.needs_render = true;
+        }
+        // The focused text input's caret toggles every blink interval
+        if self.content_loaded && self.doc.advance_caret_blink(std::time::Instant::now()) {
+            self.needs_render = true;
+        }
+        // Skip the D2D frame entirely when neither the host nor the renderer has anything dirty
+        if self.content_loaded && !self.needs_render && !self.renderer.is_dirty() {
+            return false;
+        }
+        // Frame pacing: the invalidation stays pending until a call lands after the interval
+        if !self.force_frame
+            && !frame_due(
+                self.frame_interval,
+                self.last_frame_at,
+                std::time::Instant::now(),
+            )
+        {
+            return false;
+        }
+        // Host-side invalidations (input, scroll, layout) don't carry a region yet
+        if self.needs_render {
+            self.renderer.mark_full();
+        }
         debug_log(&format!("render_once: begin (dirty={}, content_loaded={})", self.needs_render, self.content_loaded));
-    let (logical_w, logical_h) = self.doc.viewport().window_size;
-    let scale = self.doc.viewport().scale_f64(); // always 1.0 currently
+        let (logical_w, logical_h) = self.doc.viewport().window_size;
+        let scale = self.doc.viewport().scale_f64(); // page zoom; device scale is applied by the renderer
     let phys_w = ((logical_w as f32) * self.device_scale).round().max(1.0) as u32;
-    let phys_h = ((logical_h as f32) * self.device_scale).round().max(1.0) as u32;
-        if self.content_loaded { self.doc.resolve(); }
+        let phys_h = ((logical_h as f32) * self.device_scale).round().max(1.0) as u32;
+        if self.content_loaded {
+            self.doc.resolve();
+            // Clear to the page's canvas background so uncovered areas (e.g. during resize) match the document
+            self.renderer
+                .set_clear_color(root_background_color(&self.doc));
+        }
 
         if self.swapchain.is_none() && self.attacher.is_some() {
             debug_log("render_once: No swapchain yet; attempting lazy creation");
-            self.create_and_attach_swapchain();
+            if let Err(e) = self.create_and_attach_swapchain() {
+                debug_log(&format!("render_once: lazy swapchain creation failed: {e}"));
+            }
         }
 
     // Clone swapchain COM pointer out to avoid holding an immutable borrow of self during rendering
@@ -571,11 +1087,16 @@ impl BlitzHost {
                                 }
                             }
                         }
-                        if self.d3d_context.is_none() { debug_log("render_once: No D3D context available"); return; }
+                        if self.d3d_context.is_none() { debug_log("render_once: No D3D context available"); return false; }
                         let (w,h) = (phys_w.max(1), phys_h.max(1));
                         if self.content_loaded {
                             want_disable_test_pattern = true;
-                            self.renderer.render(|scene| paint_scene(scene, &self.doc, scale, w, h));
+                            // Scene is built in logical px; the renderer magnifies by device_scale at playback.
+                            self.renderer.set_scale(self.device_scale);
+                            self.renderer.set_scrollbar_thumb(self.scrollbar_geometry().map(|(_, thumb)| thumb));
+                            self.renderer.set_inspect_box(self.hovered_inspect_box());
+                            let (lw, lh) = (logical_w.max(1), logical_h.max(1));
+                            self.renderer.render(|scene| paint_scene(scene, &self.doc, scale, lw, lh));
                             debug_log(&format!("render_once: D2D command_count={} ({}x{})", self.renderer.last_command_count(), w, h));
                         } else if !self.placeholder_drawn {
                             want_enable_test_pattern = true;
@@ -586,47 +1107,256 @@ impl BlitzHost {
                     },
                     Err(e) => debug_log(&format!("render_once: Failed to get back buffer: {:?}", e)),
                 }
-                let sync_interval = if (!self.content_loaded && self.placeholder_drawn) || (self.content_loaded && self.placeholder_drawn) { 0 } else { 1 };
-                let hr = sc.Present(sync_interval, DXGI_PRESENT(0));
-                if hr.is_ok() { debug_log("render_once: presented"); } else { debug_log(&format!("render_once: Failed to present swapchain: {:?}", hr)); }
+                // Present only what the renderer actually drew; skipped frames would re-show the old buffer
+                let frame = self.renderer.take_frame_result();
+                if frame.needs_present {
+                    let sync_interval = if (!self.content_loaded && self.placeholder_drawn) || (self.content_loaded && self.placeholder_drawn) { 0 } else { 1 };
+                    let hr = present_frame(&sc, sync_interval, frame.presented_region);
+                    if hr.is_ok() { debug_log("render_once: presented"); } else { debug_log(&format!("render_once: Failed to present swapchain: {:?}", hr)); }
+                    self.notify_first_frame();
+                } else {
+                    debug_log("render_once: nothing drawn; skipping Present");
+                }
     }
+    self.last_frame_at = Some(std::time::Instant::now());
+    self.force_frame = false;
     if want_enable_test_pattern { if let Some(r) = self.renderer_mut() { r.set_test_pattern(true); } }
     if want_disable_test_pattern { if let Some(r) = self.renderer_mut() { r.set_test_pattern(false); } }
     if self.content_loaded { self.needs_render = false; }
-    return;
+    if self.renderer.take_device_lost() {
+        self.handle_device_lost();
+    }
+    return true;
     }
 
         // Fallback path (should not normally trigger in WinUI panel scenario)
         if self.content_loaded {
-            let (phys_w, phys_h) = {
-                let (lw, lh) = self.doc.viewport().window_size;
-                let pw = ((lw as f32) * self.device_scale).round().max(1.0) as u32;
-                let ph = ((lh as f32) * self.device_scale).round().max(1.0) as u32;
-                (pw, ph)
-            };
-            self.renderer.render(|scene| paint_scene(scene, &self.doc, scale, phys_w, phys_h));
+            let (lw, lh) = self.doc.viewport().window_size;
+            self.renderer.set_scale(self.device_scale);
+            self.renderer.set_scrollbar_thumb(self.scrollbar_geometry().map(|(_, thumb)| thumb));
+            self.renderer.set_inspect_box(self.hovered_inspect_box());
+            self.renderer.render(|scene| paint_scene(scene, &self.doc, scale, lw.max(1), lh.max(1)));
+            self.notify_first_frame();
             debug_log(&format!("render_once: D2D command_count={} (fallback path)", self.renderer.last_command_count()));
             self.needs_render = false;
         } else if !self.placeholder_drawn {
             self.renderer.render(|_scene| { /* placeholder fallback */ });
             self.placeholder_drawn = true;
             debug_log("render_once: placeholder frame rendered (fallback path, no content)");
+        } else {
+            return false;
+        }
+        self.last_frame_at = Some(std::time::Instant::now());
+        self.force_frame = false;
+        true
+    }
+
+    // The renderer lost its D3D device (GPU reset, driver update). The swapchain and the shared device are dead
+    // with it, so drop them; the next render_once recreates both through create_and_attach_swapchain and repaints.
+    fn handle_device_lost(&mut self) {
+        debug_log("render_once: D3D device lost; recreating device and swapchain");
+        crate::global_gfx::release_d3d_device();
+        self.swapchain = None;
+        self.pending_swapchain = None;
+        self.d3d_device = None;
+        self.d3d_context = None;
+        self.needs_render = true;
+        self.force_frame = true;
+    }
+
+    // Box model of the hovered element for the inspect overlay
+    fn hovered_inspect_box(&self) -> Option<anyrender_d2d::InspectBox> {
+        let bm = self.doc.box_model(self.doc.get_hover_node_id()?)?;
+        let z = self.zoom as f64;
+        Some(anyrender_d2d::InspectBox {
+            margin: bm.margin.scale_from_origin(z),
+            border: bm.border.scale_from_origin(z),
+            padding: bm.padding.scale_from_origin(z),
+            content: bm.content.scale_from_origin(z),
+        })
+    }
+
+    /// Set the URL that relative URLs (images, stylesheets, fonts, links, RequestUrl) resolve against. Takes effect
+    /// for the current document's later fetches and for every later load_html; an empty string clears it for later
+    /// loads. Returns false, leaving the base unchanged, if `url` isn't an absolute URL.
+    pub fn set_base_url(&mut self, url: &str) -> bool {
+        // Only a page loaded from disk (load_url) may read local files
+        if let Some(p) = &self.provider {
+            p.set_file_root(None);
+        }
+        if url.is_empty() {
+            self.base_url = None;
+            return true;
+        }
+        match blitz_traits::net::Url::parse(url) {
+            Ok(parsed) => {
+                self.doc.set_base_url(parsed.as_str());
+                self.base_url = Some(parsed);
+                true
+            }
+            Err(err) => {
+                debug_log(&format!("set_base_url: invalid url '{}': {}", url, err));
+                false
+            }
+        }
+    }
+
+    /// Load the page at an absolute URL, which also becomes the base URL. A `file:` page is read from disk and its
+    /// relative resources are read from its directory (never above it); `http(s):` pages are fetched through the
+    /// host's INetworkFetcher and load when the fetch completes. Returns false if the URL is invalid, the file
+    /// can't be read, or no network fetcher has been set for an http(s) URL.
+    pub fn load_url(&mut self, url: &str) -> bool {
+        let Ok(parsed) = blitz_traits::net::Url::parse(url) else {
+            debug_log(&format!("load_url: invalid url '{}'", url));
+            return false;
+        };
+        self.pending_page = None;
+        match parsed.scheme() {
+            "file" => {
+                let Ok(path) = parsed.to_file_path() else {
+                    debug_log(&format!("load_url: not a local file url '{}'", url));
+                    return false;
+                };
+                let html = match std::fs::read(&path) {
+                    Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+                    Err(err) => {
+                        debug_log(&format!(
+                            "load_url: reading {} failed: {}",
+                            path.display(),
+                            err
+                        ));
+                        return false;
+                    }
+                };
+                if self.provider.is_none() {
+                    self.provider = Some(net_bridge::make_offline_provider());
+                    self.ensure_default_resource_callback();
+                }
+                self.set_base_url(parsed.as_str());
+                if let Some(p) = &self.provider {
+                    p.set_file_root(path.parent().map(|dir| dir.to_path_buf()));
+                }
+                self.load_html(&html);
+                true
+            }
+            "http" | "https" => {
+                if self.network_fetcher.is_none() {
+                    debug_log(&format!("load_url: no network fetcher for '{}'", url));
+                    return false;
+                }
+                let Some(p) = self.provider.clone() else {
+                    return false;
+                };
+                let body = Arc::new(std::sync::Mutex::new(None));
+                self.pending_page = Some((parsed.clone(), body.clone()));
+                use blitz_traits::net::NetProvider;
+                p.fetch(
+                    self.doc.id(),
+                    blitz_traits::net::Request::get(parsed),
+                    Box::new(PageHandler { body }),
+                );
+                true
+            }
+            scheme => {
+                debug_log(&format!("load_url: unsupported scheme '{}'", scheme));
+                false
+            }
         }
     }
 
     pub fn load_html(&mut self, html: &str) {
-        // If swapchain active, restart initial metrics now so timings reflect real document; else defer until swapchain creation
-        let swapchain_ready = self.swapchain.is_some();
-        if swapchain_ready { self.renderer.restart_initial_measurement(); } else { self.pending_content_measurement = true; }
-        // Build config with net provider if available so new document can issue resource fetches.
-    let mut cfg = DocumentConfig::default();
-    if let Some(p) = &self.provider { cfg.net_provider = Some(p.clone() as _); }
-        let new_doc = HtmlDocument::from_html(html, cfg);
+        self.stream = None;
+        let cfg = self.prepare_new_document();
+        self.install_document(HtmlDocument::from_html(html, cfg));
+        debug_log(&format!(
+            "load_html: new document length={} chars",
+            html.len()
+        ));
+        self.finish_document_load();
+    }
+
+    /// Start a document whose HTML arrives in chunks (push_html_chunk, then end_stream). The partial document is
+    /// laid out and painted as chunks arrive, so content above the fold shows before the tail is received.
+    pub fn begin_stream(&mut self) {
+        let cfg = self.prepare_new_document();
+        self.install_document(HtmlDocument::empty(cfg));
+        self.stream = Some(HtmlStreamParser::new());
+        self.content_loaded = true;
+        debug_log("begin_stream: started streamed document");
+    }
+
+    /// Parse the next bytes of the streamed document (chunks may split tags and characters) and paint the result
+    /// once a frame is due. Returns false if no stream is open.
+    pub fn push_html_chunk(&mut self, bytes: &[u8]) -> bool {
+        let Some(stream) = self.stream.as_mut() else {
+            debug_log("push_html_chunk: no stream open (call begin_stream first)");
+            return false;
+        };
+        stream.push(&mut self.doc, bytes);
+        self.needs_render = true;
+        self.render_once();
+        true
+    }
+
+    /// Finish the streamed document: buffered input is parsed and elements left open are closed. Returns false if
+    /// no stream is open.
+    pub fn end_stream(&mut self) -> bool {
+        let Some(stream) = self.stream.take() else {
+            debug_log("end_stream: no stream open");
+            return false;
+        };
+        stream.finish(&mut self.doc);
+        debug_log("end_stream: streamed document complete");
+        self.finish_document_load();
+        true
+    }
+
+    // Reset per-document host and renderer state ahead of a new document, returning its config
+    fn prepare_new_document(&mut self) -> DocumentConfig {
+        // If swapchain active, drop the previous document's renderer state and restart initial metrics now so timings
+        // reflect the new document; else defer the measurement restart until swapchain creation
+        if self.swapchain.is_some() {
+            self.renderer.reset_for_new_document();
+        } else {
+            self.pending_content_measurement = true;
+        }
+        self.document_ready = false;
+        self.first_frame_painted
+            .store(false, std::sync::atomic::Ordering::SeqCst);
+        self.arm_first_frame_listener();
+        self.document_config()
+    }
+
+    // Build config with net provider if available so new document can issue resource fetches.
+    fn document_config(&self) -> DocumentConfig {
+        let mut cfg = DocumentConfig::default();
+        if let Some(p) = &self.provider {
+            cfg.net_provider = Some(p.clone() as _);
+        }
+        cfg.base_url = self.base_url.as_ref().map(|url| url.to_string());
+        cfg.shell_provider = Some(self.shell.clone() as _);
+        cfg.navigation_provider = Some(self.navigation.clone() as _);
+        cfg
+    }
+
+    // Replace the current document, carrying over the viewport, scroll position and forced colors. Fetches the old
+    // document still has in flight are dropped, so their responses can't land in the new one.
+    fn install_document(&mut self, new_doc: HtmlDocument) {
+        if let Some(p) = &self.provider {
+            p.cancel_document(self.doc.id());
+        }
+        self.pending_page = None;
         let scroll = self.doc.viewport_scroll();
         let viewport = self.doc.viewport().clone();
+        let forced_colors = self.doc.forced_colors().copied();
         self.doc = Box::new(new_doc);
         self.doc.set_viewport(viewport);
         self.doc.set_viewport_scroll(scroll);
+        self.doc.set_forced_colors(forced_colors);
+    }
+
+    // Lay out the fully parsed document, make sure its resources are requested, and paint it
+    fn finish_document_load(&mut self) {
         // Perform initial style/layout/shaping before first real frame so metrics capture them
         self.doc.resolve();
         if self.provider.is_some() { 
@@ -640,10 +1370,11 @@ impl BlitzHost {
         } else {
             debug_log("load_html: no provider present at parse (will rely on later rescan)");
         }
-        debug_log(&format!("load_html: new document length={} chars", html.len()));
+        self.complete_local_file_reads();
         self.content_loaded = true;
-        if swapchain_ready {
+        if self.swapchain.is_some() {
             self.needs_render = true; // schedule first real paint now
+            self.force_frame = true;
             self.render_once();
         } else {
             // Will render automatically when swapchain attaches
@@ -651,6 +1382,30 @@ impl BlitzHost {
         }
     }
 
+    /// Drop the current document (DOM, styles, decoded images) and the renderer's per-document caches, and present
+    /// an empty transparent frame, e.g. while the panel is hidden. The D3D/D2D devices and swapchain stay alive, so
+    /// a later load_html renders as quickly as on a warm host.
+    pub fn unload(&mut self) {
+        let cfg = self.document_config();
+        self.install_document(HtmlDocument::from_html(BLANK_HTML, cfg));
+        self.navigation.pending.lock().unwrap().clear();
+        self.scrollbar_drag = None;
+        self.stream = None;
+        self.renderer.reset_for_new_document();
+        // The blank page is not a document the host loaded: replace any still-armed first-frame callback so
+        // its frame doesn't report as one
+        self.renderer.on_first_frame(Box::new(|_| {}));
+        self.first_frame_painted
+            .store(false, std::sync::atomic::Ordering::SeqCst);
+        self.document_ready = false;
+        self.needs_render = true;
+        self.force_frame = true;
+        if self.swapchain.is_some() {
+            self.render_once();
+        }
+        debug_log("unload: document dropped");
+    }
+
     // Helper to quickly inject a test snippet that should trigger network fetches for image + stylesheet.
     pub fn load_test_network_snippet(&mut self) {
         let snippet = r#"<html><head>
@@ -667,6 +1422,11 @@ impl BlitzHost {
         use blitz_traits::events::{BlitzMouseButtonEvent, MouseEventButtons, UiEvent};
         let buttons = MouseEventButtons::from_bits_truncate(buttons as u8);
         let mods = keyboard_types::Modifiers::from_bits_truncate(mods);
+        if let Some(grab) = self.scrollbar_drag {
+            self.scroll_to_thumb_top(y as f64 - grab);
+            return;
+        }
+        let (x, y) = (x / self.zoom, y / self.zoom);
     self.doc.handle_ui_event(UiEvent::MouseMove(BlitzMouseButtonEvent {
             x,
             y,
@@ -678,56 +1438,302 @@ impl BlitzHost {
     }
 
     pub fn pointer_down(&mut self, x: f32, y: f32, button: u8, buttons: u32, mods: u32) {
-        use blitz_traits::events::{BlitzMouseButtonEvent, MouseEventButton, MouseEventButtons, UiEvent};
-        let btn = match button {
-            0 => MouseEventButton::Main,
-            1 => MouseEventButton::Auxiliary,
-            2 => MouseEventButton::Secondary,
-            3 => MouseEventButton::Fourth,
-            4 => MouseEventButton::Fifth,
-            _ => MouseEventButton::Main,
+        use blitz_traits::events::{
+            BlitzMouseButtonEvent, MouseEventButton, MouseEventButtons, UiEvent,
+        };
+        let event = BlitzMouseButtonEvent {
+            x: x / self.zoom,
+            y: y / self.zoom,
+            button: mouse_button_from_host(button),
+            buttons: MouseEventButtons::from_bits_truncate(buttons as u8),
+            mods: keyboard_types::Modifiers::from_bits_truncate(mods),
         };
+        if event.button == MouseEventButton::Main
+            && !blitz_dom::is_context_menu_trigger(&event)
+            && self.scrollbar_pointer_down(x as f64, y as f64)
+        {
+            return;
+        }
+        self.doc.handle_ui_event(UiEvent::MouseDown(event));
+        self.needs_render = true;
+    }
+
+    pub fn pointer_up(&mut self, x: f32, y: f32, button: u8, buttons: u32, mods: u32) {
+        use blitz_traits::events::{BlitzMouseButtonEvent, MouseEventButtons, UiEvent};
+        let btn = mouse_button_from_host(button);
         let buttons = MouseEventButtons::from_bits_truncate(buttons as u8);
         let mods = keyboard_types::Modifiers::from_bits_truncate(mods);
-    self.doc.handle_ui_event(UiEvent::MouseDown(BlitzMouseButtonEvent {
+        if self.scrollbar_drag.take().is_some() {
+            self.needs_render = true;
+            return;
+        }
+        let (x, y) = (x / self.zoom, y / self.zoom);
+    self.doc.handle_ui_event(UiEvent::MouseUp(BlitzMouseButtonEvent {
             x,
             y,
             button: btn,
             buttons,
             mods,
         }));
-    self.needs_render = true;
+        self.needs_render = true;
+        self.deliver_navigation_requests();
     }
 
-    pub fn pointer_up(&mut self, x: f32, y: f32, button: u8, buttons: u32, mods: u32) {
-        use blitz_traits::events::{BlitzMouseButtonEvent, MouseEventButton, MouseEventButtons, UiEvent};
-        let btn = match button {
-            0 => MouseEventButton::Main,
-            1 => MouseEventButton::Auxiliary,
-            2 => MouseEventButton::Secondary,
-            3 => MouseEventButton::Fourth,
-            4 => MouseEventButton::Fifth,
-            _ => MouseEventButton::Main,
-        };
-        let buttons = MouseEventButtons::from_bits_truncate(buttons as u8);
+    // Pointer capture: route pointer move/up to `node_id` regardless of hit-testing (e.g. while
+    // dragging a slider thumb outside its box). Released automatically on the next pointer_up.
+    pub fn set_pointer_capture(&mut self, node_id: usize) {
+        self.doc.set_pointer_capture(node_id);
+    }
+
+    pub fn release_pointer_capture(&mut self) {
+        self.doc.release_pointer_capture();
+    }
+
+    /// Mouse wheel / trackpad delta. With Ctrl held (`mods` uses the keyboard_types bits) the wheel zooms
+    /// the page instead of scrolling it.
+    pub fn wheel_scroll(&mut self, dx: f64, dy: f64, mods: u32) {
+        use blitz_traits::events::{BlitzWheelEvent, UiEvent};
         let mods = keyboard_types::Modifiers::from_bits_truncate(mods);
-    self.doc.handle_ui_event(UiEvent::MouseUp(BlitzMouseButtonEvent {
-            x,
-            y,
-            button: btn,
-            buttons,
+        if mods.contains(keyboard_types::Modifiers::CONTROL) {
+            if dy != 0.0 {
+                self.set_zoom(wheel_zoom(self.zoom, dy));
+            }
+            return;
+        }
+        // The hovered element's nearest scroll container takes the delta first; what it can't use reaches the viewport
+        self.doc.handle_ui_event(UiEvent::Wheel(BlitzWheelEvent {
+            delta_x: dx,
+            delta_y: dy,
             mods,
         }));
     self.needs_render = true;
     }
 
-    pub fn wheel_scroll(&mut self, dx: f64, dy: f64) {
-        if let Some(hover_node_id) = self.doc.get_hover_node_id() {
-            self.doc.scroll_node_by(hover_node_id, dx, dy);
+    /// IME composition update (CoreTextEditContext TextUpdating). `cursor_start`/`cursor_end` are
+    /// UTF-16 offsets into `text`; an out-of-range cursor hides it. Empty text clears (cancels) the
+    /// composition.
+    pub fn ime_set_composition(&mut self, text: &str, cursor_start: u32, cursor_end: u32) {
+        let cursor = utf16_to_byte_index(text, cursor_start)
+            .zip(utf16_to_byte_index(text, cursor_end))
+            .map(|(a, b)| (a.min(b), a.max(b)));
+        self.dispatch_ime(blitz_traits::events::BlitzImeEvent::Preedit(
+            text.to_string(),
+            cursor,
+        ));
+    }
+
+    /// Insert the final IME text, replacing any in-progress composition.
+    pub fn ime_commit(&mut self, text: &str) {
+        self.dispatch_ime(blitz_traits::events::BlitzImeEvent::Preedit(
+            String::new(),
+            None,
+        ));
+        self.dispatch_ime(blitz_traits::events::BlitzImeEvent::Commit(
+            text.to_string(),
+        ));
+    }
+
+    /// Drop the in-progress composition without inserting anything.
+    pub fn ime_cancel(&mut self) {
+        self.dispatch_ime(blitz_traits::events::BlitzImeEvent::Preedit(
+            String::new(),
+            None,
+        ));
+    }
+
+    /// What is under a panel point (logical px, like pointer events), so the host can show a native
+    /// tooltip or set the OS cursor. None when nothing is hit.
+    pub fn hit_test(&self, x: f32, y: f32) -> Option<HitInfo> {
+        let hit = self.hit_at(x, y)?;
+        let mut info = HitInfo {
+            node_id: hit.node_id,
+            ..Default::default()
+        };
+        info.cursor = self
+            .doc
+            .cursor_for_node(hit.node_id)
+            .unwrap_or_default()
+            .name()
+            .to_string();
+        // Text nodes report their element; href/title come from the nearest ancestor carrying them
+        let mut maybe_node = self.doc.get_node(hit.node_id);
+        while let Some(node) = maybe_node {
+            if let Some(element) = node.element_data() {
+                if info.tag_name.is_empty() {
+                    info.tag_name = element.name.local.to_string();
+                }
+                let is_link = element.name.local == local_name!("a")
+                    || element.name.local == local_name!("area");
+                if info.href.is_none() && is_link {
+                    info.href = element.attr(local_name!("href")).map(str::to_string);
+                }
+                if info.title.is_none() {
+                    info.title = element.attr(local_name!("title")).map(str::to_string);
+                }
+            }
+            maybe_node = node.parent.and_then(|id| self.doc.get_node(id));
+        }
+        Some(info)
+    }
+
+    /// Cursor to show at a panel point (logical px), from the hit node's computed `cursor`. Default
+    /// when nothing is hit.
+    pub fn cursor_at(&self, x: f32, y: f32) -> CursorKind {
+        let Some(hit) = self.hit_at(x, y) else {
+            return CursorKind::Default;
+        };
+        self.doc
+            .cursor_for_node(hit.node_id)
+            .map_or(CursorKind::None, CursorKind::from)
+    }
+
+    // Hit-test a panel point (logical px), undoing the viewport scroll and page zoom
+    fn hit_at(&self, x: f32, y: f32) -> Option<blitz_traits::events::HitResult> {
+        let scroll = self.doc.viewport_scroll();
+        self.doc.hit(
+            (x + scroll.x as f32) / self.zoom,
+            (y + scroll.y as f32) / self.zoom,
+        )
+    }
+
+    /// Caret/composition rect of the focused input (logical px, panel-relative) so the host can
+    /// place the IME candidate window.
+    pub fn ime_caret_rect(&self) -> Option<kurbo::Rect> {
+        self.doc
+            .ime_cursor_area()
+            .map(|r| r.scale_from_origin(self.zoom as f64))
+    }
+
+    /// Set the page zoom (1.0 = 100%), clamped to MIN_ZOOM..=MAX_ZOOM. The document is re-laid out at the new
+    /// zoom so text reflows rather than being bitmap-scaled.
+    pub fn set_zoom(&mut self, zoom: f32) {
+        let zoom = if zoom.is_finite() {
+            zoom.clamp(MIN_ZOOM, MAX_ZOOM)
         } else {
-            self.doc.scroll_viewport_by(dx, dy);
+            1.0
+        };
+        if zoom == self.zoom {
+            return;
         }
-    self.needs_render = true;
+        self.zoom = zoom;
+        let mut viewport = self.doc.viewport().clone();
+        viewport.set_zoom(zoom);
+        self.doc.set_viewport(viewport);
+        self.needs_render = true;
+        debug_log(&format!("SetZoom: zoom={:.3}", zoom));
+    }
+
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    fn dispatch_ime(&mut self, event: blitz_traits::events::BlitzImeEvent) {
+        self.doc
+            .handle_ui_event(blitz_traits::events::UiEvent::Ime(event));
+        self.needs_render = true;
+    }
+
+    /// Scroll the viewport so the node's top-left is at the top of the window.
+    pub fn scroll_to(&mut self, node_id: usize) {
+        self.doc.scroll_node_into_view(node_id);
+        self.needs_render = true;
+    }
+
+    /// Scroll to a URL fragment target (element id or `<a name>`; empty or `top` scrolls to the top), e.g. right
+    /// after load_html for a URL with a `#fragment`. Returns false and leaves the scroll alone if nothing matches.
+    pub fn scroll_to_fragment(&mut self, fragment: &str) -> bool {
+        let fragment = fragment.strip_prefix('#').unwrap_or(fragment);
+        let found = self.doc.scroll_to_fragment(fragment);
+        if found {
+            self.needs_render = true;
+        }
+        found
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        let x = self.doc.viewport_scroll().x;
+        self.doc.scroll_viewport_to(x, 0.0);
+        self.needs_render = true;
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        let x = self.doc.viewport_scroll().x;
+        let (_, max_y) = self.doc.viewport_scroll_max();
+        self.doc.scroll_viewport_to(x, max_y);
+        self.needs_render = true;
+    }
+
+    /// Scroll the viewport by a content delta (positive `dy` moves down the page, like `window.scrollBy`).
+    pub fn scroll_by(&mut self, dx: f64, dy: f64) {
+        let cur = self.doc.viewport_scroll();
+        self.doc.scroll_viewport_to(cur.x + dx, cur.y + dy);
+        self.needs_render = true;
+    }
+
+    /// Scroll by whole pages (window heights, less a small overlap); negative pages scroll up.
+    pub fn scroll_by_page(&mut self, pages: i32) {
+        let (_, h) = self.logical_window_size();
+        self.scroll_by(0.0, pages as f64 * (h - SCROLL_PAGE_OVERLAP).max(h * 0.5));
+    }
+
+    fn logical_window_size(&self) -> (f64, f64) {
+        let vp = self.doc.viewport();
+        let scale = vp.scale() as f64;
+        (
+            vp.window_size.0 as f64 / scale,
+            vp.window_size.1 as f64 / scale,
+        )
+    }
+
+    // Overlay scrollbar (track, thumb) in logical px, or None when the page doesn't overflow vertically.
+    fn scrollbar_geometry(&self) -> Option<(kurbo::Rect, kurbo::Rect)> {
+        let (_, max_y) = self.doc.viewport_scroll_max();
+        if max_y <= 0.0 {
+            return None;
+        }
+        // The track spans the panel; the thumb ratio compares content px (viewport vs overflow)
+        let (w, h) = self.doc.viewport().window_size;
+        let (w, h) = (w as f64, h as f64);
+        let (_, view_h) = self.logical_window_size();
+        let track = kurbo::Rect::new(w - SCROLLBAR_WIDTH, 0.0, w, h);
+        let thumb_h = (view_h / (view_h + max_y) * track.height())
+            .max(SCROLLBAR_MIN_THUMB)
+            .min(track.height());
+        let frac = (self.doc.viewport_scroll().y / max_y).clamp(0.0, 1.0);
+        let thumb_y = track.y0 + frac * (track.height() - thumb_h);
+        let thumb = kurbo::Rect::new(track.x0 + 2.0, thumb_y, track.x1 - 2.0, thumb_y + thumb_h);
+        Some((track, thumb))
+    }
+
+    // Starts a thumb drag when (x, y) hits the scrollbar. Clicking the track centres the thumb on the
+    // pointer. Returns true when the event was consumed.
+    fn scrollbar_pointer_down(&mut self, x: f64, y: f64) -> bool {
+        let Some((track, thumb)) = self.scrollbar_geometry() else {
+            return false;
+        };
+        if !track.contains(kurbo::Point::new(x, y)) {
+            return false;
+        }
+        let grab = if (thumb.y0..=thumb.y1).contains(&y) {
+            y - thumb.y0
+        } else {
+            thumb.height() / 2.0
+        };
+        self.scrollbar_drag = Some(grab);
+        self.scroll_to_thumb_top(y - grab);
+        true
+    }
+
+    fn scroll_to_thumb_top(&mut self, top: f64) {
+        let Some((track, thumb)) = self.scrollbar_geometry() else {
+            return;
+        };
+        let (_, max_y) = self.doc.viewport_scroll_max();
+        let travel = (track.height() - thumb.height()).max(1.0);
+        let frac = ((top - track.y0) / travel).clamp(0.0, 1.0);
+        let x = self.doc.viewport_scroll().x;
+        self.doc.scroll_viewport_to(x, frac * max_y);
+        self.needs_render = true;
     }
 
     pub fn key_down(&mut self, vk: u32, ch: u32, mods: u32, is_auto_repeating: bool) {
@@ -748,7 +1754,8 @@ impl BlitzHost {
             text,
         };
     self.doc.handle_ui_event(UiEvent::KeyDown(evt));
-    self.needs_render = true;
+        self.needs_render = true;
+        self.deliver_navigation_requests();
     }
 
     pub fn key_up(&mut self, vk: u32, ch: u32, mods: u32) {
@@ -772,6 +1779,59 @@ impl BlitzHost {
     self.needs_render = true;
     }
 
+    /// Selected text of the focused text input, or else of the document text selection (made by
+    /// dragging over page text), if any. Ctrl+C is dispatched through the document so page scripts
+    /// see it, and the text is kept in the clipboard mirror.
+    pub fn copy_selection(&mut self) -> Option<String> {
+        let text = self
+            .focused_selected_text()
+            .or_else(|| self.doc.selected_text());
+        self.send_clipboard_shortcut("c");
+        let text = text?;
+        let _ = blitz_traits::shell::ShellProvider::set_clipboard_text(&*self.shell, text.clone());
+        Some(text)
+    }
+
+    /// Like [`copy_selection`](Self::copy_selection) but also removes the selection from the input.
+    pub fn cut_selection(&mut self) -> Option<String> {
+        let text = self.focused_selected_text()?;
+        // Ctrl+X through the editor deletes the selection, fills the clipboard mirror and fires `input`.
+        self.send_clipboard_shortcut("x");
+        Some(text)
+    }
+
+    /// Insert host-supplied clipboard text into the focused text input, replacing any selection.
+    pub fn paste_text(&mut self, text: &str) {
+        let _ =
+            blitz_traits::shell::ShellProvider::set_clipboard_text(&*self.shell, text.to_string());
+        self.send_clipboard_shortcut("v");
+    }
+
+    fn focused_selected_text(&self) -> Option<String> {
+        let node = self.doc.get_node(self.doc.get_focussed_node_id()?)?;
+        let input = node.element_data()?.text_input_data()?;
+        input.editor.selected_text().map(|s| s.to_string())
+    }
+
+    // Route Ctrl+<key> through the document so the focused editor applies it (and dispatches `input`).
+    fn send_clipboard_shortcut(&mut self, key: &str) {
+        use blitz_traits::events::{BlitzKeyEvent, KeyState, UiEvent};
+        let mut evt = BlitzKeyEvent {
+            key: keyboard_types::Key::Character(key.into()),
+            code: keyboard_types::Code::Unidentified,
+            modifiers: keyboard_types::Modifiers::CONTROL,
+            location: keyboard_types::Location::Standard,
+            is_auto_repeating: false,
+            is_composing: false,
+            state: KeyState::Pressed,
+            text: None,
+        };
+        self.doc.handle_ui_event(UiEvent::KeyDown(evt.clone()));
+        evt.state = KeyState::Released;
+        self.doc.handle_ui_event(UiEvent::KeyUp(evt));
+        self.needs_render = true;
+    }
+
     // Receive sub-phase timing from C# attacher (kind codes: 1=UI add,2=SetSwapChain)
     pub fn report_attach_subphase(&mut self, kind: u8, ms: f32) {
         if let Some(r) = self.renderer_mut() {
@@ -784,6 +1844,282 @@ impl BlitzHost {
     }
 }
 
+// Overlay scrollbar metrics (logical px)
+const SCROLLBAR_WIDTH: f64 = 10.0;
+const SCROLLBAR_MIN_THUMB: f64 = 24.0;
+const SCROLL_PAGE_OVERLAP: f64 = 40.0;
+
+// PNG-encode premultiplied RGBA8 rows as read back from D2D, un-premultiplying to the straight alpha PNG expects.
+fn encode_png(mut rgba: Vec<u8>, width: u32, height: u32) -> Option<Vec<u8>> {
+    if rgba.is_empty() || rgba.len() != width as usize * height as usize * 4 {
+        return None;
+    }
+    for px in rgba.chunks_exact_mut(4) {
+        let a = px[3] as u32;
+        if a != 0 && a != 255 {
+            for c in &mut px[..3] {
+                *c = ((*c as u32 * 255 + a / 2) / a).min(255) as u8;
+            }
+        }
+    }
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().ok()?;
+    writer.write_image_data(&rgba).ok()?;
+    writer.finish().ok()?;
+    Some(out)
+}
+
+// Page zoom range, and the zoom factor per wheel notch (the wheel delta, in px, hosts report for one notch)
+const MIN_ZOOM: f32 = 0.25;
+const MAX_ZOOM: f32 = 5.0;
+const WHEEL_ZOOM_STEP: f32 = 1.1;
+const WHEEL_NOTCH_PX: f64 = 48.0;
+
+// Zoom after a Ctrl+wheel delta: wheeling up (positive dy, as for wheel_scroll) zooms in, proportionally to the
+// delta so precision touchpads zoom smoothly.
+fn wheel_zoom(zoom: f32, dy: f64) -> f32 {
+    (zoom * WHEEL_ZOOM_STEP.powf((dy / WHEEL_NOTCH_PX) as f32)).clamp(MIN_ZOOM, MAX_ZOOM)
+}
+
+/// Element under a point, as reported by [`BlitzHost::hit_test`]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HitInfo {
+    /// Hit node (may be a text node)
+    pub node_id: usize,
+    /// Local name of the hit element, or of a text node's parent element
+    pub tag_name: String,
+    /// `href` of the enclosing link, if any
+    pub href: Option<String>,
+    /// Nearest `title` attribute, for a tooltip
+    pub title: Option<String>,
+    /// CSS cursor keyword to show (`pointer` over links, `text` over text, else the computed `cursor`)
+    pub cursor: String,
+}
+
+impl HitInfo {
+    pub fn to_json(&self) -> String {
+        let opt = |v: &Option<String>| v.as_deref().map_or("null".to_string(), json_string);
+        format!(
+            "{{\"node_id\":{},\"tag_name\":{},\"href\":{},\"title\":{},\"cursor\":{}}}",
+            self.node_id,
+            json_string(&self.tag_name),
+            opt(&self.href),
+            opt(&self.title),
+            json_string(&self.cursor),
+        )
+    }
+}
+
+/// Host-neutral cursor set that CSS `cursor` keywords map onto, as returned by [`BlitzHost::cursor_at`].
+/// The discriminants are the values IHost.GetCursor returns.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[repr(i32)]
+pub enum CursorKind {
+    #[default]
+    Default = 0,
+    Pointer = 1,
+    Text = 2,
+    Crosshair = 3,
+    Move = 4,
+    Help = 5,
+    Wait = 6,
+    Progress = 7,
+    NotAllowed = 8,
+    EwResize = 9,
+    NsResize = 10,
+    NeswResize = 11,
+    NwseResize = 12,
+    /// `cursor: none`: hide the cursor
+    None = 13,
+}
+
+impl From<CursorIcon> for CursorKind {
+    // Keywords without a close system cursor (e.g. copy, alias, zoom-in) fall back to Default
+    fn from(icon: CursorIcon) -> Self {
+        match icon {
+            CursorIcon::Pointer => CursorKind::Pointer,
+            CursorIcon::Text | CursorIcon::VerticalText => CursorKind::Text,
+            CursorIcon::Crosshair | CursorIcon::Cell => CursorKind::Crosshair,
+            CursorIcon::Move | CursorIcon::AllScroll | CursorIcon::Grab | CursorIcon::Grabbing => {
+                CursorKind::Move
+            }
+            CursorIcon::Help => CursorKind::Help,
+            CursorIcon::Wait => CursorKind::Wait,
+            CursorIcon::Progress => CursorKind::Progress,
+            CursorIcon::NotAllowed | CursorIcon::NoDrop => CursorKind::NotAllowed,
+            CursorIcon::EResize
+            | CursorIcon::WResize
+            | CursorIcon::EwResize
+            | CursorIcon::ColResize => CursorKind::EwResize,
+            CursorIcon::NResize
+            | CursorIcon::SResize
+            | CursorIcon::NsResize
+            | CursorIcon::RowResize => CursorKind::NsResize,
+            CursorIcon::NeResize | CursorIcon::SwResize | CursorIcon::NeswResize => {
+                CursorKind::NeswResize
+            }
+            CursorIcon::NwResize | CursorIcon::SeResize | CursorIcon::NwseResize => {
+                CursorKind::NwseResize
+            }
+            _ => CursorKind::Default,
+        }
+    }
+}
+
+// Decode IHost.SetDebugOverlayOptions arguments; unknown corner codes keep the overlay top-left
+fn overlay_options_from_abi(
+    lines: u32,
+    corner: u8,
+    opacity: f32,
+    max_width: f32,
+) -> anyrender_d2d::OverlayOptions {
+    use anyrender_d2d::OverlayCorner;
+    anyrender_d2d::OverlayOptions {
+        phases: lines & 1 != 0,
+        fps: lines & 2 != 0,
+        commands: lines & 4 != 0,
+        diagnostics: lines & 8 != 0,
+        corner: match corner {
+            1 => OverlayCorner::TopRight,
+            2 => OverlayCorner::BottomLeft,
+            3 => OverlayCorner::BottomRight,
+            _ => OverlayCorner::TopLeft,
+        },
+        opacity,
+        max_width,
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// Present the swapchain; a partial frame passes its changed region as the dirty rect so less is composed.
+// Safety: `sc` must be the swapchain the frame was just drawn into.
+unsafe fn present_frame(
+    sc: &IDXGISwapChain1,
+    sync_interval: u32,
+    region: Option<kurbo::Rect>,
+) -> windows::core::HRESULT {
+    let Some(r) = region.filter(|r| r.area() > 0.0) else {
+        return unsafe { sc.Present(sync_interval, DXGI_PRESENT(0)) };
+    };
+    let mut dirty = windows::Win32::Foundation::RECT {
+        left: r.x0 as i32,
+        top: r.y0 as i32,
+        right: r.x1 as i32,
+        bottom: r.y1 as i32,
+    };
+    let params = DXGI_PRESENT_PARAMETERS {
+        DirtyRectsCount: 1,
+        pDirtyRects: &mut dirty,
+        pScrollRect: std::ptr::null_mut(),
+        pScrollOffset: std::ptr::null_mut(),
+    };
+    unsafe { sc.Present1(sync_interval, DXGI_PRESENT(0), &params) }
+}
+
+// The Windows app theme (Settings > Personalization > Colors), light when it can't be read
+fn system_color_scheme() -> ColorScheme {
+    use windows::Win32::System::Registry::{HKEY_CURRENT_USER, RRF_RT_REG_DWORD, RegGetValueW};
+    let mut apps_use_light_theme: u32 = 1;
+    let mut size = std::mem::size_of::<u32>() as u32;
+    let status = unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            windows::core::w!("Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize"),
+            windows::core::w!("AppsUseLightTheme"),
+            RRF_RT_REG_DWORD,
+            None,
+            Some(&mut apps_use_light_theme as *mut u32 as *mut core::ffi::c_void),
+            Some(&mut size),
+        )
+    };
+    color_scheme_from_apps_use_light_theme(status.is_ok().then_some(apps_use_light_theme))
+}
+
+fn color_scheme_from_apps_use_light_theme(value: Option<u32>) -> ColorScheme {
+    match value {
+        Some(0) => ColorScheme::Dark,
+        _ => ColorScheme::Light,
+    }
+}
+
+// Decode IHost.SetColorScheme's code: 0=Light, 1=Dark, anything else follows the system
+pub(crate) fn color_scheme_from_abi(scheme: u8) -> Option<ColorScheme> {
+    match scheme {
+        0 => Some(ColorScheme::Light),
+        1 => Some(ColorScheme::Dark),
+        _ => None,
+    }
+}
+
+// Resolve a host-supplied URL against the base URL; absolute and data: URLs are returned as they are.
+fn resolve_against_base(
+    base: Option<&blitz_traits::net::Url>,
+    url: &str,
+) -> Option<blitz_traits::net::Url> {
+    match base {
+        Some(base) => base.join(url).ok(),
+        None => blitz_traits::net::Url::parse(url).ok(),
+    }
+}
+
+// Whether a frame may be presented now under the target frame interval
+fn frame_due(
+    interval: Option<std::time::Duration>,
+    last_frame: Option<std::time::Instant>,
+    now: std::time::Instant,
+) -> bool {
+    match (interval, last_frame) {
+        (Some(interval), Some(last)) => now.saturating_duration_since(last) >= interval,
+        _ => true,
+    }
+}
+
+// Map the host's button code (0=left,1=middle,2=right,3/4=X buttons) onto a DOM button. Some WinUI
+// pointer paths report button 0 with only the right bit set in `buttons`;
+// `blitz_dom::is_context_menu_trigger` treats that as a secondary press.
+fn mouse_button_from_host(button: u8) -> blitz_traits::events::MouseEventButton {
+    use blitz_traits::events::MouseEventButton;
+    match button {
+        0 => MouseEventButton::Main,
+        1 => MouseEventButton::Auxiliary,
+        2 => MouseEventButton::Secondary,
+        3 => MouseEventButton::Fourth,
+        4 => MouseEventButton::Fifth,
+        _ => MouseEventButton::Main,
+    }
+}
+
+fn utf16_to_byte_index(text: &str, utf16_idx: u32) -> Option<usize> {
+    let mut units = 0u32;
+    for (byte_idx, ch) in text.char_indices() {
+        if units >= utf16_idx {
+            return (units == utf16_idx).then_some(byte_idx);
+        }
+        units += ch.len_utf16() as u32;
+    }
+    (units == utf16_idx).then_some(text.len())
+}
+
 fn char_from_u32(ch: u32) -> Option<String> {
     char::from_u32(ch).map(|c| c.to_string())
 }
@@ -805,6 +2141,145 @@ fn vk_or_char_to_key(vk: u32, ch: u32) -> keyboard_types::Key {
         VK_UP => Key::ArrowUp,
         VK_RIGHT => Key::ArrowRight,
         VK_DOWN => Key::ArrowDown,
+        VK_PRIOR => Key::PageUp,
+        VK_NEXT => Key::PageDown,
+        VK_HOME => Key::Home,
+        VK_END => Key::End,
         _ => Key::Unidentified,
     }
 }
+
+#[test]
+fn utf16_offsets_map_to_byte_offsets() {
+    let text = "a\u{4F60}\u{1F600}b";
+    assert_eq!(utf16_to_byte_index(text, 0), Some(0));
+    assert_eq!(utf16_to_byte_index(text, 2), Some(4));
+    assert_eq!(utf16_to_byte_index(text, 3), None); // inside a surrogate pair
+    assert_eq!(utf16_to_byte_index(text, 4), Some(8));
+    assert_eq!(utf16_to_byte_index(text, 5), Some(9));
+    assert_eq!(utf16_to_byte_index(text, 6), None);
+}
+
+#[test]
+fn ctrl_wheel_zoom_steps_and_clamps() {
+    assert!((wheel_zoom(1.0, 48.0) - 1.1).abs() < 1e-6);
+    assert!((wheel_zoom(1.1, -48.0) - 1.0).abs() < 1e-6);
+    assert!((wheel_zoom(1.0, 24.0) - 1.1f32.sqrt()).abs() < 1e-6);
+    assert_eq!(wheel_zoom(4.9, 480.0), MAX_ZOOM);
+    assert_eq!(wheel_zoom(0.3, -480.0), MIN_ZOOM);
+}
+
+#[test]
+fn target_fps_paces_a_tight_render_loop() {
+    use std::time::{Duration, Instant};
+    let start = Instant::now();
+    let interval = Some(Duration::from_secs_f32(1.0 / 60.0));
+    let mut last = None;
+    let mut presented = 0;
+    // 1000 calls over one second
+    for ms in 0..1000 {
+        let now = start + Duration::from_millis(ms);
+        if frame_due(interval, last, now) {
+            last = Some(now);
+            presented += 1;
+        }
+    }
+    assert!((55..=61).contains(&presented), "presented {presented}");
+    assert!(frame_due(None, last, start));
+}
+
+#[test]
+fn hit_info_serializes_as_json() {
+    let info = HitInfo {
+        node_id: 7,
+        tag_name: "a".into(),
+        href: Some("/docs?q=\"x\"".into()),
+        title: None,
+        cursor: "pointer".into(),
+    };
+    assert_eq!(
+        info.to_json(),
+        r#"{"node_id":7,"tag_name":"a","href":"/docs?q=\"x\"","title":null,"cursor":"pointer"}"#
+    );
+}
+
+#[test]
+fn captured_pixels_encode_as_straight_alpha_png() {
+    // Opaque red, then 50% blue premultiplied (0, 0, 128, 128)
+    let png_bytes = encode_png(vec![255, 0, 0, 255, 0, 0, 128, 128], 2, 1).unwrap();
+    let mut reader = png::Decoder::new(&png_bytes[..]).read_info().unwrap();
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf).unwrap();
+    assert_eq!(
+        (info.width, info.height, info.color_type),
+        (2, 1, png::ColorType::Rgba)
+    );
+    assert_eq!(&buf[..8], &[255, 0, 0, 255, 0, 0, 255, 128]);
+    // A short buffer (e.g. failed device creation) yields no image
+    assert_eq!(encode_png(Vec::new(), 2, 1), None);
+}
+
+#[test]
+fn relative_urls_resolve_against_the_base_url() {
+    let base = blitz_traits::net::Url::parse("https://site/").unwrap();
+    let resolve = |url| resolve_against_base(Some(&base), url).map(|u| u.to_string());
+    assert_eq!(
+        resolve("images/logo.png").as_deref(),
+        Some("https://site/images/logo.png")
+    );
+    assert_eq!(
+        resolve("https://cdn.example/a.css").as_deref(),
+        Some("https://cdn.example/a.css")
+    );
+    assert_eq!(
+        resolve("data:text/plain,hi").as_deref(),
+        Some("data:text/plain,hi")
+    );
+    // Without a base only absolute URLs are accepted
+    assert!(resolve_against_base(None, "images/logo.png").is_none());
+}
+
+#[test]
+fn css_cursors_map_to_host_cursor_kinds() {
+    assert_eq!(CursorKind::from(CursorIcon::Pointer), CursorKind::Pointer);
+    assert_eq!(CursorKind::from(CursorIcon::Text), CursorKind::Text);
+    assert_eq!(
+        CursorKind::from(CursorIcon::ColResize),
+        CursorKind::EwResize
+    );
+    assert_eq!(CursorKind::from(CursorIcon::Grab), CursorKind::Move);
+    // No system equivalent
+    assert_eq!(CursorKind::from(CursorIcon::ZoomIn), CursorKind::Default);
+    assert_eq!(CursorKind::None as i32, 13);
+}
+
+#[test]
+fn overlay_options_decode_line_bits_and_corner() {
+    let fps_only = overlay_options_from_abi(2, 1, 0.7, 980.0);
+    assert!(fps_only.fps && !fps_only.phases && !fps_only.commands && !fps_only.diagnostics);
+    assert_eq!(fps_only.corner, anyrender_d2d::OverlayCorner::TopRight);
+    assert_eq!(fps_only.opacity, 0.7);
+    assert_eq!(
+        overlay_options_from_abi(0xf, 9, 1.0, 980.0).corner,
+        anyrender_d2d::OverlayCorner::TopLeft
+    );
+}
+
+#[test]
+fn color_scheme_codes_and_system_theme() {
+    assert!(matches!(color_scheme_from_abi(0), Some(ColorScheme::Light)));
+    assert!(matches!(color_scheme_from_abi(1), Some(ColorScheme::Dark)));
+    assert!(color_scheme_from_abi(2).is_none());
+    assert!(matches!(
+        color_scheme_from_apps_use_light_theme(Some(0)),
+        ColorScheme::Dark
+    ));
+    assert!(matches!(
+        color_scheme_from_apps_use_light_theme(Some(1)),
+        ColorScheme::Light
+    ));
+    assert!(matches!(
+        color_scheme_from_apps_use_light_theme(None),
+        ColorScheme::Light
+    ));
+}
@@ -7,7 +7,9 @@ use crate::event::{BlitzShellEvent, create_waker};
 use anyrender::WindowRenderer;
 use blitz_dom::Document;
 use blitz_paint::paint_scene;
-use blitz_traits::events::{BlitzMouseButtonEvent, MouseEventButton, MouseEventButtons, UiEvent};
+use blitz_traits::events::{
+    BlitzMouseButtonEvent, BlitzWheelEvent, MouseEventButton, MouseEventButtons, UiEvent,
+};
 use blitz_traits::shell::Viewport;
 use winit::keyboard::PhysicalKey;
 
@@ -201,13 +203,14 @@ impl<Rend: WindowRenderer> View<Rend> {
     }
 
     pub fn redraw(&mut self) {
+        self.doc.advance_caret_blink(std::time::Instant::now());
         self.doc.resolve();
         let (width, height) = self.doc.viewport().window_size;
         let scale = self.doc.viewport().scale_f64();
         self.renderer
             .render(|scene| paint_scene(scene, &self.doc, scale, width, height));
 
-        if self.doc.is_animating() {
+        if self.doc.wants_animation_frame() {
             self.request_redraw();
         }
     }
@@ -370,11 +373,11 @@ impl<Rend: WindowRenderer> View<Rend> {
                     winit::event::MouseScrollDelta::PixelDelta(offsets) => (offsets.x, offsets.y)
                 };
 
-                if let Some(hover_node_id) = self.doc.get_hover_node_id() {
-                    self.doc.scroll_node_by(hover_node_id, scroll_x, scroll_y);
-                } else {
-                    self.doc.scroll_viewport_by(scroll_x, scroll_y);
-                }
+                self.doc.handle_ui_event(UiEvent::Wheel(BlitzWheelEvent {
+                    delta_x: scroll_x,
+                    delta_y: scroll_y,
+                    mods: winit_modifiers_to_kbt_modifiers(self.keyboard_modifiers.state()),
+                }));
                 self.request_redraw();
             }
 
@@ -53,6 +53,7 @@ pub enum UiEvent {
     KeyUp(BlitzKeyEvent),
     KeyDown(BlitzKeyEvent),
     Ime(BlitzImeEvent),
+    Wheel(BlitzWheelEvent),
 }
 impl UiEvent {
     pub fn discriminant(&self) -> u8 {
@@ -100,11 +101,13 @@ pub enum DomEventKind {
     MouseDown,
     MouseUp,
     Click,
+    ContextMenu,
     KeyPress,
     KeyDown,
     KeyUp,
     Input,
     Ime,
+    Wheel,
 }
 impl DomEventKind {
     pub fn discriminant(self) -> u8 {
@@ -119,11 +122,13 @@ impl FromStr for DomEventKind {
             "mousedown" => Ok(Self::MouseDown),
             "mouseup" => Ok(Self::MouseUp),
             "click" => Ok(Self::Click),
+            "contextmenu" => Ok(Self::ContextMenu),
             "keypress" => Ok(Self::KeyPress),
             "keydown" => Ok(Self::KeyDown),
             "keyup" => Ok(Self::KeyUp),
             "input" => Ok(Self::Input),
             "composition" => Ok(Self::Ime),
+            "wheel" => Ok(Self::Wheel),
             _ => Err(()),
         }
     }
@@ -136,11 +141,13 @@ pub enum DomEventData {
     MouseDown(BlitzMouseButtonEvent),
     MouseUp(BlitzMouseButtonEvent),
     Click(BlitzMouseButtonEvent),
+    ContextMenu(BlitzMouseButtonEvent),
     KeyPress(BlitzKeyEvent),
     KeyDown(BlitzKeyEvent),
     KeyUp(BlitzKeyEvent),
     Input(BlitzInputEvent),
     Ime(BlitzImeEvent),
+    Wheel(BlitzWheelEvent),
 }
 impl DomEventData {
     pub fn discriminant(&self) -> u8 {
@@ -159,11 +166,13 @@ impl DomEventData {
             Self::MouseDown { .. } => "mousedown",
             Self::MouseUp { .. } => "mouseup",
             Self::Click { .. } => "click",
+            Self::ContextMenu { .. } => "contextmenu",
             Self::KeyPress { .. } => "keypress",
             Self::KeyDown { .. } => "keydown",
             Self::KeyUp { .. } => "keyup",
             Self::Input { .. } => "input",
             Self::Ime { .. } => "composition",
+            Self::Wheel { .. } => "wheel",
         }
     }
 
@@ -173,11 +182,13 @@ impl DomEventData {
             Self::MouseDown { .. } => DomEventKind::MouseDown,
             Self::MouseUp { .. } => DomEventKind::MouseUp,
             Self::Click { .. } => DomEventKind::Click,
+            Self::ContextMenu { .. } => DomEventKind::ContextMenu,
             Self::KeyPress { .. } => DomEventKind::KeyPress,
             Self::KeyDown { .. } => DomEventKind::KeyDown,
             Self::KeyUp { .. } => DomEventKind::KeyUp,
             Self::Input { .. } => DomEventKind::Input,
             Self::Ime { .. } => DomEventKind::Ime,
+            Self::Wheel { .. } => DomEventKind::Wheel,
         }
     }
 
@@ -187,10 +198,12 @@ impl DomEventData {
             Self::MouseDown { .. } => true,
             Self::MouseUp { .. } => true,
             Self::Click { .. } => true,
+            Self::ContextMenu { .. } => true,
             Self::KeyDown { .. } => true,
             Self::KeyUp { .. } => true,
             Self::KeyPress { .. } => true,
             Self::Ime { .. } => true,
+            Self::Wheel { .. } => true,
             Self::Input { .. } => false,
         }
     }
@@ -201,11 +214,13 @@ impl DomEventData {
             Self::MouseDown { .. } => true,
             Self::MouseUp { .. } => true,
             Self::Click { .. } => true,
+            Self::ContextMenu { .. } => true,
             Self::KeyDown { .. } => true,
             Self::KeyUp { .. } => true,
             Self::KeyPress { .. } => true,
             Self::Ime { .. } => true,
             Self::Input { .. } => true,
+            Self::Wheel { .. } => true,
         }
     }
 }
@@ -229,6 +244,15 @@ pub struct BlitzMouseButtonEvent {
     pub mods: Modifiers,
 }
 
+/// A mouse wheel / trackpad scroll. Deltas are in CSS px, positive when the content should move
+/// right/down (i.e. the user scrolls towards the start of the page).
+#[derive(Clone, Debug)]
+pub struct BlitzWheelEvent {
+    pub delta_x: f64,
+    pub delta_y: f64,
+    pub mods: Modifiers,
+}
+
 bitflags! {
     /// The buttons property indicates which buttons are pressed on the mouse
     /// (or other input device) when a mouse event is triggered.
@@ -33,6 +33,9 @@ pub struct NavigationOptions {
     pub method: Method,
 
     pub document_resource: Body,
+
+    /// Browsing context the link or form asked for (its `target` attribute, e.g. `_blank`)
+    pub target: Option<String>,
 }
 
 impl NavigationOptions {
@@ -43,6 +46,7 @@ impl NavigationOptions {
             source_document,
             method: Method::GET,
             document_resource: Body::Empty,
+            target: None,
         }
     }
     pub fn set_document_resource(mut self, document_resource: Body) -> Self {
@@ -55,6 +59,11 @@ impl NavigationOptions {
         self
     }
 
+    pub fn set_target(mut self, target: Option<String>) -> Self {
+        self.target = target;
+        self
+    }
+
     pub fn into_request(self) -> Request {
         Request {
             url: self.url,
@@ -167,7 +167,8 @@ impl EventHandler for DioxusEventHandler<'_> {
             DomEventData::MouseMove { .. }
             | DomEventData::MouseDown { .. }
             | DomEventData::MouseUp { .. }
-            | DomEventData::Click(_) => Some(wrap_event_data(NativeClickData)),
+            | DomEventData::Click(_)
+            | DomEventData::ContextMenu(_) => Some(wrap_event_data(NativeClickData)),
 
             DomEventData::KeyDown(kevent)
             | DomEventData::KeyUp(kevent)
@@ -182,6 +183,7 @@ impl EventHandler for DioxusEventHandler<'_> {
 
             // TODO: Implement IME handling
             DomEventData::Ime(_) => None,
+            DomEventData::Wheel(_) => None,
         };
 
         let Some(event_data) = event_data else {
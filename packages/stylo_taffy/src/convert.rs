@@ -131,6 +131,29 @@ pub fn inset(val: &stylo::InsetVal) -> taffy::LengthPercentageAuto {
     }
 }
 
+/// Insets as taffy sees them. Sticky boxes are laid out in flow like `relative` ones, but their insets
+/// only apply while scrolling (the renderer clamps them), so they must not shift the box here.
+#[inline]
+pub fn inset_rect(
+    position: stylo::Position,
+    pos: &style::properties::style_structs::Position,
+) -> taffy::Rect<taffy::LengthPercentageAuto> {
+    if matches!(position, stylo::Position::Sticky) {
+        return taffy::Rect {
+            left: taffy::LengthPercentageAuto::AUTO,
+            right: taffy::LengthPercentageAuto::AUTO,
+            top: taffy::LengthPercentageAuto::AUTO,
+            bottom: taffy::LengthPercentageAuto::AUTO,
+        };
+    }
+    taffy::Rect {
+        left: self::inset(&pos.left),
+        right: self::inset(&pos.right),
+        top: self::inset(&pos.top),
+        bottom: self::inset(&pos.bottom),
+    }
+}
+
 #[inline]
 pub fn is_block(input: stylo::Display) -> bool {
     matches!(input.outside(), stylo::DisplayOutside::Block)
@@ -567,12 +590,7 @@ pub fn to_taffy_style(style: &stylo::ComputedValues) -> taffy::Style<Atom> {
         },
         aspect_ratio: self::aspect_ratio(pos.aspect_ratio),
 
-        inset: taffy::Rect {
-            left: self::inset(&pos.left),
-            right: self::inset(&pos.right),
-            top: self::inset(&pos.top),
-            bottom: self::inset(&pos.bottom),
-        },
+        inset: self::inset_rect(style.clone_position(), pos),
         margin: taffy::Rect {
             left: self::margin(&margin.margin_left),
             right: self::margin(&margin.margin_right),
@@ -71,13 +71,7 @@ impl<T: Deref<Target = ComputedValues>> taffy::CoreStyle for TaffyStyloStyle<T>
 
     #[inline]
     fn inset(&self) -> taffy::Rect<taffy::LengthPercentageAuto> {
-        let position_styles = self.0.get_position();
-        taffy::Rect {
-            left: convert::inset(&position_styles.left),
-            right: convert::inset(&position_styles.right),
-            top: convert::inset(&position_styles.top),
-            bottom: convert::inset(&position_styles.bottom),
-        }
+        convert::inset_rect(self.0.get_box().position, self.0.get_position())
     }
 
     #[inline]